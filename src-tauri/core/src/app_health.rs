@@ -0,0 +1,133 @@
+//! App Health Module
+//!
+//! Consolidated health snapshot emitted to the frontend every 30 seconds, so
+//! the status bar and tray show real backend health instead of assuming
+//! everything is fine.
+
+use std::time::Duration as StdDuration;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::heartbeat::ConnectionStatus;
+use crate::preflight::LOW_FREE_BYTES;
+
+/// How often the snapshot is refreshed
+pub const REPORT_INTERVAL: StdDuration = StdDuration::from_secs(30);
+/// Remaining session time below which the driver is warned
+const SESSION_WARNING_MINUTES: i64 = 60;
+/// Heartbeat age after which a signed-in app looks stuck
+const STALE_HEARTBEAT_MINUTES: i64 = 10;
+
+/// Whether the stored session is usable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionHealth {
+    SignedOut,
+    Valid,
+    ExpiringSoon,
+    Expired,
+}
+
+/// Raw readings gathered by the reporter
+#[derive(Debug, Clone)]
+pub struct HealthInputs {
+    pub now: DateTime<Utc>,
+    pub telemetry_connected: bool,
+    pub queued_jobs: usize,
+    pub last_heartbeat: Option<DateTime<Utc>>,
+    pub session_expires_at: Option<DateTime<Utc>>,
+    pub free_disk_bytes: Option<u64>,
+    pub connection: ConnectionStatus,
+}
+
+/// Payload of the `app_health` event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppHealth {
+    pub checked_at: DateTime<Utc>,
+    pub telemetry_connected: bool,
+    pub queued_jobs: usize,
+    pub last_heartbeat: Option<DateTime<Utc>>,
+    pub session: SessionHealth,
+    pub free_disk_bytes: Option<u64>,
+    pub connection: ConnectionStatus,
+    /// Short, user-facing problems, most important first
+    pub warnings: Vec<String>,
+}
+
+impl AppHealth {
+    /// Judge the readings
+    pub fn assess(inputs: &HealthInputs) -> Self {
+        let session = match inputs.session_expires_at {
+            None => SessionHealth::SignedOut,
+            Some(expires) if expires <= inputs.now => SessionHealth::Expired,
+            Some(expires) if expires - inputs.now < Duration::minutes(SESSION_WARNING_MINUTES) => {
+                SessionHealth::ExpiringSoon
+            }
+            Some(_) => SessionHealth::Valid,
+        };
+
+        let mut warnings = Vec::new();
+        match session {
+            SessionHealth::Expired => warnings.push("Session expired, sign in again".to_string()),
+            SessionHealth::ExpiringSoon => warnings.push("Session expires within the hour".to_string()),
+            _ => {}
+        }
+        let heartbeat_stale = inputs.last_heartbeat
+            .map_or(true, |at| inputs.now - at > Duration::minutes(STALE_HEARTBEAT_MINUTES));
+        if session == SessionHealth::Valid && heartbeat_stale && inputs.connection != ConnectionStatus::Maintenance {
+            warnings.push("No contact with the server for a while".to_string());
+        }
+        if inputs.queued_jobs > 0 {
+            warnings.push(format!("{} job(s) waiting to sync", inputs.queued_jobs));
+        }
+        if inputs.free_disk_bytes.is_some_and(|free| free < LOW_FREE_BYTES) {
+            warnings.push("Low disk space".to_string());
+        }
+
+        Self {
+            checked_at: inputs.now,
+            telemetry_connected: inputs.telemetry_connected,
+            queued_jobs: inputs.queued_jobs,
+            last_heartbeat: inputs.last_heartbeat,
+            session,
+            free_disk_bytes: inputs.free_disk_bytes,
+            connection: inputs.connection,
+            warnings,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn flags_expiring_session_queue_and_disk() {
+        let now = Utc.with_ymd_and_hms(2025, 9, 1, 18, 0, 0).unwrap();
+        let mut inputs = HealthInputs {
+            now,
+            telemetry_connected: true,
+            queued_jobs: 0,
+            last_heartbeat: Some(now - Duration::seconds(40)),
+            session_expires_at: Some(now + Duration::days(20)),
+            free_disk_bytes: Some(50 * 1024 * 1024 * 1024),
+            connection: ConnectionStatus::Online,
+        };
+        let healthy = AppHealth::assess(&inputs);
+        assert_eq!(healthy.session, SessionHealth::Valid);
+        assert!(healthy.warnings.is_empty());
+
+        inputs.session_expires_at = Some(now + Duration::minutes(20));
+        inputs.queued_jobs = 2;
+        inputs.free_disk_bytes = Some(10 * 1024 * 1024);
+        let unhealthy = AppHealth::assess(&inputs);
+        assert_eq!(unhealthy.session, SessionHealth::ExpiringSoon);
+        assert_eq!(unhealthy.warnings, vec![
+            "Session expires within the hour".to_string(),
+            "2 job(s) waiting to sync".to_string(),
+            "Low disk space".to_string(),
+        ]);
+    }
+}
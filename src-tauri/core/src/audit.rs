@@ -0,0 +1,243 @@
+//! Audit Module
+//!
+//! Append-only, hash-chained local record of logins, logouts, submissions
+//! and manual changes. Each entry carries the hash of the previous one, so
+//! editing or removing a line breaks the chain; the file can be exported to
+//! settle VTC disputes about whether a job was really submitted or altered.
+
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::sync::JobSubmission;
+
+/// File name of the audit log inside the storage directory
+pub const AUDIT_FILE: &str = "audit.log";
+/// `prev_hash` of the first entry
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Something worth recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum AuditEvent {
+    #[serde(rename_all = "camelCase")]
+    Login { user_id: String },
+    #[serde(rename_all = "camelCase")]
+    Logout { user_id: Option<String> },
+    #[serde(rename_all = "camelCase")]
+    SessionRefreshed { user_id: String },
+    /// A job the server accepted; `digest` covers the submitted job data
+    #[serde(rename_all = "camelCase")]
+    JobSubmitted { job_id: String, digest: String, route: String, revenue: f64 },
+    #[serde(rename_all = "camelCase")]
+    JobSubmissionFailed { digest: String, route: String, error: String },
+    /// Driver-initiated changes (imports, manual entries, edits)
+    #[serde(rename_all = "camelCase")]
+    ManualEdit { action: String, detail: serde_json::Value },
+}
+
+impl AuditEvent {
+    /// Record of a submission the server accepted
+    pub fn submitted(submission: &JobSubmission, job_id: &str) -> Self {
+        AuditEvent::JobSubmitted {
+            job_id: job_id.to_string(),
+            digest: submission.digest(),
+            route: route(submission),
+            revenue: submission.revenue,
+        }
+    }
+
+    /// Record of a submission the server did not accept
+    pub fn submission_failed(submission: &JobSubmission, error: &str) -> Self {
+        AuditEvent::JobSubmissionFailed {
+            digest: submission.digest(),
+            route: route(submission),
+            error: error.to_string(),
+        }
+    }
+}
+
+/// One line of the audit log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub seq: u64,
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub event: AuditEvent,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+impl AuditEntry {
+    /// Hash over everything but the hash itself
+    fn compute_hash(seq: u64, at: &DateTime<Utc>, event: &AuditEvent, prev_hash: &str) -> String {
+        let body = serde_json::json!({ "seq": seq, "at": at, "event": event, "prevHash": prev_hash });
+        format!("{:x}", Sha256::digest(body.to_string().as_bytes()))
+    }
+}
+
+/// Result of checking the chain
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditVerification {
+    pub entries: u64,
+    pub valid: bool,
+    /// Sequence number (or line, if unreadable) where the chain first breaks
+    pub broken_at: Option<u64>,
+}
+
+/// Audit log errors
+#[derive(Debug, thiserror::Error)]
+pub enum AuditError {
+    #[error("Failed to write audit log: {0}")]
+    Io(String),
+
+    #[error("Failed to encode audit entry: {0}")]
+    Encode(String),
+}
+
+/// Writer for the hash-chained log file
+pub struct AuditLog {
+    path: PathBuf,
+    next_seq: u64,
+    last_hash: String,
+}
+
+impl AuditLog {
+    /// Open (or start) the log at `path`, continuing its chain
+    pub fn open(path: PathBuf) -> Self {
+        let last = std::fs::File::open(&path)
+            .ok()
+            .and_then(|file| BufReader::new(file).lines().map_while(Result::ok).filter(|line| !line.trim().is_empty()).last())
+            .and_then(|line| match serde_json::from_str::<AuditEntry>(&line) {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    warn!("Last audit entry is unreadable, chain will show a break: {}", e);
+                    None
+                }
+            });
+
+        Self {
+            path,
+            next_seq: last.as_ref().map_or(0, |entry| entry.seq + 1),
+            last_hash: last.map_or_else(|| GENESIS_HASH.to_string(), |entry| entry.hash),
+        }
+    }
+
+    /// Location of the log file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append an entry and flush it to disk
+    pub fn append(&mut self, event: AuditEvent, now: DateTime<Utc>) -> Result<AuditEntry, AuditError> {
+        let entry = chain(self.next_seq, &self.last_hash, event, now);
+        let mut line = serde_json::to_string(&entry).map_err(|e| AuditError::Encode(e.to_string()))?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| AuditError::Io(e.to_string()))?;
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.sync_data())
+            .map_err(|e| AuditError::Io(e.to_string()))?;
+
+        self.next_seq += 1;
+        self.last_hash = entry.hash.clone();
+        Ok(entry)
+    }
+
+    /// Check the whole file
+    pub fn verify(&self) -> AuditVerification {
+        match std::fs::File::open(&self.path) {
+            Ok(file) => verify_lines(BufReader::new(file).lines().map_while(Result::ok)),
+            Err(_) => AuditVerification { entries: 0, valid: true, broken_at: None },
+        }
+    }
+
+    /// Copy the log to `dest` for sharing; returns the chain check
+    pub fn export(&self, dest: &Path) -> Result<AuditVerification, AuditError> {
+        let verification = self.verify();
+        if self.path.exists() {
+            std::fs::copy(&self.path, dest).map_err(|e| AuditError::Io(e.to_string()))?;
+        } else {
+            std::fs::write(dest, b"").map_err(|e| AuditError::Io(e.to_string()))?;
+        }
+        Ok(verification)
+    }
+}
+
+/// Build the entry that follows `prev_hash`
+fn chain(seq: u64, prev_hash: &str, event: AuditEvent, at: DateTime<Utc>) -> AuditEntry {
+    let hash = AuditEntry::compute_hash(seq, &at, &event, prev_hash);
+    AuditEntry { seq, at, event, prev_hash: prev_hash.to_string(), hash }
+}
+
+/// Walk the chain, stopping at the first entry that doesn't link up
+pub fn verify_lines<I, S>(lines: I) -> AuditVerification
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut expected_prev = GENESIS_HASH.to_string();
+    let mut entries = 0;
+    for (line_no, line) in lines.into_iter().enumerate() {
+        let line = line.as_ref();
+        if line.trim().is_empty() {
+            continue;
+        }
+        let broken = |at: u64| AuditVerification { entries, valid: false, broken_at: Some(at) };
+        let Ok(entry) = serde_json::from_str::<AuditEntry>(line) else {
+            return broken(line_no as u64);
+        };
+        let hash = AuditEntry::compute_hash(entry.seq, &entry.at, &entry.event, &entry.prev_hash);
+        if entry.seq != entries || entry.prev_hash != expected_prev || entry.hash != hash {
+            return broken(entry.seq);
+        }
+        expected_prev = entry.hash;
+        entries += 1;
+    }
+    AuditVerification { entries, valid: true, broken_at: None }
+}
+
+fn route(submission: &JobSubmission) -> String {
+    format!("{} → {}", submission.source_city, submission.destination_city)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn edited_entry_breaks_the_chain() {
+        let t = Utc.with_ymd_and_hms(2025, 8, 1, 9, 0, 0).unwrap();
+        let login = chain(0, GENESIS_HASH, AuditEvent::Login { user_id: "u1".into() }, t);
+        let submitted = chain(1, &login.hash, AuditEvent::JobSubmitted {
+            job_id: "j1".into(),
+            digest: "abc".into(),
+            route: "Berlin → Hamburg".into(),
+            revenue: 12_000.0,
+        }, t);
+        let logout = chain(2, &submitted.hash, AuditEvent::Logout { user_id: Some("u1".into()) }, t);
+        let mut lines: Vec<String> = [&login, &submitted, &logout]
+            .iter()
+            .map(|entry| serde_json::to_string(entry).unwrap())
+            .collect();
+
+        assert_eq!(verify_lines(&lines), AuditVerification { entries: 3, valid: true, broken_at: None });
+
+        lines[1] = lines[1].replace("12000.0", "24000.0");
+        assert_eq!(verify_lines(&lines), AuditVerification { entries: 1, valid: false, broken_at: Some(1) });
+
+        lines.remove(1);
+        assert_eq!(verify_lines(&lines).broken_at, Some(2));
+    }
+}
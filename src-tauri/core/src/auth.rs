@@ -0,0 +1,387 @@
+//! Authentication Module
+//!
+//! Handles device token management and session state.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::clock::{self, SharedClock};
+use crate::storage::{SecureStorage, StorageError};
+
+/// How long before expiry a session is refreshed
+pub const REFRESH_WINDOW_HOURS: i64 = 24;
+/// How long before expiry the user is warned, when refreshing hasn't worked
+pub const EXPIRY_WARNING_HOURS: i64 = 6;
+/// Storage key for the sessions of every linked account
+const ACCOUNTS_KEY: &str = "accounts";
+/// Single-session key used before multi-account support
+const LEGACY_SESSION_KEY: &str = "session";
+
+/// Session data stored securely on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub access_token: String,
+    pub user_id: String,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    /// Exchanged for a new access token before expiry
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// Device public key the server knows for this session; sessions from
+    /// before signing, or a replaced key, register it again
+    #[serde(default)]
+    pub device_key: Option<String>,
+}
+
+impl Session {
+    /// Check if the session is expired at the given instant
+    pub fn is_expired_at(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        now >= self.expires_at
+    }
+}
+
+/// Sessions of every account linked on this PC, keyed by user id
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Accounts {
+    /// Account whose token submissions use
+    pub active: Option<String>,
+    pub sessions: BTreeMap<String, Session>,
+}
+
+/// Linked account as shown in the account switcher
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSummary {
+    pub user_id: String,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub expired: bool,
+    pub active: bool,
+}
+
+impl Accounts {
+    /// Load stored accounts, migrating a single-session store
+    pub fn load(storage: &SecureStorage) -> Result<Self, StorageError> {
+        if storage.exists(ACCOUNTS_KEY) {
+            return storage.load(ACCOUNTS_KEY);
+        }
+        if !storage.exists(LEGACY_SESSION_KEY) {
+            return Ok(Self::default());
+        }
+
+        let mut accounts = Self::default();
+        accounts.insert(storage.load::<Session>(LEGACY_SESSION_KEY)?);
+        accounts.save(storage)?;
+        let _ = storage.delete(LEGACY_SESSION_KEY);
+        info!("Migrated stored session to multi-account storage");
+        Ok(accounts)
+    }
+
+    /// Persist accounts to storage
+    pub fn save(&self, storage: &SecureStorage) -> Result<(), StorageError> {
+        storage.save(ACCOUNTS_KEY, self)
+    }
+
+    /// Add or update an account and make it the active one
+    pub fn insert(&mut self, session: Session) {
+        self.active = Some(session.user_id.clone());
+        self.sessions.insert(session.user_id.clone(), session);
+    }
+
+    /// Session of the active account
+    pub fn active_session(&self) -> Option<&Session> {
+        self.sessions.get(self.active.as_deref()?)
+    }
+
+    /// Make another linked account active
+    pub fn switch(&mut self, user_id: &str) -> Option<&Session> {
+        let session = self.sessions.get(user_id)?;
+        self.active = Some(user_id.to_string());
+        Some(session)
+    }
+
+    /// Forget an account; removing the active one leaves none active
+    pub fn remove(&mut self, user_id: &str) -> Option<Session> {
+        if self.active.as_deref() == Some(user_id) {
+            self.active = None;
+        }
+        self.sessions.remove(user_id)
+    }
+
+    /// Every linked account, for the account switcher
+    pub fn summaries(&self, now: chrono::DateTime<chrono::Utc>) -> Vec<AccountSummary> {
+        self.sessions.values()
+            .map(|session| AccountSummary {
+                user_id: session.user_id.clone(),
+                display_name: session.display_name.clone(),
+                avatar_url: session.avatar_url.clone(),
+                expires_at: session.expires_at,
+                expired: session.is_expired_at(now),
+                active: self.active.as_ref() == Some(&session.user_id),
+            })
+            .collect()
+    }
+}
+
+/// Read-mostly copy of the access token for hot paths.
+///
+/// Submissions, heartbeats and position uploads read from here without
+/// contending with login/logout, which publish through [`AuthManager`].
+pub struct TokenCache {
+    current: RwLock<Option<CachedToken>>,
+    clock: SharedClock,
+}
+
+struct CachedToken {
+    access_token: Arc<str>,
+    expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl TokenCache {
+    fn new(clock: SharedClock) -> Self {
+        Self { current: RwLock::new(None), clock }
+    }
+
+    /// Current access token, if one is set and not expired
+    pub fn get(&self) -> Option<Arc<str>> {
+        let current = self.current.read().ok()?;
+        current.as_ref()
+            .filter(|token| self.clock.now() < token.expires_at)
+            .map(|token| token.access_token.clone())
+    }
+
+    fn publish(&self, session: Option<&Session>) {
+        if let Ok(mut current) = self.current.write() {
+            *current = session.map(|session| CachedToken {
+                access_token: session.access_token.as_str().into(),
+                expires_at: session.expires_at,
+            });
+        }
+    }
+}
+
+/// Manages authentication state
+pub struct AuthManager {
+    session: Option<Session>,
+    clock: SharedClock,
+    tokens: Arc<TokenCache>,
+}
+
+impl AuthManager {
+    /// Create a new auth manager
+    pub fn new() -> Self {
+        Self::with_clock(clock::system())
+    }
+
+    /// Create an auth manager driven by a custom clock
+    pub fn with_clock(clock: SharedClock) -> Self {
+        let tokens = Arc::new(TokenCache::new(clock.clone()));
+        Self { session: None, clock, tokens }
+    }
+
+    /// Shared token cache kept in sync with this manager
+    pub fn token_cache(&self) -> Arc<TokenCache> {
+        self.tokens.clone()
+    }
+
+    /// Set the current session
+    pub fn set_session(&mut self, session: Session) {
+        info!("Session set for user: {}", session.user_id);
+        self.tokens.publish(Some(&session));
+        self.session = Some(session);
+    }
+
+    /// Get the current session if valid
+    pub fn get_session(&self) -> Option<&Session> {
+        match &self.session {
+            Some(session) if !session.is_expired_at(self.clock.now()) => Some(session),
+            Some(_) => {
+                warn!("Session is expired");
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Get the access token if authenticated
+    pub fn get_access_token(&self) -> Option<&str> {
+        self.get_session().map(|s| s.access_token.as_str())
+    }
+
+    /// Check if currently authenticated
+    pub fn is_authenticated(&self) -> bool {
+        self.get_session().is_some()
+    }
+
+    /// Refresh token of a valid session that expires within the refresh window
+    pub fn refresh_due(&self) -> Option<String> {
+        let session = self.get_session()?;
+        let window = chrono::Duration::hours(REFRESH_WINDOW_HOURS);
+        if session.expires_at - self.clock.now() > window {
+            return None;
+        }
+        session.refresh_token.clone()
+    }
+
+    /// Expiry of a valid session that ends within the warning window
+    pub fn expiring_soon(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let session = self.get_session()?;
+        let window = chrono::Duration::hours(EXPIRY_WARNING_HOURS);
+        (session.expires_at - self.clock.now() <= window).then_some(session.expires_at)
+    }
+
+    /// Swap in a refreshed access token; returns the updated session to persist
+    pub fn refresh_session(
+        &mut self,
+        access_token: String,
+        refresh_token: Option<String>,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Option<Session> {
+        let session = self.session.as_mut()?;
+        session.access_token = access_token;
+        session.expires_at = expires_at;
+        if refresh_token.is_some() {
+            session.refresh_token = refresh_token;
+        }
+        info!("Session refreshed, valid until {}", expires_at);
+        self.tokens.publish(Some(session));
+        Some(session.clone())
+    }
+
+    /// Record the device key registered for the current session; returns the
+    /// updated session to persist
+    pub fn set_device_key(&mut self, public_key: String) -> Option<Session> {
+        let session = self.session.as_mut()?;
+        session.device_key = Some(public_key);
+        Some(session.clone())
+    }
+
+    /// Clear the current session
+    pub fn clear_session(&mut self) {
+        info!("Session cleared");
+        self.tokens.publish(None);
+        self.session = None;
+    }
+}
+
+impl Default for AuthManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn session_expiring_at(expires_at: chrono::DateTime<Utc>) -> Session {
+        session_for("user", expires_at)
+    }
+
+    fn session_for(user_id: &str, expires_at: chrono::DateTime<Utc>) -> Session {
+        Session {
+            access_token: "token".into(),
+            user_id: user_id.into(),
+            display_name: "Driver".into(),
+            avatar_url: None,
+            expires_at,
+            refresh_token: Some("refresh".into()),
+            device_key: None,
+        }
+    }
+
+    #[test]
+    fn session_is_valid_until_expiry_instant() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let clock = Arc::new(MockClock::new(start));
+        let mut auth = AuthManager::with_clock(clock.clone());
+        auth.set_session(session_expiring_at(start + Duration::hours(1)));
+
+        assert_eq!(auth.get_access_token(), Some("token"));
+
+        clock.advance(Duration::hours(1) - Duration::seconds(1));
+        assert!(auth.is_authenticated());
+
+        clock.advance(Duration::seconds(1));
+        assert!(!auth.is_authenticated());
+        assert_eq!(auth.get_access_token(), None);
+    }
+
+    #[test]
+    fn clearing_session_removes_token() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let mut auth = AuthManager::with_clock(Arc::new(MockClock::new(start)));
+        auth.set_session(session_expiring_at(start + Duration::days(30)));
+
+        auth.clear_session();
+        assert!(!auth.is_authenticated());
+    }
+
+    #[test]
+    fn refresh_is_due_within_window() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let clock = Arc::new(MockClock::new(start));
+        let mut auth = AuthManager::with_clock(clock.clone());
+        let tokens = auth.token_cache();
+        auth.set_session(session_expiring_at(start + Duration::days(30)));
+        assert_eq!(auth.refresh_due(), None);
+
+        clock.advance(Duration::days(29) + Duration::hours(1));
+        assert_eq!(auth.refresh_due().as_deref(), Some("refresh"));
+        assert_eq!(auth.expiring_soon(), None);
+        clock.advance(Duration::hours(18));
+        assert_eq!(auth.expiring_soon(), Some(start + Duration::days(30)));
+
+        auth.refresh_session("fresh".into(), None, start + Duration::days(60));
+        assert_eq!(tokens.get().as_deref(), Some("fresh"));
+        assert_eq!(auth.refresh_due(), None);
+    }
+
+    #[test]
+    fn token_cache_follows_manager() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let clock = Arc::new(MockClock::new(start));
+        let mut auth = AuthManager::with_clock(clock.clone());
+        let tokens = auth.token_cache();
+        assert!(tokens.get().is_none());
+
+        auth.set_session(session_expiring_at(start + Duration::hours(1)));
+        assert_eq!(tokens.get().as_deref(), Some("token"));
+
+        clock.advance(Duration::hours(1));
+        assert!(tokens.get().is_none());
+
+        auth.clear_session();
+        clock.set(start);
+        assert!(tokens.get().is_none());
+    }
+
+    #[test]
+    fn accounts_switch_and_remove() {
+        let start = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let mut accounts = Accounts::default();
+        accounts.insert(session_for("alice", start + Duration::days(30)));
+        accounts.insert(session_for("bob", start - Duration::days(1)));
+        assert_eq!(accounts.active_session().map(|s| s.user_id.as_str()), Some("bob"));
+
+        assert!(accounts.switch("carol").is_none());
+        assert_eq!(accounts.switch("alice").map(|s| s.user_id.as_str()), Some("alice"));
+
+        let summaries = accounts.summaries(start);
+        assert_eq!(summaries.len(), 2);
+        assert!(summaries[0].active && !summaries[0].expired);
+        assert!(!summaries[1].active && summaries[1].expired);
+
+        accounts.remove("bob");
+        assert_eq!(accounts.active.as_deref(), Some("alice"));
+        accounts.remove("alice");
+        assert!(accounts.active_session().is_none());
+        assert!(accounts.sessions.is_empty());
+    }
+}
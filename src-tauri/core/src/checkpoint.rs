@@ -0,0 +1,277 @@
+//! Checkpoint Module
+//!
+//! Periodically persists in-flight tracking state so a crash or power loss
+//! loses at most a few seconds of progress, and restores it on startup.
+//! An interrupted job is offered to the driver to resume, submit as a
+//! partial record, or discard.
+
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::job_queue::{JobQueue, QueuedJob};
+use crate::speeding::ConductReport;
+use crate::storage::{SecureStorage, StorageError};
+use crate::sync::JobSubmission;
+use crate::telemetry::{ActiveJob, Game, JobPhase, TelemetryReader};
+
+/// Storage key for the checkpoint
+const CHECKPOINT_KEY: &str = "checkpoint";
+/// How often state is written while tracking
+pub const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Snapshot of in-memory tracking state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Checkpoint {
+    pub saved_at: DateTime<Utc>,
+    pub job: Option<ActiveJob>,
+    pub job_phase: Option<JobPhase>,
+    /// Submissions waiting to be retried, with their accounts
+    #[serde(default)]
+    pub queue: Vec<QueuedJob>,
+    /// Submissions queued before the queue recorded accounts
+    #[serde(default, skip_serializing)]
+    pub failed_jobs: Vec<JobSubmission>,
+    /// Violations collected so far on the job
+    #[serde(default)]
+    pub conduct: ConductReport,
+}
+
+impl Checkpoint {
+    /// Whether there is anything worth persisting
+    pub fn is_empty(&self) -> bool {
+        self.job.is_none() && self.queue.is_empty() && self.failed_jobs.is_empty()
+    }
+
+    /// Load the last checkpoint, if any
+    pub fn load(storage: &SecureStorage) -> Option<Self> {
+        if !storage.exists(CHECKPOINT_KEY) {
+            return None;
+        }
+        storage.load(CHECKPOINT_KEY)
+            .map_err(|e| warn!("Discarding unreadable checkpoint: {}", e))
+            .ok()
+    }
+
+    /// Persist this checkpoint
+    pub fn save(&self, storage: &SecureStorage) -> Result<(), StorageError> {
+        storage.save(CHECKPOINT_KEY, self)
+    }
+
+    /// Remove the stored checkpoint
+    pub fn clear(storage: &SecureStorage) -> Result<(), StorageError> {
+        storage.delete(CHECKPOINT_KEY)
+    }
+}
+
+/// Decides when to write checkpoints
+#[derive(Debug, Default)]
+pub struct Checkpointer {
+    last_saved: Option<Instant>,
+    stored: bool,
+}
+
+impl Checkpointer {
+    /// Create a new checkpointer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the next checkpoint is due
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.last_saved
+            .map_or(true, |last| now.duration_since(last) >= CHECKPOINT_INTERVAL)
+    }
+
+    /// Write on the next check instead of waiting out the interval
+    pub fn request(&mut self) {
+        self.last_saved = None;
+    }
+
+    /// Write `checkpoint`, or clear the stored one once there is nothing left to protect
+    pub fn write(&mut self, checkpoint: &Checkpoint, storage: &SecureStorage, now: Instant) {
+        self.last_saved = Some(now);
+
+        if checkpoint.is_empty() {
+            if self.stored {
+                debug!("Tracking idle, clearing checkpoint");
+                if let Err(e) = Checkpoint::clear(storage) {
+                    warn!("Failed to clear checkpoint: {}", e);
+                }
+                self.stored = false;
+            }
+            return;
+        }
+
+        match checkpoint.save(storage) {
+            Ok(()) => self.stored = true,
+            Err(e) => warn!("Failed to write checkpoint: {}", e),
+        }
+    }
+}
+
+/// Job found in the last checkpoint, waiting for the driver to decide on it
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveredJob {
+    pub saved_at: DateTime<Utc>,
+    pub job: ActiveJob,
+    pub phase: JobPhase,
+    pub conduct: ConductReport,
+}
+
+/// What to do with a recovered job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecoveryAction {
+    /// Keep tracking it when the game reports the job again
+    Resume,
+    /// Submit what was driven as a partial record
+    SubmitPartial,
+    /// Forget the job
+    Discard,
+}
+
+/// State restored from the last checkpoint
+#[derive(Debug, Default)]
+pub struct Recovery {
+    /// Submissions that were still waiting to be retried
+    pub queue: JobQueue,
+    /// Interrupted job, already restored into the reader
+    pub job: Option<RecoveredJob>,
+}
+
+/// Restore state from the last checkpoint into the telemetry reader
+pub fn recover(storage: &SecureStorage, telemetry: &mut TelemetryReader) -> Recovery {
+    let Some(checkpoint) = Checkpoint::load(storage) else {
+        return Recovery::default();
+    };
+
+    info!(
+        "Recovering checkpoint from {} ({} pending submissions)",
+        checkpoint.saved_at,
+        checkpoint.queue.len() + checkpoint.failed_jobs.len()
+    );
+
+    let mut job = None;
+    if let (Some(active), Some(phase)) = (checkpoint.job, checkpoint.job_phase) {
+        info!("Resuming job {} -> {}", active.source_city, active.destination_city);
+        telemetry.restore_job(active.clone(), phase);
+        job = Some(RecoveredJob { saved_at: checkpoint.saved_at, job: active, phase, conduct: checkpoint.conduct });
+    }
+
+    // Whoever drains first takes the jobs queued without an account
+    let mut queue = JobQueue::from_jobs(checkpoint.queue);
+    for submission in checkpoint.failed_jobs {
+        queue.push(None, submission);
+    }
+    Recovery { queue, job }
+}
+
+/// Partial record of a job that was never delivered
+pub fn partial_submission(recovered: &RecoveredJob, game: Game) -> JobSubmission {
+    let mut job = recovered.job.clone();
+    // The last leg ended when the run was interrupted
+    let remaining = job.distance_remaining;
+    if let Some(leg) = job.legs.last_mut().filter(|leg| leg.ended_at.is_none()) {
+        leg.ended_at = Some(recovered.saved_at);
+        leg.end_remaining_km = Some(remaining);
+    }
+
+    let mut submission = JobSubmission::from_completed(&job, game)
+        .with_telemetry("conduct", recovered.conduct.summary())
+        .with_telemetry("partial", serde_json::json!({
+            "drivenKm": job.driven_km(),
+            "plannedRevenue": job.revenue,
+            "phase": recovered.phase,
+            "interruptedAt": recovered.saved_at,
+        }));
+    // Nothing was paid out for an undelivered load, and only the part
+    // driven counts towards distance totals
+    submission.revenue = 0.0;
+    submission.distance_km = job.driven_km();
+    submission.partial = true;
+    submission
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::speeding::Collision;
+    use crate::telemetry::{DamageReading, FuelUsage, JobLeg};
+
+    #[test]
+    fn due_every_interval() {
+        let start = Instant::now();
+        let mut checkpointer = Checkpointer::new();
+        assert!(checkpointer.is_due(start));
+
+        checkpointer.last_saved = Some(start);
+        assert!(!checkpointer.is_due(start + Duration::from_secs(4)));
+        assert!(checkpointer.is_due(start + CHECKPOINT_INTERVAL));
+
+        checkpointer.request();
+        assert!(checkpointer.is_due(start + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn recovers_interrupted_job_with_conduct() {
+        let dir = std::env::temp_dir().join(format!("vtc-checkpoint-{}", uuid::Uuid::new_v4()));
+        let storage = SecureStorage::in_dir(dir.clone());
+        let now = Utc::now();
+        let job = ActiveJob {
+            cargo: "Machinery".into(),
+            source_city: "Berlin".into(),
+            destination_city: "Hamburg".into(),
+            source_company: None,
+            destination_company: None,
+            distance_km: 290,
+            distance_remaining: 90,
+            revenue: 12_000,
+            started_at: now,
+            pickup_damage: DamageReading::default(),
+            delivery_damage: None,
+            max_damage: DamageReading::default(),
+            market: None,
+            trailer_ownership: None,
+            truck: None,
+            trailer: None,
+            legs: vec![JobLeg::open(now, 290)],
+            fuel: FuelUsage::default(),
+            odometer: Default::default(),
+            integrity: Default::default(),
+            snapshots: Default::default(),
+        };
+        let mut conduct = ConductReport::default();
+        conduct.record_collision(Collision { at: now, cargo_damage: 1.0, chassis_damage: 2.0 });
+        let checkpoint = Checkpoint {
+            saved_at: now,
+            job: Some(job),
+            job_phase: Some(JobPhase::InTransit),
+            queue: Vec::new(),
+            failed_jobs: Vec::new(),
+            conduct,
+        };
+        checkpoint.save(&storage).unwrap();
+
+        let mut reader = TelemetryReader::new();
+        let recovery = recover(&storage, &mut reader);
+        let recovered = recovery.job.expect("recovered job");
+        assert_eq!(recovered.conduct.collisions.len(), 1);
+        assert!(reader.job_snapshot().is_some());
+
+        let submission = partial_submission(&recovered, Game::Ets2);
+        assert_eq!(submission.revenue, 0.0);
+        let partial = &submission.telemetry_data.as_ref().unwrap()["partial"];
+        assert_eq!(partial["plannedRevenue"], 12_000);
+        assert_eq!(partial["drivenKm"], 200);
+        assert!(submission.partial);
+        assert_eq!(submission.distance_km, 200);
+
+        reader.abandon_job();
+        assert!(reader.job_snapshot().is_none());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
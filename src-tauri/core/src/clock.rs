@@ -0,0 +1,84 @@
+//! Clock Module
+//!
+//! Injectable wall-clock source so time-dependent logic can be tested deterministically.
+
+use std::sync::{Arc, Mutex};
+use chrono::{DateTime, Utc};
+
+/// Source of the current time
+pub trait Clock: Send + Sync {
+    /// Current UTC time
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Clock backed by the system time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Manually driven clock for tests and simulations
+#[derive(Debug)]
+pub struct MockClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl MockClock {
+    /// Create a mock clock frozen at `start`
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self { now: Mutex::new(start) }
+    }
+
+    /// Jump to an absolute time
+    pub fn set(&self, now: DateTime<Utc>) {
+        if let Ok(mut current) = self.now.lock() {
+            *current = now;
+        }
+    }
+
+    /// Move time forward by `by`
+    pub fn advance(&self, by: chrono::Duration) {
+        if let Ok(mut current) = self.now.lock() {
+            *current += by;
+        }
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.now.lock()
+            .map(|now| *now)
+            .unwrap_or_else(|poisoned| *poisoned.into_inner())
+    }
+}
+
+/// Shared clock handle
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The default clock used by the application
+pub fn system() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn mock_clock_advances_and_sets() {
+        let start = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+        let clock = MockClock::new(start);
+        assert_eq!(clock.now(), start);
+
+        clock.advance(Duration::minutes(5));
+        assert_eq!(clock.now(), start + Duration::minutes(5));
+
+        clock.set(start);
+        assert_eq!(clock.now(), start);
+    }
+}
@@ -0,0 +1,132 @@
+//! Connectivity Module
+//!
+//! Judges the connection to the API from periodic lightweight probes: latency
+//! over the last few probes and the current failure streak. After a few
+//! failed probes in a row the app goes into offline mode, where finished jobs
+//! are queued instead of being sent, until a probe gets through again.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Probe interval while the API is reachable
+pub const PROBE_INTERVAL: Duration = Duration::from_secs(30);
+/// Probe interval while offline, to notice the way back quickly
+pub const OFFLINE_PROBE_INTERVAL: Duration = Duration::from_secs(10);
+/// Longest a probe may take before it counts as failed
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Failed probes in a row before going offline
+const OFFLINE_AFTER_FAILURES: u32 = 3;
+/// Average latency above which the connection counts as slow (ms)
+const SLOW_LATENCY_MS: u64 = 1500;
+/// Probes averaged for the latency
+const LATENCY_SAMPLES: usize = 5;
+
+/// How well the API can be reached
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Connectivity {
+    /// No probe has finished yet
+    #[default]
+    Unknown,
+    Online,
+    /// Reachable, but responding slowly
+    Slow,
+    Offline,
+}
+
+/// Payload of `connectivity_changed`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityReport {
+    pub state: Connectivity,
+    /// Average over the last successful probes
+    pub latency_ms: Option<u64>,
+    pub failure_streak: u32,
+    pub last_probe_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+/// Connectivity from the probe outcomes so far
+#[derive(Debug, Default)]
+pub struct ConnectivityMonitor {
+    report: ConnectivityReport,
+    latencies: VecDeque<u64>,
+}
+
+impl ConnectivityMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a probe's round trip time or error; returns the new report if
+    /// the connectivity changed
+    pub fn record(&mut self, outcome: Result<Duration, String>, now: DateTime<Utc>) -> Option<ConnectivityReport> {
+        let previous = self.report.state;
+        self.report.last_probe_at = Some(now);
+        match outcome {
+            Ok(latency) => {
+                if self.latencies.len() == LATENCY_SAMPLES {
+                    self.latencies.pop_front();
+                }
+                self.latencies.push_back(latency.as_millis() as u64);
+                let average = self.latencies.iter().sum::<u64>() / self.latencies.len() as u64;
+                self.report.latency_ms = Some(average);
+                self.report.failure_streak = 0;
+                self.report.last_error = None;
+                self.report.state = if average > SLOW_LATENCY_MS { Connectivity::Slow } else { Connectivity::Online };
+            }
+            Err(error) => {
+                self.report.failure_streak += 1;
+                self.report.last_error = Some(error);
+                if self.report.failure_streak >= OFFLINE_AFTER_FAILURES {
+                    self.report.state = Connectivity::Offline;
+                    // Latency from before the outage says nothing about the way back
+                    self.latencies.clear();
+                    self.report.latency_ms = None;
+                }
+            }
+        }
+        (self.report.state != previous).then(|| self.report.clone())
+    }
+
+    pub fn report(&self) -> ConnectivityReport {
+        self.report.clone()
+    }
+
+    /// Work should be queued rather than sent
+    pub fn is_offline(&self) -> bool {
+        self.report.state == Connectivity::Offline
+    }
+
+    /// Delay before the next probe
+    pub fn next_probe(&self) -> Duration {
+        if self.is_offline() { OFFLINE_PROBE_INTERVAL } else { PROBE_INTERVAL }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn goes_offline_after_a_failure_streak_and_back() {
+        let now = Utc::now();
+        let mut monitor = ConnectivityMonitor::new();
+        let ok = |ms| Ok(Duration::from_millis(ms));
+
+        assert_eq!(monitor.record(ok(80), now).unwrap().state, Connectivity::Online);
+        assert_eq!(monitor.record(Err("timed out".into()), now), None);
+        assert_eq!(monitor.record(Err("timed out".into()), now), None);
+        let offline = monitor.record(Err("timed out".into()), now).unwrap();
+        assert_eq!((offline.state, offline.failure_streak, offline.latency_ms), (Connectivity::Offline, 3, None));
+        assert!(monitor.is_offline());
+        assert_eq!(monitor.next_probe(), OFFLINE_PROBE_INTERVAL);
+
+        assert_eq!(monitor.record(ok(4000), now).unwrap().state, Connectivity::Slow);
+        assert_eq!(monitor.record(ok(100), now), None);
+        assert_eq!(monitor.record(ok(100), now).unwrap().state, Connectivity::Online);
+        assert_eq!(monitor.report().latency_ms, Some(1400));
+    }
+}
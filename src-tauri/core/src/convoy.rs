@@ -0,0 +1,377 @@
+//! Convoy Module
+//!
+//! Convoy mode tags every job and the driven route during an event with a
+//! convoy ID, then produces a combined report for posting after the event.
+//! For events from the web platform it also tracks attendance: time spent
+//! on a multiplayer server and distance driven within the event window.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::telemetry::{ActiveJob, TelemetryState};
+
+/// Minimum spacing between recorded route points
+const ROUTE_INTERVAL_SECS: i64 = 10;
+/// Longest gap between samples still counted as attendance; longer gaps
+/// mean the app or PC was asleep
+const MAX_SAMPLE_GAP_SECS: i64 = 30;
+
+/// Event scheduled on the web platform
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvoyEvent {
+    pub id: String,
+    pub name: String,
+    #[serde(alias = "starts_at")]
+    pub starts_at: DateTime<Utc>,
+    #[serde(alias = "ends_at")]
+    pub ends_at: DateTime<Utc>,
+}
+
+/// The driver's participation in an event
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attendance {
+    /// Seconds connected to a multiplayer server during the event window,
+    /// fractional since samples arrive every poll
+    pub online_secs: f64,
+    /// Kilometres driven while connected during the event window
+    pub distance_km: f64,
+    /// Multiplayer server the driver was last seen on
+    pub server: Option<String>,
+    pub first_seen: Option<DateTime<Utc>>,
+    pub last_seen: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    last_sample: Option<DateTime<Utc>>,
+}
+
+impl Attendance {
+    /// Count the time since the previous sample if the driver was online for it
+    fn observe(&mut self, state: &TelemetryState, now: DateTime<Utc>) {
+        let online = state.connected && state.server.is_some();
+        let previous = std::mem::replace(&mut self.last_sample, online.then_some(now));
+        if !online {
+            return;
+        }
+        self.server = state.server.clone();
+        self.first_seen.get_or_insert(now);
+        self.last_seen = Some(now);
+
+        let Some(previous) = previous else {
+            return;
+        };
+        let elapsed = now - previous;
+        if elapsed.num_seconds() > MAX_SAMPLE_GAP_SECS || elapsed <= chrono::Duration::zero() {
+            return;
+        }
+        let secs = elapsed.num_milliseconds() as f64 / 1000.0;
+        self.online_secs += secs;
+        if !state.paused {
+            self.distance_km += f64::from(state.speed.abs()) * secs / 3600.0;
+        }
+    }
+}
+
+/// Attendance report posted to the event once it ends
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttendanceReport {
+    pub event_id: String,
+    pub online_minutes: i64,
+    pub distance_km: f64,
+    pub jobs: usize,
+    pub server: Option<String>,
+    pub first_seen: Option<DateTime<Utc>>,
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+/// A job delivered during the convoy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvoyJob {
+    pub cargo: String,
+    pub source_city: String,
+    pub destination_city: String,
+    pub distance_km: u32,
+    pub revenue: u64,
+    pub delivered_at: DateTime<Utc>,
+}
+
+/// A point on the driven route
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutePoint {
+    pub at: DateTime<Utc>,
+    pub x: f64,
+    pub z: f64,
+}
+
+/// An in-progress or finished convoy recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvoySession {
+    /// Event calendar ID or a manually entered identifier
+    pub id: String,
+    pub name: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub jobs: Vec<ConvoyJob>,
+    pub route: Vec<RoutePoint>,
+    /// Platform event being attended, if the ID named one
+    #[serde(default)]
+    pub event: Option<ConvoyEvent>,
+    #[serde(default)]
+    pub attendance: Attendance,
+}
+
+impl ConvoySession {
+    /// Start recording a convoy
+    pub fn start(id: String, name: Option<String>, now: DateTime<Utc>) -> Self {
+        Self {
+            id,
+            name,
+            started_at: now,
+            ended_at: None,
+            jobs: Vec::new(),
+            route: Vec::new(),
+            event: None,
+            attendance: Attendance::default(),
+        }
+    }
+
+    /// Start recording a platform event, tracking attendance in its window
+    pub fn for_event(event: ConvoyEvent, now: DateTime<Utc>) -> Self {
+        let mut session = Self::start(event.id.clone(), Some(event.name.clone()), now);
+        session.event = Some(event);
+        session
+    }
+
+    /// Update attendance from a telemetry poll; only counted inside the event window
+    pub fn record_attendance(&mut self, state: &TelemetryState, now: DateTime<Utc>) {
+        let Some(event) = &self.event else {
+            return;
+        };
+        if now < event.starts_at || now > event.ends_at {
+            return;
+        }
+        self.attendance.observe(state, now);
+    }
+
+    /// Whether the event window has closed
+    pub fn event_ended(&self, now: DateTime<Utc>) -> bool {
+        self.event.as_ref().is_some_and(|event| now > event.ends_at)
+    }
+
+    /// Attendance to submit for a platform event
+    pub fn attendance_report(&self) -> Option<AttendanceReport> {
+        let event = self.event.as_ref()?;
+        Some(AttendanceReport {
+            event_id: event.id.clone(),
+            online_minutes: (self.attendance.online_secs / 60.0) as i64,
+            distance_km: (self.attendance.distance_km * 10.0).round() / 10.0,
+            jobs: self.jobs.len(),
+            server: self.attendance.server.clone(),
+            first_seen: self.attendance.first_seen,
+            last_seen: self.attendance.last_seen,
+        })
+    }
+
+    /// Record a delivered job
+    pub fn record_job(&mut self, job: &ActiveJob, now: DateTime<Utc>) {
+        self.jobs.push(ConvoyJob {
+            cargo: job.cargo.clone(),
+            source_city: job.source_city.clone(),
+            destination_city: job.destination_city.clone(),
+            distance_km: job.distance_km,
+            revenue: job.revenue,
+            delivered_at: now,
+        });
+    }
+
+    /// Record a route point, thinned to one per interval
+    pub fn record_position(&mut self, x: f64, z: f64, now: DateTime<Utc>) {
+        if let Some(last) = self.route.last() {
+            if (now - last.at).num_seconds() < ROUTE_INTERVAL_SECS {
+                return;
+            }
+        }
+        self.route.push(RoutePoint { at: now, x, z });
+    }
+
+    /// Finish the recording and build the report
+    pub fn finish(mut self, now: DateTime<Utc>) -> ConvoyReport {
+        self.ended_at = Some(now);
+        ConvoyReport {
+            summary: self.summary_markdown(),
+            route: self.route_geojson(),
+            session: self,
+        }
+    }
+
+    fn title(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.id)
+    }
+
+    /// Discord-ready summary
+    fn summary_markdown(&self) -> String {
+        let ended_at = self.ended_at.unwrap_or(self.started_at);
+        let minutes = (ended_at - self.started_at).num_minutes();
+        let distance: u32 = self.jobs.iter().map(|job| job.distance_km).sum();
+        let revenue: u64 = self.jobs.iter().map(|job| job.revenue).sum();
+
+        let mut out = format!(
+            "**Convoy: {}**\n{} – {} UTC ({}h {:02}m)\nJobs: {} · Distance: {} km · Revenue: {}\n",
+            self.title(),
+            self.started_at.format("%Y-%m-%d %H:%M"),
+            ended_at.format("%H:%M"),
+            minutes / 60,
+            minutes % 60,
+            self.jobs.len(),
+            distance,
+            revenue,
+        );
+        for job in &self.jobs {
+            out.push_str(&format!(
+                "- {} → {} ({}, {} km)\n",
+                job.source_city, job.destination_city, job.cargo, job.distance_km
+            ));
+        }
+        out
+    }
+
+    /// Driven route as a GeoJSON LineString in game world coordinates
+    fn route_geojson(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "Feature",
+            "properties": {
+                "convoyId": self.id,
+                "name": self.title(),
+            },
+            "geometry": {
+                "type": "LineString",
+                "coordinates": self.route.iter().map(|p| [p.x, p.z]).collect::<Vec<_>>(),
+            },
+        })
+    }
+}
+
+/// Combined convoy report for sharing
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvoyReport {
+    pub session: ConvoySession,
+    /// Markdown summary for pasting into Discord
+    pub summary: String,
+    /// Route as GeoJSON
+    pub route: serde_json::Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn report_combines_jobs_and_thinned_route() {
+        let t = Utc.with_ymd_and_hms(2025, 3, 1, 19, 0, 0).unwrap();
+        let mut convoy = ConvoySession::start("evt-42".into(), Some("Friday Night Run".into()), t);
+
+        convoy.record_position(0.0, 0.0, t);
+        convoy.record_position(5.0, 0.0, t + Duration::seconds(3));
+        convoy.record_position(50.0, 10.0, t + Duration::seconds(12));
+        convoy.record_job(&ActiveJob {
+            cargo: "Machinery".into(),
+            source_city: "Berlin".into(),
+            destination_city: "Hamburg".into(),
+            source_company: None,
+            destination_company: None,
+            distance_km: 290,
+            distance_remaining: 0,
+            revenue: 12_000,
+            started_at: t,
+            pickup_damage: Default::default(),
+            delivery_damage: None,
+            max_damage: Default::default(),
+            market: None,
+            trailer_ownership: None,
+            truck: None,
+            trailer: None,
+            legs: Vec::new(),
+            fuel: Default::default(),
+            odometer: Default::default(),
+            integrity: Default::default(),
+            snapshots: Default::default(),
+        }, t + Duration::minutes(80));
+
+        let report = convoy.finish(t + Duration::minutes(95));
+        assert_eq!(report.route["geometry"]["coordinates"].as_array().unwrap().len(), 2);
+        assert!(report.summary.contains("**Convoy: Friday Night Run**"));
+        assert!(report.summary.contains("(1h 35m)"));
+        assert!(report.summary.contains("- Berlin → Hamburg (Machinery, 290 km)"));
+        assert!(report.session.attendance_report().is_none());
+    }
+
+    #[test]
+    fn attendance_counts_online_driving_inside_the_window() {
+        let t = Utc.with_ymd_and_hms(2025, 3, 1, 19, 0, 0).unwrap();
+        let event = ConvoyEvent {
+            id: "evt-7".into(),
+            name: "Sunday Convoy".into(),
+            starts_at: t,
+            ends_at: t + Duration::hours(1),
+        };
+        let mut convoy = ConvoySession::for_event(event, t - Duration::minutes(10));
+        let online = TelemetryState {
+            connected: true,
+            server: Some("TruckersMP".into()),
+            speed: 72.0,
+            ..Default::default()
+        };
+
+        // Before the window nothing counts
+        convoy.record_attendance(&online, t - Duration::minutes(1));
+        assert_eq!(convoy.attendance.first_seen, None);
+
+        for secs in (0..=600).step_by(10) {
+            convoy.record_attendance(&online, t + Duration::seconds(secs));
+        }
+        // Singleplayer and a long gap don't count
+        convoy.record_attendance(&TelemetryState { server: None, ..online.clone() }, t + Duration::seconds(610));
+        convoy.record_attendance(&online, t + Duration::seconds(620));
+        convoy.record_attendance(&online, t + Duration::seconds(900));
+
+        let report = convoy.attendance_report().unwrap();
+        assert_eq!(report.online_minutes, 10);
+        assert_eq!(report.distance_km, 12.0);
+        assert_eq!(report.server.as_deref(), Some("TruckersMP"));
+        assert_eq!(report.first_seen, Some(t));
+        assert!(!convoy.event_ended(t + Duration::minutes(60)));
+        assert!(convoy.event_ended(t + Duration::minutes(61)));
+    }
+
+    #[test]
+    fn attendance_adds_up_at_poll_cadence() {
+        let t = Utc.with_ymd_and_hms(2025, 3, 1, 19, 0, 0).unwrap();
+        let event = ConvoyEvent {
+            id: "evt-8".into(),
+            name: "Poll Cadence".into(),
+            starts_at: t,
+            ends_at: t + Duration::hours(1),
+        };
+        let mut convoy = ConvoySession::for_event(event, t);
+        let online = TelemetryState {
+            connected: true,
+            server: Some("Simulation 1".into()),
+            speed: 72.0,
+            ..Default::default()
+        };
+
+        // Ten minutes of 100 ms polls
+        for i in 0..=6000 {
+            convoy.record_attendance(&online, t + Duration::milliseconds(i * 100));
+        }
+        let report = convoy.attendance_report().unwrap();
+        assert_eq!(report.online_minutes, 10);
+        assert!((report.distance_km - 12.0).abs() < 1e-6);
+    }
+}
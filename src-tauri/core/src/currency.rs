@@ -0,0 +1,87 @@
+//! Currency Module
+//!
+//! Currencies the games pay in and a bundled conversion table, so revenue
+//! from ETS2 (euros) and ATS (dollars) can be totalled in one display
+//! currency. Rates are fixed approximations; the games have no exchange rate.
+
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use serde::{Deserialize, Serialize};
+
+/// Value of one unit of each currency in the base currency
+const RATES_FILE: &str = include_str!("../data/currencies.json");
+
+/// Currency of an amount
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Currency {
+    #[default]
+    Eur,
+    Usd,
+}
+
+impl Currency {
+    /// ISO 4217 code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Eur => "EUR",
+            Currency::Usd => "USD",
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Currency::Eur => "€",
+            Currency::Usd => "$",
+        }
+    }
+
+    /// Currency for an ISO code, e.g. one stored in the history database
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code.to_ascii_uppercase().as_str() {
+            "EUR" => Some(Currency::Eur),
+            "USD" => Some(Currency::Usd),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Currency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RateTable {
+    rates: BTreeMap<Currency, f64>,
+}
+
+fn rates() -> &'static RateTable {
+    static RATES: OnceLock<RateTable> = OnceLock::new();
+    RATES.get_or_init(|| serde_json::from_str(RATES_FILE).expect("bundled currency rates"))
+}
+
+/// Convert `amount` between currencies
+pub fn convert(amount: f64, from: Currency, to: Currency) -> f64 {
+    if from == to {
+        return amount;
+    }
+    let rate = |currency| rates().rates.get(&currency).copied().unwrap_or(1.0);
+    amount * rate(from) / rate(to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_through_base_currency() {
+        assert_eq!(convert(1000.0, Currency::Eur, Currency::Eur), 1000.0);
+        let euros = convert(1000.0, Currency::Usd, Currency::Eur);
+        assert!((euros - 920.0).abs() < 1e-9);
+        assert!((convert(euros, Currency::Eur, Currency::Usd) - 1000.0).abs() < 1e-9);
+        assert_eq!(Currency::from_code("usd"), Some(Currency::Usd));
+        assert_eq!(serde_json::to_value(Currency::Usd).unwrap(), "USD");
+    }
+}
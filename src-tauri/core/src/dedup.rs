@@ -0,0 +1,56 @@
+//! Event Deduplication Module
+//!
+//! The SCS SDK can repeat a gameplay event across several frames. Events are
+//! keyed by type and game timestamp so fines, tolls and deliveries are only
+//! counted once.
+
+use std::collections::VecDeque;
+
+/// How many recent event keys are remembered
+const MEMORY: usize = 64;
+
+/// Identity of a gameplay event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventKey {
+    pub kind: &'static str,
+    pub game_timestamp: u64,
+}
+
+/// Drops events that were already seen recently
+#[derive(Debug, Default)]
+pub struct EventDeduplicator {
+    recent: VecDeque<EventKey>,
+}
+
+impl EventDeduplicator {
+    /// Create an empty deduplicator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` the first time a key is seen, `false` for repeats
+    pub fn admit(&mut self, key: EventKey) -> bool {
+        if self.recent.contains(&key) {
+            return false;
+        }
+        if self.recent.len() >= MEMORY {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(key);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeats_are_dropped_per_kind_and_timestamp() {
+        let mut dedup = EventDeduplicator::new();
+        assert!(dedup.admit(EventKey { kind: "fine", game_timestamp: 100 }));
+        assert!(!dedup.admit(EventKey { kind: "fine", game_timestamp: 100 }));
+        assert!(dedup.admit(EventKey { kind: "toll", game_timestamp: 100 }));
+        assert!(dedup.admit(EventKey { kind: "fine", game_timestamp: 101 }));
+    }
+}
@@ -0,0 +1,110 @@
+//! Route Distance Module
+//!
+//! Approximate road distances between well-known cities, used to sanity-check
+//! the planned distance reported for a job (economy mods, teleports), and a
+//! comparison of the planned distance with what the truck actually drove.
+//! Route tables come from the bundled game data.
+
+use serde::Serialize;
+
+use crate::games;
+use crate::telemetry::Game;
+
+/// Claimed/reference ratios outside this range are flagged
+const MIN_RATIO: f64 = 0.6;
+const MAX_RATIO: f64 = 1.6;
+/// Driven/planned ratios outside this range are flagged; ferries and trains
+/// legitimately cover part of a route without driving it
+const MIN_DRIVEN_RATIO: f64 = 0.5;
+const MAX_DRIVEN_RATIO: f64 = 2.0;
+/// Jobs shorter than this aren't compared; yard moves and rounding dominate (km)
+const MIN_DRIVEN_CHECK_KM: u32 = 20;
+
+/// Outcome of comparing a job's distance with the reference table
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DistanceCheck {
+    pub reference_km: u32,
+    pub claimed_km: u32,
+    pub ratio: f64,
+    pub flagged: bool,
+}
+
+/// Outcome of comparing a job's planned distance with the distance driven
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DrivenCheck {
+    pub planned_km: u32,
+    /// Route progress reported by the navigation
+    pub navigation_km: u32,
+    /// Distance on the truck's odometer, if it was read
+    pub odometer_km: Option<u32>,
+    /// Odometer distance over planned distance
+    pub ratio: Option<f64>,
+    pub flagged: bool,
+}
+
+/// Compare the planned distance with the odometer; a teleport advances the
+/// route without the odometer, a long detour the odometer without the route
+pub fn check_driven(planned_km: u32, navigation_km: u32, odometer_km: Option<u32>) -> DrivenCheck {
+    let ratio = odometer_km
+        .filter(|_| planned_km >= MIN_DRIVEN_CHECK_KM)
+        .map(|odometer_km| f64::from(odometer_km) / f64::from(planned_km));
+    DrivenCheck {
+        planned_km,
+        navigation_km,
+        odometer_km,
+        ratio,
+        flagged: ratio.is_some_and(|ratio| !(MIN_DRIVEN_RATIO..=MAX_DRIVEN_RATIO).contains(&ratio)),
+    }
+}
+
+/// Reference distance between two canonical city IDs, in either direction
+pub fn reference_km(game: Game, from: &str, to: &str) -> Option<u32> {
+    games::data(game).routes.iter()
+        .find(|(a, b, _)| (a == from && b == to) || (a == to && b == from))
+        .map(|(_, _, km)| *km)
+}
+
+/// Compare a claimed route distance against the reference table, when the
+/// game's data supports it
+pub fn check(game: Game, from: &str, to: &str, claimed_km: u32) -> Option<DistanceCheck> {
+    if !games::data(game).features.distance_check {
+        return None;
+    }
+    let reference_km = reference_km(game, from, to)?;
+    let ratio = f64::from(claimed_km) / f64::from(reference_km);
+    Some(DistanceCheck {
+        reference_km,
+        claimed_km,
+        ratio,
+        flagged: !(MIN_RATIO..=MAX_RATIO).contains(&ratio),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_wildly_off_distances() {
+        assert_eq!(reference_km(Game::Ets2, "hamburg", "berlin"), Some(290));
+        assert!(!check(Game::Ets2, "berlin", "hamburg", 310).unwrap().flagged);
+        assert!(check(Game::Ets2, "berlin", "hamburg", 1200).unwrap().flagged);
+        assert!(check(Game::Ets2, "berlin", "lisboa", 2800).is_none());
+        assert!(check(Game::Ats, "berlin", "hamburg", 1200).is_none());
+    }
+
+    #[test]
+    fn flags_driven_distance_far_from_planned() {
+        assert!(!check_driven(290, 290, Some(301)).flagged);
+        // Teleported most of the way
+        let teleported = check_driven(290, 290, Some(40));
+        assert!(teleported.flagged);
+        assert_eq!(teleported.ratio, Some(40.0 / 290.0));
+        assert!(check_driven(290, 290, Some(700)).flagged);
+        // Nothing to compare against
+        assert!(!check_driven(290, 290, None).flagged);
+        assert!(!check_driven(5, 5, Some(30)).flagged);
+    }
+}
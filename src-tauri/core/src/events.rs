@@ -0,0 +1,217 @@
+//! Event Emission Module
+//!
+//! Rate control for high-frequency events sent to the webview, and typed
+//! events for discrete changes the UI reacts to without diffing state.
+
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tracing::debug;
+
+use crate::telemetry::{ActiveJob, Game, GameplayEventKind, TelemetryEvent, TelemetryState};
+
+/// Minimum spacing between frames while the window is visible
+const ACTIVE_INTERVAL: Duration = Duration::from_millis(100);
+/// Minimum spacing between frames while the window is hidden or minimized
+const BACKGROUND_INTERVAL: Duration = Duration::from_secs(2);
+/// Upper bound for the backoff applied after failed emits
+const MAX_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Coalesces telemetry frames so a slow or hidden webview never builds a backlog.
+///
+/// Only the latest frame is ever considered; intermediate frames that arrive
+/// while a previous emit is still "cooling down" are simply dropped.
+pub struct FrameThrottle {
+    last_emit: Option<Instant>,
+    last_frame: Option<TelemetryState>,
+    consecutive_failures: u32,
+    dropped: u64,
+}
+
+impl FrameThrottle {
+    /// Create a new throttle
+    pub fn new() -> Self {
+        Self {
+            last_emit: None,
+            last_frame: None,
+            consecutive_failures: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Decide whether `frame` should be sent now
+    pub fn should_emit(&mut self, frame: &TelemetryState, window_active: bool, now: Instant) -> bool {
+        // Identical frames carry no new information
+        if self.last_frame.as_ref() == Some(frame) {
+            return false;
+        }
+
+        let Some(last_emit) = self.last_emit else {
+            return true;
+        };
+
+        if now.duration_since(last_emit) >= self.min_interval(window_active) {
+            true
+        } else {
+            self.dropped += 1;
+            false
+        }
+    }
+
+    /// Record a successful emit of `frame`
+    pub fn record_success(&mut self, frame: TelemetryState, now: Instant) {
+        if self.consecutive_failures > 0 {
+            debug!(
+                "Webview caught up after {} failed emits ({} frames dropped)",
+                self.consecutive_failures, self.dropped
+            );
+        }
+        self.last_emit = Some(now);
+        self.last_frame = Some(frame);
+        self.consecutive_failures = 0;
+        self.dropped = 0;
+    }
+
+    /// Record a failed emit; subsequent frames are spaced out further
+    pub fn record_failure(&mut self, now: Instant) {
+        self.last_emit = Some(now);
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+    }
+
+    fn min_interval(&self, window_active: bool) -> Duration {
+        let base = if window_active { ACTIVE_INTERVAL } else { BACKGROUND_INTERVAL };
+        if self.consecutive_failures == 0 {
+            return base;
+        }
+        let backoff = base.saturating_mul(1 << self.consecutive_failures.min(6));
+        backoff.min(MAX_BACKOFF)
+    }
+}
+
+impl Default for FrameThrottle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Discrete event sent to the webview under its own name
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum FrontendEvent {
+    GameConnected { game: Game },
+    GameDisconnected {},
+    JobStarted(ActiveJob),
+    JobResumed(ActiveJob),
+    JobCompleted(ActiveJob),
+    JobCancelled { job: ActiveJob, penalty: Option<i64> },
+    FineReceived { offence: String, amount: i64 },
+}
+
+impl FrontendEvent {
+    /// Typed counterpart of a telemetry event, if the UI has one
+    pub fn from_telemetry(event: &TelemetryEvent) -> Option<Self> {
+        Some(match event {
+            TelemetryEvent::Connected(game) => FrontendEvent::GameConnected { game: *game },
+            TelemetryEvent::Disconnected => FrontendEvent::GameDisconnected {},
+            TelemetryEvent::JobStarted(job) => FrontendEvent::JobStarted(job.clone()),
+            TelemetryEvent::JobResumed(job) => FrontendEvent::JobResumed(job.clone()),
+            TelemetryEvent::JobCompleted(job) => FrontendEvent::JobCompleted(job.clone()),
+            TelemetryEvent::JobCancelled { job, penalty } => FrontendEvent::JobCancelled {
+                job: job.clone(),
+                penalty: *penalty,
+            },
+            TelemetryEvent::Gameplay(event) => match &event.kind {
+                GameplayEventKind::Fine { offence, amount } => FrontendEvent::FineReceived {
+                    offence: offence.clone(),
+                    amount: *amount,
+                },
+                _ => return None,
+            },
+            TelemetryEvent::JobPhaseChanged { .. } => return None,
+        })
+    }
+
+    /// Tauri event name
+    pub fn name(&self) -> &'static str {
+        match self {
+            FrontendEvent::GameConnected { .. } => "game_connected",
+            FrontendEvent::GameDisconnected {} => "game_disconnected",
+            FrontendEvent::JobStarted(_) => "job_started",
+            FrontendEvent::JobResumed(_) => "job_resumed",
+            FrontendEvent::JobCompleted(_) => "job_completed",
+            FrontendEvent::JobCancelled { .. } => "job_cancelled",
+            FrontendEvent::FineReceived { .. } => "fine_received",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::GameplayEvent;
+
+    fn frame(speed: f32) -> TelemetryState {
+        TelemetryState { speed, ..TelemetryState::default() }
+    }
+
+    #[test]
+    fn identical_frames_are_coalesced() {
+        let start = Instant::now();
+        let mut throttle = FrameThrottle::new();
+        assert!(throttle.should_emit(&frame(10.0), true, start));
+        throttle.record_success(frame(10.0), start);
+
+        let later = start + Duration::from_secs(1);
+        assert!(!throttle.should_emit(&frame(10.0), true, later));
+        assert!(throttle.should_emit(&frame(11.0), true, later));
+    }
+
+    #[test]
+    fn hidden_window_uses_background_interval() {
+        let start = Instant::now();
+        let mut throttle = FrameThrottle::new();
+        throttle.record_success(frame(10.0), start);
+
+        assert!(!throttle.should_emit(&frame(11.0), false, start + Duration::from_secs(1)));
+        assert!(throttle.should_emit(&frame(11.0), false, start + BACKGROUND_INTERVAL));
+    }
+
+    #[test]
+    fn failures_back_off_up_to_cap_and_reset_on_success() {
+        let start = Instant::now();
+        let mut throttle = FrameThrottle::new();
+
+        throttle.record_failure(start);
+        assert!(!throttle.should_emit(&frame(1.0), true, start + ACTIVE_INTERVAL));
+        assert!(throttle.should_emit(&frame(1.0), true, start + ACTIVE_INTERVAL * 2));
+
+        for _ in 0..10 {
+            throttle.record_failure(start);
+        }
+        assert!(!throttle.should_emit(&frame(1.0), true, start + MAX_BACKOFF - Duration::from_millis(1)));
+        assert!(throttle.should_emit(&frame(1.0), true, start + MAX_BACKOFF));
+
+        throttle.record_success(frame(1.0), start);
+        assert!(throttle.should_emit(&frame(2.0), true, start + ACTIVE_INTERVAL));
+    }
+
+    #[test]
+    fn typed_events_for_discrete_changes() {
+        let connected = FrontendEvent::from_telemetry(&TelemetryEvent::Connected(Game::Ats)).unwrap();
+        assert_eq!(connected.name(), "game_connected");
+        assert_eq!(serde_json::to_value(&connected).unwrap(), serde_json::json!({ "game": "ats" }));
+
+        let disconnected = FrontendEvent::from_telemetry(&TelemetryEvent::Disconnected).unwrap();
+        assert_eq!(serde_json::to_value(&disconnected).unwrap(), serde_json::json!({}));
+
+        let fine = TelemetryEvent::Gameplay(GameplayEvent {
+            game_timestamp: 1,
+            kind: GameplayEventKind::Fine { offence: "red_signal".into(), amount: 500 },
+        });
+        let fine = FrontendEvent::from_telemetry(&fine).unwrap();
+        assert_eq!(fine.name(), "fine_received");
+        assert_eq!(serde_json::to_value(&fine).unwrap(), serde_json::json!({ "offence": "red_signal", "amount": 500 }));
+
+        let toll = TelemetryEvent::Gameplay(GameplayEvent { game_timestamp: 2, kind: GameplayEventKind::Toll { amount: 20 } });
+        assert!(FrontendEvent::from_telemetry(&toll).is_none());
+    }
+}
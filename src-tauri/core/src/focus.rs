@@ -0,0 +1,194 @@
+//! Focus Module
+//!
+//! Watches whether the game window is in the foreground so the overlay can
+//! follow it, toasts stay out of exclusive fullscreen, and AFK periods are
+//! marked from the time the driver actually tabbed away.
+
+use std::time::{Duration as StdDuration, Instant};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::settings::FocusSettings;
+#[cfg(windows)]
+use crate::telemetry::Game;
+
+/// How often the foreground window is checked
+const POLL_INTERVAL: StdDuration = StdDuration::from_secs(1);
+
+/// One reading of the foreground window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FocusSample {
+    /// The game owns the foreground window
+    pub focused: bool,
+    /// A fullscreen exclusive (D3D) application is running
+    pub fullscreen: bool,
+}
+
+/// Current focus state, shared with the overlay and notifications
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameFocus {
+    pub focused: bool,
+    pub fullscreen: bool,
+    /// Away from the game longer than the AFK threshold
+    pub afk: bool,
+}
+
+impl GameFocus {
+    /// Whether a toast would pop over (or minimize) the game
+    pub fn in_fullscreen_game(&self) -> bool {
+        self.focused && self.fullscreen
+    }
+}
+
+/// A stretch of time spent away from the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AfkPeriod {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Turns focus samples into state changes and AFK periods
+#[derive(Debug, Default)]
+pub struct FocusTracker {
+    current: Option<GameFocus>,
+    unfocused_since: Option<DateTime<Utc>>,
+    periods: Vec<AfkPeriod>,
+    last_poll: Option<Instant>,
+}
+
+impl FocusTracker {
+    /// Create a tracker with no game window seen yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the foreground window should be sampled again
+    pub fn poll_due(&mut self, now: Instant) -> bool {
+        let due = self.last_poll.map_or(true, |last| now.duration_since(last) >= POLL_INTERVAL);
+        if due {
+            self.last_poll = Some(now);
+        }
+        due
+    }
+
+    /// Feed a sample; returns the new state when it changed
+    pub fn observe(&mut self, sample: FocusSample, settings: &FocusSettings, now: DateTime<Utc>) -> Option<GameFocus> {
+        if sample.focused {
+            self.close_period(now);
+            self.unfocused_since = None;
+        } else if self.unfocused_since.is_none() {
+            self.unfocused_since = Some(now);
+        }
+
+        let afk_after = Duration::seconds(settings.afk_after_secs as i64);
+        let focus = GameFocus {
+            focused: sample.focused,
+            fullscreen: sample.fullscreen,
+            afk: self.unfocused_since.is_some_and(|since| now - since >= afk_after),
+        };
+        self.update(Some(focus)).then_some(focus)
+    }
+
+    /// The game went away; closes any open AFK period. Returns true if the
+    /// state changed
+    pub fn reset(&mut self, now: DateTime<Utc>) -> bool {
+        self.close_period(now);
+        self.unfocused_since = None;
+        self.update(None)
+    }
+
+    /// Current state, or `None` while no game window is known
+    pub fn current(&self) -> Option<GameFocus> {
+        self.current
+    }
+
+    /// AFK periods since the last call; an ongoing one is split at `now`
+    pub fn take_afk_periods(&mut self, now: DateTime<Utc>) -> Vec<AfkPeriod> {
+        if self.current.is_some_and(|focus| focus.afk) {
+            self.close_period(now);
+            self.unfocused_since = Some(now);
+        }
+        std::mem::take(&mut self.periods)
+    }
+
+    fn close_period(&mut self, now: DateTime<Utc>) {
+        if let (Some(start), Some(true)) = (self.unfocused_since, self.current.map(|focus| focus.afk)) {
+            self.periods.push(AfkPeriod { start, end: now });
+        }
+    }
+
+    fn update(&mut self, focus: Option<GameFocus>) -> bool {
+        let changed = focus != self.current;
+        self.current = focus;
+        changed
+    }
+}
+
+/// Sample the foreground window; `None` where the platform can't tell
+pub fn sample() -> Option<FocusSample> {
+    #[cfg(windows)]
+    unsafe {
+        use windows::core::PWSTR;
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::System::Threading::{
+            OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+        };
+        use windows::Win32::UI::Shell::{SHQueryUserNotificationState, QUNS_RUNNING_D3D_FULL_SCREEN};
+        use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+
+        let fullscreen = SHQueryUserNotificationState().is_ok_and(|state| state == QUNS_RUNNING_D3D_FULL_SCREEN);
+        let window = GetForegroundWindow();
+        if window.is_invalid() {
+            return Some(FocusSample { focused: false, fullscreen });
+        }
+
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(window, Some(&mut pid));
+        // Elevated processes can't be opened, and they aren't the game either
+        let Ok(process) = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) else {
+            return Some(FocusSample { focused: false, fullscreen });
+        };
+        let mut buffer = [0u16; 260];
+        let mut len = buffer.len() as u32;
+        let named = QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, PWSTR(buffer.as_mut_ptr()), &mut len).is_ok();
+        let _ = CloseHandle(process);
+
+        let path = String::from_utf16_lossy(&buffer[..len as usize]);
+        let name = path.rsplit(['\\', '/']).next().unwrap_or_default();
+        let focused = named && Game::from_process_name(name).is_some();
+        Some(FocusSample { focused, fullscreen })
+    }
+
+    #[cfg(not(windows))]
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn afk_period_starts_when_focus_is_lost() {
+        let t = Utc.with_ymd_and_hms(2025, 7, 1, 20, 0, 0).unwrap();
+        let settings = FocusSettings { afk_after_secs: 120, ..FocusSettings::default() };
+        let focused = FocusSample { focused: true, fullscreen: true };
+        let away = FocusSample { focused: false, fullscreen: false };
+        let mut tracker = FocusTracker::new();
+
+        assert!(tracker.observe(focused, &settings, t).unwrap().in_fullscreen_game());
+        assert_eq!(tracker.observe(away, &settings, t + Duration::seconds(10)), Some(GameFocus::default()));
+        assert_eq!(tracker.observe(away, &settings, t + Duration::seconds(60)), None);
+        assert!(tracker.observe(away, &settings, t + Duration::seconds(130)).unwrap().afk);
+        tracker.observe(focused, &settings, t + Duration::seconds(300));
+
+        // Short alt-tabs don't count
+        tracker.observe(away, &settings, t + Duration::seconds(400));
+        tracker.observe(focused, &settings, t + Duration::seconds(430));
+
+        let periods = tracker.take_afk_periods(t + Duration::seconds(500));
+        assert_eq!(periods, vec![AfkPeriod { start: t + Duration::seconds(10), end: t + Duration::seconds(300) }]);
+    }
+}
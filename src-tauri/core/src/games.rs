@@ -0,0 +1,140 @@
+//! Game Data Module
+//!
+//! Per-game constants (plugin IDs, process names, unit defaults, city and
+//! route tables, feature toggles) bundled from `data/games`, so supporting a
+//! new game or map patch is a data update rather than a code change.
+
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use serde::{Deserialize, Serialize};
+
+use crate::currency::Currency;
+use crate::settings::Units;
+use crate::telemetry::Game;
+
+/// One data file per supported game
+const GAME_FILES: [&str; 2] = [
+    include_str!("../data/games/ets2.json"),
+    include_str!("../data/games/ats.json"),
+];
+/// Cargo names are shared between the games
+const CARGO_FILE: &str = include_str!("../data/cargo.json");
+
+/// Localized names by canonical ID
+pub type NameTable = BTreeMap<String, Vec<String>>;
+
+/// Checks that depend on the game's data being good enough
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"), default)]
+pub struct GameFeatures {
+    /// Compare job distances against the route table
+    pub distance_check: bool,
+}
+
+/// Multiplayer mod a game can run under
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct MultiplayerClient {
+    pub name: String,
+    /// Executable names of the client, lowercase without extension
+    #[serde(default)]
+    pub process_names: Vec<String>,
+    /// Lowercase DLL names the client injects into the game
+    #[serde(default)]
+    pub modules: Vec<String>,
+}
+
+/// Everything game-specific the app knows about one game
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct GameData {
+    pub game: Game,
+    /// Game ID published by the telemetry plugin
+    pub plugin_id: u32,
+    /// Executable names without extension
+    pub process_names: Vec<String>,
+    /// Units the game itself displays
+    pub units: Units,
+    /// Currency the game pays in
+    pub currency: Currency,
+    pub features: GameFeatures,
+    #[serde(default)]
+    pub multiplayer: Vec<MultiplayerClient>,
+    #[serde(skip_serializing)]
+    pub cities: NameTable,
+    /// Approximate road distances (km) between canonical city IDs
+    #[serde(skip_serializing)]
+    pub routes: Vec<(String, String, u32)>,
+}
+
+struct Bundled {
+    games: Vec<GameData>,
+    cargo: NameTable,
+}
+
+fn bundled() -> &'static Bundled {
+    static DATA: OnceLock<Bundled> = OnceLock::new();
+    DATA.get_or_init(|| Bundled {
+        games: GAME_FILES.iter()
+            .map(|json| serde_json::from_str(json).expect("bundled game data"))
+            .collect(),
+        cargo: serde_json::from_str(CARGO_FILE).expect("bundled cargo names"),
+    })
+}
+
+/// Data for every supported game
+pub fn all() -> &'static [GameData] {
+    &bundled().games
+}
+
+/// Data for `game`
+pub fn data(game: Game) -> &'static GameData {
+    all().iter()
+        .find(|data| data.game == game)
+        .expect("every game has a data file")
+}
+
+/// Canonical cargo names
+pub fn cargo() -> &'static NameTable {
+    &bundled().cargo
+}
+
+/// Game for the ID the telemetry plugin publishes
+pub fn from_plugin_id(id: u32) -> Option<Game> {
+    all().iter().find(|data| data.plugin_id == id).map(|data| data.game)
+}
+
+/// Game for an executable name, with or without `.exe`
+pub fn from_process_name(name: &str) -> Option<Game> {
+    let name = name.to_ascii_lowercase();
+    let name = name.trim_end_matches(".exe");
+    all().iter()
+        .find(|data| data.process_names.iter().any(|process| process == name))
+        .map(|data| data.game)
+}
+
+/// Game for its short ID (`ets2`, `ats`)
+pub fn from_id(id: &str) -> Option<Game> {
+    all().iter().find(|data| data.game.to_string() == id).map(|data| data.game)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_data_is_consistent() {
+        for game in [Game::Ets2, Game::Ats] {
+            let data = data(game);
+            assert_eq!(from_plugin_id(data.plugin_id), Some(game));
+            assert_eq!(from_id(&game.to_string()), Some(game));
+            for (from, to, _) in &data.routes {
+                assert!(data.cities.contains_key(from) && data.cities.contains_key(to), "{from} -> {to}");
+            }
+        }
+        assert_eq!(data(Game::Ats).units, Units::Imperial);
+        assert_eq!(data(Game::Ats).currency, Currency::Usd);
+        assert_eq!(from_plugin_id(0), None);
+        assert!(cargo().contains_key("machinery"));
+    }
+}
@@ -0,0 +1,580 @@
+//! Pipeline Harness
+//!
+//! Drives the job pipeline end to end in tests: a scripted game feeds frames
+//! to the telemetry reader, delivered jobs go through the real API client to
+//! a local mock backend, and outcomes land in history, the retry queue and
+//! checkpoints the way the telemetry loop handles them.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use chrono::Utc;
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::checkpoint::{self, Checkpoint};
+use crate::history::{HistoryStatus, JobHistory};
+use crate::job_queue::JobQueue;
+use crate::scs::{JobFrame, ScsFrame, SpecialFlags};
+use crate::storage::SecureStorage;
+use crate::sync::{ApiClient, ApiError, JobResponse, JobSubmission, RetryPolicy};
+use crate::telemetry::{FuelLevel, Game, Position, TelemetryEvent, TelemetryReader, TruckInfo, TruckWear};
+
+/// Canned answer from the mock backend
+#[derive(Debug, Clone)]
+pub(crate) enum Reply {
+    Json(u16, Value),
+    /// Read the request, then close the connection without answering
+    Drop,
+}
+
+/// Request seen by the mock backend
+#[derive(Debug, Clone)]
+pub(crate) struct Recorded {
+    pub path: String,
+    pub authorization: Option<String>,
+    pub idempotency_key: Option<String>,
+    pub body: Value,
+}
+
+type Replies = Arc<Mutex<HashMap<String, VecDeque<Reply>>>>;
+type Requests = Arc<Mutex<Vec<Recorded>>>;
+
+/// Minimal HTTP server answering API paths from scripted replies
+pub(crate) struct MockBackend {
+    pub base_url: String,
+    replies: Replies,
+    requests: Requests,
+}
+
+impl MockBackend {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind mock backend");
+        let base_url = format!("http://{}", listener.local_addr().expect("mock backend address"));
+        let replies = Replies::default();
+        let requests = Requests::default();
+
+        let (script, log) = (replies.clone(), requests.clone());
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                tokio::spawn(serve(socket, script.clone(), log.clone()));
+            }
+        });
+        Self { base_url, replies, requests }
+    }
+
+    /// Queue the answer to the next request for `path`
+    pub fn reply(&self, path: &str, reply: Reply) {
+        self.replies.lock().unwrap().entry(path.to_string()).or_default().push_back(reply);
+    }
+
+    /// Requests made to `path`, oldest first
+    pub fn requests(&self, path: &str) -> Vec<Recorded> {
+        self.requests.lock().unwrap().iter().filter(|r| r.path == path).cloned().collect()
+    }
+}
+
+/// Unscripted paths: current schemas for capability discovery, 404 otherwise
+fn default_reply(path: &str) -> Reply {
+    match path {
+        "/api/telemetry/capabilities" => Reply::Json(200, json!({ "job_schemas": [1, 2] })),
+        _ => Reply::Json(404, json!({ "error": "Not mocked" })),
+    }
+}
+
+async fn serve(mut socket: TcpStream, replies: Replies, requests: Requests) {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end + 4;
+        }
+        match socket.read(&mut chunk).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let path = head.split_whitespace().nth(1).unwrap_or_default().to_string();
+    let header = |name: &str| {
+        head.lines().skip(1).find_map(|line| {
+            line.split_once(':')
+                .filter(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.trim().to_string())
+        })
+    };
+    let length: usize = header("content-length").and_then(|v| v.parse().ok()).unwrap_or(0);
+    while buf.len() < header_end + length {
+        match socket.read(&mut chunk).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+        }
+    }
+
+    requests.lock().unwrap().push(Recorded {
+        path: path.clone(),
+        authorization: header("authorization"),
+        idempotency_key: header("idempotency-key"),
+        body: serde_json::from_slice(&buf[header_end..header_end + length]).unwrap_or(Value::Null),
+    });
+    let reply = replies.lock().unwrap()
+        .get_mut(&path)
+        .and_then(VecDeque::pop_front)
+        .unwrap_or_else(|| default_reply(&path));
+
+    if let Reply::Json(status, body) = reply {
+        let body = body.to_string();
+        let response = format!(
+            "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        let _ = socket.write_all(response.as_bytes()).await;
+    }
+}
+
+/// Game session producing frames for one job at a time
+pub(crate) struct ScriptedGame {
+    frame: ScsFrame,
+}
+
+impl ScriptedGame {
+    pub fn new() -> Self {
+        Self {
+            frame: ScsFrame {
+                revision: 12,
+                sdk_active: true,
+                paused: false,
+                render_time: 0,
+                game: Some(Game::Ets2),
+                game_time: 0,
+                rest_stop_minutes: 600,
+                speed_kmh: 0.0,
+                speed_limit_kmh: Some(90.0),
+                engine_rpm: 0.0,
+                fuel: FuelLevel { liters: 400.0, capacity: 600.0, avg_consumption: 0.3, range_km: 1300.0 },
+                wear: Some(TruckWear::default()),
+                cargo_damage: 0.0,
+                odometer_km: 120_000.0,
+                position: Position { x: 0.0, y: 0.0, z: 0.0, heading: 0.0 },
+                truck: Some(TruckInfo {
+                    brand_id: "volvo".into(),
+                    brand: "Volvo".into(),
+                    model_id: "vehicle.volvo.fh16_2012".into(),
+                    model: "FH16 2012".into(),
+                    license_plate: "B VT 1234".into(),
+                    license_plate_country: "germany".into(),
+                }),
+                trailer: None,
+                job: None,
+                flags: SpecialFlags::default(),
+                amounts: Default::default(),
+                unknown: Vec::new(),
+            },
+        }
+    }
+
+    /// Accept Berlin -> Hamburg with `remaining_km` still to go
+    pub fn take_job(&mut self, remaining_km: u32) -> ScsFrame {
+        self.frame.job = Some(JobFrame {
+            cargo: "Machinery".into(),
+            source_city: "Berlin".into(),
+            source_company: "Posped".into(),
+            destination_city: "Hamburg".into(),
+            destination_company: "Tradeaux".into(),
+            planned_distance_km: 290,
+            distance_remaining_km: remaining_km,
+            income: 12_000,
+            market: "freight_market".into(),
+        });
+        self.tick()
+    }
+
+    /// Drive in 10 km steps until `remaining_km` is left
+    pub fn drive_to(&mut self, remaining_km: u32) -> Vec<ScsFrame> {
+        let mut frames = Vec::new();
+        self.frame.speed_kmh = 80.0;
+        while let Some(job) = self.frame.job.as_mut().filter(|job| job.distance_remaining_km > remaining_km) {
+            let step = (job.distance_remaining_km - remaining_km).min(10);
+            job.distance_remaining_km -= step;
+            self.frame.fuel.liters -= step as f32 * 0.3;
+            self.frame.position.x += f64::from(step) * 1000.0;
+            self.frame.odometer_km += step as f32;
+            // The time it takes to drive the step at 80 km/h
+            self.frame.render_time += u64::from(step) * 45_000_000;
+            frames.push(self.tick());
+        }
+        frames
+    }
+
+    /// Park at the destination and hand over the cargo
+    pub fn deliver(&mut self) -> ScsFrame {
+        let revenue = self.frame.job.take().map_or(0, |job| job.income as i64);
+        self.frame.speed_kmh = 0.0;
+        self.frame.flags.job_delivered = true;
+        self.frame.amounts.delivered_revenue = revenue;
+        self.tick()
+    }
+
+    fn tick(&mut self) -> ScsFrame {
+        self.frame.render_time += 16_000;
+        self.frame.game_time += 1;
+        self.frame.clone()
+    }
+}
+
+/// Scratch storage directory removed with the pipeline
+struct ScratchDir(PathBuf);
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// The telemetry loop's job handling, without Tauri
+pub(crate) struct Pipeline {
+    pub reader: TelemetryReader,
+    pub api: ApiClient,
+    pub history: JobHistory,
+    pub storage: SecureStorage,
+    pub token: Option<String>,
+    /// Submissions waiting to be retried
+    pub queue: Vec<JobSubmission>,
+    scratch: Arc<ScratchDir>,
+}
+
+/// Retries as in the app, without the waits
+const TEST_RETRIES: RetryPolicy = RetryPolicy {
+    max_attempts: 3,
+    base_delay: std::time::Duration::from_millis(1),
+    max_delay: std::time::Duration::from_millis(4),
+};
+
+fn api_client(backend: &MockBackend) -> ApiClient {
+    let api = ApiClient::new(&backend.base_url);
+    api.set_retry_policy(TEST_RETRIES);
+    api
+}
+
+impl Pipeline {
+    pub fn new(backend: &MockBackend) -> Self {
+        let dir = std::env::temp_dir().join(format!("vtc-harness-{}", uuid::Uuid::new_v4()));
+        Self {
+            reader: TelemetryReader::new(),
+            api: api_client(backend),
+            history: JobHistory::in_memory().expect("in-memory history"),
+            storage: SecureStorage::in_dir(dir.clone()),
+            token: Some("token-1".into()),
+            queue: Vec::new(),
+            scratch: Arc::new(ScratchDir(dir)),
+        }
+    }
+
+    /// App restart: in-memory state is lost and recovered from the checkpoint,
+    /// the history database survives
+    pub fn restart(self, backend: &MockBackend) -> Self {
+        let mut reader = TelemetryReader::new();
+        let queue = checkpoint::recover(&self.storage, &mut reader).queue
+            .jobs()
+            .iter()
+            .map(|job| job.submission.clone())
+            .collect();
+        Self {
+            reader,
+            api: api_client(backend),
+            storage: SecureStorage::in_dir(self.scratch.0.clone()),
+            queue,
+            ..self
+        }
+    }
+
+    /// Feed one frame (`None`: the game went away) and handle the events
+    pub async fn step(&mut self, frame: Option<&ScsFrame>) -> Vec<TelemetryEvent> {
+        let events = self.reader.feed(frame);
+        for event in &events {
+            if let TelemetryEvent::JobCompleted(job) = event {
+                let game = self.reader.get_state().game.unwrap_or(Game::Ets2);
+                let submission = JobSubmission::from_completed(job, game);
+                self.history.record(&submission, HistoryStatus::Pending, Utc::now()).expect("record job");
+                self.submit(submission).await;
+            }
+        }
+        self.checkpoint();
+        events
+    }
+
+    pub async fn run(&mut self, frames: &[ScsFrame]) -> Vec<TelemetryEvent> {
+        let mut events = Vec::new();
+        for frame in frames {
+            events.extend(self.step(Some(frame)).await);
+        }
+        events
+    }
+
+    /// Retry every queued submission once, batched as in the app
+    pub async fn retry_queued(&mut self) {
+        let mut queue = std::mem::take(&mut self.queue);
+        queue.retain(|submission| self.history.confirmed_job_id(submission).expect("look up job").is_none());
+        let Some(token) = self.token.clone() else {
+            self.queue = queue;
+            return;
+        };
+        let results = self.api.telemetry.submit_jobs_batch(&token, &queue).await;
+        for (submission, result) in queue.into_iter().zip(results) {
+            self.record(submission, result);
+        }
+    }
+
+    async fn submit(&mut self, submission: JobSubmission) {
+        if self.history.confirmed_job_id(&submission).expect("look up job").is_some() {
+            return;
+        }
+        let Some(token) = self.token.clone() else {
+            self.queue.push(submission);
+            return;
+        };
+        let result = self.api.telemetry.submit_job(&token, &submission).await;
+        self.record(submission, result);
+    }
+
+    fn record(&mut self, submission: JobSubmission, result: Result<JobResponse, ApiError>) {
+        let (status, job_id, error) = match &result {
+            Ok(response) => (HistoryStatus::Submitted, Some(response.job_id.clone()), None),
+            Err(e) => (HistoryStatus::Failed, None, Some(e.to_string())),
+        };
+        self.history.set_status(&submission, status, job_id.as_deref(), error.as_deref()).expect("update job");
+        if result.is_err_and(|e| !e.rejects_job()) {
+            self.queue.push(submission);
+        }
+    }
+
+    fn checkpoint(&self) {
+        let (job, job_phase) = self.reader.job_snapshot().unzip();
+        let checkpoint = Checkpoint {
+            saved_at: Utc::now(),
+            job,
+            job_phase,
+            queue: self.queue.iter()
+                .fold(JobQueue::new(), |mut queue, submission| {
+                    queue.push(None, submission.clone());
+                    queue
+                })
+                .jobs()
+                .to_vec(),
+            failed_jobs: Vec::new(),
+            conduct: Default::default(),
+        };
+        checkpoint.save(&self.storage).expect("write checkpoint");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::{HistoryEntry, HistoryFilter};
+
+    const JOB_PATH: &str = "/api/telemetry/job";
+
+    fn accepted(job_id: &str) -> Reply {
+        Reply::Json(200, json!({ "success": true, "job_id": job_id, "message": "Job recorded" }))
+    }
+
+    fn only_entry(pipeline: &Pipeline) -> HistoryEntry {
+        let page = pipeline.history.page(0, &HistoryFilter::default()).unwrap();
+        assert_eq!(page.total, 1);
+        page.entries[0].clone()
+    }
+
+    async fn deliver_job(pipeline: &mut Pipeline) -> Vec<TelemetryEvent> {
+        let mut game = ScriptedGame::new();
+        let mut events = pipeline.step(Some(&game.take_job(290))).await;
+        events.extend(pipeline.run(&game.drive_to(0)).await);
+        events.extend(pipeline.step(Some(&game.deliver())).await);
+        events
+    }
+
+    #[tokio::test]
+    async fn brief_network_drop_is_retried_in_place() {
+        let backend = MockBackend::start().await;
+        backend.reply(JOB_PATH, Reply::Drop);
+        backend.reply(JOB_PATH, Reply::Json(502, json!({ "error": "Bad gateway" })));
+        backend.reply(JOB_PATH, accepted("job-0"));
+        let mut pipeline = Pipeline::new(&backend);
+
+        deliver_job(&mut pipeline).await;
+        assert_eq!(only_entry(&pipeline).status, HistoryStatus::Submitted);
+        assert!(pipeline.queue.is_empty());
+        let sent = backend.requests(JOB_PATH);
+        assert_eq!(sent.len(), 3);
+        assert!(sent.iter().all(|request| request.idempotency_key == sent[0].idempotency_key));
+    }
+
+    #[tokio::test]
+    async fn network_drop_mid_submit_is_queued_and_retried() {
+        let backend = MockBackend::start().await;
+        for _ in 0..TEST_RETRIES.max_attempts {
+            backend.reply(JOB_PATH, Reply::Drop);
+        }
+        backend.reply(JOB_PATH, accepted("job-1"));
+        let mut pipeline = Pipeline::new(&backend);
+
+        let events = deliver_job(&mut pipeline).await;
+        assert!(events.iter().any(|e| matches!(e, TelemetryEvent::JobCompleted(_))));
+        assert_eq!(only_entry(&pipeline).status, HistoryStatus::Failed);
+        assert_eq!(pipeline.queue.len(), 1);
+        let stale = pipeline.queue.clone();
+
+        pipeline.retry_queued().await;
+        let entry = only_entry(&pipeline);
+        assert_eq!(entry.status, HistoryStatus::Submitted);
+        assert_eq!(entry.server_job_id.as_deref(), Some("job-1"));
+        assert!(pipeline.queue.is_empty());
+
+        // A copy queued before the confirmation is never sent again
+        pipeline.queue = stale;
+        pipeline.retry_queued().await;
+        assert!(pipeline.queue.is_empty());
+
+        let sent = backend.requests(JOB_PATH);
+        let (first, last) = (&sent[0], &sent[sent.len() - 1]);
+        assert_eq!(sent.len() as u32, TEST_RETRIES.max_attempts + 1);
+        assert_eq!(first.body, last.body);
+        assert!(first.idempotency_key.is_some());
+        assert_eq!(first.idempotency_key, last.idempotency_key);
+        assert_eq!(last.body["schema_version"], 2);
+        assert_eq!(last.body["revenue"], 12_000.0);
+        assert_eq!(last.body["driven_km"], 290);
+        assert_eq!(last.body["telemetry_data"]["drivenCheck"]["flagged"], false);
+        assert_eq!(last.body["telemetry_data"]["integrity"]["flagged"], false);
+        assert!(last.body["telemetry_data"]["integrity"]["signature"].is_null());
+        assert_eq!(last.body["telemetry_data"]["snapshots"]["encoding"], "gzip+base64");
+        assert_eq!(last.body["telemetry_data"]["truck"]["modelId"], "vehicle.volvo.fh16_2012");
+    }
+
+    #[tokio::test]
+    async fn token_expiry_during_job_holds_submission_until_signed_in() {
+        let backend = MockBackend::start().await;
+        backend.reply(JOB_PATH, Reply::Json(401, json!({ "error": "Token expired" })));
+        backend.reply(JOB_PATH, accepted("job-2"));
+        let mut pipeline = Pipeline::new(&backend);
+
+        deliver_job(&mut pipeline).await;
+        let entry = only_entry(&pipeline);
+        assert_eq!(entry.status, HistoryStatus::Failed);
+        assert!(entry.error.unwrap().contains("Token expired"));
+
+        // Signed out: nothing is sent, the job stays queued
+        pipeline.token = None;
+        pipeline.retry_queued().await;
+        assert_eq!(backend.requests(JOB_PATH).len(), 1);
+        assert_eq!(pipeline.queue.len(), 1);
+
+        pipeline.token = Some("token-2".into());
+        pipeline.retry_queued().await;
+        assert_eq!(only_entry(&pipeline).status, HistoryStatus::Submitted);
+        assert_eq!(backend.requests(JOB_PATH)[1].authorization.as_deref(), Some("Bearer token-2"));
+    }
+
+    #[tokio::test]
+    async fn rejected_token_is_published_as_revoked() {
+        let backend = MockBackend::start().await;
+        backend.reply(JOB_PATH, Reply::Json(401, json!({ "error": "Device revoked" })));
+        let mut pipeline = Pipeline::new(&backend);
+        let mut revocations = pipeline.api.revocations();
+
+        deliver_job(&mut pipeline).await;
+        assert!(revocations.has_changed().unwrap());
+        assert_eq!(revocations.borrow_and_update().as_deref(), Some("token-1"));
+
+        // Unauthenticated requests can't revoke anything
+        backend.reply("/api/auth/device/refresh", Reply::Json(401, json!({ "error": "Invalid refresh token" })));
+        let refused = pipeline.api.auth.refresh_session("refresh-1").await;
+        assert!(matches!(refused, Err(ApiError::Unauthorized(_))));
+        assert!(!revocations.has_changed().unwrap());
+    }
+
+    #[tokio::test]
+    async fn game_crash_at_90_percent_resumes_after_restart() {
+        let backend = MockBackend::start().await;
+        backend.reply(JOB_PATH, accepted("job-3"));
+        let mut pipeline = Pipeline::new(&backend);
+
+        let mut game = ScriptedGame::new();
+        pipeline.step(Some(&game.take_job(290))).await;
+        pipeline.run(&game.drive_to(29)).await;
+        let events = pipeline.step(None).await;
+        assert!(events.iter().any(|e| matches!(e, TelemetryEvent::Disconnected)));
+        assert!(backend.requests(JOB_PATH).is_empty());
+
+        // App and game restarted; the save continues where it crashed
+        let mut pipeline = pipeline.restart(&backend);
+        let mut game = ScriptedGame::new();
+        let events = pipeline.step(Some(&game.take_job(29))).await;
+        assert!(events.iter().any(|e| matches!(e, TelemetryEvent::JobResumed(_))));
+        pipeline.run(&game.drive_to(0)).await;
+        pipeline.step(Some(&game.deliver())).await;
+
+        assert_eq!(only_entry(&pipeline).status, HistoryStatus::Submitted);
+        let body = &backend.requests(JOB_PATH)[0].body;
+        let legs = body["telemetry_data"]["legs"].as_array().unwrap();
+        assert_eq!(legs.len(), 2);
+        assert_eq!(legs[0]["endRemainingKm"], 29);
+        let fuel_used = body["fuel_used"].as_f64().unwrap();
+        assert!((fuel_used - 87.0).abs() < 0.01, "fuel used {}", fuel_used);
+    }
+
+    #[tokio::test]
+    async fn queued_jobs_are_flushed_in_one_batch_with_per_entry_results() {
+        const BATCH_PATH: &str = "/api/telemetry/jobs/batch";
+        let backend = MockBackend::start().await;
+        backend.reply("/api/telemetry/capabilities", Reply::Json(200, json!({ "job_schemas": [1, 2], "batch_jobs": true })));
+        for _ in 0..TEST_RETRIES.max_attempts {
+            backend.reply(JOB_PATH, Reply::Drop);
+        }
+        let mut pipeline = Pipeline::new(&backend);
+        deliver_job(&mut pipeline).await;
+
+        let mut second = pipeline.queue[0].clone();
+        second.cargo = "Apples".into();
+        second.idempotency_key = Some("second".into());
+        pipeline.history.record(&second, HistoryStatus::Failed, Utc::now()).unwrap();
+        pipeline.queue.push(second);
+        let first_key = pipeline.queue[0].request_key();
+        backend.reply(BATCH_PATH, Reply::Json(200, json!({ "results": [
+            { "idempotency_key": "second", "success": false, "error": "Cargo not available" },
+            { "idempotency_key": first_key, "success": true, "job_id": "job-4", "message": "Job recorded" },
+        ] })));
+
+        pipeline.retry_queued().await;
+        let sent = backend.requests(BATCH_PATH);
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].body["jobs"].as_array().unwrap().len(), 2);
+        assert_eq!(sent[0].body["jobs"][1]["payload"]["schema_version"], 2);
+
+        let page = pipeline.history.page(0, &HistoryFilter::default()).unwrap();
+        let status = |cargo: &str| page.entries.iter().find(|e| e.cargo == cargo).unwrap().clone();
+        assert_eq!(status("Apples").status, HistoryStatus::Failed);
+        assert_eq!(status("Apples").error.as_deref(), Some("Request rejected: Cargo not available"));
+        assert_eq!(status("machinery").server_job_id.as_deref(), Some("job-4"));
+        // The server refused it, so it isn't queued again
+        assert!(pipeline.queue.is_empty());
+    }
+
+    #[tokio::test]
+    async fn job_refused_by_the_server_is_not_retried_or_queued() {
+        let backend = MockBackend::start().await;
+        backend.reply(JOB_PATH, Reply::Json(422, json!({ "error": "Distance out of range" })));
+        let mut pipeline = Pipeline::new(&backend);
+
+        deliver_job(&mut pipeline).await;
+        let entry = only_entry(&pipeline);
+        assert_eq!(entry.status, HistoryStatus::Failed);
+        assert_eq!(entry.error.as_deref(), Some("Request rejected: Distance out of range"));
+        assert_eq!(backend.requests(JOB_PATH).len(), 1);
+        assert!(pipeline.queue.is_empty());
+    }
+}
@@ -0,0 +1,146 @@
+//! Heartbeat Module
+//!
+//! Timing and status rules for the backend heartbeat scheduler: follow the
+//! server's `next_heartbeat_in` hint, back off while requests fail, and
+//! summarize the result as a connection status for the UI.
+
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::clock::SharedClock;
+use crate::sync_health::SyncStatus;
+
+/// Bounds for the server's interval hint (seconds)
+const MIN_INTERVAL_SECS: u64 = 15;
+const MAX_INTERVAL_SECS: u64 = 300;
+/// Used when the server sends no usable hint
+const DEFAULT_INTERVAL_SECS: u64 = 60;
+/// How often to look for a session while logged out
+pub const LOGGED_OUT_CHECK: Duration = Duration::from_secs(5);
+/// Longest wait between looks at the clock, so a jump (e.g. waking from
+/// sleep) is noticed
+const CLOCK_CHECK: Duration = Duration::from_secs(5);
+
+/// Delay before the next heartbeat after a successful one
+pub fn after_success(next_heartbeat_in: u32) -> Duration {
+    let secs = match u64::from(next_heartbeat_in) {
+        0 => DEFAULT_INTERVAL_SECS,
+        secs => secs.clamp(MIN_INTERVAL_SECS, MAX_INTERVAL_SECS),
+    };
+    Duration::from_secs(secs)
+}
+
+/// Delay before retrying after `consecutive_failures` failed heartbeats
+pub fn after_failure(consecutive_failures: u32) -> Duration {
+    let exponent = consecutive_failures.saturating_sub(1).min(5);
+    Duration::from_secs((MIN_INTERVAL_SECS << exponent).min(MAX_INTERVAL_SECS))
+}
+
+/// When the next heartbeat is due, by the injected clock
+pub struct HeartbeatSchedule {
+    clock: SharedClock,
+    due_at: DateTime<Utc>,
+}
+
+impl HeartbeatSchedule {
+    /// Schedule whose first heartbeat is due right away
+    pub fn new(clock: SharedClock) -> Self {
+        let due_at = clock.now();
+        Self { clock, due_at }
+    }
+
+    /// Next heartbeat `delay` from now
+    pub fn schedule(&mut self, delay: Duration) {
+        self.due_at = self.clock.now() + chrono::Duration::from_std(delay).unwrap_or_else(|_| chrono::Duration::seconds(MAX_INTERVAL_SECS as i64));
+    }
+
+    /// Make the next heartbeat due right away
+    pub fn reset(&mut self) {
+        self.due_at = self.clock.now();
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.clock.now() >= self.due_at
+    }
+
+    /// How long to wait before checking again
+    pub fn wait(&self) -> Duration {
+        (self.due_at - self.clock.now()).to_std().unwrap_or(Duration::ZERO).min(CLOCK_CHECK)
+    }
+}
+
+/// Connection state shown by the UI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectionStatus {
+    LoggedOut,
+    Connecting,
+    Online,
+    Degraded,
+    Offline,
+    Maintenance,
+}
+
+impl ConnectionStatus {
+    /// Combine session, maintenance and sync health into one status
+    pub fn derive(authenticated: bool, maintenance: bool, sync: SyncStatus) -> Self {
+        match (authenticated, maintenance, sync) {
+            (false, _, _) => ConnectionStatus::LoggedOut,
+            (true, true, _) => ConnectionStatus::Maintenance,
+            (true, false, SyncStatus::Unknown) => ConnectionStatus::Connecting,
+            (true, false, SyncStatus::Online) => ConnectionStatus::Online,
+            (true, false, SyncStatus::Degraded) => ConnectionStatus::Degraded,
+            (true, false, SyncStatus::Offline) => ConnectionStatus::Offline,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_server_hint_and_backs_off() {
+        assert_eq!(after_success(30), Duration::from_secs(30));
+        assert_eq!(after_success(1), Duration::from_secs(MIN_INTERVAL_SECS));
+        assert_eq!(after_success(0), Duration::from_secs(DEFAULT_INTERVAL_SECS));
+        assert_eq!(after_success(86_400), Duration::from_secs(MAX_INTERVAL_SECS));
+
+        assert_eq!(after_failure(1), Duration::from_secs(15));
+        assert_eq!(after_failure(3), Duration::from_secs(60));
+        assert_eq!(after_failure(40), Duration::from_secs(MAX_INTERVAL_SECS));
+
+        assert_eq!(ConnectionStatus::derive(true, true, SyncStatus::Online), ConnectionStatus::Maintenance);
+        assert_eq!(ConnectionStatus::derive(false, false, SyncStatus::Online), ConnectionStatus::LoggedOut);
+    }
+
+    #[test]
+    fn schedule_follows_the_clock() {
+        use std::sync::Arc;
+        use chrono::TimeZone;
+        use crate::clock::MockClock;
+
+        let clock = Arc::new(MockClock::new(Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap()));
+        let mut schedule = HeartbeatSchedule::new(clock.clone());
+        assert!(schedule.is_due());
+
+        schedule.schedule(after_success(30));
+        assert!(!schedule.is_due());
+        assert_eq!(schedule.wait(), CLOCK_CHECK);
+        clock.advance(chrono::Duration::seconds(27));
+        assert_eq!(schedule.wait(), Duration::from_secs(3));
+        clock.advance(chrono::Duration::seconds(3));
+        assert!(schedule.is_due());
+        assert_eq!(schedule.wait(), Duration::ZERO);
+
+        // A long sleep of the machine makes it due at once
+        schedule.schedule(after_failure(6));
+        clock.advance(chrono::Duration::hours(8));
+        assert!(schedule.is_due());
+
+        schedule.schedule(after_success(60));
+        schedule.reset();
+        assert!(schedule.is_due());
+    }
+}
@@ -0,0 +1,791 @@
+//! History Module
+//!
+//! Local SQLite record of every completed job and whether it reached the
+//! platform, so past deliveries can be browsed while the web side is down.
+
+use std::path::Path;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use crate::currency::{self, Currency};
+use crate::games;
+use crate::route::{JobRoute, RoutePoint};
+use crate::sync::JobSubmission;
+
+/// File name of the history database inside the storage directory
+pub const HISTORY_FILE: &str = "history.db";
+/// Jobs per page of `get_job_history`
+pub const PAGE_SIZE: u32 = 25;
+/// Entries in the top cargo and favourite route lists of `local_stats`
+const TOP_ENTRIES: u32 = 5;
+/// Bumped whenever `migrate` gains a step
+const SCHEMA_VERSION: i32 = 6;
+
+/// Where a recorded job stands with the platform
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HistoryStatus {
+    /// Not (yet) accepted: queued, offline or signed out
+    Pending,
+    Submitted,
+    Failed,
+    /// Imported from another tracker and kept local
+    Imported,
+    /// Kept back by the submission rules; the reason is in `error`
+    Skipped,
+    /// Undelivered job recorded from a checkpoint; sync outcomes only fill
+    /// in `server_job_id` and `error`
+    Partial,
+}
+
+impl HistoryStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            HistoryStatus::Pending => "pending",
+            HistoryStatus::Submitted => "submitted",
+            HistoryStatus::Failed => "failed",
+            HistoryStatus::Imported => "imported",
+            HistoryStatus::Skipped => "skipped",
+            HistoryStatus::Partial => "partial",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "submitted" => HistoryStatus::Submitted,
+            "failed" => HistoryStatus::Failed,
+            "imported" => HistoryStatus::Imported,
+            "skipped" => HistoryStatus::Skipped,
+            "partial" => HistoryStatus::Partial,
+            _ => HistoryStatus::Pending,
+        }
+    }
+}
+
+/// One delivered job
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub id: i64,
+    pub delivered_at: DateTime<Utc>,
+    pub game: String,
+    pub cargo: String,
+    pub source_city: String,
+    pub destination_city: String,
+    pub distance_km: u32,
+    pub revenue: f64,
+    /// Currency of `revenue`
+    pub currency: Currency,
+    pub damage_percent: f64,
+    /// Litres burned, for jobs recorded with fuel tracking
+    pub fuel_used: Option<f64>,
+    /// Litres per 100 km
+    pub average_consumption: Option<f64>,
+    pub status: HistoryStatus,
+    /// Job ID assigned by the platform once submitted
+    pub server_job_id: Option<String>,
+    /// Last submission error, if any
+    pub error: Option<String>,
+    /// Entered by hand rather than detected
+    pub manual: bool,
+}
+
+/// A recorded job that hasn't reached the platform
+#[derive(Debug, Clone)]
+pub struct UnsyncedJob {
+    /// Digest of the submission the job was recorded with
+    pub digest: String,
+    pub delivered_at: DateTime<Utc>,
+    /// Rebuilt from the stored columns; telemetry extras aren't kept
+    pub submission: JobSubmission,
+}
+
+/// Narrows `get_job_history`; empty fields match everything
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryFilter {
+    pub game: Option<String>,
+    pub status: Option<HistoryStatus>,
+    /// Matched against cargo and both cities
+    pub search: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl HistoryFilter {
+    /// SQL condition and its parameters
+    fn clause(&self) -> (String, Vec<Value>) {
+        let mut conditions = vec!["1 = 1".to_string()];
+        let mut values = Vec::new();
+        if let Some(game) = &self.game {
+            conditions.push("game = ?".into());
+            values.push(Value::Text(game.clone()));
+        }
+        if let Some(status) = self.status {
+            conditions.push("status = ?".into());
+            values.push(Value::Text(status.as_str().into()));
+        }
+        if let Some(search) = self.search.as_deref().map(str::trim).filter(|search| !search.is_empty()) {
+            conditions.push("(cargo LIKE ? OR source_city LIKE ? OR destination_city LIKE ?)".into());
+            let pattern = format!("%{}%", search);
+            values.extend(std::iter::repeat(Value::Text(pattern)).take(3));
+        }
+        if let Some(from) = self.from {
+            conditions.push("delivered_at >= ?".into());
+            values.push(Value::Text(timestamp(from)));
+        }
+        if let Some(to) = self.to {
+            conditions.push("delivered_at < ?".into());
+            values.push(Value::Text(timestamp(to)));
+        }
+        (conditions.join(" AND "), values)
+    }
+}
+
+/// One page of history, newest first
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPage {
+    pub entries: Vec<HistoryEntry>,
+    pub page: u32,
+    pub page_size: u32,
+    pub total: u64,
+}
+
+/// Lifetime totals over the local history
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobStats {
+    pub jobs: u64,
+    pub submitted: u64,
+    pub pending: u64,
+    pub failed: u64,
+    /// Not submitted because of the submission rules
+    pub skipped: u64,
+    pub distance_km: u64,
+    /// Revenue of every job, converted to `currency`
+    pub revenue: f64,
+    pub currency: Currency,
+    pub average_damage_percent: f64,
+    /// Litres burned over the jobs that tracked fuel
+    pub fuel_used: f64,
+}
+
+/// Window of `get_local_stats`, ending now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StatsPeriod {
+    Week,
+    Month,
+    Year,
+    All,
+}
+
+impl StatsPeriod {
+    /// Start of the window ending at `now`
+    fn start(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let days = match self {
+            StatsPeriod::Week => 7,
+            StatsPeriod::Month => 30,
+            StatsPeriod::Year => 365,
+            StatsPeriod::All => return None,
+        };
+        Some(now - chrono::Duration::days(days))
+    }
+}
+
+/// How often a cargo was hauled
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CargoCount {
+    pub cargo: String,
+    pub count: u32,
+    pub distance_km: u64,
+}
+
+/// How often a route was driven, in either direction
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteCount {
+    pub source_city: String,
+    pub destination_city: String,
+    pub count: u32,
+}
+
+/// Dashboard statistics over one period of the local history
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalStats {
+    pub period: StatsPeriod,
+    pub from: Option<DateTime<Utc>>,
+    pub to: DateTime<Utc>,
+    pub jobs: u64,
+    pub distance_km: u64,
+    /// Converted to `currency`
+    pub revenue: f64,
+    pub currency: Currency,
+    pub average_damage_percent: f64,
+    pub top_cargo: Vec<CargoCount>,
+    pub favorite_routes: Vec<RouteCount>,
+}
+
+/// History database errors
+#[derive(Debug, thiserror::Error)]
+pub enum HistoryError {
+    #[error("Failed to open job history: {0}")]
+    Open(String),
+
+    #[error("Job history query failed: {0}")]
+    Query(String),
+}
+
+/// Handle to the history database
+pub struct JobHistory {
+    conn: Connection,
+}
+
+impl JobHistory {
+    /// Open (or create) the database at `path`
+    pub fn open(path: &Path) -> Result<Self, HistoryError> {
+        let conn = Connection::open(path).map_err(|e| HistoryError::Open(e.to_string()))?;
+        Self::with_connection(conn)
+    }
+
+    /// Throwaway database, used when the file can't be opened
+    pub fn in_memory() -> Result<Self, HistoryError> {
+        let conn = Connection::open_in_memory().map_err(|e| HistoryError::Open(e.to_string()))?;
+        Self::with_connection(conn)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self, HistoryError> {
+        let history = Self { conn };
+        history.migrate().map_err(|e| HistoryError::Open(e.to_string()))?;
+        Ok(history)
+    }
+
+    fn migrate(&self) -> rusqlite::Result<()> {
+        let version: i32 = self.conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version < 1 {
+            self.conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS jobs (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    delivered_at TEXT NOT NULL,
+                    game TEXT NOT NULL,
+                    cargo TEXT NOT NULL,
+                    source_city TEXT NOT NULL,
+                    destination_city TEXT NOT NULL,
+                    distance_km INTEGER NOT NULL,
+                    revenue REAL NOT NULL,
+                    damage_percent REAL NOT NULL,
+                    status TEXT NOT NULL,
+                    server_job_id TEXT,
+                    error TEXT,
+                    digest TEXT NOT NULL
+                );
+                CREATE INDEX IF NOT EXISTS jobs_delivered_at ON jobs (delivered_at);
+                CREATE INDEX IF NOT EXISTS jobs_digest ON jobs (digest);",
+            )?;
+        }
+        if version < 2 {
+            self.conn.execute_batch(
+                "ALTER TABLE jobs ADD COLUMN fuel_used REAL;
+                ALTER TABLE jobs ADD COLUMN average_consumption REAL;",
+            )?;
+        }
+        if version < 3 {
+            self.conn.execute_batch(
+                "ALTER TABLE jobs ADD COLUMN idempotency_key TEXT;
+                UPDATE jobs SET idempotency_key = digest;
+                CREATE INDEX IF NOT EXISTS jobs_idempotency_key ON jobs (idempotency_key);",
+            )?;
+        }
+        if version < 4 {
+            self.conn.execute_batch(
+                "ALTER TABLE jobs ADD COLUMN currency TEXT;
+                UPDATE jobs SET currency = CASE game WHEN 'ats' THEN 'USD' ELSE 'EUR' END;",
+            )?;
+        }
+        if version < 5 {
+            self.conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS routes (
+                    job_id INTEGER PRIMARY KEY REFERENCES jobs (id),
+                    points TEXT NOT NULL
+                );",
+            )?;
+        }
+        if version < 6 {
+            self.conn.execute_batch("ALTER TABLE jobs ADD COLUMN manual INTEGER NOT NULL DEFAULT 0;")?;
+        }
+        self.conn.pragma_update(None, "user_version", SCHEMA_VERSION)
+    }
+
+    /// Record a delivered job; returns its row ID
+    pub fn record(
+        &self,
+        submission: &JobSubmission,
+        status: HistoryStatus,
+        delivered_at: DateTime<Utc>,
+    ) -> Result<i64, HistoryError> {
+        self.conn.execute(
+            "INSERT INTO jobs (delivered_at, game, cargo, source_city, destination_city,
+                distance_km, revenue, damage_percent, fuel_used, average_consumption, status, digest,
+                idempotency_key, currency)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            params![
+                timestamp(delivered_at),
+                submission.game,
+                submission.cargo,
+                submission.source_city,
+                submission.destination_city,
+                submission.distance_km,
+                submission.revenue,
+                submission.damage_percent,
+                submission.fuel_used,
+                submission.average_consumption,
+                if submission.partial { HistoryStatus::Partial.as_str() } else { status.as_str() },
+                submission.digest(),
+                submission.request_key(),
+                submission.revenue_currency().code(),
+            ],
+        ).map_err(|e| HistoryError::Query(e.to_string()))?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Record a job entered by hand, pending submission; returns its row ID
+    pub fn record_manual(&self, submission: &JobSubmission, delivered_at: DateTime<Utc>) -> Result<i64, HistoryError> {
+        let id = self.record(submission, HistoryStatus::Pending, delivered_at)?;
+        self.conn.execute("UPDATE jobs SET manual = 1 WHERE id = ?1", params![id])
+            .map_err(|e| HistoryError::Query(e.to_string()))?;
+        Ok(id)
+    }
+
+    /// Update the job recorded for `submission` after an attempt to submit it
+    pub fn set_status(
+        &self,
+        submission: &JobSubmission,
+        status: HistoryStatus,
+        server_job_id: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<(), HistoryError> {
+        self.conn.execute(
+            "UPDATE jobs SET status = CASE status WHEN 'partial' THEN status ELSE ?1 END,
+                server_job_id = COALESCE(?2, server_job_id), error = ?3
+             WHERE digest = ?4",
+            params![status.as_str(), server_job_id, error, submission.digest()],
+        ).map_err(|e| HistoryError::Query(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Store the route driven for the job recorded for `submission`
+    pub fn record_route(&self, submission: &JobSubmission, points: &[RoutePoint]) -> Result<(), HistoryError> {
+        let points = serde_json::to_string(points).map_err(|e| HistoryError::Query(e.to_string()))?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO routes (job_id, points)
+             SELECT id, ?2 FROM jobs WHERE digest = ?1 ORDER BY id DESC LIMIT 1",
+            params![submission.digest(), points],
+        ).map_err(|e| HistoryError::Query(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Route of job `id`, if one was recorded
+    pub fn route(&self, id: i64) -> Result<Option<JobRoute>, HistoryError> {
+        let row = self.conn
+            .query_row(
+                "SELECT jobs.game, jobs.source_city, jobs.destination_city, jobs.cargo, routes.points
+                 FROM routes JOIN jobs ON jobs.id = routes.job_id WHERE routes.job_id = ?1",
+                params![id],
+                |row| Ok((row.get::<_, String>(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get::<_, String>(4)?)),
+            )
+            .optional()
+            .map_err(|e| HistoryError::Query(e.to_string()))?;
+        let Some((game, source_city, destination_city, cargo, points)) = row else {
+            return Ok(None);
+        };
+        let points = serde_json::from_str(&points).map_err(|e| HistoryError::Query(e.to_string()))?;
+        Ok(Some(JobRoute {
+            game: games::from_id(&game).unwrap_or(crate::telemetry::Game::Ets2),
+            source_city,
+            destination_city,
+            cargo,
+            points,
+        }))
+    }
+
+    /// Job `id` for another attempt, unless it was submitted or imported.
+    /// Telemetry details aren't kept, but a manual entry stays flagged as one
+    pub fn unsynced_job(&self, id: i64) -> Result<Option<UnsyncedJob>, HistoryError> {
+        self.conn
+            .query_row(
+                "SELECT digest, delivered_at, game, cargo, source_city, destination_city, distance_km,
+                    revenue, currency, damage_percent, fuel_used, average_consumption, idempotency_key, manual
+                 FROM jobs WHERE id = ?1 AND status IN ('pending', 'failed', 'skipped')",
+                params![id],
+                |row| {
+                    let delivered_at: String = row.get(1)?;
+                    let delivered_at = DateTime::parse_from_rfc3339(&delivered_at)
+                        .map(|at| at.with_timezone(&Utc))
+                        .unwrap_or_default();
+                    let currency: Option<String> = row.get(8)?;
+                    let manual: bool = row.get(13)?;
+                    Ok(UnsyncedJob {
+                        digest: row.get(0)?,
+                        delivered_at,
+                        submission: JobSubmission {
+                            game: row.get(2)?,
+                            cargo: row.get(3)?,
+                            source_city: row.get(4)?,
+                            destination_city: row.get(5)?,
+                            distance_km: row.get(6)?,
+                            revenue: row.get(7)?,
+                            currency: currency.as_deref().and_then(Currency::from_code),
+                            damage_percent: row.get(9)?,
+                            chassis_damage_percent: 0.0,
+                            truck_id: None,
+                            trailer_id: None,
+                            trailer_ownership: None,
+                            telemetry_data: manual.then(|| serde_json::json!({
+                                "manual": { "deliveredAt": delivered_at, "note": null },
+                            })),
+                            server: None,
+                            fuel_used: row.get(10)?,
+                            average_consumption: row.get(11)?,
+                            driven_km: None,
+                            navigation_km: None,
+                            idempotency_key: row.get(12)?,
+                            partial: false,
+                        },
+                    })
+                },
+            )
+            .optional()
+            .map_err(|e| HistoryError::Query(e.to_string()))
+    }
+
+    /// Point job `id` at `submission`, so status updates for it find the row
+    pub fn relink(&self, id: i64, submission: &JobSubmission) -> Result<(), HistoryError> {
+        self.conn.execute("UPDATE jobs SET digest = ?1 WHERE id = ?2", params![submission.digest(), id])
+            .map_err(|e| HistoryError::Query(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Server job ID of a submission the server already accepted
+    pub fn confirmed_job_id(&self, submission: &JobSubmission) -> Result<Option<String>, HistoryError> {
+        self.conn
+            .query_row(
+                "SELECT server_job_id FROM jobs WHERE idempotency_key = ?1 AND server_job_id IS NOT NULL
+                    AND status IN ('submitted', 'partial') LIMIT 1",
+                params![submission.request_key()],
+                |row| row.get(0),
+            )
+            .optional()
+            .map(Option::flatten)
+            .map_err(|e| HistoryError::Query(e.to_string()))
+    }
+
+    /// Page `page` (zero-based) of jobs matching `filter`, newest first
+    pub fn page(&self, page: u32, filter: &HistoryFilter) -> Result<HistoryPage, HistoryError> {
+        let (clause, mut values) = filter.clause();
+        let total: i64 = self.conn
+            .query_row(&format!("SELECT COUNT(*) FROM jobs WHERE {}", clause), params_from_iter(&values), |row| row.get(0))
+            .map_err(|e| HistoryError::Query(e.to_string()))?;
+
+        values.push(Value::Integer(PAGE_SIZE.into()));
+        values.push(Value::Integer(i64::from(page) * i64::from(PAGE_SIZE)));
+        let sql = format!(
+            "SELECT id, delivered_at, game, cargo, source_city, destination_city, distance_km,
+                revenue, damage_percent, status, server_job_id, error, fuel_used, average_consumption,
+                currency, manual
+             FROM jobs WHERE {} ORDER BY delivered_at DESC, id DESC LIMIT ? OFFSET ?",
+            clause
+        );
+        let mut statement = self.conn.prepare(&sql).map_err(|e| HistoryError::Query(e.to_string()))?;
+        let entries = statement
+            .query_map(params_from_iter(&values), entry_from_row)
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+            .map_err(|e| HistoryError::Query(e.to_string()))?;
+
+        Ok(HistoryPage { entries, page, page_size: PAGE_SIZE, total: total as u64 })
+    }
+
+    /// Totals over every recorded job, with revenue converted to `display`
+    pub fn stats(&self, display: Currency) -> Result<JobStats, HistoryError> {
+        let revenue = self.revenue_in(display, &HistoryFilter::default())?;
+        self.conn
+            .query_row(
+                "SELECT COUNT(*),
+                    COALESCE(SUM(status = 'submitted'), 0),
+                    COALESCE(SUM(status = 'pending'), 0),
+                    COALESCE(SUM(status = 'failed'), 0),
+                    COALESCE(SUM(status = 'skipped'), 0),
+                    COALESCE(SUM(distance_km), 0),
+                    COALESCE(AVG(damage_percent), 0.0),
+                    COALESCE(SUM(fuel_used), 0.0)
+                 FROM jobs",
+                [],
+                |row| {
+                    Ok(JobStats {
+                        jobs: row.get::<_, i64>(0)? as u64,
+                        submitted: row.get::<_, i64>(1)? as u64,
+                        pending: row.get::<_, i64>(2)? as u64,
+                        failed: row.get::<_, i64>(3)? as u64,
+                        skipped: row.get::<_, i64>(4)? as u64,
+                        distance_km: row.get::<_, i64>(5)? as u64,
+                        revenue,
+                        currency: display,
+                        average_damage_percent: row.get(6)?,
+                        fuel_used: row.get(7)?,
+                    })
+                },
+            )
+            .map_err(|e| HistoryError::Query(e.to_string()))
+    }
+
+    /// Totals, top cargo and favourite routes over `period`
+    pub fn local_stats(&self, period: StatsPeriod, display: Currency, now: DateTime<Utc>) -> Result<LocalStats, HistoryError> {
+        let from = period.start(now);
+        let filter = HistoryFilter { from, to: Some(now), ..HistoryFilter::default() };
+        let (clause, values) = filter.clause();
+        let (jobs, distance_km, average_damage_percent) = self.conn
+            .query_row(
+                &format!(
+                    "SELECT COUNT(*), COALESCE(SUM(distance_km), 0), COALESCE(AVG(damage_percent), 0.0)
+                     FROM jobs WHERE {}",
+                    clause
+                ),
+                params_from_iter(&values),
+                |row| Ok((row.get::<_, i64>(0)? as u64, row.get::<_, i64>(1)? as u64, row.get(2)?)),
+            )
+            .map_err(|e| HistoryError::Query(e.to_string()))?;
+
+        let top_cargo = self.top(
+            &format!(
+                "SELECT cargo, COUNT(*) AS count, SUM(distance_km) FROM jobs WHERE {}
+                 GROUP BY cargo ORDER BY count DESC, cargo LIMIT {}",
+                clause, TOP_ENTRIES
+            ),
+            &values,
+            |row| Ok(CargoCount { cargo: row.get(0)?, count: row.get(1)?, distance_km: row.get::<_, i64>(2)? as u64 }),
+        )?;
+        // Both directions of a route count as one, named after its first city alphabetically
+        let favorite_routes = self.top(
+            &format!(
+                "SELECT MIN(source_city, destination_city) AS a, MAX(source_city, destination_city) AS b,
+                    COUNT(*) AS count
+                 FROM jobs WHERE {} GROUP BY a, b ORDER BY count DESC, a, b LIMIT {}",
+                clause, TOP_ENTRIES
+            ),
+            &values,
+            |row| Ok(RouteCount { source_city: row.get(0)?, destination_city: row.get(1)?, count: row.get(2)? }),
+        )?;
+
+        Ok(LocalStats {
+            period,
+            from,
+            to: now,
+            jobs,
+            distance_km,
+            revenue: self.revenue_in(display, &filter)?,
+            currency: display,
+            average_damage_percent,
+            top_cargo,
+            favorite_routes,
+        })
+    }
+
+    fn top<T>(&self, sql: &str, values: &[Value], map: impl FnMut(&Row<'_>) -> rusqlite::Result<T>) -> Result<Vec<T>, HistoryError> {
+        let mut statement = self.conn.prepare(sql).map_err(|e| HistoryError::Query(e.to_string()))?;
+        statement
+            .query_map(params_from_iter(values), map)
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+            .map_err(|e| HistoryError::Query(e.to_string()))
+    }
+
+    /// Revenue of the jobs matching `filter` summed per currency, then converted to `display`
+    fn revenue_in(&self, display: Currency, filter: &HistoryFilter) -> Result<f64, HistoryError> {
+        let (clause, values) = filter.clause();
+        let mut statement = self.conn
+            .prepare(&format!("SELECT currency, SUM(revenue) FROM jobs WHERE {} GROUP BY currency", clause))
+            .map_err(|e| HistoryError::Query(e.to_string()))?;
+        let totals = statement
+            .query_map(params_from_iter(&values), |row| Ok((row.get::<_, Option<String>>(0)?, row.get::<_, f64>(1)?)))
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+            .map_err(|e| HistoryError::Query(e.to_string()))?;
+        Ok(totals.into_iter()
+            .map(|(code, total)| {
+                let from = code.as_deref().and_then(Currency::from_code).unwrap_or_default();
+                currency::convert(total, from, display)
+            })
+            .sum())
+    }
+}
+
+fn entry_from_row(row: &Row<'_>) -> rusqlite::Result<HistoryEntry> {
+    let delivered_at: String = row.get(1)?;
+    let status: String = row.get(9)?;
+    let currency: Option<String> = row.get(14)?;
+    Ok(HistoryEntry {
+        id: row.get(0)?,
+        delivered_at: DateTime::parse_from_rfc3339(&delivered_at)
+            .map(|at| at.with_timezone(&Utc))
+            .unwrap_or_default(),
+        game: row.get(2)?,
+        cargo: row.get(3)?,
+        source_city: row.get(4)?,
+        destination_city: row.get(5)?,
+        distance_km: row.get(6)?,
+        revenue: row.get(7)?,
+        currency: currency.as_deref().and_then(Currency::from_code).unwrap_or_default(),
+        damage_percent: row.get(8)?,
+        fuel_used: row.get(12)?,
+        average_consumption: row.get(13)?,
+        status: HistoryStatus::parse(&status),
+        server_job_id: row.get(10)?,
+        error: row.get(11)?,
+        manual: row.get(15)?,
+    })
+}
+
+/// Fixed-width UTC timestamp, so text order matches time order
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn submission(cargo: &str, destination: &str, distance_km: u32) -> JobSubmission {
+        JobSubmission {
+            game: "ets2".into(),
+            cargo: cargo.into(),
+            source_city: "Berlin".into(),
+            destination_city: destination.into(),
+            distance_km,
+            revenue: 10_000.0,
+            currency: None,
+            damage_percent: 2.0,
+            chassis_damage_percent: 0.0,
+            truck_id: None,
+            trailer_id: None,
+            trailer_ownership: None,
+            telemetry_data: None,
+            server: None,
+            fuel_used: None,
+            average_consumption: None,
+            driven_km: None,
+            navigation_km: None,
+            idempotency_key: Some(format!("{}-{}", destination, distance_km)),
+            partial: false,
+        }
+    }
+
+    #[test]
+    fn partial_jobs_keep_their_status_through_sync() {
+        let history = JobHistory::in_memory().unwrap();
+        let t = Utc.with_ymd_and_hms(2025, 8, 1, 9, 0, 0).unwrap();
+        let partial = JobSubmission { partial: true, ..submission("Steel", "Hamburg", 120) };
+        history.record(&partial, HistoryStatus::Pending, t).unwrap();
+        history.set_status(&partial, HistoryStatus::Failed, None, Some("Server error")).unwrap();
+        history.set_status(&partial, HistoryStatus::Submitted, Some("j7"), None).unwrap();
+
+        let entry = &history.page(0, &HistoryFilter::default()).unwrap().entries[0];
+        assert_eq!((entry.status, entry.server_job_id.as_deref()), (HistoryStatus::Partial, Some("j7")));
+        assert_eq!(history.confirmed_job_id(&partial).unwrap().as_deref(), Some("j7"));
+        assert!(history.unsynced_job(entry.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn unsynced_jobs_come_back_with_their_manual_flag() {
+        let history = JobHistory::in_memory().unwrap();
+        let t = Utc.with_ymd_and_hms(2025, 8, 1, 9, 0, 0).unwrap();
+        let submitted = submission("Steel", "Hamburg", 290);
+        history.record(&submitted, HistoryStatus::Pending, t).unwrap();
+        history.set_status(&submitted, HistoryStatus::Submitted, Some("j1"), None).unwrap();
+        history.record(&submission("Apples", "Prague", 350), HistoryStatus::Pending, t).unwrap();
+        history.record_manual(&submission("Lumber", "Dresden", 190), t).unwrap();
+
+        assert!(history.unsynced_job(1).unwrap().is_none());
+        let unsynced = history.unsynced_job(2).unwrap().unwrap();
+        assert_eq!(unsynced.digest, submission("Apples", "Prague", 350).digest());
+        assert_eq!(unsynced.submission.request_key(), "Prague-350");
+        assert!(unsynced.submission.telemetry_data.is_none());
+
+        let manual = history.unsynced_job(3).unwrap().unwrap().submission;
+        let marker = &manual.telemetry_data.unwrap()["manual"];
+        assert_eq!(marker["deliveredAt"], serde_json::json!(t));
+    }
+
+    #[test]
+    fn records_pages_and_updates_jobs() {
+        let history = JobHistory::in_memory().unwrap();
+        let t = Utc.with_ymd_and_hms(2025, 8, 1, 9, 0, 0).unwrap();
+        let hamburg = JobSubmission { fuel_used: Some(87.0), average_consumption: Some(30.0), ..submission("Steel", "Hamburg", 290) };
+        history.record(&hamburg, HistoryStatus::Pending, t).unwrap();
+        for i in 0..PAGE_SIZE {
+            let job = submission("Apples", "Prague", 350 + i);
+            history.record(&job, HistoryStatus::Pending, t + Duration::hours(i64::from(i) + 1)).unwrap();
+        }
+
+        assert_eq!(history.confirmed_job_id(&hamburg).unwrap(), None);
+        history.set_status(&hamburg, HistoryStatus::Submitted, Some("j1"), None).unwrap();
+        assert_eq!(history.confirmed_job_id(&hamburg).unwrap().as_deref(), Some("j1"));
+        let points = vec![RoutePoint { at: t, x: 1.0, z: 2.0 }];
+        history.record_route(&hamburg, &points).unwrap();
+        let route = history.route(1).unwrap().unwrap();
+        assert_eq!((route.destination_city.as_str(), route.points), ("Hamburg", points));
+        assert_eq!(history.route(2).unwrap(), None);
+
+        let first = history.page(0, &HistoryFilter::default()).unwrap();
+        assert_eq!(first.total, u64::from(PAGE_SIZE) + 1);
+        assert_eq!(first.entries.len(), PAGE_SIZE as usize);
+        assert_eq!(first.entries[0].distance_km, 350 + PAGE_SIZE - 1);
+
+        let last = history.page(1, &HistoryFilter::default()).unwrap();
+        assert_eq!(last.entries.len(), 1);
+        assert_eq!(last.entries[0].delivered_at, t);
+        assert_eq!(last.entries[0].server_job_id.as_deref(), Some("j1"));
+        assert_eq!(last.entries[0].average_consumption, Some(30.0));
+        assert!(!last.entries[0].manual);
+
+        let filter = HistoryFilter { search: Some("hamb".into()), ..HistoryFilter::default() };
+        assert_eq!(history.page(0, &filter).unwrap().total, 1);
+        let filter = HistoryFilter { from: Some(t + Duration::hours(PAGE_SIZE.into())), ..HistoryFilter::default() };
+        assert_eq!(history.page(0, &filter).unwrap().total, 1);
+
+        let stats = history.stats(Currency::Eur).unwrap();
+        assert_eq!(stats.jobs, u64::from(PAGE_SIZE) + 1);
+        assert_eq!(stats.submitted, 1);
+        assert_eq!(stats.pending, u64::from(PAGE_SIZE));
+        assert_eq!(stats.fuel_used, 87.0);
+
+        // Dollars from ATS are converted before being added to euros
+        let ats = JobSubmission { game: "ats".into(), revenue: 1_000.0, ..submission("Lumber", "Reno", 400) };
+        history.record(&ats, HistoryStatus::Pending, t - Duration::days(1)).unwrap();
+        let euros = 10_000.0 * f64::from(PAGE_SIZE + 1) + currency::convert(1_000.0, Currency::Usd, Currency::Eur);
+        assert!((history.stats(Currency::Eur).unwrap().revenue - euros).abs() < 1e-6);
+        assert_eq!(history.page(1, &HistoryFilter::default()).unwrap().entries.last().map(|entry| entry.currency), Some(Currency::Usd));
+    }
+
+    #[test]
+    fn local_stats_cover_the_period() {
+        let history = JobHistory::in_memory().unwrap();
+        let now = Utc.with_ymd_and_hms(2025, 8, 31, 12, 0, 0).unwrap();
+        let back = |destination: &str| JobSubmission { source_city: destination.into(), destination_city: "Berlin".into(), ..submission("Steel", destination, 300) };
+        history.record(&submission("Steel", "Hamburg", 290), HistoryStatus::Pending, now - Duration::days(1)).unwrap();
+        history.record(&back("Hamburg"), HistoryStatus::Pending, now - Duration::days(2)).unwrap();
+        history.record(&submission("Apples", "Prague", 350), HistoryStatus::Pending, now - Duration::days(3)).unwrap();
+        history.record(&submission("Apples", "Wien", 680), HistoryStatus::Pending, now - Duration::days(20)).unwrap();
+
+        let week = history.local_stats(StatsPeriod::Week, Currency::Eur, now).unwrap();
+        assert_eq!((week.jobs, week.distance_km, week.revenue), (3, 940, 30_000.0));
+        assert_eq!(week.top_cargo[0], CargoCount { cargo: "Steel".into(), count: 2, distance_km: 590 });
+        assert_eq!(week.favorite_routes[0], RouteCount { source_city: "Berlin".into(), destination_city: "Hamburg".into(), count: 2 });
+
+        let month = history.local_stats(StatsPeriod::Month, Currency::Eur, now).unwrap();
+        assert_eq!(month.jobs, 4);
+        assert_eq!(month.top_cargo.iter().map(|cargo| cargo.count).collect::<Vec<_>>(), [2, 2]);
+    }
+}
@@ -0,0 +1,384 @@
+//! Import Module
+//!
+//! Reads logbook exports from other trackers (Trucky, TrucksBook; CSV or
+//! JSON) and maps them onto our job fields, so drivers keep their history
+//! when migrating.
+
+use std::collections::HashSet;
+use std::io::Read;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::games;
+use crate::names;
+use crate::storage::{SecureStorage, StorageError};
+use crate::sync::JobSubmission;
+use crate::telemetry::Game;
+
+/// Storage key for the imported logbook
+const IMPORTED_KEY: &str = "imported_jobs";
+
+/// Supported export formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogbookFormat {
+    Trucky,
+    TrucksBook,
+}
+
+impl LogbookFormat {
+    /// Accepted column headers per field, lowercase
+    fn columns(self, field: Field) -> &'static [&'static str] {
+        match (self, field) {
+            (LogbookFormat::Trucky, Field::Id) => &["id", "job_id"],
+            (LogbookFormat::Trucky, Field::Game) => &["game", "game_id"],
+            (LogbookFormat::Trucky, Field::Cargo) => &["cargo_name", "cargo"],
+            (LogbookFormat::Trucky, Field::Source) => &["source_city_name", "source_city"],
+            (LogbookFormat::Trucky, Field::Destination) => &["destination_city_name", "destination_city"],
+            (LogbookFormat::Trucky, Field::Distance) => &["driven_distance_km", "planned_distance_km", "distance"],
+            (LogbookFormat::Trucky, Field::Revenue) => &["income", "revenue"],
+            (LogbookFormat::Trucky, Field::Damage) => &["cargo_damage", "damage"],
+            (LogbookFormat::Trucky, Field::DeliveredAt) => &["completed_at", "delivered_at"],
+            (LogbookFormat::TrucksBook, Field::Id) => &["job id", "id"],
+            (LogbookFormat::TrucksBook, Field::Game) => &["game"],
+            (LogbookFormat::TrucksBook, Field::Cargo) => &["cargo"],
+            (LogbookFormat::TrucksBook, Field::Source) => &["from", "source city"],
+            (LogbookFormat::TrucksBook, Field::Destination) => &["to", "destination city"],
+            (LogbookFormat::TrucksBook, Field::Distance) => &["distance", "distance (km)"],
+            (LogbookFormat::TrucksBook, Field::Revenue) => &["income", "profit"],
+            (LogbookFormat::TrucksBook, Field::Damage) => &["damage", "damage (%)"],
+            (LogbookFormat::TrucksBook, Field::DeliveredAt) => &["date", "delivered"],
+        }
+    }
+}
+
+impl std::fmt::Display for LogbookFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LogbookFormat::Trucky => write!(f, "trucky"),
+            LogbookFormat::TrucksBook => write!(f, "trucksbook"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Field {
+    Id,
+    Game,
+    Cargo,
+    Source,
+    Destination,
+    Distance,
+    Revenue,
+    Damage,
+    DeliveredAt,
+}
+
+/// A job read from another tracker's export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedJob {
+    pub source: LogbookFormat,
+    /// ID in the original tracker
+    pub external_id: Option<String>,
+    pub game: String,
+    pub cargo: String,
+    pub source_city: String,
+    pub destination_city: String,
+    pub distance_km: u32,
+    pub revenue: f64,
+    pub damage_percent: f64,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl ImportedJob {
+    /// Key used to skip jobs that were already imported
+    fn dedup_key(&self) -> String {
+        match &self.external_id {
+            Some(id) => format!("{}:{}", self.source, id),
+            None => format!(
+                "{}:{}:{}:{}:{:?}",
+                self.source, self.source_city, self.destination_city, self.distance_km, self.delivered_at
+            ),
+        }
+    }
+
+    /// Build a historical submission flagged as imported
+    pub fn to_submission(&self) -> JobSubmission {
+        let game = games::from_id(&self.game).unwrap_or(Game::Ets2);
+        JobSubmission {
+            game: self.game.clone(),
+            cargo: names::canonical_cargo(&self.cargo),
+            source_city: names::canonical_city(game, &self.source_city),
+            destination_city: names::canonical_city(game, &self.destination_city),
+            distance_km: self.distance_km,
+            revenue: self.revenue,
+            currency: Some(games::data(game).currency),
+            damage_percent: self.damage_percent,
+            chassis_damage_percent: 0.0,
+            truck_id: None,
+            trailer_id: None,
+            trailer_ownership: None,
+            telemetry_data: Some(serde_json::json!({
+                "imported": {
+                    "source": self.source,
+                    "externalId": self.external_id,
+                    "deliveredAt": self.delivered_at,
+                },
+            })),
+            server: None,
+            fuel_used: None,
+            average_consumption: None,
+            driven_km: None,
+            navigation_km: None,
+            // Stable per source job, so importing the same file twice is harmless
+            idempotency_key: Some(self.dedup_key()),
+            partial: false,
+        }
+    }
+}
+
+/// Parsed export contents
+#[derive(Debug, Default)]
+pub struct ParsedLogbook {
+    pub jobs: Vec<ImportedJob>,
+    /// Rows that could not be mapped (missing route or unreadable numbers)
+    pub skipped_rows: usize,
+}
+
+/// Import errors
+#[derive(Debug, thiserror::Error)]
+pub enum ImportError {
+    #[error("Failed to read export: {0}")]
+    Read(String),
+
+    #[error("Missing column for {0}")]
+    MissingColumn(String),
+}
+
+/// Parse an export in `format`, CSV or JSON depending on its contents
+pub fn parse<R: Read>(format: LogbookFormat, mut reader: R) -> Result<ParsedLogbook, ImportError> {
+    let mut contents = Vec::new();
+    reader.read_to_end(&mut contents).map_err(|e| ImportError::Read(e.to_string()))?;
+    let text = String::from_utf8_lossy(&contents);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    if text.starts_with('[') || text.starts_with('{') {
+        parse_json(format, text)
+    } else {
+        parse_csv(format, text.as_bytes())
+    }
+}
+
+/// Parse a JSON export in `format`: an array of jobs, or an object holding
+/// one under `data` or `jobs` as API dumps do
+pub fn parse_json(format: LogbookFormat, text: &str) -> Result<ParsedLogbook, ImportError> {
+    let value: serde_json::Value = serde_json::from_str(text).map_err(|e| ImportError::Read(e.to_string()))?;
+    let rows = match &value {
+        serde_json::Value::Array(rows) => rows,
+        object => ["data", "jobs"].iter()
+            .find_map(|key| object.get(key).and_then(serde_json::Value::as_array))
+            .ok_or_else(|| ImportError::Read("no list of jobs found".into()))?,
+    };
+
+    let mut parsed = ParsedLogbook::default();
+    for row in rows {
+        let Some(fields) = row.as_object() else {
+            parsed.skipped_rows += 1;
+            continue;
+        };
+        let get = |field: Field| {
+            format.columns(field).iter()
+                .find_map(|name| fields.iter().find(|(key, _)| key.to_lowercase() == *name).map(|(_, value)| value))
+                .and_then(json_text)
+        };
+        match map_row(format, get) {
+            Some(job) => parsed.jobs.push(job),
+            None => parsed.skipped_rows += 1,
+        }
+    }
+    Ok(parsed)
+}
+
+/// Text of a JSON field; objects such as `{"id": .., "name": "Berlin"}` give their name
+fn json_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(text) => Some(text.trim().to_string()).filter(|text| !text.is_empty()),
+        serde_json::Value::Number(number) => Some(number.to_string()),
+        serde_json::Value::Object(object) => object.get("name").and_then(json_text),
+        _ => None,
+    }
+}
+
+/// Map one export row; `None` when it has no usable route
+fn map_row(format: LogbookFormat, get: impl Fn(Field) -> Option<String>) -> Option<ImportedJob> {
+    let source_city = get(Field::Source)?;
+    let destination_city = get(Field::Destination)?;
+    let distance_km = get(Field::Distance).as_deref().and_then(parse_number)?;
+    Some(ImportedJob {
+        source: format,
+        external_id: get(Field::Id),
+        game: get(Field::Game).as_deref().map(normalize_game).unwrap_or_else(|| "ets2".into()),
+        cargo: get(Field::Cargo).unwrap_or_default(),
+        source_city,
+        destination_city,
+        distance_km: distance_km.round().max(0.0) as u32,
+        revenue: get(Field::Revenue).as_deref().and_then(parse_number).unwrap_or_default(),
+        damage_percent: get(Field::Damage).as_deref().and_then(parse_number).unwrap_or_default(),
+        delivered_at: get(Field::DeliveredAt).as_deref().and_then(parse_date),
+    })
+}
+
+/// Parse a CSV export in `format`
+pub fn parse_csv<R: Read>(format: LogbookFormat, reader: R) -> Result<ParsedLogbook, ImportError> {
+    let mut csv = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    let headers: Vec<String> = csv.headers()
+        .map_err(|e| ImportError::Read(e.to_string()))?
+        .iter()
+        .map(|header| header.trim_start_matches('\u{feff}').to_lowercase())
+        .collect();
+    let column = |field: Field| {
+        format.columns(field).iter()
+            .find_map(|name| headers.iter().position(|header| header == name))
+    };
+
+    let required = |field: Field, name: &str| column(field).map(|_| ()).ok_or_else(|| ImportError::MissingColumn(name.into()));
+    required(Field::Source, "source city")?;
+    required(Field::Destination, "destination city")?;
+    required(Field::Distance, "distance")?;
+
+    let mut parsed = ParsedLogbook::default();
+    for (line, record) in csv.records().enumerate() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                debug!("Skipping unreadable row {}: {}", line + 2, e);
+                parsed.skipped_rows += 1;
+                continue;
+            }
+        };
+        let get = |field: Field| {
+            column(field)
+                .and_then(|i| record.get(i))
+                .filter(|value| !value.is_empty())
+                .map(String::from)
+        };
+        match map_row(format, get) {
+            Some(job) => parsed.jobs.push(job),
+            None => parsed.skipped_rows += 1,
+        }
+    }
+    Ok(parsed)
+}
+
+/// Add `jobs` to the stored logbook; returns the ones that were new
+pub fn store(storage: &SecureStorage, jobs: Vec<ImportedJob>) -> Result<Vec<ImportedJob>, StorageError> {
+    let mut stored = load(storage);
+    let mut seen: HashSet<String> = stored.iter().map(ImportedJob::dedup_key).collect();
+
+    let added: Vec<ImportedJob> = jobs.into_iter()
+        .filter(|job| seen.insert(job.dedup_key()))
+        .collect();
+    if !added.is_empty() {
+        stored.extend(added.iter().cloned());
+        storage.save(IMPORTED_KEY, &stored)?;
+    }
+    Ok(added)
+}
+
+/// Load previously imported jobs
+pub fn load(storage: &SecureStorage) -> Vec<ImportedJob> {
+    if !storage.exists(IMPORTED_KEY) {
+        return Vec::new();
+    }
+    storage.load(IMPORTED_KEY).unwrap_or_else(|e| {
+        warn!("Failed to load imported jobs: {}", e);
+        Vec::new()
+    })
+}
+
+/// Parse numbers like "12,345", "€ 1.234,50" or "3.5 %"
+fn parse_number(value: &str) -> Option<f64> {
+    let cleaned: String = value.chars()
+        .filter(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | '-'))
+        .collect();
+    // A trailing two-digit group after a comma is a decimal comma
+    let normalized = match cleaned.rfind(',') {
+        Some(i) if cleaned.len() - i == 3 && !cleaned[i..].contains('.') => {
+            cleaned[..i].replace('.', "") + "." + &cleaned[i + 1..]
+        }
+        _ => cleaned.replace(',', ""),
+    };
+    normalized.parse().ok()
+}
+
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|date| date.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+                .or_else(|_| NaiveDateTime::parse_from_str(value, "%d.%m.%Y %H:%M"))
+                .ok()
+                .map(|date| date.and_utc())
+        })
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|date| date.and_utc())
+        })
+}
+
+fn normalize_game(value: &str) -> String {
+    let value = value.to_lowercase();
+    if value.contains("ats") || value.contains("american") {
+        "ats".into()
+    } else {
+        "ets2".into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_trucksbook_columns() {
+        let export = "\u{feff}Job ID,Game,Cargo,From,To,Distance,Income,Damage,Date\n\
+            981,Euro Truck Simulator 2,Machinery,Berlin,Hamburg,\"1,290 km\",\"€ 12.345,50\",2.5 %,2025-02-03 18:20:00\n\
+            982,American Truck Simulator,Lumber,Reno,,410,5000,0,2025-02-04\n";
+
+        let parsed = parse_csv(LogbookFormat::TrucksBook, export.as_bytes()).unwrap();
+        assert_eq!(parsed.skipped_rows, 1);
+        let job = &parsed.jobs[0];
+        assert_eq!(job.external_id.as_deref(), Some("981"));
+        assert_eq!(job.game, "ets2");
+        assert_eq!(job.distance_km, 1290);
+        assert_eq!(job.revenue, 12345.5);
+        assert_eq!(job.damage_percent, 2.5);
+        assert!(job.delivered_at.is_some());
+        assert_eq!(job.to_submission().telemetry_data.unwrap()["imported"]["source"], "trucksbook");
+    }
+
+    #[test]
+    fn maps_trucky_json() {
+        let export = r#"{"data": [
+            {"id": 77, "game": "ets2", "cargo_name": "Apples", "source_city": {"id": 4, "name": "Lyon"},
+             "destination_city_name": "Paris", "driven_distance_km": 466.4, "income": 18200, "completed_at": "2025-02-03T18:20:00Z"},
+            {"id": 78, "source_city_name": "Lyon"},
+            "not a job"
+        ]}"#;
+
+        let parsed = parse(LogbookFormat::Trucky, export.as_bytes()).unwrap();
+        assert_eq!(parsed.skipped_rows, 2);
+        let job = &parsed.jobs[0];
+        assert_eq!(job.external_id.as_deref(), Some("77"));
+        assert_eq!((job.source_city.as_str(), job.destination_city.as_str()), ("Lyon", "Paris"));
+        assert_eq!((job.distance_km, job.revenue), (466, 18200.0));
+        assert!(job.delivered_at.is_some());
+    }
+}
@@ -0,0 +1,256 @@
+//! Integrity Module
+//!
+//! Records signs of tampering while a job is driven: position jumps that no
+//! truck could drive, impossible speeds, accelerated game time and job terms
+//! edited mid-delivery, and jobs driven in the telemetry simulator. The
+//! summary travels in `telemetry_data.integrity`; the device signature over
+//! the request body is what tells the server it wasn't edited on the way.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::telemetry::Position;
+
+/// Format of the summary, bumped when its fields change meaning
+const REPORT_VERSION: u32 = 1;
+/// Most signals kept per job; later ones are only counted
+const MAX_SIGNALS: usize = 20;
+/// Faster than any truck, even with engine mods (km/h)
+const MAX_PLAUSIBLE_SPEED_KMH: f32 = 250.0;
+/// Position changes shorter than this are never a teleport (m)
+const MIN_JUMP_M: f64 = 500.0;
+/// Average speed between two polls above which the truck was moved (km/h)
+const MAX_JUMP_SPEED_KMH: f64 = 400.0;
+/// Game seconds per real second at the default time scale
+const NORMAL_TIME_SCALE: f64 = 20.0;
+/// Time scales above this many times normal count as time acceleration
+const MAX_TIME_SCALE_FACTOR: f64 = 3.0;
+/// Real time to observe before judging the time scale (s)
+const MIN_TIME_SCALE_SAMPLE_SECS: f64 = 300.0;
+/// Polls further apart than this are not compared (s); the game or app was suspended
+const MAX_SAMPLE_GAP_SECS: f64 = 5.0;
+/// Game time jumps larger than this in one poll are sleeping, not acceleration (min)
+const MAX_GAME_MINUTES_PER_POLL: u32 = 30;
+
+/// One telemetry reading, as far as the checks need it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sample {
+    /// Game render timestamp (µs), advancing in real time
+    pub render_time: u64,
+    /// In-game time (minutes)
+    pub game_time: u32,
+    pub paused: bool,
+    pub speed_kmh: f32,
+    pub position: Option<Position>,
+    /// A ferry or train is moving the truck
+    pub transported: bool,
+    /// Produced by the telemetry simulator
+    #[serde(default)]
+    pub simulated: bool,
+}
+
+/// Something that shouldn't happen in an unmodified game
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum SignalKind {
+    /// The truck moved further between two polls than it could drive
+    Teleport { distance_m: f64, seconds: f64 },
+    SpeedSpike { speed_kmh: f32 },
+    /// Game time ran faster than the game allows
+    TimeAcceleration { scale: f64 },
+    /// Job terms changed while it was being delivered
+    JobEdited { field: String, from: u64, to: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Signal {
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub kind: SignalKind,
+}
+
+/// Signals collected during one job
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IntegrityMonitor {
+    pub signals: Vec<Signal>,
+    /// Signals beyond `MAX_SIGNALS`
+    pub dropped: u32,
+    pub max_speed_kmh: f32,
+    /// Game minutes and real seconds seen while the game ran unpaused
+    pub game_minutes: f64,
+    pub real_secs: f64,
+    /// Revenue and planned distance when the job was first seen
+    pub terms: Option<(u64, u32)>,
+    /// Previous sample; cleared when the job resumes in a new session
+    pub last: Option<Sample>,
+    /// Some of the job was driven in the simulator
+    pub simulated: bool,
+}
+
+impl IntegrityMonitor {
+    /// Compare a poll with the previous one
+    pub fn observe(&mut self, sample: Sample, now: DateTime<Utc>) {
+        self.max_speed_kmh = self.max_speed_kmh.max(sample.speed_kmh);
+        self.simulated |= sample.simulated;
+        let was_spiking = self.last.as_ref().is_some_and(|last| last.speed_kmh > MAX_PLAUSIBLE_SPEED_KMH);
+        if sample.speed_kmh > MAX_PLAUSIBLE_SPEED_KMH && !was_spiking {
+            self.record(SignalKind::SpeedSpike { speed_kmh: sample.speed_kmh }, now);
+        }
+
+        if let Some(last) = self.last.take() {
+            let seconds = sample.render_time.saturating_sub(last.render_time) as f64 / 1_000_000.0;
+            if seconds > 0.0 && seconds <= MAX_SAMPLE_GAP_SECS {
+                self.compare(&last, &sample, seconds, now);
+            }
+        }
+        self.last = Some(sample);
+    }
+
+    fn compare(&mut self, last: &Sample, sample: &Sample, seconds: f64, now: DateTime<Utc>) {
+        if let (Some(from), Some(to)) = (&last.position, &sample.position) {
+            let distance_m = ((to.x - from.x).powi(2) + (to.z - from.z).powi(2)).sqrt();
+            let speed_kmh = distance_m / seconds * 3.6;
+            let transported = last.transported || sample.transported;
+            if distance_m > MIN_JUMP_M && speed_kmh > MAX_JUMP_SPEED_KMH && !transported {
+                self.record(SignalKind::Teleport { distance_m: distance_m.round(), seconds }, now);
+            }
+        }
+
+        let game_minutes = sample.game_time.saturating_sub(last.game_time);
+        if !sample.paused && !last.paused && game_minutes <= MAX_GAME_MINUTES_PER_POLL {
+            self.game_minutes += f64::from(game_minutes);
+            self.real_secs += seconds;
+        }
+    }
+
+    /// Compare the job's terms with those it was first seen with
+    pub fn observe_terms(&mut self, revenue: u64, planned_km: u32, now: DateTime<Utc>) {
+        let Some((first_revenue, first_km)) = self.terms else {
+            self.terms = Some((revenue, planned_km));
+            return;
+        };
+        if revenue != first_revenue {
+            self.record(SignalKind::JobEdited { field: "revenue".into(), from: first_revenue, to: revenue }, now);
+        }
+        if planned_km != first_km {
+            let edit = SignalKind::JobEdited { field: "distanceKm".into(), from: first_km.into(), to: planned_km.into() };
+            self.record(edit, now);
+        }
+        self.terms = Some((revenue, planned_km));
+    }
+
+    /// Game seconds per real second, once enough time was observed
+    pub fn time_scale(&self) -> Option<f64> {
+        (self.real_secs >= MIN_TIME_SCALE_SAMPLE_SECS).then(|| self.game_minutes * 60.0 / self.real_secs)
+    }
+
+    fn record(&mut self, kind: SignalKind, now: DateTime<Utc>) {
+        if self.signals.len() >= MAX_SIGNALS {
+            self.dropped += 1;
+            return;
+        }
+        self.signals.push(Signal { at: now, kind });
+    }
+
+    /// Summary attached to the job's submission
+    pub fn report(&self, now: DateTime<Utc>) -> IntegrityReport {
+        let time_scale = self.time_scale();
+        let mut signals = self.signals.clone();
+        if let Some(scale) = time_scale.filter(|scale| *scale > NORMAL_TIME_SCALE * MAX_TIME_SCALE_FACTOR) {
+            signals.push(Signal { at: now, kind: SignalKind::TimeAcceleration { scale } });
+        }
+        IntegrityReport {
+            version: REPORT_VERSION,
+            flagged: !signals.is_empty() || self.dropped > 0 || self.simulated,
+            signals,
+            dropped: self.dropped,
+            max_speed_kmh: self.max_speed_kmh,
+            time_scale,
+            simulated: self.simulated,
+        }
+    }
+}
+
+/// Integrity summary of one job
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub version: u32,
+    pub flagged: bool,
+    pub signals: Vec<Signal>,
+    pub dropped: u32,
+    pub max_speed_kmh: f32,
+    pub time_scale: Option<f64>,
+    /// Driven in the simulator, not the game
+    pub simulated: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn sample(secs: f64, x: f64, speed_kmh: f32) -> Sample {
+        Sample {
+            render_time: (secs * 1_000_000.0) as u64,
+            game_time: (secs / 3.0) as u32,
+            paused: false,
+            speed_kmh,
+            position: Some(Position { x, y: 0.0, z: 0.0, heading: 0.0 }),
+            transported: false,
+            simulated: false,
+        }
+    }
+
+    #[test]
+    fn flags_teleports_spikes_and_edits_but_not_ferries() {
+        let t = Utc.with_ymd_and_hms(2025, 3, 1, 19, 0, 0).unwrap();
+        let mut monitor = IntegrityMonitor::default();
+        monitor.observe_terms(12_000, 290, t);
+        for i in 0..3600 {
+            let secs = f64::from(i) * 0.1;
+            monitor.observe(sample(secs, secs * 25.0, 90.0), t);
+        }
+        assert_eq!(monitor.report(t), IntegrityReport {
+            version: 1,
+            flagged: false,
+            signals: Vec::new(),
+            dropped: 0,
+            max_speed_kmh: 90.0,
+            time_scale: monitor.time_scale(),
+            simulated: false,
+        });
+        assert!((monitor.time_scale().unwrap() - 20.0).abs() < 0.5);
+
+        // A ferry crossing moves the truck without flagging it
+        monitor.observe(Sample { transported: true, ..sample(360.1, 80_000.0, 0.0) }, t);
+        monitor.observe(sample(360.2, 200_000.0, 300.0), t);
+        monitor.observe(sample(360.3, 300_000.0, 320.0), t);
+        monitor.observe_terms(99_000, 290, t);
+
+        let report = monitor.report(t);
+        assert!(report.flagged);
+        let kinds: Vec<_> = report.signals.iter().map(|signal| &signal.kind).collect();
+        assert!(matches!(kinds[..], [
+            SignalKind::SpeedSpike { .. },
+            SignalKind::Teleport { .. },
+            SignalKind::JobEdited { .. },
+        ]));
+    }
+
+    #[test]
+    fn flags_jobs_driven_in_the_simulator() {
+        let t = Utc.with_ymd_and_hms(2025, 3, 1, 19, 0, 0).unwrap();
+        let mut monitor = IntegrityMonitor::default();
+        monitor.observe(sample(0.0, 0.0, 80.0), t);
+        assert!(!monitor.report(t).flagged);
+
+        monitor.observe(Sample { simulated: true, ..sample(0.1, 2.0, 80.0) }, t);
+        monitor.observe(sample(0.2, 4.0, 80.0), t);
+        let report = monitor.report(t);
+        assert!(report.flagged && report.simulated && report.signals.is_empty());
+    }
+}
@@ -0,0 +1,175 @@
+//! Job Queue Module
+//!
+//! Job submissions waiting to be sent, each kept with the account it was
+//! recorded for so a drain only ever sends the signed-in account's jobs.
+//! Jobs without an account (queued before accounts were recorded) go to
+//! the first account that drains the queue. A job being submitted stays
+//! queued, marked in flight, until its outcome is known, so a checkpoint
+//! taken meanwhile still holds it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::sync::JobSubmission;
+
+/// A queued submission and the account it belongs to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedJob {
+    /// `None` for jobs queued without an account
+    pub user_id: Option<String>,
+    pub submission: JobSubmission,
+    /// Being submitted right now; not persisted, so a crash makes it pending again
+    #[serde(skip)]
+    in_flight: bool,
+}
+
+impl QueuedJob {
+    /// Whether `user_id` may send this job
+    fn belongs_to(&self, user_id: &str) -> bool {
+        self.user_id.as_deref().map_or(true, |owner| owner == user_id)
+    }
+}
+
+/// Submissions waiting to be retried, per account
+#[derive(Debug, Clone, Default)]
+pub struct JobQueue {
+    jobs: Vec<QueuedJob>,
+}
+
+impl JobQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue restored from a checkpoint
+    pub fn from_jobs(jobs: Vec<QueuedJob>) -> Self {
+        Self { jobs }
+    }
+
+    /// Queue `submission` for `user_id`
+    pub fn push(&mut self, user_id: Option<&str>, submission: JobSubmission) {
+        self.jobs.push(QueuedJob { user_id: user_id.map(str::to_string), submission, in_flight: false });
+    }
+
+    /// Queue `submission` for `user_id` as it is being submitted; `resolve`
+    /// or `release` it once the outcome is known
+    pub fn begin(&mut self, user_id: &str, submission: JobSubmission) {
+        self.jobs.push(QueuedJob { user_id: Some(user_id.to_string()), submission, in_flight: true });
+    }
+
+    /// Mark every job `user_id` may send as in flight and return copies,
+    /// claiming those without an account; jobs already in flight are skipped
+    pub fn start_drain(&mut self, user_id: &str) -> Vec<JobSubmission> {
+        self.jobs.iter_mut()
+            .filter(|job| !job.in_flight && job.belongs_to(user_id))
+            .map(|job| {
+                job.user_id = Some(user_id.to_string());
+                job.in_flight = true;
+                job.submission.clone()
+            })
+            .collect()
+    }
+
+    /// The submission went through or can never succeed; drop it
+    pub fn resolve(&mut self, submission: &JobSubmission) {
+        let key = submission.request_key();
+        self.jobs.retain(|job| !(job.in_flight && job.submission.request_key() == key));
+    }
+
+    /// The submission failed or was interrupted; keep it for the next retry
+    pub fn release(&mut self, submission: &JobSubmission) {
+        let key = submission.request_key();
+        if let Some(job) = self.jobs.iter_mut().find(|job| job.in_flight && job.submission.request_key() == key) {
+            job.in_flight = false;
+        }
+    }
+
+    /// Take the waiting job of `user_id` whose submission has `digest`
+    pub fn remove(&mut self, user_id: &str, digest: &str) -> Option<JobSubmission> {
+        let index = self.jobs.iter()
+            .position(|job| !job.in_flight && job.belongs_to(user_id) && job.submission.digest() == digest)?;
+        Some(self.jobs.remove(index).submission)
+    }
+
+    /// Jobs `user_id` would send, including those in flight
+    pub fn pending(&self, user_id: &str) -> usize {
+        self.jobs.iter().filter(|job| job.belongs_to(user_id)).count()
+    }
+
+    /// Jobs of every account
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    /// Every queued job, in flight or not, for checkpoints
+    pub fn jobs(&self) -> &[QueuedJob] {
+        &self.jobs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(cargo: &str) -> JobSubmission {
+        JobSubmission {
+            game: "ets2".into(),
+            cargo: cargo.into(),
+            source_city: "Berlin".into(),
+            destination_city: "Hamburg".into(),
+            distance_km: 290,
+            revenue: 12_000.0,
+            currency: None,
+            damage_percent: 0.0,
+            chassis_damage_percent: 0.0,
+            truck_id: None,
+            trailer_id: None,
+            trailer_ownership: None,
+            telemetry_data: None,
+            server: None,
+            fuel_used: None,
+            average_consumption: None,
+            driven_km: None,
+            navigation_km: None,
+            idempotency_key: Some(cargo.into()),
+            partial: false,
+        }
+    }
+
+    #[test]
+    fn drains_only_the_accounts_own_jobs() {
+        let mut queue = JobQueue::new();
+        queue.push(Some("alice"), job("apples"));
+        queue.push(Some("bob"), job("bricks"));
+        queue.push(None, job("cement"));
+        assert_eq!((queue.pending("alice"), queue.pending("bob")), (2, 2));
+
+        let cargo = |jobs: Vec<JobSubmission>| jobs.into_iter().map(|job| job.cargo).collect::<Vec<_>>();
+        assert_eq!(cargo(queue.start_drain("bob")), ["bricks", "cement"]);
+        assert_eq!(queue.pending("alice"), 1);
+        assert!(queue.remove("bob", &job("apples").digest()).is_none());
+        assert!(queue.remove("alice", &job("apples").digest()).is_some());
+        assert_eq!(queue.len(), 2);
+    }
+
+    #[test]
+    fn jobs_stay_queued_until_resolved() {
+        let mut queue = JobQueue::new();
+        queue.push(Some("alice"), job("apples"));
+        queue.begin("alice", job("bricks"));
+
+        // A drain leaves the job being submitted alone
+        assert_eq!(queue.start_drain("alice").len(), 1);
+        assert!(queue.start_drain("alice").is_empty());
+        assert_eq!(queue.jobs().len(), 2);
+
+        queue.resolve(&job("bricks"));
+        queue.release(&job("apples"));
+        assert_eq!(queue.jobs().len(), 1);
+        assert_eq!(queue.start_drain("alice").len(), 1);
+    }
+}
@@ -0,0 +1,93 @@
+//! Last Errors Module
+//!
+//! Most recent failure of each subsystem with a stable code, so the
+//! troubleshooting panel can show the actual cause of a problem.
+
+use std::collections::BTreeMap;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Part of the app an error came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Subsystem {
+    Telemetry,
+    Sync,
+    Auth,
+    Storage,
+}
+
+/// Latest failure of one subsystem
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsystemError {
+    pub subsystem: Subsystem,
+    /// Machine-readable cause, e.g. `network` or `decryption`
+    pub code: String,
+    pub message: String,
+    pub at: DateTime<Utc>,
+    /// Set once the subsystem worked again after the error
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// Last error per subsystem
+#[derive(Debug, Default)]
+pub struct LastErrors {
+    errors: BTreeMap<Subsystem, SubsystemError>,
+}
+
+impl LastErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the subsystem's last error
+    pub fn record(&mut self, subsystem: Subsystem, code: &str, message: impl Into<String>, at: DateTime<Utc>) {
+        self.errors.insert(subsystem, SubsystemError {
+            subsystem,
+            code: code.to_string(),
+            message: message.into(),
+            at,
+            resolved_at: None,
+        });
+    }
+
+    /// The subsystem succeeded; its last error stays visible as resolved
+    pub fn resolve(&mut self, subsystem: Subsystem, at: DateTime<Utc>) {
+        if let Some(error) = self.errors.get_mut(&subsystem) {
+            error.resolved_at.get_or_insert(at);
+        }
+    }
+
+    /// Every subsystem's last error
+    pub fn all(&self) -> Vec<SubsystemError> {
+        self.errors.values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn keeps_latest_error_until_resolved() {
+        let t = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let mut errors = LastErrors::new();
+        errors.resolve(Subsystem::Sync, t);
+        assert!(errors.all().is_empty());
+
+        errors.record(Subsystem::Sync, "network", "Network error: timed out", t);
+        errors.record(Subsystem::Sync, "server", "Server error: Invalid job", t + Duration::seconds(5));
+        errors.record(Subsystem::Storage, "decryption", "Decryption error: bad key", t);
+        errors.resolve(Subsystem::Sync, t + Duration::seconds(10));
+        errors.resolve(Subsystem::Sync, t + Duration::seconds(20));
+
+        let all = errors.all();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].code, "server");
+        assert_eq!(all[0].resolved_at, Some(t + Duration::seconds(10)));
+        assert_eq!(all[1].subsystem, Subsystem::Storage);
+        assert!(all[1].resolved_at.is_none());
+    }
+}
@@ -0,0 +1,58 @@
+//! VTC Tracker Core
+//!
+//! Telemetry reading, job tracking, API sync, storage and auth, independent of
+//! Tauri so headless tools (CLI loggers, relay boxes) can reuse the tracking
+//! logic. The desktop app is a thin shell around this crate.
+
+pub mod app_health;
+pub mod audit;
+pub mod auth;
+pub mod checkpoint;
+pub mod clock;
+pub mod connectivity;
+pub mod convoy;
+pub mod currency;
+pub mod dedup;
+pub mod distances;
+pub mod events;
+pub mod focus;
+pub mod games;
+pub mod heartbeat;
+pub mod history;
+pub mod import;
+pub mod integrity;
+pub mod job_queue;
+pub mod storage;
+pub mod sync;
+pub mod sync_health;
+pub mod telemetry;
+pub mod last_errors;
+pub mod local_api;
+pub mod local_auth;
+pub mod logging;
+pub mod maintenance;
+pub mod manual;
+pub mod minimap;
+pub mod multiplayer;
+pub mod names;
+pub mod overlay;
+pub mod positions;
+pub mod power;
+pub mod preflight;
+pub mod privacy;
+pub mod processes;
+pub mod progress;
+pub mod reconnect;
+pub mod route;
+pub mod rules;
+pub mod scs;
+pub mod server_status;
+pub mod settings;
+pub mod signing;
+pub mod snapshots;
+pub mod speeding;
+pub mod tachograph;
+pub mod vehicles;
+
+#[cfg(test)]
+mod harness;
@@ -0,0 +1,234 @@
+//! Local API Module
+//!
+//! Loopback HTTP/WebSocket endpoint for the overlay and local integrations.
+//! Every request must carry the install's `LocalAccessToken`, so a web page
+//! open in a browser on the same machine can't read live telemetry.
+//!
+//! - `GET /telemetry` returns the latest telemetry as JSON
+//! - `GET /telemetry/stream` upgraded to a WebSocket pushes it periodically
+
+use std::sync::Arc;
+use std::time::Duration;
+use futures_util::{SinkExt, StreamExt};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+use crate::local_auth::LocalAccessToken;
+
+/// Where the local API listens
+pub const LOCAL_API_ADDR: &str = "127.0.0.1:30421";
+/// Interval between frames on the telemetry stream
+const STREAM_INTERVAL: Duration = Duration::from_millis(250);
+/// Longest request head accepted
+const MAX_HEAD_BYTES: usize = 8 * 1024;
+/// Time a client gets to send its request head
+const HEAD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// What the local API serves
+pub trait LocalSource: Send + Sync + 'static {
+    /// Token clients must present, read per request so a rotation applies at once
+    fn token(&self) -> LocalAccessToken;
+
+    /// Latest telemetry, already stripped of what privacy settings hide
+    fn telemetry(&self) -> serde_json::Value;
+}
+
+/// Parsed request line and the headers the API cares about
+#[derive(Debug, Default)]
+struct Request {
+    method: String,
+    path: String,
+    query: Option<String>,
+    authorization: Option<String>,
+    websocket_key: Option<String>,
+}
+
+impl Request {
+    fn parse(head: &str) -> Option<Self> {
+        let mut lines = head.split("\r\n");
+        let mut request_line = lines.next()?.split_whitespace();
+        let method = request_line.next()?.to_string();
+        let target = request_line.next()?;
+        let (path, query) = match target.split_once('?') {
+            Some((path, query)) => (path.to_string(), Some(query.to_string())),
+            None => (target.to_string(), None),
+        };
+        let mut request = Request { method, path, query, ..Request::default() };
+        for line in lines {
+            let Some((name, value)) = line.split_once(':') else { continue };
+            let value = value.trim().to_string();
+            if name.eq_ignore_ascii_case("authorization") {
+                request.authorization = Some(value);
+            } else if name.eq_ignore_ascii_case("sec-websocket-key") {
+                request.websocket_key = Some(value);
+            }
+        }
+        Some(request)
+    }
+
+    fn authorized(&self, token: &LocalAccessToken) -> bool {
+        token.verify_request(self.authorization.as_deref(), self.query.as_deref())
+    }
+}
+
+/// Accept local clients until cancelled
+pub async fn serve<S: LocalSource>(listener: TcpListener, source: Arc<S>, cancel: CancellationToken) {
+    loop {
+        let stream = tokio::select! {
+            _ = cancel.cancelled() => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    warn!("Local API accept failed: {}", e);
+                    continue;
+                }
+            },
+        };
+        let source = source.clone();
+        let cancel = cancel.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle(stream, source, cancel).await {
+                debug!("Local API connection ended: {}", e);
+            }
+        });
+    }
+    debug!("Local API stopped");
+}
+
+async fn handle<S: LocalSource>(mut stream: TcpStream, source: Arc<S>, cancel: CancellationToken) -> std::io::Result<()> {
+    let head = match tokio::time::timeout(HEAD_TIMEOUT, read_head(&mut stream)).await {
+        Ok(head) => head?,
+        Err(_) => return Ok(()),
+    };
+    let Some(request) = head.as_deref().and_then(Request::parse) else {
+        return respond(&mut stream, "400 Bad Request", "").await;
+    };
+    if !request.authorized(&source.token()) {
+        return respond(&mut stream, "401 Unauthorized", "").await;
+    }
+    if request.method != "GET" {
+        return respond(&mut stream, "405 Method Not Allowed", "").await;
+    }
+    match (request.path.as_str(), &request.websocket_key) {
+        ("/telemetry", _) => respond(&mut stream, "200 OK", &source.telemetry().to_string()).await,
+        ("/telemetry/stream", Some(key)) => {
+            let accept = derive_accept_key(key.as_bytes());
+            stream.write_all(format!(
+                "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+                accept,
+            ).as_bytes()).await?;
+            let socket = WebSocketStream::from_raw_socket(stream, Role::Server, None).await;
+            stream_telemetry(socket, request, source, cancel).await;
+            Ok(())
+        }
+        _ => respond(&mut stream, "404 Not Found", "").await,
+    }
+}
+
+/// Push telemetry until the client leaves or its token is rotated away
+async fn stream_telemetry<S: LocalSource>(
+    socket: WebSocketStream<TcpStream>,
+    request: Request,
+    source: Arc<S>,
+    cancel: CancellationToken,
+) {
+    let (mut sink, mut incoming) = socket.split();
+    let mut interval = tokio::time::interval(STREAM_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            message = incoming.next() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            _ = interval.tick() => {
+                if !request.authorized(&source.token()) {
+                    break;
+                }
+                if sink.send(Message::text(source.telemetry().to_string())).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    let _ = sink.send(Message::Close(None)).await;
+}
+
+/// Read up to the blank line ending the request head; `None` if it's too long
+async fn read_head(stream: &mut TcpStream) -> std::io::Result<Option<String>> {
+    let mut head = Vec::new();
+    let mut byte = [0u8; 1];
+    while !head.ends_with(b"\r\n\r\n") {
+        if head.len() >= MAX_HEAD_BYTES || stream.read(&mut byte).await? == 0 {
+            return Ok(None);
+        }
+        head.push(byte[0]);
+    }
+    Ok(String::from_utf8(head).ok())
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, body: &str) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body,
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed(LocalAccessToken);
+
+    impl LocalSource for Fixed {
+        fn token(&self) -> LocalAccessToken {
+            self.0.clone()
+        }
+
+        fn telemetry(&self) -> serde_json::Value {
+            serde_json::json!({ "speed": 72.5 })
+        }
+    }
+
+    async fn get(addr: std::net::SocketAddr, target: &str, authorization: Option<&str>) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let header = authorization.map(|value| format!("Authorization: {}\r\n", value)).unwrap_or_default();
+        stream.write_all(format!("GET {} HTTP/1.1\r\nHost: localhost\r\n{}\r\n", target, header).as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_without_the_install_token() {
+        let dir = std::env::temp_dir().join(format!("vtc-local-api-{}", uuid::Uuid::new_v4()));
+        let token = LocalAccessToken::load_or_create(&crate::storage::SecureStorage::in_dir(dir.clone()));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let cancel = CancellationToken::new();
+        tokio::spawn(serve(listener, Arc::new(Fixed(token.clone())), cancel.clone()));
+
+        assert!(get(addr, "/telemetry", None).await.starts_with("HTTP/1.1 401"));
+        assert!(get(addr, "/telemetry?token=nope", None).await.starts_with("HTTP/1.1 401"));
+        assert!(get(addr, "/telemetry/stream", Some("Bearer nope")).await.starts_with("HTTP/1.1 401"));
+
+        let bearer = format!("Bearer {}", token.as_str());
+        let ok = get(addr, "/telemetry", Some(&bearer)).await;
+        assert!(ok.starts_with("HTTP/1.1 200") && ok.ends_with(r#"{"speed":72.5}"#), "{}", ok);
+        let query = format!("/telemetry?token={}", token.as_str());
+        assert!(get(addr, &query, None).await.starts_with("HTTP/1.1 200"));
+        assert!(get(addr, "/settings", Some(&bearer)).await.starts_with("HTTP/1.1 404"));
+        cancel.cancel();
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,97 @@
+//! Local Access Module
+//!
+//! Per-install token required by local HTTP/WebSocket endpoints, so web pages
+//! open in a browser on the same machine can't read live telemetry.
+
+use tracing::{info, warn};
+
+use crate::storage::{SecureStorage, StorageError};
+
+/// Storage key for the local access token
+const TOKEN_KEY: &str = "local_access_token";
+
+/// Secret shared between the app and its local clients (overlay, integrations)
+#[derive(Clone)]
+pub struct LocalAccessToken(String);
+
+impl LocalAccessToken {
+    /// Load the install's token, creating one on first run
+    pub fn load_or_create(storage: &SecureStorage) -> Self {
+        match storage.load::<String>(TOKEN_KEY) {
+            Ok(token) if !token.is_empty() => Self(token),
+            _ => Self::regenerate(storage).unwrap_or_else(|e| {
+                warn!("Failed to persist local access token: {}", e);
+                Self(generate())
+            }),
+        }
+    }
+
+    /// Replace the token, invalidating all existing local clients
+    pub fn regenerate(storage: &SecureStorage) -> Result<Self, StorageError> {
+        let token = generate();
+        storage.save(TOKEN_KEY, &token)?;
+        info!("Generated new local access token");
+        Ok(Self(token))
+    }
+
+    /// The raw token value
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Check a presented token in constant time
+    pub fn verify(&self, presented: &str) -> bool {
+        let expected = self.0.as_bytes();
+        let presented = presented.as_bytes();
+        if expected.len() != presented.len() {
+            return false;
+        }
+        expected.iter()
+            .zip(presented)
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+    }
+
+    /// Check an incoming local request.
+    ///
+    /// Accepts `Authorization: Bearer <token>` or a `token=<token>` query parameter,
+    /// since browser WebSocket clients cannot set headers.
+    pub fn verify_request(&self, authorization: Option<&str>, query: Option<&str>) -> bool {
+        if let Some(token) = authorization.and_then(|h| h.strip_prefix("Bearer ")) {
+            return self.verify(token.trim());
+        }
+
+        query
+            .into_iter()
+            .flat_map(|q| q.split('&'))
+            .filter_map(|pair| pair.strip_prefix("token="))
+            .any(|token| self.verify(token))
+    }
+}
+
+impl std::fmt::Debug for LocalAccessToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LocalAccessToken(..)")
+    }
+}
+
+fn generate() -> String {
+    format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_header_or_query_token_only() {
+        let token = LocalAccessToken(generate());
+        let header = format!("Bearer {}", token.as_str());
+        let query = format!("v=1&token={}", token.as_str());
+
+        assert!(token.verify_request(Some(&header), None));
+        assert!(token.verify_request(None, Some(&query)));
+        assert!(!token.verify_request(None, None));
+        assert!(!token.verify_request(Some("Bearer nope"), Some(&query)));
+        assert!(!token.verify_request(None, Some("token=nope")));
+    }
+}
@@ -0,0 +1,499 @@
+//! Logging Module
+//!
+//! Structured logging with file output for diagnostics. Per-frame telemetry
+//! logs are sampled and written to disk in batches; everything else is
+//! written immediately. Old files are purged per the retention settings.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::{Level, Metadata};
+use tracing_subscriber::{fmt, fmt::MakeWriter, filter, EnvFilter, prelude::*};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::settings::LoggingSettings;
+
+/// Target for per-frame telemetry logs, which are sampled and batched
+pub const FRAME_TARGET: &str = "telemetry_frame";
+/// Prefix of the daily log files
+const LOG_FILE_PREFIX: &str = "vtc-tracker.log";
+/// Batched frame logs are written once they grow this large (bytes)
+const FRAME_BATCH_BYTES: usize = 64 * 1024;
+/// Longest configurable wait before batched frame logs are written (s)
+const MAX_FRAME_FLUSH_SECS: u64 = 60;
+
+/// Keep one in this many frame logs
+static FRAME_SAMPLE_RATE: AtomicU32 = AtomicU32::new(10);
+/// Longest time frame logs wait in memory (ms)
+static FRAME_FLUSH_MS: AtomicU64 = AtomicU64::new(2000);
+static FRAMES_SEEN: AtomicU64 = AtomicU64::new(0);
+/// Most entries `read_entries` returns at once
+pub const MAX_READ_ENTRIES: usize = 5000;
+/// Keys whose values never leave the machine
+const SECRET_KEYS: [&str; 5] = ["access_token", "refresh_token", "token", "code", "password"];
+/// Unbroken runs this long are treated as keys, hashes or tokens
+const SECRET_MIN_LEN: usize = 32;
+
+/// Initialize logging with console and file output
+pub fn init() {
+    let log_dir = log_directory();
+    
+    // Ensure log directory exists
+    let _ = std::fs::create_dir_all(&log_dir);
+    
+    // Create rolling file appender (daily rotation; `purge` removes old files)
+    let file_appender = RollingFileAppender::new(
+        Rotation::DAILY,
+        &log_dir,
+        LOG_FILE_PREFIX,
+    );
+    
+    // Create file layer
+    let file_layer = fmt::layer()
+        .with_ansi(false)
+        .with_target(true)
+        .with_writer(BatchedWriter::new(file_appender));
+    
+    // Create console layer (debug builds only)
+    #[cfg(debug_assertions)]
+    let console_layer = Some(
+        fmt::layer()
+            .with_target(true)
+            .pretty()
+    );
+    
+    #[cfg(not(debug_assertions))]
+    let console_layer: Option<fmt::Layer<_>> = None;
+    
+    // Set up filter
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| {
+            #[cfg(debug_assertions)]
+            { EnvFilter::new("debug,hyper=warn,reqwest=warn") }
+            #[cfg(not(debug_assertions))]
+            { EnvFilter::new("info,hyper=warn,reqwest=warn") }
+        });
+    
+    // Build subscriber
+    let subscriber = tracing_subscriber::registry()
+        .with(filter)
+        .with(filter::filter_fn(sample_frames))
+        .with(file_layer)
+        .with(console_layer);
+    
+    // Set global subscriber
+    let _ = tracing::subscriber::set_global_default(subscriber);
+}
+
+/// Apply the logging settings to the running subscriber
+pub fn configure(settings: &LoggingSettings) {
+    FRAME_SAMPLE_RATE.store(settings.frame_sample_rate.max(1), Ordering::Relaxed);
+    let flush_secs = settings.frame_flush_secs.min(MAX_FRAME_FLUSH_SECS);
+    FRAME_FLUSH_MS.store(flush_secs * 1000, Ordering::Relaxed);
+}
+
+/// Let every other log through, but only one in `FRAME_SAMPLE_RATE` frame logs
+fn sample_frames(metadata: &Metadata<'_>) -> bool {
+    if metadata.target() != FRAME_TARGET || !metadata.is_event() {
+        return true;
+    }
+    let rate = u64::from(FRAME_SAMPLE_RATE.load(Ordering::Relaxed).max(1));
+    FRAMES_SEEN.fetch_add(1, Ordering::Relaxed) % rate == 0
+}
+
+/// Holds frame logs back and writes them out together; any other log
+/// writes the held lines first so the file stays in order
+struct BatchedWriter<W> {
+    batch: Mutex<Batch<W>>,
+}
+
+struct Batch<W> {
+    inner: W,
+    pending: Vec<u8>,
+    since: Option<Instant>,
+}
+
+impl<W: Write> Batch<W> {
+    fn write_pending(&mut self) -> io::Result<()> {
+        self.since = None;
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let result = self.inner.write_all(&self.pending);
+        self.pending.clear();
+        result
+    }
+}
+
+impl<W> BatchedWriter<W> {
+    fn new(inner: W) -> Self {
+        Self { batch: Mutex::new(Batch { inner, pending: Vec::new(), since: None }) }
+    }
+}
+
+impl<'a, W: Write + 'a> MakeWriter<'a> for BatchedWriter<W> {
+    type Writer = BatchHandle<'a, W>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        BatchHandle { batch: &self.batch, batched: false }
+    }
+
+    fn make_writer_for(&'a self, meta: &Metadata<'_>) -> Self::Writer {
+        BatchHandle { batch: &self.batch, batched: meta.target() == FRAME_TARGET }
+    }
+}
+
+/// Writer for a single log line
+struct BatchHandle<'a, W> {
+    batch: &'a Mutex<Batch<W>>,
+    batched: bool,
+}
+
+impl<W: Write> Write for BatchHandle<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut batch = self.batch.lock()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "log writer poisoned"))?;
+        if !self.batched {
+            batch.write_pending()?;
+            return batch.inner.write(buf);
+        }
+
+        batch.pending.extend_from_slice(buf);
+        let since = *batch.since.get_or_insert_with(Instant::now);
+        let window = Duration::from_millis(FRAME_FLUSH_MS.load(Ordering::Relaxed));
+        if batch.pending.len() >= FRAME_BATCH_BYTES || since.elapsed() >= window {
+            batch.write_pending()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.batched {
+            return Ok(());
+        }
+        self.batch.lock()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "log writer poisoned"))?
+            .inner
+            .flush()
+    }
+}
+
+/// Directory holding the daily log files
+pub fn log_directory() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("VTCTracker")
+        .join("logs")
+}
+
+/// Daily log files in `dir`, oldest first
+fn log_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| name.starts_with(LOG_FILE_PREFIX))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // Daily files are suffixed with the date, so names sort by age
+    files.sort();
+    files
+}
+
+/// Last `max_lines` log lines across the newest files, oldest first
+pub fn recent_lines(dir: &Path, max_lines: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for file in log_files(dir).iter().rev() {
+        let Ok(content) = std::fs::read_to_string(file) else {
+            continue;
+        };
+        let mut older: Vec<String> = content.lines().map(str::to_string).collect();
+        older.append(&mut lines);
+        lines = older;
+        if lines.len() >= max_lines {
+            break;
+        }
+    }
+    let skip = lines.len().saturating_sub(max_lines);
+    lines.split_off(skip)
+}
+
+/// Files removed by a purge
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeReport {
+    pub files_removed: usize,
+    pub bytes_freed: u64,
+}
+
+/// Delete log files past the retention period as of `now`, then the oldest
+/// files until the directory fits the size cap. Today's file is always kept
+pub fn purge(dir: &Path, settings: &LoggingSettings, now: DateTime<Utc>) -> PurgeReport {
+    let max_age = Duration::from_secs(u64::from(settings.retention_days.max(1)) * 24 * 60 * 60);
+    let max_bytes = settings.max_total_mb.saturating_mul(1024 * 1024);
+    let report = purge_at(dir, max_age, max_bytes, SystemTime::from(now));
+    if report.files_removed > 0 {
+        tracing::info!("Purged {} log files ({} bytes)", report.files_removed, report.bytes_freed);
+    }
+    report
+}
+
+fn purge_at(dir: &Path, max_age: Duration, max_bytes: u64, now: SystemTime) -> PurgeReport {
+    let mut files: Vec<(PathBuf, u64, SystemTime)> = log_files(dir)
+        .into_iter()
+        .filter_map(|path| {
+            let metadata = std::fs::metadata(&path).ok()?;
+            let modified = metadata.modified().unwrap_or(now);
+            Some((path, metadata.len(), modified))
+        })
+        .collect();
+    // The newest file is the one being written
+    files.pop();
+
+    let mut report = PurgeReport::default();
+    let mut total: u64 = files.iter().map(|(_, len, _)| len).sum();
+    let remove = |path: &Path, len: u64, report: &mut PurgeReport| match std::fs::remove_file(path) {
+        Ok(()) => {
+            report.files_removed += 1;
+            report.bytes_freed += len;
+            true
+        }
+        Err(e) => {
+            tracing::warn!("Failed to delete log file {:?}: {}", path, e);
+            false
+        }
+    };
+    // Oldest first, so the size cap trims from the far end
+    for (path, len, modified) in &files {
+        let expired = now.duration_since(*modified).is_ok_and(|age| age > max_age);
+        if (expired || total > max_bytes) && remove(path, *len, &mut report) {
+            total -= len;
+        }
+    }
+    report
+}
+
+/// One parsed log line
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    pub timestamp: DateTime<Utc>,
+    pub level: String,
+    pub target: String,
+    /// Message and fields; continuation lines of multi-line messages included
+    pub message: String,
+}
+
+impl LogEntry {
+    /// Parse a `<timestamp> <LEVEL> <target>: <message>` line as the file layer writes it
+    fn parse(line: &str) -> Option<Self> {
+        let (timestamp, rest) = line.split_once(' ')?;
+        let timestamp = DateTime::parse_from_rfc3339(timestamp).ok()?.with_timezone(&Utc);
+        let rest = rest.trim_start();
+        let (level, rest) = rest.split_once(' ')?;
+        level.parse::<Level>().ok()?;
+        let (target, message) = rest.trim_start().split_once(": ").unwrap_or(("", rest.trim_start()));
+        Some(Self {
+            timestamp,
+            level: level.to_string(),
+            target: target.to_string(),
+            message: message.to_string(),
+        })
+    }
+
+    fn level(&self) -> Option<Level> {
+        self.level.parse().ok()
+    }
+}
+
+/// Newest `max_entries` log entries at `min_level` or more severe, written
+/// after `since`, oldest first
+pub fn read_entries(
+    dir: &Path,
+    max_entries: usize,
+    min_level: Option<Level>,
+    since: Option<DateTime<Utc>>,
+) -> Vec<LogEntry> {
+    let max_entries = max_entries.min(MAX_READ_ENTRIES);
+    let wanted = |entry: &LogEntry| {
+        since.map_or(true, |since| entry.timestamp > since)
+            && min_level.map_or(true, |min| entry.level().is_some_and(|level| level <= min))
+    };
+
+    let mut entries: Vec<LogEntry> = Vec::new();
+    for file in log_files(dir).iter().rev() {
+        let Ok(content) = std::fs::read_to_string(file) else {
+            continue;
+        };
+        let mut parsed: Vec<LogEntry> = Vec::new();
+        for line in content.lines() {
+            match (LogEntry::parse(line), parsed.last_mut()) {
+                (Some(entry), _) => parsed.push(entry),
+                (None, Some(previous)) if !line.trim().is_empty() => {
+                    previous.message.push('\n');
+                    previous.message.push_str(line);
+                }
+                (None, _) => {}
+            }
+        }
+        let reached_since = since.is_some_and(|since| parsed.first().is_some_and(|first| first.timestamp <= since));
+        parsed.retain(|entry| wanted(entry));
+        parsed.append(&mut entries);
+        entries = parsed;
+        if entries.len() >= max_entries || reached_since {
+            break;
+        }
+    }
+    let skip = entries.len().saturating_sub(max_entries);
+    entries.split_off(skip)
+}
+
+/// Mask tokens, codes, email addresses and the Windows user name in a log line
+pub fn redact(line: &str) -> String {
+    let mut words: Vec<String> = Vec::new();
+    for word in line.split_whitespace() {
+        let after_bearer = words.last().is_some_and(|previous| previous.eq_ignore_ascii_case("bearer"));
+        words.push(if after_bearer { "[redacted]".to_string() } else { redact_word(word) });
+    }
+    words.join(" ")
+}
+
+fn redact_word(word: &str) -> String {
+    let lower = word.to_ascii_lowercase();
+    for key in SECRET_KEYS {
+        for separator in ["=", "\":", ":"] {
+            if let Some(start) = lower.find(&format!("{}{}", key, separator)) {
+                let end = start + key.len() + separator.len();
+                return format!("{}[redacted]", &word[..end]);
+            }
+        }
+    }
+
+    if let Some(at) = word.find('@') {
+        if word[at..].contains('.') && at > 0 {
+            return "[email]".to_string();
+        }
+    }
+
+    let longest_run = word
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    if longest_run >= SECRET_MIN_LEN {
+        return "[redacted]".to_string();
+    }
+
+    match lower.find("\\users\\") {
+        Some(start) => {
+            let name_start = start + "\\users\\".len();
+            let name_end = word[name_start..].find('\\').map_or(word.len(), |end| name_start + end);
+            format!("{}<user>{}", &word[..name_start], &word[name_end..])
+        }
+        None => word.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_secrets_and_personal_data() {
+        let line = r#"POST /api/telemetry/job Authorization: Bearer abc.def {"access_token":"xyz"} code=ABC123"#;
+        assert_eq!(
+            redact(line),
+            r#"POST /api/telemetry/job Authorization: Bearer [redacted] {"access_token":[redacted] code=[redacted]"#
+        );
+        assert_eq!(redact("Signed in as driver@example.com"), "Signed in as [email]");
+        assert_eq!(
+            redact(r"Loaded C:\Users\Jane\AppData\Local\VTCTracker\settings.json"),
+            r"Loaded C:\Users\<user>\AppData\Local\VTCTracker\settings.json"
+        );
+        assert_eq!(redact("digest 9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"), "digest [redacted]");
+    }
+
+    #[test]
+    fn reads_filtered_entries_across_files() {
+        let dir = std::env::temp_dir().join(format!("vtc-logs-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join(format!("{}.2025-03-01", LOG_FILE_PREFIX)),
+            "2025-03-01T23:59:58.000000Z  INFO vtc_tracker_desktop: Started\n\
+             2025-03-01T23:59:59.000000Z  WARN vtc_tracker_core::sync: Request failed\n",
+        ).unwrap();
+        std::fs::write(
+            dir.join(format!("{}.2025-03-02", LOG_FILE_PREFIX)),
+            "2025-03-02T00:00:01.000000Z ERROR vtc_tracker_core::storage: Write failed\n\
+             caused by: disk full\n\
+             2025-03-02T00:00:02.000000Z DEBUG vtc_tracker_core::telemetry: Poll\n",
+        ).unwrap();
+
+        let all = read_entries(&dir, 10, None, None);
+        assert_eq!(all.len(), 4);
+        assert_eq!(all[0].message, "Started");
+
+        let warnings = read_entries(&dir, 10, Some(Level::WARN), None);
+        assert_eq!(warnings.iter().map(|e| e.level.as_str()).collect::<Vec<_>>(), ["WARN", "ERROR"]);
+        assert_eq!(warnings[1].target, "vtc_tracker_core::storage");
+        assert_eq!(warnings[1].message, "Write failed\ncaused by: disk full");
+
+        let since = DateTime::parse_from_rfc3339("2025-03-02T00:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(read_entries(&dir, 10, None, Some(since)).len(), 2);
+        assert_eq!(read_entries(&dir, 1, None, None)[0].level, "DEBUG");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn purge_drops_expired_then_oldest_files() {
+        let dir = std::env::temp_dir().join(format!("vtc-logs-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for day in 1..=4 {
+            std::fs::write(dir.join(format!("{}.2025-03-0{}", LOG_FILE_PREFIX, day)), vec![b'x'; 100]).unwrap();
+        }
+        std::fs::write(dir.join("crash.dmp"), b"unrelated").unwrap();
+        let now = SystemTime::now();
+
+        // Nothing is old enough and everything fits
+        assert_eq!(purge_at(&dir, Duration::from_secs(3600), 1000, now), PurgeReport::default());
+
+        // Over the cap: oldest go first, today's file stays even when it alone is too big
+        let report = purge_at(&dir, Duration::from_secs(3600), 150, now);
+        assert_eq!(report, PurgeReport { files_removed: 2, bytes_freed: 200 });
+        let report = purge_at(&dir, Duration::from_secs(3600), 0, now);
+        assert_eq!(report.files_removed, 1);
+        assert_eq!(log_files(&dir), vec![dir.join(format!("{}.2025-03-04", LOG_FILE_PREFIX))]);
+
+        // Expired by age
+        std::fs::write(dir.join(format!("{}.2025-03-05", LOG_FILE_PREFIX)), b"today").unwrap();
+        let later = now + Duration::from_secs(7200);
+        assert_eq!(purge_at(&dir, Duration::from_secs(3600), u64::MAX, later).files_removed, 1);
+        assert!(dir.join("crash.dmp").exists());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn frame_logs_wait_for_the_next_immediate_line() {
+        let writer = BatchedWriter::new(Vec::new());
+        let mut frame = BatchHandle { batch: &writer.batch, batched: true };
+        let mut event = BatchHandle { batch: &writer.batch, batched: false };
+
+        frame.write_all(b"frame 1\n").unwrap();
+        frame.write_all(b"frame 2\n").unwrap();
+        assert!(writer.batch.lock().unwrap().inner.is_empty());
+
+        event.write_all(b"job started\n").unwrap();
+        let batch = writer.batch.lock().unwrap();
+        assert_eq!(batch.inner, b"frame 1\nframe 2\njob started\n");
+        assert!(batch.pending.is_empty());
+    }
+}
@@ -0,0 +1,175 @@
+//! Maintenance Module
+//!
+//! Tracks truck wear between repairs and raises maintenance reminders.
+
+use serde::Serialize;
+use tracing::info;
+
+use crate::settings::MaintenanceSettings;
+use crate::telemetry::TruckWear;
+
+/// Truck component tracked for wear
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Component {
+    Engine,
+    Transmission,
+    Cabin,
+    Chassis,
+    Wheels,
+}
+
+impl Component {
+    const ALL: [Component; 5] = [
+        Component::Engine,
+        Component::Transmission,
+        Component::Cabin,
+        Component::Chassis,
+        Component::Wheels,
+    ];
+
+    fn wear(self, wear: &TruckWear) -> f32 {
+        match self {
+            Component::Engine => wear.engine,
+            Component::Transmission => wear.transmission,
+            Component::Cabin => wear.cabin,
+            Component::Chassis => wear.chassis,
+            Component::Wheels => wear.wheels,
+        }
+    }
+
+    fn threshold(self, settings: &MaintenanceSettings) -> f32 {
+        match self {
+            Component::Engine => settings.engine_threshold,
+            Component::Transmission => settings.transmission_threshold,
+            Component::Cabin => settings.cabin_threshold,
+            Component::Chassis => settings.chassis_threshold,
+            Component::Wheels => settings.wheels_threshold,
+        }
+    }
+}
+
+impl std::fmt::Display for Component {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Component::Engine => write!(f, "engine"),
+            Component::Transmission => write!(f, "transmission"),
+            Component::Cabin => write!(f, "cabin"),
+            Component::Chassis => write!(f, "chassis"),
+            Component::Wheels => write!(f, "wheels"),
+        }
+    }
+}
+
+/// Reminder that a component has crossed its wear threshold
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceReminder {
+    pub component: Component,
+    /// Current wear in percent
+    pub wear_percent: f32,
+    /// Wear accumulated since the component was last repaired, in percent
+    pub wear_since_repair_percent: f32,
+    pub message: String,
+}
+
+/// Watches truck wear and reports threshold crossings once per repair cycle
+#[derive(Debug, Default)]
+pub struct MaintenanceTracker {
+    /// Wear right after the last detected repair
+    baseline: Option<TruckWear>,
+    last: Option<TruckWear>,
+    reminded: Vec<Component>,
+}
+
+impl MaintenanceTracker {
+    /// Create a new tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the latest wear reading and collect any new reminders
+    pub fn observe(&mut self, wear: &TruckWear, settings: &MaintenanceSettings) -> Vec<MaintenanceReminder> {
+        let baseline = self.baseline.get_or_insert_with(|| wear.clone());
+
+        // A drop in wear means the component was repaired: start a new cycle for it
+        if let Some(last) = &self.last {
+            for component in Component::ALL {
+                if component.wear(wear) < component.wear(last) {
+                    info!("Detected {} repair", component);
+                    set_wear(baseline, component, component.wear(wear));
+                    self.reminded.retain(|c| *c != component);
+                }
+            }
+        }
+        self.last = Some(wear.clone());
+
+        if !settings.enabled {
+            return Vec::new();
+        }
+
+        let mut reminders = Vec::new();
+        for component in Component::ALL {
+            let wear_percent = component.wear(wear) * 100.0;
+            if wear_percent < component.threshold(settings) || self.reminded.contains(&component) {
+                continue;
+            }
+
+            self.reminded.push(component);
+            reminders.push(MaintenanceReminder {
+                component,
+                wear_percent,
+                wear_since_repair_percent: (component.wear(wear) - component.wear(baseline)).max(0.0) * 100.0,
+                message: format!("{} at {:.0}% wear", capitalize(&component.to_string()), wear_percent),
+            });
+        }
+        reminders
+    }
+}
+
+fn set_wear(wear: &mut TruckWear, component: Component, value: f32) {
+    match component {
+        Component::Engine => wear.engine = value,
+        Component::Transmission => wear.transmission = value,
+        Component::Cabin => wear.cabin = value,
+        Component::Chassis => wear.chassis = value,
+        Component::Wheels => wear.wheels = value,
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine_wear(engine: f32) -> TruckWear {
+        TruckWear { engine, ..TruckWear::default() }
+    }
+
+    #[test]
+    fn reminds_once_per_repair_cycle() {
+        let settings = MaintenanceSettings::default();
+        let mut tracker = MaintenanceTracker::new();
+
+        assert!(tracker.observe(&engine_wear(0.05), &settings).is_empty());
+
+        let reminders = tracker.observe(&engine_wear(0.18), &settings);
+        assert_eq!(reminders.len(), 1);
+        assert_eq!(reminders[0].component, Component::Engine);
+        assert_eq!(reminders[0].message, "Engine at 18% wear");
+        assert!((reminders[0].wear_since_repair_percent - 13.0).abs() < 0.01);
+
+        assert!(tracker.observe(&engine_wear(0.20), &settings).is_empty());
+
+        // Repair, then wear past the threshold again
+        assert!(tracker.observe(&engine_wear(0.0), &settings).is_empty());
+        assert_eq!(tracker.observe(&engine_wear(0.16), &settings).len(), 1);
+    }
+}
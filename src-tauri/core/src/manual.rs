@@ -0,0 +1,157 @@
+//! Manual Jobs Module
+//!
+//! Deliveries entered by hand when auto-detection missed them. The entry is
+//! validated and turned into a regular submission, flagged as manual in
+//! `telemetry_data` so the platform can review it.
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::games;
+use crate::names;
+use crate::sync::JobSubmission;
+use crate::telemetry::Game;
+
+/// Longest job either game offers, with some margin (km)
+const MAX_DISTANCE_KM: u32 = 10_000;
+
+/// Manual job validation errors
+#[derive(Debug, thiserror::Error)]
+pub enum ManualJobError {
+    #[error("Unknown game: {0}")]
+    Game(String),
+
+    #[error("{0} is required")]
+    Missing(&'static str),
+
+    #[error("Distance must be between 1 and {MAX_DISTANCE_KM} km")]
+    Distance,
+
+    #[error("Revenue can't be negative")]
+    Revenue,
+
+    #[error("Damage must be between 0 and 100%")]
+    Damage,
+
+    #[error("Delivery time is in the future")]
+    DeliveredAt,
+}
+
+/// A delivery entered by hand
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManualJob {
+    pub game: String,
+    pub cargo: String,
+    pub source_city: String,
+    pub destination_city: String,
+    pub distance_km: u32,
+    pub revenue: f64,
+    #[serde(default)]
+    pub damage_percent: f64,
+    /// Defaults to now
+    pub delivered_at: Option<DateTime<Utc>>,
+    /// Why the job was entered by hand
+    pub note: Option<String>,
+}
+
+impl ManualJob {
+    /// Reject entries the platform couldn't make sense of
+    pub fn validate(&self, now: DateTime<Utc>) -> Result<Game, ManualJobError> {
+        let game = games::from_id(&self.game).ok_or_else(|| ManualJobError::Game(self.game.clone()))?;
+        for (field, value) in [
+            ("Cargo", &self.cargo),
+            ("Source city", &self.source_city),
+            ("Destination city", &self.destination_city),
+        ] {
+            if value.trim().is_empty() {
+                return Err(ManualJobError::Missing(field));
+            }
+        }
+        if !(1..=MAX_DISTANCE_KM).contains(&self.distance_km) {
+            return Err(ManualJobError::Distance);
+        }
+        if !self.revenue.is_finite() || self.revenue < 0.0 {
+            return Err(ManualJobError::Revenue);
+        }
+        if !(0.0..=100.0).contains(&self.damage_percent) {
+            return Err(ManualJobError::Damage);
+        }
+        if self.delivered_at.is_some_and(|at| at > now) {
+            return Err(ManualJobError::DeliveredAt);
+        }
+        Ok(game)
+    }
+
+    /// Validate and build the submission
+    pub fn to_submission(&self, now: DateTime<Utc>) -> Result<JobSubmission, ManualJobError> {
+        let game = self.validate(now)?;
+        Ok(JobSubmission {
+            game: self.game.clone(),
+            cargo: names::canonical_cargo(&self.cargo),
+            source_city: names::canonical_city(game, &self.source_city),
+            destination_city: names::canonical_city(game, &self.destination_city),
+            distance_km: self.distance_km,
+            revenue: self.revenue,
+            currency: Some(games::data(game).currency),
+            damage_percent: self.damage_percent,
+            chassis_damage_percent: 0.0,
+            truck_id: None,
+            trailer_id: None,
+            trailer_ownership: None,
+            telemetry_data: Some(serde_json::json!({
+                "manual": {
+                    "deliveredAt": self.delivered_at.unwrap_or(now),
+                    "note": self.note,
+                },
+            })),
+            server: None,
+            fuel_used: None,
+            average_consumption: None,
+            driven_km: None,
+            navigation_km: None,
+            idempotency_key: Some(uuid::Uuid::new_v4().to_string()),
+            partial: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> ManualJob {
+        ManualJob {
+            game: "ets2".into(),
+            cargo: "Machinery".into(),
+            source_city: "Berlin".into(),
+            destination_city: "Hamburg".into(),
+            distance_km: 290,
+            revenue: 12_000.0,
+            damage_percent: 1.5,
+            delivered_at: None,
+            note: Some("Game crashed at the drop-off".into()),
+        }
+    }
+
+    #[test]
+    fn validates_and_flags_the_submission_as_manual() {
+        let now = Utc::now();
+        let submission = entry().to_submission(now).unwrap();
+        assert_eq!(submission.distance_km, 290);
+        assert!(submission.idempotency_key.is_some());
+        assert_eq!(submission.telemetry_data.unwrap()["manual"]["note"], "Game crashed at the drop-off");
+
+        let invalid = [
+            ManualJob { game: "fs22".into(), ..entry() },
+            ManualJob { cargo: " ".into(), ..entry() },
+            ManualJob { distance_km: 0, ..entry() },
+            ManualJob { revenue: -1.0, ..entry() },
+            ManualJob { damage_percent: 120.0, ..entry() },
+            ManualJob { delivered_at: Some(now + chrono::Duration::hours(1)), ..entry() },
+        ];
+        for job in invalid {
+            assert!(job.validate(now).is_err(), "{:?}", job);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Mini Map Module
+//!
+//! Projects the truck's world position onto the tile grid of the community
+//! map, so the frontend can draw a live mini-map with the same tiles. Each
+//! game's world is fitted into a square that zoom level 0 shows as a single
+//! 256 px tile; every further zoom level doubles the pixel size.
+
+use std::time::{Duration, Instant};
+use serde::Serialize;
+
+use crate::telemetry::{Game, Position};
+
+/// Edge of one map tile (px)
+pub const TILE_SIZE: u32 = 256;
+/// Deepest zoom level the tile servers render
+pub const MAX_ZOOM: u8 = 8;
+/// Minimum spacing between `map_position` events
+const FEED_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The square of the world covered by a game's tiles (m)
+struct MapGrid {
+    min_x: f64,
+    min_z: f64,
+    size: f64,
+}
+
+const ETS2_GRID: MapGrid = MapGrid { min_x: -100_000.0, min_z: -100_000.0, size: 200_000.0 };
+const ATS_GRID: MapGrid = MapGrid { min_x: -130_000.0, min_z: -100_000.0, size: 200_000.0 };
+
+/// The truck's place on the map
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MapPosition {
+    pub game: Game,
+    /// Pixel coordinates at `MAX_ZOOM`, from the top left of the map
+    pub x: f64,
+    pub y: f64,
+    /// Clockwise from north (degrees)
+    pub heading: f32,
+    pub zoom: u8,
+    pub tile_size: u32,
+}
+
+impl MapPosition {
+    /// Project a world position onto `game`'s map
+    pub fn project(game: Game, position: &Position) -> Self {
+        let grid = match game {
+            Game::Ets2 => &ETS2_GRID,
+            Game::Ats => &ATS_GRID,
+        };
+        let pixels = f64::from(TILE_SIZE) * f64::from(1u32 << MAX_ZOOM);
+        let scale = pixels / grid.size;
+        Self {
+            game,
+            x: ((position.x - grid.min_x) * scale).clamp(0.0, pixels),
+            y: ((position.z - grid.min_z) * scale).clamp(0.0, pixels),
+            // The SDK turns counter-clockwise with 0 facing north
+            heading: ((1.0 - position.heading.rem_euclid(1.0)) * 360.0) % 360.0,
+            zoom: MAX_ZOOM,
+            tile_size: TILE_SIZE,
+        }
+    }
+
+    /// Tile containing the position at `zoom`
+    pub fn tile(&self, zoom: u8) -> (u32, u32) {
+        let shift = MAX_ZOOM.saturating_sub(zoom.min(MAX_ZOOM));
+        let tile = f64::from(TILE_SIZE) * f64::from(1u32 << shift);
+        ((self.x / tile) as u32, (self.y / tile) as u32)
+    }
+}
+
+/// Decides when the frontend gets a new map position
+#[derive(Debug, Default)]
+pub struct MiniMapFeed {
+    last_emit: Option<Instant>,
+    last: Option<MapPosition>,
+}
+
+impl MiniMapFeed {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The position to send now, if it changed and the interval has passed;
+    /// `Some(None)` once when the position is lost
+    pub fn next(&mut self, game: Option<Game>, position: Option<&Position>, now: Instant) -> Option<Option<MapPosition>> {
+        let current = game.zip(position).map(|(game, position)| MapPosition::project(game, position));
+        if current == self.last {
+            return None;
+        }
+        let due = self.last_emit.map_or(true, |last| now.duration_since(last) >= FEED_INTERVAL);
+        if current.is_some() && !due {
+            return None;
+        }
+        self.last_emit = Some(now);
+        self.last = current.clone();
+        Some(current)
+    }
+
+    /// The last position sent
+    pub fn current(&self) -> Option<&MapPosition> {
+        self.last.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(x: f64, z: f64, heading: f32) -> Position {
+        Position { x, y: 0.0, z, heading }
+    }
+
+    #[test]
+    fn projects_onto_the_tile_grid_and_throttles() {
+        let centre = MapPosition::project(Game::Ets2, &at(0.0, 0.0, 0.25));
+        assert_eq!((centre.x, centre.y, centre.heading), (32_768.0, 32_768.0, 270.0));
+        assert_eq!(centre.tile(0), (0, 0));
+        assert_eq!(centre.tile(1), (1, 1));
+        assert_eq!(centre.tile(MAX_ZOOM), (128, 128));
+        let corner = MapPosition::project(Game::Ats, &at(-130_000.0, -100_000.0, 0.0));
+        assert_eq!((corner.x, corner.y, corner.heading), (0.0, 0.0, 0.0));
+
+        let mut feed = MiniMapFeed::new();
+        let start = Instant::now();
+        assert!(feed.next(Some(Game::Ets2), Some(&at(0.0, 0.0, 0.0)), start).is_some());
+        assert_eq!(feed.next(Some(Game::Ets2), Some(&at(5.0, 0.0, 0.0)), start + Duration::from_millis(100)), None);
+        assert!(feed.next(Some(Game::Ets2), Some(&at(5.0, 0.0, 0.0)), start + FEED_INTERVAL).is_some());
+        // Losing the position is sent straight away, once
+        assert_eq!(feed.next(Some(Game::Ets2), None, start + FEED_INTERVAL), Some(None));
+        assert_eq!(feed.next(Some(Game::Ets2), None, start + FEED_INTERVAL * 2), None);
+        assert_eq!(feed.current(), None);
+    }
+}
@@ -0,0 +1,58 @@
+//! Multiplayer Module
+//!
+//! Tells whether the game runs on a multiplayer client such as TruckersMP,
+//! from the module the client injects into the game or, failing that, from
+//! the client's own processes. Clients are listed in the bundled game data.
+
+use crate::games::{self, MultiplayerClient};
+use crate::processes::{self, ProcessInfo};
+use crate::telemetry::Game;
+
+/// Multiplayer client `game` is running on, `None` in singleplayer
+pub fn detect(game: Game) -> Option<String> {
+    detect_in(game, &games::data(game).multiplayer, &processes::running(), processes::modules)
+}
+
+fn detect_in(
+    game: Game,
+    clients: &[MultiplayerClient],
+    processes: &[ProcessInfo],
+    modules: impl Fn(u32) -> Vec<String>,
+) -> Option<String> {
+    let game_process = processes.iter().find(|process| games::from_process_name(&process.name) == Some(game));
+    let loaded: Vec<String> = game_process
+        .map(|process| modules(process.pid).iter().map(|module| module.to_ascii_lowercase()).collect())
+        .unwrap_or_default();
+    let running: Vec<String> = processes.iter()
+        .map(|process| process.name.to_ascii_lowercase().trim_end_matches(".exe").to_string())
+        .collect();
+
+    clients.iter()
+        .find(|client| client.modules.iter().any(|module| loaded.contains(module)))
+        .or_else(|| clients.iter().find(|client| client.process_names.iter().any(|name| running.contains(name))))
+        .map(|client| client.name.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, name: &str) -> ProcessInfo {
+        ProcessInfo { pid, name: name.into() }
+    }
+
+    #[test]
+    fn finds_client_by_module_or_process() {
+        let clients = &games::data(Game::Ets2).multiplayer;
+        let game = [process(7, "explorer.exe"), process(42, "eurotrucks2.exe")];
+        let injected = |pid: u32| match pid {
+            42 => vec!["eurotrucks2.exe".to_string(), "CORE_ETS2MP.DLL".to_string()],
+            _ => Vec::new(),
+        };
+        assert_eq!(detect_in(Game::Ets2, clients, &game, injected).as_deref(), Some("TruckersMP"));
+        assert_eq!(detect_in(Game::Ets2, clients, &game, |_| Vec::new()), None);
+
+        let launcher = [process(42, "eurotrucks2.exe"), process(43, "TruckersMP-Launcher.exe")];
+        assert_eq!(detect_in(Game::Ets2, clients, &launcher, |_| Vec::new()).as_deref(), Some("TruckersMP"));
+    }
+}
@@ -0,0 +1,58 @@
+//! Name Normalization Module
+//!
+//! Maps localized city and cargo names from telemetry to canonical game IDs,
+//! so the backend sees one identifier regardless of the game language. The
+//! tables come from the bundled game data.
+
+use crate::games::{self, NameTable};
+use crate::telemetry::Game;
+
+/// Look up the canonical ID for a city of `game` in any supported language
+pub fn city_id(game: Game, name: &str) -> Option<&'static str> {
+    lookup(&games::data(game).cities, name)
+}
+
+/// Look up the canonical ID for a cargo name in any supported language
+pub fn cargo_id(name: &str) -> Option<&'static str> {
+    lookup(games::cargo(), name)
+}
+
+/// Canonical city ID, or the trimmed input when the name is unknown
+pub fn canonical_city(game: Game, name: &str) -> String {
+    city_id(game, name).map(str::to_string).unwrap_or_else(|| name.trim().to_string())
+}
+
+/// Canonical cargo ID, or the trimmed input when the name is unknown
+pub fn canonical_cargo(name: &str) -> String {
+    cargo_id(name).map(str::to_string).unwrap_or_else(|| name.trim().to_string())
+}
+
+fn lookup(table: &'static NameTable, name: &str) -> Option<&'static str> {
+    let needle = name.trim().to_lowercase();
+    table.iter()
+        .find(|(id, aliases)| {
+            **id == needle || aliases.iter().any(|alias| alias.to_lowercase() == needle)
+        })
+        .map(|(id, _)| id.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn localized_variants_share_one_id() {
+        for name in ["Köln", "Cologne", "Kolín nad Rýnem", " köln ", "koln"] {
+            assert_eq!(city_id(Game::Ets2, name), Some("koln"), "{name}");
+        }
+        assert_eq!(city_id(Game::Ats, "Лос-Анджелес"), Some("los_angeles"));
+        assert_eq!(city_id(Game::Ats, "Köln"), None);
+        assert_eq!(cargo_id("Maschinen"), Some("machinery"));
+    }
+
+    #[test]
+    fn unknown_names_pass_through() {
+        assert_eq!(city_id(Game::Ets2, "Nowhere"), None);
+        assert_eq!(canonical_city(Game::Ets2, " Nowhere "), "Nowhere");
+    }
+}
@@ -0,0 +1,237 @@
+//! Overlay Module
+//!
+//! Layout and theme of the in-game HUD. Presets live in settings, the overlay
+//! window asks for the active one, and drivers share presets as JSON.
+
+use serde::{Deserialize, Serialize};
+
+/// Name of the built-in preset
+const DEFAULT_PRESET: &str = "default";
+
+/// Overlay preset errors
+#[derive(Debug, thiserror::Error)]
+pub enum OverlayError {
+    #[error("Invalid preset JSON: {0}")]
+    Parse(String),
+
+    #[error("Invalid preset: {0}")]
+    Invalid(String),
+
+    #[error("No overlay preset named {0}")]
+    NotFound(String),
+}
+
+/// Saved presets and the one the overlay shows
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OverlaySettings {
+    pub active_preset: String,
+    pub presets: Vec<OverlayPreset>,
+}
+
+impl Default for OverlaySettings {
+    fn default() -> Self {
+        Self {
+            active_preset: DEFAULT_PRESET.into(),
+            presets: vec![OverlayPreset::default()],
+        }
+    }
+}
+
+impl OverlaySettings {
+    /// Preset the overlay should render; the built-in one if the active name
+    /// went missing
+    pub fn active(&self) -> OverlayPreset {
+        self.preset(&self.active_preset)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn preset(&self, name: &str) -> Option<&OverlayPreset> {
+        self.presets.iter().find(|p| p.name == name)
+    }
+
+    /// Add a preset, replacing one with the same name
+    pub fn upsert(&mut self, preset: OverlayPreset) {
+        match self.presets.iter_mut().find(|p| p.name == preset.name) {
+            Some(existing) => *existing = preset,
+            None => self.presets.push(preset),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), OverlayError> {
+        for (i, preset) in self.presets.iter().enumerate() {
+            preset.validate()?;
+            if self.presets[..i].iter().any(|p| p.name == preset.name) {
+                return Err(OverlayError::Invalid(format!("duplicate preset {}", preset.name)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A shareable HUD layout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayPreset {
+    pub name: String,
+    #[serde(default)]
+    pub theme: OverlayTheme,
+    pub widgets: Vec<OverlayWidget>,
+}
+
+impl Default for OverlayPreset {
+    fn default() -> Self {
+        Self {
+            name: DEFAULT_PRESET.into(),
+            theme: OverlayTheme::default(),
+            widgets: vec![
+                OverlayWidget::new(WidgetKind::Speed, 0.02, 0.85),
+                OverlayWidget::new(WidgetKind::SpeedLimit, 0.12, 0.85),
+                OverlayWidget::new(WidgetKind::Job, 0.02, 0.02),
+                OverlayWidget::new(WidgetKind::Damage, 0.85, 0.02),
+            ],
+        }
+    }
+}
+
+impl OverlayPreset {
+    /// Parse a preset shared by another driver
+    pub fn from_json(json: &str) -> Result<Self, OverlayError> {
+        let preset: Self = serde_json::from_str(json).map_err(|e| OverlayError::Parse(e.to_string()))?;
+        preset.validate()?;
+        Ok(preset)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn validate(&self) -> Result<(), OverlayError> {
+        if self.name.trim().is_empty() {
+            return Err(OverlayError::Invalid("name is empty".into()));
+        }
+        self.theme.validate()?;
+        for (i, widget) in self.widgets.iter().enumerate() {
+            let on_screen = |v: f32| (0.0..=1.0).contains(&v);
+            if !on_screen(widget.x) || !on_screen(widget.y) {
+                return Err(OverlayError::Invalid(format!("{:?} is off screen", widget.kind)));
+            }
+            if !(0.5..=3.0).contains(&widget.scale) {
+                return Err(OverlayError::Invalid(format!("{:?} scale must be between 0.5 and 3", widget.kind)));
+            }
+            if self.widgets[..i].iter().any(|w| w.kind == widget.kind) {
+                return Err(OverlayError::Invalid(format!("{:?} placed twice", widget.kind)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Colors of the HUD, as `#rrggbb` or `#rrggbbaa`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OverlayTheme {
+    pub background: String,
+    pub text: String,
+    pub accent: String,
+    pub warning: String,
+    /// Window opacity (0.0–1.0)
+    pub opacity: f32,
+}
+
+impl Default for OverlayTheme {
+    fn default() -> Self {
+        Self {
+            background: "#101820".into(),
+            text: "#f5f5f5".into(),
+            accent: "#f2a900".into(),
+            warning: "#e5484d".into(),
+            opacity: 0.85,
+        }
+    }
+}
+
+impl OverlayTheme {
+    fn validate(&self) -> Result<(), OverlayError> {
+        for color in [&self.background, &self.text, &self.accent, &self.warning] {
+            let hex = color.strip_prefix('#').unwrap_or_default();
+            if !matches!(hex.len(), 6 | 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(OverlayError::Invalid(format!("bad color {}", color)));
+            }
+        }
+        if !(0.0..=1.0).contains(&self.opacity) {
+            return Err(OverlayError::Invalid("opacity must be between 0 and 1".into()));
+        }
+        Ok(())
+    }
+}
+
+/// A HUD element placed on screen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayWidget {
+    pub kind: WidgetKind,
+    /// Top-left corner as a fraction of the screen (0.0–1.0)
+    pub x: f32,
+    pub y: f32,
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+}
+
+impl OverlayWidget {
+    fn new(kind: WidgetKind, x: f32, y: f32) -> Self {
+        Self { kind, x, y, scale: default_scale(), visible: default_visible() }
+    }
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+/// What a widget shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WidgetKind {
+    Speed,
+    SpeedLimit,
+    Job,
+    Eta,
+    Fuel,
+    Damage,
+    Clock,
+    Convoy,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shares_presets_as_json() {
+        let mut preset = OverlayPreset { name: "night".into(), ..OverlayPreset::default() };
+        preset.theme.accent = "#00ff88".into();
+        assert_eq!(OverlayPreset::from_json(&preset.to_json()).unwrap(), preset);
+
+        let minimal = OverlayPreset::from_json(r#"{"name":"mini","widgets":[{"kind":"speed","x":0.5,"y":0.9}]}"#).unwrap();
+        assert_eq!(minimal.theme, OverlayTheme::default());
+        assert!(minimal.widgets[0].visible);
+
+        let off_screen = r#"{"name":"bad","widgets":[{"kind":"clock","x":1.5,"y":0}]}"#;
+        assert!(matches!(OverlayPreset::from_json(off_screen), Err(OverlayError::Invalid(_))));
+        preset.theme.text = "white".into();
+        assert!(preset.validate().is_err());
+
+        let mut settings = OverlaySettings::default();
+        settings.upsert(OverlayPreset { name: "night".into(), ..OverlayPreset::default() });
+        settings.active_preset = "gone".into();
+        assert_eq!(settings.presets.len(), 2);
+        assert_eq!(settings.active().name, DEFAULT_PRESET);
+    }
+}
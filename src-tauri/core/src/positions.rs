@@ -0,0 +1,249 @@
+//! Position Batching Module
+//!
+//! Collects live-map position samples and packs them into compact,
+//! delta-encoded batches uploaded every 10–30 seconds, backing off while the
+//! server rejects or rate-limits uploads.
+
+use std::io::Write;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Packet format version understood by the backend
+const PACKET_VERSION: u8 = 1;
+/// Minimum spacing between recorded samples
+const MIN_SAMPLE_INTERVAL_MS: i64 = 1000;
+/// Samples kept while uploads are failing; older ones are dropped first
+const MAX_PENDING_SAMPLES: usize = 300;
+/// Bounds for the server-driven batch window (seconds)
+const MIN_WINDOW_SECS: u32 = 10;
+const MAX_WINDOW_SECS: u32 = 30;
+const DEFAULT_WINDOW_SECS: u32 = 15;
+/// Delay after the first failed upload; doubles with each further failure
+const BACKOFF_BASE_SECS: i64 = 15;
+const BACKOFF_MAX_SECS: i64 = 300;
+
+/// A single position sample
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionSample {
+    pub at: DateTime<Utc>,
+    /// World X coordinate in metres
+    pub x: f64,
+    /// World Z coordinate in metres
+    pub z: f64,
+    /// Heading as a fraction of a full turn (0.0–1.0)
+    pub heading: f32,
+    /// Speed in km/h
+    pub speed: f32,
+}
+
+/// Delta-encoded batch of samples.
+///
+/// The first sample is absolute; every row in `samples` is
+/// `[dt_ms, dx_dm, dz_dm, heading_milliturns, speed_dkmh]`, with time and
+/// position relative to the previous row.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PositionBatch {
+    pub v: u8,
+    pub t0: i64,
+    pub x0: i64,
+    pub z0: i64,
+    pub samples: Vec<[i64; 5]>,
+}
+
+impl PositionBatch {
+    /// Encode samples relative to their predecessor
+    pub fn encode(samples: &[PositionSample]) -> Option<Self> {
+        let first = samples.first()?;
+        let t0 = first.at.timestamp_millis();
+        let x0 = decimetres(first.x);
+        let z0 = decimetres(first.z);
+
+        let (mut t, mut x, mut z) = (t0, x0, z0);
+        let rows = samples.iter()
+            .map(|sample| {
+                let (st, sx, sz) = (sample.at.timestamp_millis(), decimetres(sample.x), decimetres(sample.z));
+                let row = [
+                    st - t,
+                    sx - x,
+                    sz - z,
+                    (sample.heading.rem_euclid(1.0) * 1000.0).round() as i64,
+                    (sample.speed * 10.0).round() as i64,
+                ];
+                (t, x, z) = (st, sx, sz);
+                row
+            })
+            .collect();
+
+        Some(Self { v: PACKET_VERSION, t0, x0, z0, samples: rows })
+    }
+
+    /// Serialize to gzip-compressed JSON for upload
+    pub fn to_gzip_json(&self) -> std::io::Result<Vec<u8>> {
+        let json = serde_json::to_vec(self)?;
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&json)?;
+        encoder.finish()
+    }
+}
+
+/// Accumulates samples and decides when a batch is due
+#[derive(Debug)]
+pub struct PositionBatcher {
+    pending: Vec<PositionSample>,
+    window_secs: u32,
+    window_started: Option<DateTime<Utc>>,
+    /// Consecutive failed uploads
+    failures: u32,
+    backoff_until: Option<DateTime<Utc>>,
+}
+
+impl PositionBatcher {
+    /// Create a batcher with the default window
+    pub fn new() -> Self {
+        Self {
+            pending: Vec::new(),
+            window_secs: DEFAULT_WINDOW_SECS,
+            window_started: None,
+            failures: 0,
+            backoff_until: None,
+        }
+    }
+
+    /// Record a sample, skipping ones closer than the minimum interval
+    pub fn push(&mut self, sample: PositionSample) {
+        if let Some(last) = self.pending.last() {
+            if (sample.at - last.at).num_milliseconds() < MIN_SAMPLE_INTERVAL_MS {
+                return;
+            }
+        }
+        if self.pending.len() >= MAX_PENDING_SAMPLES {
+            self.pending.remove(0);
+        }
+        self.window_started.get_or_insert(sample.at);
+        self.pending.push(sample);
+    }
+
+    /// Whether the current window has elapsed and samples are waiting
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        let backing_off = self.backoff_until.is_some_and(|until| now < until);
+        !backing_off && self.window_started
+            .is_some_and(|start| (now - start).num_seconds() >= i64::from(self.window_secs))
+    }
+
+    /// Hold uploads back after a failure, at least until `retry_at` when the
+    /// server asked for a pause
+    pub fn record_failure(&mut self, now: DateTime<Utc>, retry_at: Option<DateTime<Utc>>) {
+        self.failures += 1;
+        let exponent = self.failures.saturating_sub(1).min(5);
+        let delay = chrono::Duration::seconds((BACKOFF_BASE_SECS << exponent).min(BACKOFF_MAX_SECS));
+        self.backoff_until = Some(retry_at.map_or(now + delay, |at| at.max(now + delay)));
+    }
+
+    /// An upload went through; resume the normal window
+    pub fn record_success(&mut self) {
+        self.failures = 0;
+        self.backoff_until = None;
+    }
+
+    /// Take all pending samples for upload
+    pub fn take(&mut self) -> Vec<PositionSample> {
+        self.window_started = None;
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Put samples from a failed upload back so they go out with the next batch
+    pub fn requeue(&mut self, samples: Vec<PositionSample>) {
+        let mut merged = samples;
+        merged.append(&mut self.pending);
+        let overflow = merged.len().saturating_sub(MAX_PENDING_SAMPLES);
+        merged.drain(..overflow);
+        self.window_started = merged.first().map(|s| s.at);
+        self.pending = merged;
+    }
+
+    /// Apply the batch window requested by the server
+    pub fn set_window(&mut self, secs: u32) {
+        self.window_secs = secs.clamp(MIN_WINDOW_SECS, MAX_WINDOW_SECS);
+    }
+
+    /// Current batch window in seconds
+    pub fn window_secs(&self) -> u32 {
+        self.window_secs
+    }
+
+    /// Drop everything pending (e.g. when live map is switched off)
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.window_started = None;
+    }
+}
+
+impl Default for PositionBatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn decimetres(metres: f64) -> i64 {
+    (metres * 10.0).round() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn sample(at: DateTime<Utc>, x: f64, z: f64) -> PositionSample {
+        PositionSample { at, x, z, heading: 0.25, speed: 72.5 }
+    }
+
+    #[test]
+    fn encodes_deltas_from_previous_sample() {
+        let t = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let batch = PositionBatch::encode(&[
+            sample(t, 1000.0, -500.0),
+            sample(t + Duration::seconds(2), 1010.5, -498.0),
+        ]).unwrap();
+
+        assert_eq!(batch.x0, 10_000);
+        assert_eq!(batch.samples[0], [0, 0, 0, 250, 725]);
+        assert_eq!(batch.samples[1], [2000, 105, 20, 250, 725]);
+        assert!(!batch.to_gzip_json().unwrap().is_empty());
+    }
+
+    #[test]
+    fn batch_is_due_after_window() {
+        let t = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let mut batcher = PositionBatcher::new();
+        batcher.set_window(5);
+        assert_eq!(batcher.window_secs(), MIN_WINDOW_SECS);
+
+        batcher.push(sample(t, 0.0, 0.0));
+        batcher.push(sample(t + Duration::milliseconds(200), 1.0, 0.0));
+        assert!(!batcher.is_due(t + Duration::seconds(9)));
+        assert!(batcher.is_due(t + Duration::seconds(10)));
+
+        assert_eq!(batcher.take().len(), 1);
+        assert!(!batcher.is_due(t + Duration::seconds(60)));
+    }
+
+    #[test]
+    fn backs_off_after_failed_uploads() {
+        let t = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let mut batcher = PositionBatcher::new();
+        batcher.push(sample(t, 0.0, 0.0));
+        let due = t + Duration::seconds(i64::from(DEFAULT_WINDOW_SECS));
+
+        batcher.record_failure(due, None);
+        batcher.record_failure(due, None);
+        assert!(!batcher.is_due(due + Duration::seconds(29)));
+        assert!(batcher.is_due(due + Duration::seconds(30)));
+
+        // A rate limit with Retry-After wins over a shorter backoff
+        batcher.record_success();
+        batcher.record_failure(due, Some(due + Duration::minutes(2)));
+        assert!(!batcher.is_due(due + Duration::seconds(100)));
+        batcher.record_success();
+        assert!(batcher.is_due(due));
+    }
+}
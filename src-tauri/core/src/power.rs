@@ -0,0 +1,133 @@
+//! Power Module
+//!
+//! Suspend and resume notifications. Windows reports them through a power
+//! notification callback; elsewhere (or if registering fails) a resume is
+//! inferred from the wall clock jumping between two checks.
+
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// How often the fallback detector looks at the wall clock
+pub const WAKE_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// A gap this long between two checks means the machine was asleep
+const SLEEP_GAP_SECS: i64 = 30;
+
+/// Machine power transition
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PowerEvent {
+    Suspend,
+    Resume,
+}
+
+/// Registered power notification, unregistered on drop
+pub struct PowerSubscription {
+    #[cfg(windows)]
+    handle: *mut std::ffi::c_void,
+    #[cfg(windows)]
+    _sender: Box<UnboundedSender<PowerEvent>>,
+}
+
+// Safety: the handle is only passed back to the unregister call in Drop
+unsafe impl Send for PowerSubscription {}
+
+/// Forward suspend/resume notifications to `sender`; `None` if the platform
+/// doesn't provide them
+pub fn subscribe(sender: UnboundedSender<PowerEvent>) -> Option<PowerSubscription> {
+    #[cfg(windows)]
+    unsafe {
+        use windows::Win32::Foundation::{HANDLE, ERROR_SUCCESS};
+        use windows::Win32::System::Power::{PowerRegisterSuspendResumeNotification, DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS};
+        use windows::Win32::UI::WindowsAndMessaging::DEVICE_NOTIFY_CALLBACK;
+
+        let sender = Box::new(sender);
+        let mut parameters = DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS {
+            Callback: Some(on_power_broadcast),
+            Context: &*sender as *const UnboundedSender<PowerEvent> as *mut std::ffi::c_void,
+        };
+        let mut handle = std::ptr::null_mut();
+        let result = PowerRegisterSuspendResumeNotification(
+            DEVICE_NOTIFY_CALLBACK,
+            HANDLE(&mut parameters as *mut DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS as *mut _),
+            &mut handle,
+        );
+        if result != ERROR_SUCCESS {
+            tracing::warn!("Failed to register for power notifications: {:?}", result);
+            return None;
+        }
+        Some(PowerSubscription { handle, _sender: sender })
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = sender;
+        None
+    }
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn on_power_broadcast(
+    context: *const std::ffi::c_void,
+    kind: u32,
+    _setting: *const std::ffi::c_void,
+) -> u32 {
+    use windows::Win32::UI::WindowsAndMessaging::{PBT_APMRESUMEAUTOMATIC, PBT_APMSUSPEND};
+
+    let event = match kind {
+        PBT_APMSUSPEND => PowerEvent::Suspend,
+        PBT_APMRESUMEAUTOMATIC => PowerEvent::Resume,
+        _ => return 0,
+    };
+    // Safety: the context is the boxed sender owned by the live subscription
+    let sender = &*(context as *const UnboundedSender<PowerEvent>);
+    let _ = sender.send(event);
+    0
+}
+
+#[cfg(windows)]
+impl Drop for PowerSubscription {
+    fn drop(&mut self) {
+        use windows::Win32::System::Power::{PowerUnregisterSuspendResumeNotification, HPOWERNOTIFY};
+
+        unsafe {
+            let _ = PowerUnregisterSuspendResumeNotification(HPOWERNOTIFY(self.handle as isize));
+        }
+    }
+}
+
+/// Infers a resume from the wall clock jumping ahead between checks
+#[derive(Debug, Default)]
+pub struct SleepDetector {
+    last_check: Option<DateTime<Utc>>,
+}
+
+impl SleepDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a check; returns true if the machine slept since the last one
+    pub fn observe(&mut self, now: DateTime<Utc>) -> bool {
+        let slept = self.last_check.is_some_and(|last| (now - last).num_seconds() >= SLEEP_GAP_SECS);
+        self.last_check = Some(now);
+        slept
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn detects_clock_jump_as_resume() {
+        let t = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let mut detector = SleepDetector::new();
+        assert!(!detector.observe(t));
+        assert!(!detector.observe(t + chrono::Duration::seconds(5)));
+        assert!(detector.observe(t + chrono::Duration::minutes(40)));
+        assert!(!detector.observe(t + chrono::Duration::minutes(40) + chrono::Duration::seconds(5)));
+    }
+}
@@ -0,0 +1,184 @@
+//! Pre-flight Module
+//!
+//! One-click go/no-go check before a long convoy: session validity for the
+//! planned duration, empty retry queue, disk space, telemetry plugin and
+//! server reachability.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::scs;
+use crate::sync_health::SyncStatus;
+
+/// Default planned session length (hours)
+pub const DEFAULT_HOURS: u32 = 3;
+/// Free disk space below which logging and checkpoints may fail
+const MIN_FREE_BYTES: u64 = 100 * 1024 * 1024;
+/// Free disk space below which we warn
+pub const LOW_FREE_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Result of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// One line of the pre-flight report
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightCheck {
+    pub id: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+/// Go/no-go report
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightReport {
+    /// False if any check failed
+    pub go: bool,
+    pub hours: u32,
+    pub checks: Vec<PreflightCheck>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Everything the checks look at, gathered by the command
+#[derive(Debug, Clone)]
+pub struct PreflightInputs {
+    pub now: DateTime<Utc>,
+    pub hours: u32,
+    pub session_expires_at: Option<DateTime<Utc>>,
+    pub queued_jobs: usize,
+    pub free_disk_bytes: Option<u64>,
+    pub storage_reset_required: bool,
+    pub telemetry_connected: bool,
+    pub plugin_revision: Option<u32>,
+    pub sync_status: SyncStatus,
+    pub maintenance: bool,
+}
+
+/// Run every check
+pub fn evaluate(inputs: &PreflightInputs) -> PreflightReport {
+    let checks = vec![
+        check_session(inputs),
+        check_queue(inputs),
+        check_disk(inputs),
+        check_plugin(inputs),
+        check_server(inputs),
+    ];
+
+    PreflightReport {
+        go: checks.iter().all(|check| check.status != CheckStatus::Fail),
+        hours: inputs.hours,
+        checks,
+        checked_at: inputs.now,
+    }
+}
+
+fn check(id: &'static str, status: CheckStatus, detail: impl Into<String>) -> PreflightCheck {
+    PreflightCheck { id, status, detail: detail.into() }
+}
+
+fn check_session(inputs: &PreflightInputs) -> PreflightCheck {
+    let needed_until = inputs.now + Duration::hours(i64::from(inputs.hours));
+    match inputs.session_expires_at {
+        None => check("session", CheckStatus::Fail, "Not linked to an account"),
+        Some(at) if at <= inputs.now => check("session", CheckStatus::Fail, "Session has expired, link the device again"),
+        Some(at) if at < needed_until => check(
+            "session",
+            CheckStatus::Fail,
+            format!("Session expires in {} min, before the {}h session ends", (at - inputs.now).num_minutes(), inputs.hours),
+        ),
+        Some(at) => check("session", CheckStatus::Pass, format!("Valid until {}", at.format("%Y-%m-%d %H:%M UTC"))),
+    }
+}
+
+fn check_queue(inputs: &PreflightInputs) -> PreflightCheck {
+    match inputs.queued_jobs {
+        0 => check("queue", CheckStatus::Pass, "No jobs waiting to be submitted"),
+        n => check("queue", CheckStatus::Warn, format!("{} job(s) waiting to be submitted, retry them first", n)),
+    }
+}
+
+fn check_disk(inputs: &PreflightInputs) -> PreflightCheck {
+    if inputs.storage_reset_required {
+        return check("disk", CheckStatus::Fail, "Stored data could not be read, sign in again");
+    }
+    match inputs.free_disk_bytes {
+        None => check("disk", CheckStatus::Warn, "Could not determine free disk space"),
+        Some(free) => {
+            let detail = format!("{} MB free", free / (1024 * 1024));
+            let status = if free < MIN_FREE_BYTES {
+                CheckStatus::Fail
+            } else if free < LOW_FREE_BYTES {
+                CheckStatus::Warn
+            } else {
+                CheckStatus::Pass
+            };
+            check("disk", status, detail)
+        }
+    }
+}
+
+fn check_plugin(inputs: &PreflightInputs) -> PreflightCheck {
+    match (inputs.telemetry_connected, inputs.plugin_revision) {
+        (true, Some(revision)) if scs::layout_for(revision).is_some() => {
+            check("plugin", CheckStatus::Pass, format!("Telemetry plugin revision {}", revision))
+        }
+        (true, Some(revision)) => check(
+            "plugin",
+            CheckStatus::Fail,
+            format!("Telemetry plugin revision {} is too old, update scs-sdk-plugin", revision),
+        ),
+        _ => check("plugin", CheckStatus::Warn, "Game not running, start it to verify the telemetry plugin"),
+    }
+}
+
+fn check_server(inputs: &PreflightInputs) -> PreflightCheck {
+    if inputs.maintenance {
+        return check("server", CheckStatus::Fail, "Server is under maintenance");
+    }
+    match inputs.sync_status {
+        SyncStatus::Online => check("server", CheckStatus::Pass, "Server reachable"),
+        SyncStatus::Offline => check("server", CheckStatus::Fail, "Server unreachable"),
+        SyncStatus::Degraded | SyncStatus::Unknown => {
+            check("server", CheckStatus::Warn, "Recent requests failed, check your connection")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn short_session_is_no_go() {
+        let now = Utc.with_ymd_and_hms(2025, 5, 9, 18, 0, 0).unwrap();
+        let mut inputs = PreflightInputs {
+            now,
+            hours: 3,
+            session_expires_at: Some(now + Duration::days(10)),
+            queued_jobs: 0,
+            free_disk_bytes: Some(20 * LOW_FREE_BYTES),
+            storage_reset_required: false,
+            telemetry_connected: true,
+            plugin_revision: Some(12),
+            sync_status: SyncStatus::Online,
+            maintenance: false,
+        };
+        let report = evaluate(&inputs);
+        assert!(report.go);
+        assert!(report.checks.iter().all(|check| check.status == CheckStatus::Pass));
+
+        inputs.session_expires_at = Some(now + Duration::hours(2));
+        inputs.queued_jobs = 2;
+        let report = evaluate(&inputs);
+        assert!(!report.go);
+        assert_eq!(report.checks[1].status, CheckStatus::Warn);
+    }
+}
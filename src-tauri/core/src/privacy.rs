@@ -0,0 +1,105 @@
+//! Privacy Module
+//!
+//! Suppresses position reporting and presence while the truck is inside a
+//! configured privacy zone, so streamers can keep their home garage hidden.
+
+use serde::Serialize;
+
+use crate::settings::{PrivacySettings, PrivacyZone};
+use crate::telemetry::Position;
+
+/// Extra margin before leaving a zone, so hovering at the edge doesn't flap
+const EXIT_MARGIN: f64 = 1.1;
+
+/// Privacy state change for the frontend
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivacyChange {
+    pub active: bool,
+    /// Zone that was entered, if any
+    pub zone: Option<String>,
+}
+
+/// Tracks whether the truck is inside a privacy zone
+#[derive(Debug, Default)]
+pub struct PrivacyGuard {
+    current: Option<String>,
+}
+
+impl PrivacyGuard {
+    /// Create a guard outside any zone
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether reporting is currently suppressed
+    pub fn is_active(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Feed the latest position; returns a change when entering or leaving a zone.
+    /// Without a position the previous state is kept.
+    pub fn observe(&mut self, position: Option<&Position>, settings: &PrivacySettings) -> Option<PrivacyChange> {
+        if !settings.enabled {
+            return self.current.take().map(|_| PrivacyChange { active: false, zone: None });
+        }
+        let position = position?;
+
+        if let Some(name) = &self.current {
+            let still_inside = settings.zones.iter()
+                .find(|zone| &zone.name == name)
+                .is_some_and(|zone| distance(zone, position) <= zone.radius * EXIT_MARGIN);
+            if still_inside {
+                return None;
+            }
+        }
+
+        let inside = settings.zones.iter()
+            .find(|zone| distance(zone, position) <= zone.radius)
+            .map(|zone| zone.name.clone());
+        if inside == self.current {
+            return None;
+        }
+        self.current = inside.clone();
+        Some(PrivacyChange { active: inside.is_some(), zone: inside })
+    }
+}
+
+/// Whether `position` lies inside one of the enabled zones
+pub fn in_zone(settings: &PrivacySettings, position: &Position) -> bool {
+    settings.enabled && settings.zones.iter().any(|zone| distance(zone, position) <= zone.radius)
+}
+
+fn distance(zone: &PrivacyZone, position: &Position) -> f64 {
+    (zone.x - position.x).hypot(zone.z - position.z)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(x: f64, z: f64) -> Position {
+        Position { x, z, ..Position::default() }
+    }
+
+    #[test]
+    fn enters_and_leaves_with_margin() {
+        let settings = PrivacySettings {
+            enabled: true,
+            zones: vec![PrivacyZone { name: "Home".into(), x: 100.0, z: 100.0, radius: 200.0 }],
+            ..PrivacySettings::default()
+        };
+        let mut guard = PrivacyGuard::new();
+
+        assert_eq!(guard.observe(Some(&at(1000.0, 1000.0)), &settings), None);
+        let entered = guard.observe(Some(&at(150.0, 100.0)), &settings).unwrap();
+        assert_eq!(entered.zone.as_deref(), Some("Home"));
+        assert!(guard.is_active());
+
+        // Just outside the radius but within the exit margin
+        assert_eq!(guard.observe(Some(&at(310.0, 100.0)), &settings), None);
+        let left = guard.observe(Some(&at(400.0, 100.0)), &settings).unwrap();
+        assert!(!left.active);
+        assert!(!guard.is_active());
+    }
+}
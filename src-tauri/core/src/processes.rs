@@ -0,0 +1,130 @@
+//! Processes Module
+//!
+//! Snapshot of running processes and the modules they loaded, for telling
+//! which game and which multiplayer client are running. Windows is read
+//! through ToolHelp and Linux through `/proc`, where Proton games show up
+//! with their Windows executable names; elsewhere nothing is reported.
+
+/// A running process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    /// Executable name, e.g. `eurotrucks2.exe`
+    pub name: String,
+}
+
+/// Every running process
+pub fn running() -> Vec<ProcessInfo> {
+    #[cfg(windows)]
+    unsafe {
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::System::Diagnostics::ToolHelp::{
+            CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
+        };
+
+        let Ok(snapshot) = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) else {
+            return Vec::new();
+        };
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+        let mut processes = Vec::new();
+        let mut more = Process32FirstW(snapshot, &mut entry).is_ok();
+        while more {
+            processes.push(ProcessInfo { pid: entry.th32ProcessID, name: wide_string(&entry.szExeFile) });
+            more = Process32NextW(snapshot, &mut entry).is_ok();
+        }
+        let _ = CloseHandle(snapshot);
+        processes
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let Ok(entries) = std::fs::read_dir("/proc") else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+            .filter_map(|pid| Some(ProcessInfo { pid, name: proc_name(pid)? }))
+            .collect()
+    }
+
+    #[cfg(not(any(windows, target_os = "linux")))]
+    Vec::new()
+}
+
+/// File names of the modules (DLLs) loaded by process `pid`
+pub fn modules(pid: u32) -> Vec<String> {
+    #[cfg(windows)]
+    unsafe {
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::System::Diagnostics::ToolHelp::{
+            CreateToolhelp32Snapshot, Module32FirstW, Module32NextW, MODULEENTRY32W, TH32CS_SNAPMODULE,
+            TH32CS_SNAPMODULE32,
+        };
+
+        let Ok(snapshot) = CreateToolhelp32Snapshot(TH32CS_SNAPMODULE | TH32CS_SNAPMODULE32, pid) else {
+            return Vec::new();
+        };
+        let mut entry = MODULEENTRY32W {
+            dwSize: std::mem::size_of::<MODULEENTRY32W>() as u32,
+            ..Default::default()
+        };
+        let mut modules = Vec::new();
+        let mut more = Module32FirstW(snapshot, &mut entry).is_ok();
+        while more {
+            modules.push(wide_string(&entry.szModule));
+            more = Module32NextW(snapshot, &mut entry).is_ok();
+        }
+        let _ = CloseHandle(snapshot);
+        modules
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let Ok(maps) = std::fs::read_to_string(format!("/proc/{}/maps", pid)) else {
+            return Vec::new();
+        };
+        let mut modules: Vec<String> = Vec::new();
+        for path in maps.lines().filter_map(|line| line.split_whitespace().nth(5)) {
+            let name = file_name(path);
+            if !name.is_empty() && !modules.iter().any(|module| module == name) {
+                modules.push(name.to_string());
+            }
+        }
+        modules
+    }
+
+    #[cfg(not(any(windows, target_os = "linux")))]
+    {
+        let _ = pid;
+        Vec::new()
+    }
+}
+
+/// Executable name of `pid`; Wine processes keep the Windows path in
+/// their command line, while `comm` is cut at 15 characters
+#[cfg(target_os = "linux")]
+fn proc_name(pid: u32) -> Option<String> {
+    let cmdline = std::fs::read(format!("/proc/{}/cmdline", pid)).unwrap_or_default();
+    let program = cmdline.split(|byte| *byte == 0).next().map(String::from_utf8_lossy).unwrap_or_default();
+    match file_name(&program) {
+        "" => std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok().map(|comm| comm.trim().to_string()),
+        name => Some(name.to_string()),
+    }
+}
+
+/// Last component of a Unix or Windows path
+#[cfg(target_os = "linux")]
+fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+/// NUL-terminated UTF-16 buffer as a string
+#[cfg(windows)]
+fn wide_string(chars: &[u16]) -> String {
+    let len = chars.iter().position(|c| *c == 0).unwrap_or(chars.len());
+    String::from_utf16_lossy(&chars[..len])
+}
@@ -0,0 +1,89 @@
+//! Progress Module
+//!
+//! Percent and ETA for sync work made of many steps (draining the job queue,
+//! uploading a position backlog), so the UI can show the app is catching up
+//! rather than stuck.
+
+use std::time::{Duration, Instant};
+use serde::Serialize;
+
+/// Backlogs smaller than this finish too fast to be worth reporting
+pub const MIN_STEPS: usize = 3;
+
+/// Progress of one multi-step sync
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncProgress {
+    pub done: usize,
+    pub total: usize,
+    pub percent: u8,
+    /// Seconds left at the pace so far, once a step has finished
+    pub eta_secs: Option<u64>,
+    /// Set on the last event, also when the sync stopped early
+    pub finished: bool,
+}
+
+/// Counts finished steps against the total
+#[derive(Debug)]
+pub struct ProgressTracker {
+    total: usize,
+    done: usize,
+    started: Instant,
+}
+
+impl ProgressTracker {
+    /// Track `total` steps, or `None` for a backlog too small to report
+    pub fn for_backlog(total: usize) -> Option<Self> {
+        (total >= MIN_STEPS).then(|| Self { total, done: 0, started: Instant::now() })
+    }
+
+    /// Progress before any step ran
+    pub fn start(&self) -> SyncProgress {
+        self.at(Duration::ZERO, false)
+    }
+
+    /// One more step is done
+    pub fn advance(&mut self) -> SyncProgress {
+        self.done = (self.done + 1).min(self.total);
+        self.at(self.started.elapsed(), false)
+    }
+
+    /// The sync is over, whether or not every step ran
+    pub fn finish(&self) -> SyncProgress {
+        self.at(self.started.elapsed(), true)
+    }
+
+    fn at(&self, elapsed: Duration, finished: bool) -> SyncProgress {
+        let left = self.total - self.done;
+        let eta_secs = (self.done > 0 && !finished).then(|| {
+            (elapsed.as_secs_f64() / self.done as f64 * left as f64).round() as u64
+        });
+        SyncProgress {
+            done: self.done,
+            total: self.total,
+            percent: (self.done * 100 / self.total.max(1)) as u8,
+            eta_secs,
+            finished,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_remaining_time_from_pace() {
+        assert!(ProgressTracker::for_backlog(MIN_STEPS - 1).is_none());
+
+        let mut tracker = ProgressTracker::for_backlog(4).unwrap();
+        assert_eq!(tracker.start().eta_secs, None);
+        tracker.done = 1;
+        let progress = tracker.at(Duration::from_secs(3), false);
+        assert_eq!((progress.percent, progress.eta_secs), (25, Some(9)));
+
+        tracker.done = 3;
+        let finished = tracker.at(Duration::from_secs(9), true);
+        assert_eq!((finished.percent, finished.eta_secs, finished.finished), (75, None, true));
+    }
+}
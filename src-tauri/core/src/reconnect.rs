@@ -0,0 +1,134 @@
+//! Reconnect Module
+//!
+//! Decides when to retry opening the telemetry shared memory while the game
+//! isn't connected: quickly for a short while after the game process shows
+//! up, and only occasionally while the app idles in the tray. Also notices
+//! when a crashed or killed game leaves a map behind that never updates.
+
+use std::time::{Duration, Instant};
+
+/// Retry interval right after the game process appears
+const FAST_INTERVAL: Duration = Duration::from_millis(100);
+/// How long to keep retrying quickly once the game is seen
+const FAST_WINDOW: Duration = Duration::from_secs(30);
+/// Retry (and process check) interval otherwise
+const SLOW_INTERVAL: Duration = Duration::from_secs(5);
+/// How long the render timestamp may stand still before the map is stale
+const STALE_AFTER: Duration = Duration::from_secs(10);
+
+/// Schedule for shared memory connection attempts
+#[derive(Debug, Default)]
+pub struct ReconnectSchedule {
+    next_attempt: Option<Instant>,
+    fast_until: Option<Instant>,
+    /// Game process seen at the last check
+    game_running: bool,
+}
+
+impl ReconnectSchedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether to try connecting now. `game_running` is only called (it
+    /// walks the process list) when not already retrying quickly
+    pub fn attempt_due(&mut self, now: Instant, game_running: impl FnOnce() -> bool) -> bool {
+        if self.next_attempt.is_some_and(|next| now < next) {
+            return false;
+        }
+
+        if !self.is_fast(now) {
+            let running = game_running();
+            if running && !self.game_running {
+                self.fast_until = Some(now + FAST_WINDOW);
+            }
+            self.game_running = running;
+        }
+
+        let interval = if self.is_fast(now) { FAST_INTERVAL } else { SLOW_INTERVAL };
+        self.next_attempt = Some(now + interval);
+        true
+    }
+
+    /// Connected; the next attempt after a disconnect is immediate, but the
+    /// game must go away and come back before fast retries resume
+    pub fn connected(&mut self) {
+        *self = Self { game_running: true, ..Self::default() };
+    }
+
+    /// Whether attempts are currently made at the fast rate
+    pub fn is_fast(&self, now: Instant) -> bool {
+        self.fast_until.is_some_and(|until| now < until)
+    }
+}
+
+/// Watches the SDK render timestamp for a game that stopped updating the
+/// map without clearing `sdkActive`
+#[derive(Debug, Default)]
+pub struct Liveness {
+    last_render_time: Option<u64>,
+    changed_at: Option<Instant>,
+}
+
+impl Liveness {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the timestamp of the current frame; false once it has been frozen
+    /// for too long. Kept across reconnects, so re-opening a stale map
+    /// doesn't count as a new session
+    pub fn observe(&mut self, render_time: u64, now: Instant) -> bool {
+        if self.last_render_time != Some(render_time) {
+            self.last_render_time = Some(render_time);
+            self.changed_at = Some(now);
+        }
+        self.changed_at.is_some_and(|changed| now.duration_since(changed) < STALE_AFTER)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_fast_only_after_game_starts() {
+        let t = Instant::now();
+        let mut schedule = ReconnectSchedule::new();
+
+        // Idle in the tray
+        assert!(schedule.attempt_due(t, || false));
+        assert!(!schedule.attempt_due(t + Duration::from_secs(1), || unreachable!()));
+        assert!(schedule.attempt_due(t + SLOW_INTERVAL, || true));
+
+        // Game detected: fast retries without re-walking the process list
+        let started = t + SLOW_INTERVAL;
+        assert!(schedule.attempt_due(started + FAST_INTERVAL, || unreachable!()));
+        assert!(schedule.attempt_due(started + FAST_INTERVAL * 2, || unreachable!()));
+
+        // Plugin never showed up: back to slow retries
+        let later = started + FAST_WINDOW;
+        assert!(schedule.attempt_due(later, || true));
+        assert!(!schedule.attempt_due(later + FAST_INTERVAL, || true));
+
+        // After a session ends, a still-running game doesn't restart fast mode
+        schedule.connected();
+        assert!(schedule.attempt_due(later, || true));
+        assert!(!schedule.attempt_due(later + FAST_INTERVAL, || true));
+    }
+
+    #[test]
+    fn frozen_render_time_marks_map_stale() {
+        let t = Instant::now();
+        let mut liveness = Liveness::new();
+
+        assert!(liveness.observe(1_000, t));
+        assert!(liveness.observe(2_000, t + Duration::from_secs(8)));
+        assert!(liveness.observe(2_000, t + Duration::from_secs(15)));
+        assert!(!liveness.observe(2_000, t + Duration::from_secs(18)));
+
+        // Re-opening the leftover map stays stale until the game writes again
+        assert!(!liveness.observe(2_000, t + Duration::from_secs(30)));
+        assert!(liveness.observe(50, t + Duration::from_secs(60)));
+    }
+}
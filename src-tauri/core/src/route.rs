@@ -0,0 +1,186 @@
+//! Route Module
+//!
+//! The path a job was driven along, kept with the local job history and
+//! exported as GPX or GeoJSON for external maps. Game world coordinates are
+//! placed on the real map with a local projection around a reference point
+//! per game, which is accurate enough for an overview but not to the metre:
+//! the game world is compressed unevenly.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::snapshots::SnapshotSeries;
+use crate::telemetry::Game;
+
+/// Mean Earth radius (m)
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// Where a game's world sits on the real map
+struct MapReference {
+    /// World coordinates of the reference point (m)
+    x: f64,
+    z: f64,
+    /// The reference point on the real map (degrees)
+    lat: f64,
+    lon: f64,
+    /// Real metres per world metre
+    scale: f64,
+}
+
+const ETS2_MAP: MapReference = MapReference { x: 16_660.0, z: 4_150.0, lat: 50.0, lon: 15.0, scale: 19.0 };
+const ATS_MAP: MapReference = MapReference { x: 0.0, z: 0.0, lat: 39.0, lon: -96.0, scale: 20.0 };
+
+/// One point of a driven route, in world coordinates (m)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutePoint {
+    pub at: DateTime<Utc>,
+    pub x: f64,
+    pub z: f64,
+}
+
+impl RoutePoint {
+    /// Approximate latitude and longitude (degrees)
+    pub fn to_wgs84(&self, game: Game) -> (f64, f64) {
+        let map = match game {
+            Game::Ets2 => &ETS2_MAP,
+            Game::Ats => &ATS_MAP,
+        };
+        // World Z grows southwards
+        let north_m = (map.z - self.z) * map.scale;
+        let east_m = (self.x - map.x) * map.scale;
+        let lat = map.lat + (north_m / EARTH_RADIUS_M).to_degrees();
+        let lon = map.lon + (east_m / (EARTH_RADIUS_M * map.lat.to_radians().cos())).to_degrees();
+        (lat, lon)
+    }
+}
+
+/// Points of the route from a job's snapshots
+pub fn from_snapshots(series: &SnapshotSeries) -> Vec<RoutePoint> {
+    series.snapshots.iter()
+        .map(|snapshot| RoutePoint { at: snapshot.at, x: snapshot.x, z: snapshot.z })
+        .collect()
+}
+
+/// Export format of `export_route`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RouteFormat {
+    Gpx,
+    GeoJson,
+}
+
+/// A recorded route with what's needed to label it
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobRoute {
+    pub game: Game,
+    pub source_city: String,
+    pub destination_city: String,
+    pub cargo: String,
+    pub points: Vec<RoutePoint>,
+}
+
+impl JobRoute {
+    fn name(&self) -> String {
+        format!("{} → {} ({})", self.source_city, self.destination_city, self.cargo)
+    }
+
+    /// Render as a GPX or GeoJSON document
+    pub fn export(&self, format: RouteFormat) -> String {
+        match format {
+            RouteFormat::Gpx => self.to_gpx(),
+            RouteFormat::GeoJson => self.to_geojson(),
+        }
+    }
+
+    fn to_gpx(&self) -> String {
+        let mut gpx = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<gpx version=\"1.1\" creator=\"VTC Tracker\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n",
+        ));
+        gpx.push_str(&format!("  <trk>\n    <name>{}</name>\n    <trkseg>\n", escape_xml(&self.name())));
+        for point in &self.points {
+            let (lat, lon) = point.to_wgs84(self.game);
+            gpx.push_str(&format!(
+                "      <trkpt lat=\"{:.6}\" lon=\"{:.6}\"><time>{}</time></trkpt>\n",
+                lat,
+                lon,
+                point.at.to_rfc3339_opts(SecondsFormat::Secs, true),
+            ));
+        }
+        gpx.push_str("    </trkseg>\n  </trk>\n</gpx>\n");
+        gpx
+    }
+
+    fn to_geojson(&self) -> String {
+        let coordinates: Vec<[f64; 2]> = self.points.iter()
+            .map(|point| {
+                let (lat, lon) = point.to_wgs84(self.game);
+                [round6(lon), round6(lat)]
+            })
+            .collect();
+        let times: Vec<String> = self.points.iter()
+            .map(|point| point.at.to_rfc3339_opts(SecondsFormat::Secs, true))
+            .collect();
+        serde_json::json!({
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "geometry": { "type": "LineString", "coordinates": coordinates },
+                "properties": {
+                    "name": self.name(),
+                    "game": self.game,
+                    "cargo": self.cargo,
+                    "sourceCity": self.source_city,
+                    "destinationCity": self.destination_city,
+                    "times": times,
+                },
+            }],
+        })
+        .to_string()
+    }
+}
+
+fn round6(value: f64) -> f64 {
+    (value * 1_000_000.0).round() / 1_000_000.0
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn exports_gpx_and_geojson_on_the_real_map() {
+        let t = Utc.with_ymd_and_hms(2025, 3, 1, 19, 0, 0).unwrap();
+        let route = JobRoute {
+            game: Game::Ets2,
+            source_city: "Praha".into(),
+            destination_city: "Brno & Co".into(),
+            cargo: "Machinery".into(),
+            points: vec![
+                RoutePoint { at: t, x: 16_660.0, z: 4_150.0 },
+                RoutePoint { at: t + chrono::Duration::minutes(30), x: 22_500.0, z: 9_000.0 },
+            ],
+        };
+
+        // The reference point lands on its coordinates; further east and south from there
+        assert_eq!(route.points[0].to_wgs84(Game::Ets2), (50.0, 15.0));
+        let (lat, lon) = route.points[1].to_wgs84(Game::Ets2);
+        assert!((48.5..50.0).contains(&lat) && (15.0..17.0).contains(&lon), "{lat}, {lon}");
+
+        let gpx = route.export(RouteFormat::Gpx);
+        assert_eq!(gpx.matches("<trkpt ").count(), 2);
+        assert!(gpx.contains("<name>Praha → Brno &amp; Co (Machinery)</name>"));
+        assert!(gpx.contains("<time>2025-03-01T19:30:00Z</time>"));
+
+        let geojson: serde_json::Value = serde_json::from_str(&route.export(RouteFormat::GeoJson)).unwrap();
+        let line = &geojson["features"][0]["geometry"];
+        assert_eq!(line["type"], "LineString");
+        assert_eq!(line["coordinates"][1][1].as_f64().unwrap(), round6(lat));
+    }
+}
@@ -0,0 +1,129 @@
+//! Submission Rules Module
+//!
+//! User-configured filters that keep deliveries which don't count for the
+//! VTC out of the auto-submit pipeline. Skipped jobs stay in the local
+//! history, flagged as not submitted. Simulator jobs are always kept back.
+
+use serde::{Deserialize, Serialize};
+
+use crate::names;
+use crate::sync::JobSubmission;
+
+/// Market of jobs that count with `freight_market_only`
+const FREIGHT_MARKET: &str = "freight_market";
+
+/// Which deliveries are submitted automatically
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SubmissionRules {
+    /// Shortest planned distance submitted (km); 0 submits every job
+    pub min_distance_km: u32,
+    /// Cargo names or IDs that are never submitted
+    pub excluded_cargo: Vec<String>,
+    /// Submit freight market jobs only; jobs without a known market are
+    /// still submitted
+    pub freight_market_only: bool,
+}
+
+/// Why a job was kept back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    TooShort { distance_km: u32, min_distance_km: u32 },
+    ExcludedCargo(String),
+    NotFreightMarket(String),
+    /// Driven in the telemetry simulator
+    Simulated,
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::TooShort { distance_km, min_distance_km } => {
+                write!(f, "Shorter than {} km ({} km)", min_distance_km, distance_km)
+            }
+            SkipReason::ExcludedCargo(cargo) => write!(f, "Cargo {} is excluded", cargo),
+            SkipReason::NotFreightMarket(market) => write!(f, "Not a freight market job ({})", market),
+            SkipReason::Simulated => write!(f, "Simulator job"),
+        }
+    }
+}
+
+impl SubmissionRules {
+    /// Why `submission` shouldn't be submitted, if it shouldn't
+    pub fn check(&self, submission: &JobSubmission) -> Option<SkipReason> {
+        let telemetry = |key: &str| submission.telemetry_data.as_ref().and_then(|data| data.get(key));
+        if telemetry("simulated").and_then(serde_json::Value::as_bool) == Some(true) {
+            return Some(SkipReason::Simulated);
+        }
+        if submission.distance_km < self.min_distance_km {
+            return Some(SkipReason::TooShort {
+                distance_km: submission.distance_km,
+                min_distance_km: self.min_distance_km,
+            });
+        }
+        let excluded = self.excluded_cargo.iter()
+            .any(|cargo| names::canonical_cargo(cargo).eq_ignore_ascii_case(&submission.cargo));
+        if excluded {
+            return Some(SkipReason::ExcludedCargo(submission.cargo.clone()));
+        }
+        let market = telemetry("market").and_then(serde_json::Value::as_str);
+        match market {
+            Some(market) if self.freight_market_only && market != FREIGHT_MARKET => {
+                Some(SkipReason::NotFreightMarket(market.to_string()))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submission(cargo: &str, distance_km: u32, market: Option<&str>) -> JobSubmission {
+        JobSubmission {
+            game: "ets2".into(),
+            cargo: names::canonical_cargo(cargo),
+            source_city: "berlin".into(),
+            destination_city: "hamburg".into(),
+            distance_km,
+            revenue: 12_000.0,
+            currency: None,
+            damage_percent: 0.0,
+            chassis_damage_percent: 0.0,
+            truck_id: None,
+            trailer_id: None,
+            trailer_ownership: None,
+            telemetry_data: market.map(|market| serde_json::json!({ "market": market })),
+            server: None,
+            fuel_used: None,
+            average_consumption: None,
+            driven_km: None,
+            navigation_km: None,
+            idempotency_key: None,
+            partial: false,
+        }
+    }
+
+    #[test]
+    fn skips_short_excluded_and_off_market_jobs() {
+        let rules = SubmissionRules {
+            min_distance_km: 100,
+            excluded_cargo: vec!["Apples".into()],
+            freight_market_only: true,
+        };
+        assert_eq!(rules.check(&submission("Machinery", 290, Some("freight_market"))), None);
+        assert_eq!(
+            rules.check(&submission("Machinery", 60, Some("freight_market"))),
+            Some(SkipReason::TooShort { distance_km: 60, min_distance_km: 100 }),
+        );
+        assert!(matches!(rules.check(&submission("apples", 290, None)), Some(SkipReason::ExcludedCargo(_))));
+        let quick_job = rules.check(&submission("Machinery", 290, Some("quick_job"))).unwrap();
+        assert_eq!(quick_job.to_string(), "Not a freight market job (quick_job)");
+        // Older plugins don't report the market
+        assert_eq!(rules.check(&submission("Machinery", 290, None)), None);
+        assert_eq!(SubmissionRules::default().check(&submission("Apples", 1, Some("quick_job"))), None);
+        let simulated = submission("Machinery", 290, None).with_telemetry("simulated", true.into());
+        assert_eq!(SubmissionRules::default().check(&simulated), Some(SkipReason::Simulated));
+    }
+}
@@ -0,0 +1,547 @@
+//! SCS Shared Memory Module
+//!
+//! Parses the `Local\SCSTelemetry` map published by scs-sdk-plugin
+//! (revisions 10–12+). The map is split into fixed-offset zones by value
+//! type; the offsets of the fields we read are kept in a per-revision layout
+//! table (`data/scs_layouts.json`) so a new plugin revision only needs a new
+//! table entry. Blocks a
+//! revision doesn't publish are left out of its layout and reported as unknown.
+
+use std::sync::OnceLock;
+use serde::Deserialize;
+
+use crate::games;
+use crate::telemetry::{FuelLevel, Game, Position, TelemetryField, TrailerInfo, TruckInfo, TruckWear};
+
+/// Size of the shared memory map created by the plugin
+pub const MAP_SIZE: usize = 32 * 1024;
+/// Offset of the plugin revision (first field of the unsigned int zone)
+const REVISION_OFFSET: usize = 40;
+/// Fixed size of string fields
+const STRING_SIZE: usize = 64;
+/// Fixed size of the short string fields (job market, fine offence)
+const SHORT_STRING_SIZE: usize = 32;
+
+/// Field offsets for one plugin revision
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Layout {
+    pub revision: u32,
+    // Zone 1: header (0)
+    sdk_active: usize,
+    paused: usize,
+    render_time: usize,
+    // Zone 2: unsigned int (40)
+    game: usize,
+    game_time: usize,
+    planned_distance_km: usize,
+    // Zone 3: int (500)
+    rest_stop: usize,
+    // Zone 4: float (700)
+    fuel_capacity: usize,
+    speed: usize,
+    engine_rpm: usize,
+    fuel: usize,
+    fuel_avg_consumption: usize,
+    fuel_range: usize,
+    wear: Option<WearOffsets>,
+    odometer: usize,
+    navigation: Option<NavigationOffsets>,
+    cargo_damage: usize,
+    // Zone 8: double placement (2200)
+    truck_x: usize,
+    truck_y: usize,
+    truck_z: usize,
+    truck_heading: usize,
+    // Zone 9: strings (2300)
+    truck_brand_id: usize,
+    truck_brand: usize,
+    truck_id: usize,
+    truck_name: usize,
+    cargo: usize,
+    destination_city: usize,
+    destination_company: usize,
+    source_city: usize,
+    source_company: usize,
+    truck_license_plate: usize,
+    truck_license_plate_country: usize,
+    job_market: usize,
+    fine_offence: usize,
+    // Zone 10: unsigned long long (4000)
+    job_income: usize,
+    // Zone 11: long long (4200)
+    cancel_penalty: usize,
+    delivered_revenue: usize,
+    fine_amount: usize,
+    toll_amount: usize,
+    ferry_amount: usize,
+    train_amount: usize,
+    // Zone 12: special event flags (4300)
+    on_job: usize,
+    job_cancelled: usize,
+    job_delivered: usize,
+    fined: usize,
+    tollgate: usize,
+    ferry: usize,
+    train: usize,
+    // Zone 13: trailers (6000), first trailer only
+    trailer: Option<TrailerOffsets>,
+    /// Bytes that must be present to read every field
+    min_size: usize,
+}
+
+/// Truck wear block
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct WearOffsets {
+    engine: usize,
+    transmission: usize,
+    cabin: usize,
+    chassis: usize,
+    wheels: usize,
+}
+
+/// First trailer's identity
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct TrailerOffsets {
+    id: usize,
+    body_type: usize,
+    license_plate: usize,
+}
+
+/// Navigation block (route advisor)
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct NavigationOffsets {
+    route_distance: usize,
+    speed_limit: usize,
+}
+
+impl Layout {
+    /// Blocks this revision doesn't publish
+    fn unknown_fields(&self) -> Vec<TelemetryField> {
+        let mut unknown = Vec::new();
+        if self.navigation.is_none() {
+            unknown.push(TelemetryField::Navigation);
+        }
+        if self.wear.is_none() {
+            unknown.push(TelemetryField::TruckWear);
+        }
+        if self.trailer.is_none() {
+            unknown.push(TelemetryField::Trailer);
+        }
+        unknown
+    }
+}
+
+/// Map name and per-revision layouts, shipped as data so a new plugin
+/// revision is a data update
+const PLUGIN_DATA: &str = include_str!("../data/scs_layouts.json");
+
+#[derive(Deserialize)]
+struct PluginFile {
+    map_name: String,
+    layouts: Vec<LayoutEntry>,
+}
+
+/// A full layout, or a revision reusing an earlier revision's offsets
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LayoutEntry {
+    Full(Box<Layout>),
+    Same { revision: u32, base: u32 },
+}
+
+struct PluginData {
+    map_name: String,
+    /// Ascending by revision
+    layouts: Vec<Layout>,
+}
+
+fn plugin_data() -> &'static PluginData {
+    static DATA: OnceLock<PluginData> = OnceLock::new();
+    DATA.get_or_init(|| load_plugin_data(PLUGIN_DATA).expect("bundled plugin layouts"))
+}
+
+fn load_plugin_data(json: &str) -> Result<PluginData, String> {
+    let file: PluginFile = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let mut layouts: Vec<Layout> = Vec::new();
+    for entry in file.layouts {
+        let layout = match entry {
+            LayoutEntry::Full(layout) => *layout,
+            LayoutEntry::Same { revision, base } => {
+                let base = layouts.iter()
+                    .find(|layout| layout.revision == base)
+                    .ok_or_else(|| format!("Revision {} refers to unknown revision {}", revision, base))?;
+                Layout { revision, ..*base }
+            }
+        };
+        layouts.push(layout);
+    }
+    layouts.sort_by_key(|layout| layout.revision);
+    Ok(PluginData { map_name: file.map_name, layouts })
+}
+
+/// Name of the shared memory map the plugin creates
+pub fn map_name() -> &'static str {
+    &plugin_data().map_name
+}
+
+/// Layout for a plugin revision; newer revisions fall back to the latest known one
+pub fn layout_for(revision: u32) -> Option<&'static Layout> {
+    let layouts = &plugin_data().layouts;
+    layouts.iter()
+        .find(|layout| layout.revision == revision)
+        .or_else(|| layouts.last().filter(|latest| revision > latest.revision))
+}
+
+/// Job data as published by the plugin
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JobFrame {
+    pub cargo: String,
+    pub source_city: String,
+    pub source_company: String,
+    pub destination_city: String,
+    pub destination_company: String,
+    pub planned_distance_km: u32,
+    /// Zero when the plugin publishes no navigation data
+    pub distance_remaining_km: u32,
+    pub income: u64,
+    /// Where the job came from ("cargo_market", "freight_market", ...)
+    pub market: String,
+}
+
+/// Special event flags (held by the plugin for a short while after the event)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpecialFlags {
+    pub job_delivered: bool,
+    pub job_cancelled: bool,
+    pub fined: bool,
+    pub tollgate: bool,
+    pub ferry: bool,
+    pub train: bool,
+}
+
+/// Amounts attached to the special events (game currency)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventAmounts {
+    pub fine_offence: String,
+    pub fine: i64,
+    pub toll: i64,
+    pub ferry: i64,
+    pub train: i64,
+    pub delivered_revenue: i64,
+    pub cancel_penalty: i64,
+}
+
+/// One decoded snapshot of the shared memory
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScsFrame {
+    pub revision: u32,
+    pub sdk_active: bool,
+    pub paused: bool,
+    /// Game render timestamp (µs); keeps advancing while the game runs,
+    /// even when paused
+    pub render_time: u64,
+    pub game: Option<Game>,
+    /// In-game time in minutes
+    pub game_time: u32,
+    /// Game minutes until the driver must sleep; negative when overdue
+    pub rest_stop_minutes: i32,
+    pub speed_kmh: f32,
+    pub speed_limit_kmh: Option<f32>,
+    pub engine_rpm: f32,
+    pub fuel: FuelLevel,
+    pub wear: Option<TruckWear>,
+    pub cargo_damage: f32,
+    /// Truck odometer (km)
+    pub odometer_km: f32,
+    pub position: Position,
+    /// `None` until the game reports a truck
+    pub truck: Option<TruckInfo>,
+    /// `None` without a trailer attached
+    pub trailer: Option<TrailerInfo>,
+    pub job: Option<JobFrame>,
+    pub flags: SpecialFlags,
+    pub amounts: EventAmounts,
+    /// Blocks the plugin revision doesn't publish
+    pub unknown: Vec<TelemetryField>,
+}
+
+/// Shared memory parsing errors
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ScsError {
+    #[error("Telemetry map too small ({0} bytes)")]
+    TooShort(usize),
+
+    #[error("Unsupported telemetry plugin revision {0}")]
+    UnsupportedRevision(u32),
+}
+
+impl ScsError {
+    /// Stable code for the troubleshooting panel
+    pub fn code(&self) -> &'static str {
+        match self {
+            ScsError::TooShort(_) => "map_too_short",
+            ScsError::UnsupportedRevision(_) => "unsupported_plugin",
+        }
+    }
+}
+
+/// Decode a snapshot of the shared memory map
+pub fn parse(buf: &[u8]) -> Result<ScsFrame, ScsError> {
+    let bytes = Bytes(buf);
+    let revision = bytes.try_u32(REVISION_OFFSET).ok_or(ScsError::TooShort(buf.len()))?;
+    let layout = layout_for(revision).ok_or(ScsError::UnsupportedRevision(revision))?;
+    decode(buf, revision, layout)
+}
+
+/// Decode a map laid out as `layout`
+fn decode(buf: &[u8], revision: u32, layout: &Layout) -> Result<ScsFrame, ScsError> {
+    let bytes = Bytes(buf);
+    if buf.len() < layout.min_size {
+        return Err(ScsError::TooShort(buf.len()));
+    }
+
+    let speed_limit = layout.navigation
+        .map(|navigation| bytes.f32(navigation.speed_limit) * 3.6)
+        .filter(|limit| *limit > 0.0);
+    let route_distance = layout.navigation.map_or(0.0, |navigation| bytes.f32(navigation.route_distance));
+    let on_job = bytes.bool(layout.on_job);
+    let cargo = bytes.string(layout.cargo, STRING_SIZE);
+    let job = (on_job && !cargo.is_empty()).then(|| JobFrame {
+        cargo,
+        source_city: bytes.string(layout.source_city, STRING_SIZE),
+        source_company: bytes.string(layout.source_company, STRING_SIZE),
+        destination_city: bytes.string(layout.destination_city, STRING_SIZE),
+        destination_company: bytes.string(layout.destination_company, STRING_SIZE),
+        planned_distance_km: bytes.u32(layout.planned_distance_km),
+        distance_remaining_km: (route_distance.max(0.0) / 1000.0).round() as u32,
+        income: bytes.u64(layout.job_income),
+        market: bytes.string(layout.job_market, SHORT_STRING_SIZE),
+    });
+    let truck_brand_id = bytes.string(layout.truck_brand_id, STRING_SIZE);
+    let truck = (!truck_brand_id.is_empty()).then(|| TruckInfo {
+        brand_id: truck_brand_id,
+        brand: bytes.string(layout.truck_brand, STRING_SIZE),
+        model_id: bytes.string(layout.truck_id, STRING_SIZE),
+        model: bytes.string(layout.truck_name, STRING_SIZE),
+        license_plate: bytes.string(layout.truck_license_plate, STRING_SIZE),
+        license_plate_country: bytes.string(layout.truck_license_plate_country, STRING_SIZE),
+    });
+    let trailer = layout.trailer
+        .map(|trailer| TrailerInfo {
+            id: bytes.string(trailer.id, STRING_SIZE),
+            body_type: bytes.string(trailer.body_type, STRING_SIZE),
+            license_plate: bytes.string(trailer.license_plate, STRING_SIZE),
+        })
+        .filter(|trailer| !trailer.id.is_empty());
+
+    Ok(ScsFrame {
+        revision,
+        sdk_active: bytes.bool(layout.sdk_active),
+        paused: bytes.bool(layout.paused),
+        render_time: bytes.u64(layout.render_time),
+        game: games::from_plugin_id(bytes.u32(layout.game)),
+        game_time: bytes.u32(layout.game_time),
+        rest_stop_minutes: bytes.i32(layout.rest_stop),
+        speed_kmh: bytes.f32(layout.speed).abs() * 3.6,
+        speed_limit_kmh: speed_limit,
+        engine_rpm: bytes.f32(layout.engine_rpm),
+        fuel: FuelLevel {
+            liters: bytes.f32(layout.fuel),
+            capacity: bytes.f32(layout.fuel_capacity),
+            avg_consumption: bytes.f32(layout.fuel_avg_consumption),
+            range_km: bytes.f32(layout.fuel_range),
+        },
+        wear: layout.wear.map(|wear| TruckWear {
+            engine: bytes.f32(wear.engine),
+            transmission: bytes.f32(wear.transmission),
+            cabin: bytes.f32(wear.cabin),
+            chassis: bytes.f32(wear.chassis),
+            wheels: bytes.f32(wear.wheels),
+        }),
+        cargo_damage: bytes.f32(layout.cargo_damage),
+        odometer_km: bytes.f32(layout.odometer),
+        position: Position {
+            x: bytes.f64(layout.truck_x),
+            y: bytes.f64(layout.truck_y),
+            z: bytes.f64(layout.truck_z),
+            heading: bytes.f64(layout.truck_heading) as f32,
+        },
+        truck,
+        trailer,
+        job,
+        flags: SpecialFlags {
+            job_delivered: bytes.bool(layout.job_delivered),
+            job_cancelled: bytes.bool(layout.job_cancelled),
+            fined: bytes.bool(layout.fined),
+            tollgate: bytes.bool(layout.tollgate),
+            ferry: bytes.bool(layout.ferry),
+            train: bytes.bool(layout.train),
+        },
+        amounts: EventAmounts {
+            fine_offence: bytes.string(layout.fine_offence, SHORT_STRING_SIZE),
+            fine: bytes.i64(layout.fine_amount),
+            toll: bytes.i64(layout.toll_amount),
+            ferry: bytes.i64(layout.ferry_amount),
+            train: bytes.i64(layout.train_amount),
+            delivered_revenue: bytes.i64(layout.delivered_revenue),
+            cancel_penalty: bytes.i64(layout.cancel_penalty),
+        },
+        unknown: layout.unknown_fields(),
+    })
+}
+
+/// Little-endian field access; callers check `min_size` first
+struct Bytes<'a>(&'a [u8]);
+
+impl Bytes<'_> {
+    fn array<const N: usize>(&self, offset: usize) -> [u8; N] {
+        let mut out = [0; N];
+        out.copy_from_slice(&self.0[offset..offset + N]);
+        out
+    }
+
+    fn try_u32(&self, offset: usize) -> Option<u32> {
+        (self.0.len() >= offset + 4).then(|| self.u32(offset))
+    }
+
+    fn bool(&self, offset: usize) -> bool {
+        self.0[offset] != 0
+    }
+
+    fn u32(&self, offset: usize) -> u32 {
+        u32::from_le_bytes(self.array(offset))
+    }
+
+    fn i32(&self, offset: usize) -> i32 {
+        i32::from_le_bytes(self.array(offset))
+    }
+
+    fn u64(&self, offset: usize) -> u64 {
+        u64::from_le_bytes(self.array(offset))
+    }
+
+    fn i64(&self, offset: usize) -> i64 {
+        i64::from_le_bytes(self.array(offset))
+    }
+
+    fn f32(&self, offset: usize) -> f32 {
+        f32::from_le_bytes(self.array(offset))
+    }
+
+    fn f64(&self, offset: usize) -> f64 {
+        f64::from_le_bytes(self.array(offset))
+    }
+
+    /// NUL-terminated UTF-8 string in a fixed-size field
+    fn string(&self, offset: usize, size: usize) -> String {
+        let field = &self.0[offset..offset + size];
+        let end = field.iter().position(|b| *b == 0).unwrap_or(size);
+        String::from_utf8_lossy(&field[..end]).trim().to_string()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Build a revision-12 map with a job in progress
+    pub(crate) fn sample_map() -> Vec<u8> {
+        let layout = *layout_for(12).unwrap();
+        let (wear, navigation, trailer) = (layout.wear.unwrap(), layout.navigation.unwrap(), layout.trailer.unwrap());
+        let mut buf = vec![0u8; MAP_SIZE];
+        let mut put = |offset: usize, bytes: &[u8]| buf[offset..offset + bytes.len()].copy_from_slice(bytes);
+
+        put(layout.sdk_active, &[1]);
+        put(layout.render_time, &86_000_000u64.to_le_bytes());
+        put(REVISION_OFFSET, &12u32.to_le_bytes());
+        put(layout.game, &1u32.to_le_bytes());
+        put(layout.game_time, &4_321u32.to_le_bytes());
+        put(layout.planned_distance_km, &290u32.to_le_bytes());
+        put(layout.speed, &(25.0f32).to_le_bytes());
+        put(navigation.speed_limit, &(80.0f32 / 3.6).to_le_bytes());
+        put(wear.engine, &(0.12f32).to_le_bytes());
+        put(navigation.route_distance, &(120_400.0f32).to_le_bytes());
+        put(layout.cargo_damage, &(0.03f32).to_le_bytes());
+        put(layout.truck_x, &(-1234.5f64).to_le_bytes());
+        put(layout.truck_heading, &(0.25f64).to_le_bytes());
+        put(layout.cargo, b"Maschinen");
+        put(layout.source_city, b"Berlin");
+        put(layout.source_company, b"Posped");
+        put(layout.destination_city, b"Hamburg");
+        put(layout.truck_brand_id, b"scania");
+        put(layout.truck_brand, b"Scania");
+        put(layout.truck_id, b"vehicle.scania.r_2016");
+        put(layout.truck_name, b"R");
+        put(layout.truck_license_plate, b"B VT 1234");
+        put(layout.truck_license_plate_country, b"germany");
+        put(trailer.id, b"trailer.scs.box");
+        put(trailer.body_type, b"dryvan");
+        put(layout.job_income, &12_000u64.to_le_bytes());
+        put(layout.job_market, b"cargo_market");
+        put(layout.on_job, &[1]);
+        put(layout.fined, &[1]);
+        put(layout.fine_offence, b"speeding");
+        put(layout.fine_amount, &350i64.to_le_bytes());
+        buf
+    }
+
+    #[test]
+    fn parses_known_revision() {
+        let frame = parse(&sample_map()).unwrap();
+
+        assert_eq!(frame.revision, 12);
+        assert_eq!(frame.render_time, 86_000_000);
+        assert_eq!(frame.game, Some(Game::Ets2));
+        assert!((frame.speed_kmh - 90.0).abs() < 1e-3);
+        assert!((frame.speed_limit_kmh.unwrap() - 80.0).abs() < 1e-3);
+        assert_eq!(frame.position.x, -1234.5);
+
+        let job = frame.job.unwrap();
+        assert_eq!(job.cargo, "Maschinen");
+        assert_eq!(job.source_company, "Posped");
+        assert_eq!(job.distance_remaining_km, 120);
+        assert_eq!(job.market, "cargo_market");
+        assert!(frame.flags.fined);
+        assert_eq!(frame.amounts.fine_offence, "speeding");
+        assert!(frame.unknown.is_empty());
+
+        let truck = frame.truck.unwrap();
+        assert_eq!((truck.brand.as_str(), truck.model_id.as_str()), ("Scania", "vehicle.scania.r_2016"));
+        assert_eq!(truck.license_plate, "B VT 1234");
+        let trailer = frame.trailer.unwrap();
+        assert_eq!(trailer.body_type, "dryvan");
+        assert!(trailer.license_plate.is_empty());
+    }
+
+    #[test]
+    fn reports_missing_blocks_as_unknown() {
+        let layout = Layout { navigation: None, ..*layout_for(12).unwrap() };
+        let frame = decode(&sample_map(), 12, &layout).unwrap();
+
+        assert_eq!(frame.unknown, vec![TelemetryField::Navigation]);
+        assert_eq!(frame.speed_limit_kmh, None);
+        assert_eq!(frame.job.unwrap().distance_remaining_km, 0);
+        assert!(frame.wear.is_some());
+    }
+
+    #[test]
+    fn rejects_old_revisions_and_short_maps() {
+        let mut map = sample_map();
+        map[REVISION_OFFSET..REVISION_OFFSET + 4].copy_from_slice(&9u32.to_le_bytes());
+        assert!(matches!(parse(&map), Err(ScsError::UnsupportedRevision(9))));
+
+        assert!(matches!(parse(&sample_map()[..1000]), Err(ScsError::TooShort(1000))));
+        assert_eq!(layout_for(14).map(|layout| layout.revision), Some(12));
+    }
+
+    #[test]
+    fn later_revisions_reuse_base_offsets() {
+        let data = load_plugin_data(PLUGIN_DATA).unwrap();
+        assert_eq!(data.map_name, "Local\\SCSTelemetry");
+        assert_eq!(data.layouts.iter().map(|layout| layout.revision).collect::<Vec<_>>(), [10, 11, 12]);
+        assert_eq!(data.layouts[2].fine_amount, data.layouts[0].fine_amount);
+
+        let dangling = r#"{ "map_name": "m", "layouts": [{ "revision": 13, "base": 12 }] }"#;
+        assert!(load_plugin_data(dangling).is_err());
+    }
+}
@@ -0,0 +1,94 @@
+//! Server Status Module
+//!
+//! Tracks server-signaled maintenance windows so submissions pause until the
+//! retry-at time instead of flooding the backend during a deploy.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+/// Pause used when the server does not say when to retry
+const DEFAULT_PAUSE_SECS: i64 = 300;
+
+/// Maintenance banner data for the frontend
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceStatus {
+    pub active: bool,
+    pub message: Option<String>,
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+/// Current maintenance state reported by the server
+#[derive(Debug, Default)]
+pub struct ServerStatus {
+    message: Option<String>,
+    retry_at: Option<DateTime<Utc>>,
+}
+
+impl ServerStatus {
+    /// Create a status with no maintenance window
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pause submissions until `retry_at` (or a default pause)
+    pub fn enter(&mut self, message: Option<String>, retry_at: Option<DateTime<Utc>>, now: DateTime<Utc>) {
+        let retry_at = retry_at
+            .filter(|at| *at > now)
+            .unwrap_or_else(|| now + Duration::seconds(DEFAULT_PAUSE_SECS));
+        self.message = message.or(self.message.take());
+        self.retry_at = Some(retry_at);
+    }
+
+    /// Leave maintenance mode; returns true if it was active
+    pub fn clear(&mut self) -> bool {
+        self.message = None;
+        self.retry_at.take().is_some()
+    }
+
+    /// Leave maintenance mode once the retry-at time has passed; returns true
+    /// when the window just ended
+    pub fn expire(&mut self, now: DateTime<Utc>) -> bool {
+        match self.retry_at {
+            Some(at) if at <= now => self.clear(),
+            _ => false,
+        }
+    }
+
+    /// Whether submissions should be held back
+    pub fn is_paused(&self, now: DateTime<Utc>) -> bool {
+        self.retry_at.is_some_and(|at| at > now)
+    }
+
+    /// Banner data for the frontend
+    pub fn status(&self, now: DateTime<Utc>) -> MaintenanceStatus {
+        MaintenanceStatus {
+            active: self.is_paused(now),
+            message: self.message.clone(),
+            retry_at: self.retry_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn pauses_until_retry_at() {
+        let t = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let mut status = ServerStatus::new();
+        assert!(!status.is_paused(t));
+
+        status.enter(Some("Deploying".into()), Some(t + Duration::minutes(2)), t);
+        assert!(status.is_paused(t + Duration::minutes(1)));
+        assert!(!status.expire(t + Duration::minutes(1)));
+        assert!(status.expire(t + Duration::minutes(2)));
+        assert!(!status.is_paused(t + Duration::minutes(2)));
+
+        // Missing or stale retry-at falls back to the default pause
+        status.enter(None, Some(t - Duration::minutes(1)), t);
+        assert_eq!(status.status(t).retry_at, Some(t + Duration::seconds(DEFAULT_PAUSE_SECS)));
+    }
+}
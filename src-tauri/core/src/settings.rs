@@ -0,0 +1,477 @@
+//! Settings Module
+//!
+//! User preferences persisted alongside the session in app storage.
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::currency::Currency;
+use crate::overlay::OverlaySettings;
+use crate::rules::SubmissionRules;
+use crate::storage::{SecureStorage, StorageError};
+use crate::sync::NetworkSettings;
+use crate::telemetry::{simulator, Game, PollActivity};
+
+/// Storage key for persisted settings
+const SETTINGS_KEY: &str = "settings";
+// TODO: Change this to your Render URL when deployed (e.g., "https://api.vtc-tracker.com")
+const DEFAULT_API_URL: &str = "http://localhost:3000";
+/// Allowed telemetry poll intervals (ms)
+const MIN_POLL_INTERVAL_MS: u64 = 50;
+const MAX_POLL_INTERVAL_MS: u64 = 1000;
+/// Allowed poll intervals while no game is running (ms)
+const MIN_IDLE_POLL_INTERVAL_MS: u64 = 2000;
+const MAX_IDLE_POLL_INTERVAL_MS: u64 = 5000;
+/// Smallest log directory size cap (MB); below this a single busy day would be cut short
+const MIN_LOG_SIZE_MB: u64 = 10;
+/// Poll interval while the game is paused, only to notice it resuming
+const PAUSED_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Settings validation errors
+#[derive(Debug, thiserror::Error)]
+pub enum SettingsError {
+    #[error("Invalid API URL: {0}")]
+    ApiUrl(String),
+
+    #[error("Poll interval must be between {MIN_POLL_INTERVAL_MS} and {MAX_POLL_INTERVAL_MS} ms")]
+    PollInterval,
+
+    #[error("Idle poll interval must be between {MIN_IDLE_POLL_INTERVAL_MS} and {MAX_IDLE_POLL_INTERVAL_MS} ms")]
+    IdlePollInterval,
+
+    #[error("{0}")]
+    Overlay(String),
+
+    #[error("{0}")]
+    Network(String),
+
+    #[error("Frame log sample rate must be at least 1")]
+    FrameSampleRate,
+
+    #[error("Logs must be kept for at least 1 day")]
+    LogRetention,
+
+    #[error("Log size cap must be at least {MIN_LOG_SIZE_MB} MB")]
+    LogSize,
+}
+
+/// User-configurable preferences
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub general: GeneralSettings,
+    pub network: NetworkSettings,
+    pub maintenance: MaintenanceSettings,
+    pub live_map: LiveMapSettings,
+    pub speeding: SpeedingSettings,
+    pub sounds: SoundSettings,
+    pub notifications: NotificationSettings,
+    pub rules: SubmissionRules,
+    pub privacy: PrivacySettings,
+    pub focus: FocusSettings,
+    pub overlay: OverlaySettings,
+    pub logging: LoggingSettings,
+}
+
+/// Connection, polling and display preferences
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GeneralSettings {
+    /// Backend URL; the `VTC_API_URL` environment variable takes precedence
+    pub api_url: Option<String>,
+    /// Telemetry poll interval while driving (ms)
+    pub poll_interval_ms: u64,
+    /// Telemetry poll interval while no game is running (ms)
+    pub idle_poll_interval_ms: u64,
+    pub units: Units,
+    /// Currency revenue totals across both games are shown in
+    pub display_currency: Currency,
+    /// Start with Windows
+    pub auto_start: bool,
+    /// Generate fake ETS2 telemetry while no game is running, for
+    /// development and demos (debug builds only); `VTC_SIMULATOR` takes
+    /// precedence
+    pub simulator: bool,
+}
+
+impl Default for GeneralSettings {
+    fn default() -> Self {
+        Self {
+            api_url: None,
+            poll_interval_ms: 100,
+            idle_poll_interval_ms: 3000,
+            units: Units::Metric,
+            display_currency: Currency::Eur,
+            auto_start: false,
+            simulator: false,
+        }
+    }
+}
+
+impl GeneralSettings {
+    /// Backend URL to use
+    pub fn api_url(&self) -> String {
+        std::env::var("VTC_API_URL")
+            .ok()
+            .or_else(|| self.api_url.clone())
+            .unwrap_or_else(|| DEFAULT_API_URL.to_string())
+    }
+
+    /// Scheme, host and port of the backend; sessions don't carry over
+    /// to another one
+    pub fn api_origin(&self) -> Option<String> {
+        reqwest::Url::parse(&self.api_url()).ok().map(|url| url.origin().ascii_serialization())
+    }
+
+    /// Game the telemetry simulator plays, if it is on; release builds
+    /// never simulate
+    pub fn simulator(&self) -> Option<Game> {
+        if !cfg!(debug_assertions) {
+            return None;
+        }
+        match std::env::var(simulator::ENV_VAR) {
+            Ok(value) => match value.trim().to_lowercase().as_str() {
+                "" | "0" | "false" | "off" => None,
+                "ats" => Some(Game::Ats),
+                _ => Some(Game::Ets2),
+            },
+            Err(_) => self.simulator.then_some(Game::Ets2),
+        }
+    }
+
+    /// Telemetry poll interval, clamped to the supported range
+    pub fn poll_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_millis(self.poll_interval_ms.clamp(MIN_POLL_INTERVAL_MS, MAX_POLL_INTERVAL_MS))
+    }
+
+    /// Poll interval for what the telemetry loop is doing right now
+    pub fn poll_interval_for(&self, activity: PollActivity) -> std::time::Duration {
+        match activity {
+            PollActivity::Active | PollActivity::Connecting => self.poll_interval(),
+            PollActivity::Paused => PAUSED_POLL_INTERVAL.max(self.poll_interval()),
+            PollActivity::Disconnected => std::time::Duration::from_millis(
+                self.idle_poll_interval_ms.clamp(MIN_IDLE_POLL_INTERVAL_MS, MAX_IDLE_POLL_INTERVAL_MS),
+            ),
+        }
+    }
+}
+
+/// Sampling and batching of per-frame diagnostic logs, and how long log
+/// files are kept
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LoggingSettings {
+    /// Keep one in this many per-frame telemetry logs
+    pub frame_sample_rate: u32,
+    /// Longest time per-frame logs are held before being written (seconds)
+    pub frame_flush_secs: u64,
+    /// Delete log files older than this many days
+    pub retention_days: u32,
+    /// Delete the oldest log files once the directory grows past this (MB)
+    pub max_total_mb: u64,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self { frame_sample_rate: 10, frame_flush_secs: 2, retention_days: 7, max_total_mb: 200 }
+    }
+}
+
+/// Display units for distances and speeds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Units {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+/// Reactions to the game window gaining or losing focus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FocusSettings {
+    /// Show the overlay window only while the game is focused
+    pub overlay_follows_game: bool,
+    /// Hold back toasts while the game runs fullscreen exclusive
+    pub quiet_in_fullscreen: bool,
+    /// Time away from the game before it counts as AFK (seconds)
+    pub afk_after_secs: u64,
+}
+
+impl Default for FocusSettings {
+    fn default() -> Self {
+        Self {
+            overlay_follows_game: true,
+            quiet_in_fullscreen: true,
+            afk_after_secs: 120,
+        }
+    }
+}
+
+/// Areas where position sharing pauses automatically
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PrivacySettings {
+    pub enabled: bool,
+    pub zones: Vec<PrivacyZone>,
+    /// Keep the truck's location out of the presence sent with heartbeats
+    pub hide_presence_position: bool,
+}
+
+/// A circular zone around a marker such as the home garage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivacyZone {
+    pub name: String,
+    /// World coordinates of the zone centre
+    pub x: f64,
+    pub z: f64,
+    /// Radius in world units (metres)
+    pub radius: f64,
+}
+
+/// Event sounds played by the backend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SoundSettings {
+    pub enabled: bool,
+    /// Playback volume (0.0–1.0)
+    pub volume: f32,
+    /// Stay silent while the game is running
+    pub mute_in_game: bool,
+    pub job_delivered: EventSound,
+    pub fine_received: EventSound,
+    pub sync_failed: EventSound,
+}
+
+impl Default for SoundSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            volume: 0.6,
+            mute_in_game: false,
+            job_delivered: EventSound::default(),
+            fine_received: EventSound::default(),
+            sync_failed: EventSound::default(),
+        }
+    }
+}
+
+/// Native toast notifications, toggled per event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationSettings {
+    pub enabled: bool,
+    /// A delivered job was accepted by the server
+    pub job_synced: bool,
+    /// A job submission failed and was queued
+    pub sync_failed: bool,
+    pub game_connected: bool,
+    /// The session is about to expire and couldn't be refreshed
+    pub session_expiring: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            job_synced: true,
+            sync_failed: true,
+            game_connected: true,
+            session_expiring: true,
+        }
+    }
+}
+
+/// Sound for a single event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EventSound {
+    pub enabled: bool,
+    /// Custom WAV/OGG file; the built-in tone is used when unset
+    pub file: Option<String>,
+}
+
+impl Default for EventSound {
+    fn default() -> Self {
+        Self { enabled: true, file: None }
+    }
+}
+
+/// Overspeed tolerance profiles used by the speeding detector
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpeedingSettings {
+    /// Profile name used in singleplayer
+    pub profile: String,
+    /// Profile name used on multiplayer servers
+    pub multiplayer_profile: String,
+    pub profiles: Vec<OverspeedProfile>,
+}
+
+/// Allowed margin above the speed limit (km/h)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverspeedProfile {
+    pub name: String,
+    pub city_tolerance: f32,
+    pub highway_tolerance: f32,
+}
+
+impl OverspeedProfile {
+    fn new(name: &str, city_tolerance: f32, highway_tolerance: f32) -> Self {
+        Self { name: name.into(), city_tolerance, highway_tolerance }
+    }
+}
+
+impl Default for SpeedingSettings {
+    fn default() -> Self {
+        Self {
+            profile: "standard".into(),
+            multiplayer_profile: "strict".into(),
+            profiles: vec![
+                OverspeedProfile::new("relaxed", 10.0, 15.0),
+                OverspeedProfile::new("standard", 5.0, 10.0),
+                OverspeedProfile::new("strict", 2.0, 5.0),
+            ],
+        }
+    }
+}
+
+impl SpeedingSettings {
+    /// Profile that applies for the current session type
+    pub fn active_profile(&self, multiplayer: bool) -> OverspeedProfile {
+        let name = if multiplayer { &self.multiplayer_profile } else { &self.profile };
+        self.profiles.iter()
+            .find(|p| &p.name == name)
+            .cloned()
+            .unwrap_or_else(|| OverspeedProfile::new("standard", 5.0, 10.0))
+    }
+}
+
+/// Live map position sharing (opt-in)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LiveMapSettings {
+    pub enabled: bool,
+}
+
+/// Wear thresholds (percent) that trigger maintenance reminders
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MaintenanceSettings {
+    pub enabled: bool,
+    pub engine_threshold: f32,
+    pub transmission_threshold: f32,
+    pub cabin_threshold: f32,
+    pub chassis_threshold: f32,
+    pub wheels_threshold: f32,
+}
+
+impl Default for MaintenanceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            engine_threshold: 15.0,
+            transmission_threshold: 15.0,
+            cabin_threshold: 25.0,
+            chassis_threshold: 20.0,
+            wheels_threshold: 20.0,
+        }
+    }
+}
+
+impl Settings {
+    /// Load settings from storage, falling back to defaults
+    pub fn load(storage: &SecureStorage) -> Self {
+        if !storage.exists(SETTINGS_KEY) {
+            debug!("No stored settings, using defaults");
+            return Self::default();
+        }
+
+        storage.load::<Settings>(SETTINGS_KEY).unwrap_or_else(|e| {
+            warn!("Failed to load settings, using defaults: {}", e);
+            Self::default()
+        })
+    }
+
+    /// Reject values the app can't run with
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if let Some(url) = &self.general.api_url {
+            let parsed = reqwest::Url::parse(url).map_err(|e| SettingsError::ApiUrl(e.to_string()))?;
+            // Tokens only travel in the clear to a backend on this PC
+            let loopback = matches!(parsed.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+            match parsed.scheme() {
+                "https" => {}
+                "http" if loopback => {}
+                "http" => return Err(SettingsError::ApiUrl("https is required for remote servers".into())),
+                scheme => return Err(SettingsError::ApiUrl(format!("unsupported scheme {}", scheme))),
+            }
+        }
+        if !(MIN_POLL_INTERVAL_MS..=MAX_POLL_INTERVAL_MS).contains(&self.general.poll_interval_ms) {
+            return Err(SettingsError::PollInterval);
+        }
+        if !(MIN_IDLE_POLL_INTERVAL_MS..=MAX_IDLE_POLL_INTERVAL_MS).contains(&self.general.idle_poll_interval_ms) {
+            return Err(SettingsError::IdlePollInterval);
+        }
+        self.overlay.validate().map_err(|e| SettingsError::Overlay(e.to_string()))?;
+        self.network.validate().map_err(|e| SettingsError::Network(e.to_string()))?;
+        if self.logging.frame_sample_rate == 0 {
+            return Err(SettingsError::FrameSampleRate);
+        }
+        if self.logging.retention_days == 0 {
+            return Err(SettingsError::LogRetention);
+        }
+        if self.logging.max_total_mb < MIN_LOG_SIZE_MB {
+            return Err(SettingsError::LogSize);
+        }
+        Ok(())
+    }
+
+    /// Persist settings to storage
+    pub fn save(&self, storage: &SecureStorage) -> Result<(), StorageError> {
+        storage.save(SETTINGS_KEY, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_unusable_general_settings() {
+        let mut settings = Settings::default();
+        assert!(settings.validate().is_ok());
+
+        settings.general.api_url = Some("ftp://example.com".into());
+        assert!(matches!(settings.validate(), Err(SettingsError::ApiUrl(_))));
+
+        settings.general.api_url = Some("https://api.example.com/".into());
+        settings.general.poll_interval_ms = 10;
+        assert!(matches!(settings.validate(), Err(SettingsError::PollInterval)));
+    }
+
+    #[test]
+    fn slows_polling_when_idle_or_paused() {
+        let general = GeneralSettings { idle_poll_interval_ms: 60_000, ..GeneralSettings::default() };
+        let ms = |activity| general.poll_interval_for(activity).as_millis();
+
+        assert_eq!(ms(PollActivity::Active), 100);
+        assert_eq!(ms(PollActivity::Paused), 500);
+        assert_eq!(ms(PollActivity::Disconnected), 5000);
+    }
+
+    #[test]
+    fn allows_plain_http_only_to_this_pc() {
+        let with_url = |url: &str| {
+            let mut settings = Settings::default();
+            settings.general.api_url = Some(url.into());
+            settings.validate()
+        };
+        assert!(with_url("http://localhost:8080").is_ok());
+        assert!(with_url("http://127.0.0.1:3000/").is_ok());
+        assert!(with_url("http://[::1]:3000/").is_ok());
+        assert!(matches!(with_url("http://api.example.com"), Err(SettingsError::ApiUrl(_))));
+        assert!(matches!(with_url("http://localhost.example.com"), Err(SettingsError::ApiUrl(_))));
+    }
+}
@@ -0,0 +1,114 @@
+//! Signing Module
+//!
+//! Ed25519 key pair identifying this install. The public key is registered
+//! with the server when the device is verified (or later, for sessions linked
+//! without it), and each job payload is signed with the private key so the
+//! backend can check it came from this client.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ed25519_dalek::{Signer, SigningKey};
+use tracing::{error, info, warn};
+
+use crate::storage::{SecureStorage, StorageError};
+
+/// Storage key for the private key
+const KEY_STORAGE_KEY: &str = "device_signing_key";
+/// Header carrying the base64 signature of the request body
+pub const SIGNATURE_HEADER: &str = "X-Device-Signature";
+
+/// The install's signing key
+#[derive(Clone)]
+pub struct DeviceKey(SigningKey);
+
+impl DeviceKey {
+    /// Load the install's key, creating one on first run. A stored key that
+    /// can't be read is replaced; sessions notice the new public key and
+    /// register it again
+    pub fn load_or_create(storage: &SecureStorage) -> Self {
+        let stored = if storage.exists(KEY_STORAGE_KEY) {
+            let secret = storage.load::<String>(KEY_STORAGE_KEY)
+                .map_err(|e| e.to_string())
+                .and_then(|encoded| BASE64.decode(encoded).map_err(|e| e.to_string()))
+                .and_then(|bytes| <[u8; 32]>::try_from(bytes).map_err(|_| "wrong key length".to_string()));
+            if let Err(e) = &secret {
+                error!("Stored device signing key is unusable ({}), replacing it", e);
+            }
+            secret.ok()
+        } else {
+            None
+        };
+        match stored {
+            Some(secret) => Self(SigningKey::from_bytes(&secret)),
+            None => Self::regenerate(storage).unwrap_or_else(|e| {
+                // Still sign this session; the server rejects the key at the next verification
+                warn!("Failed to persist device signing key: {}", e);
+                Self::generate()
+            }),
+        }
+    }
+
+    /// Replace the key; the new public key must be registered again
+    pub fn regenerate(storage: &SecureStorage) -> Result<Self, StorageError> {
+        let key = Self::generate();
+        storage.save(KEY_STORAGE_KEY, &BASE64.encode(key.0.to_bytes()))?;
+        info!("Generated new device signing key");
+        Ok(key)
+    }
+
+    fn generate() -> Self {
+        let mut secret = [0u8; 32];
+        getrandom::getrandom(&mut secret).expect("OS random number generator");
+        Self(SigningKey::from_bytes(&secret))
+    }
+
+    /// Base64 public key registered with the server
+    pub fn public_key(&self) -> String {
+        BASE64.encode(self.0.verifying_key().to_bytes())
+    }
+
+    /// Base64 signature of `message`
+    pub fn sign(&self, message: &[u8]) -> String {
+        BASE64.encode(self.0.sign(message).to_bytes())
+    }
+}
+
+impl std::fmt::Debug for DeviceKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DeviceKey({})", self.public_key())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    #[test]
+    fn key_persists_and_signatures_verify() {
+        let dir = std::env::temp_dir().join(format!("vtc-signing-{}", uuid::Uuid::new_v4()));
+        let storage = SecureStorage::in_dir(dir.clone());
+        let key = DeviceKey::load_or_create(&storage);
+        assert_eq!(DeviceKey::load_or_create(&storage).public_key(), key.public_key());
+
+        let body = br#"{"cargo":"Machinery"}"#;
+        let public: [u8; 32] = BASE64.decode(key.public_key()).unwrap().try_into().unwrap();
+        let signature: [u8; 64] = BASE64.decode(key.sign(body)).unwrap().try_into().unwrap();
+        let verifying = VerifyingKey::from_bytes(&public).unwrap();
+        assert!(verifying.verify(body, &Signature::from_bytes(&signature)).is_ok());
+        assert!(verifying.verify(b"{}", &Signature::from_bytes(&signature)).is_err());
+
+        assert_ne!(DeviceKey::regenerate(&storage).unwrap().public_key(), key.public_key());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn replaces_a_corrupt_key() {
+        let dir = std::env::temp_dir().join(format!("vtc-signing-{}", uuid::Uuid::new_v4()));
+        let storage = SecureStorage::in_dir(dir.clone());
+        storage.save(KEY_STORAGE_KEY, &BASE64.encode([7u8; 5])).unwrap();
+        let key = DeviceKey::load_or_create(&storage);
+        assert_eq!(DeviceKey::load_or_create(&storage).public_key(), key.public_key());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
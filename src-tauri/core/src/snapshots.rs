@@ -0,0 +1,220 @@
+//! Job Snapshots Module
+//!
+//! Samples the truck's position, speed and damage every 10 seconds while a
+//! job is driven. The series is gzip-compressed into
+//! `telemetry_data.snapshots` so the web platform can replay the route.
+//! Only the part outside privacy zones is shared, and only with the live map
+//! on; the series never goes into checkpoints.
+
+use std::io::Write;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::privacy;
+use crate::settings::PrivacySettings;
+use crate::telemetry::{Position, TelemetryState};
+
+/// Format of the encoded series, bumped when the row layout changes
+const SERIES_VERSION: u8 = 1;
+/// Spacing between snapshots (seconds)
+const SNAPSHOT_INTERVAL_SECS: u32 = 10;
+/// Snapshots kept per job; past this every other one is dropped and the
+/// interval doubles, so a long job still covers the whole route
+const MAX_SNAPSHOTS: usize = 2880;
+/// Largest encoded series attached to a submission (bytes)
+const MAX_ENCODED_BYTES: usize = 128 * 1024;
+/// Row layout, sent along so the backend doesn't have to hard-code it
+const FIELDS: [&str; 6] = ["t", "x", "z", "speedKmh", "cargoDamage", "chassisDamage"];
+
+/// The truck at one moment of the job
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    pub at: DateTime<Utc>,
+    /// World coordinates (m)
+    pub x: f64,
+    pub z: f64,
+    pub speed_kmh: f32,
+    /// Damage fractions (0.0–1.0)
+    pub cargo_damage: f32,
+    pub chassis_damage: f32,
+}
+
+/// Snapshots taken during one job
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SnapshotSeries {
+    pub interval_secs: u32,
+    pub snapshots: Vec<Snapshot>,
+}
+
+impl Default for SnapshotSeries {
+    fn default() -> Self {
+        Self { interval_secs: SNAPSHOT_INTERVAL_SECS, snapshots: Vec::new() }
+    }
+}
+
+impl SnapshotSeries {
+    /// Take a snapshot when the interval has passed since the last one
+    pub fn observe(&mut self, state: &TelemetryState, now: DateTime<Utc>) {
+        let Some(position) = &state.position else {
+            return;
+        };
+        let due = self.snapshots.last()
+            .map_or(true, |last| (now - last.at).num_seconds() >= i64::from(self.interval_secs));
+        if !due {
+            return;
+        }
+        if self.snapshots.len() >= MAX_SNAPSHOTS {
+            self.thin();
+        }
+        self.snapshots.push(Snapshot {
+            at: now,
+            x: position.x,
+            z: position.z,
+            speed_kmh: state.speed,
+            cargo_damage: state.damage.cargo,
+            chassis_damage: state.damage.chassis,
+        });
+    }
+
+    /// The snapshots that may leave the PC: none unless `live_map` sharing
+    /// is on, and none taken inside a privacy zone
+    pub fn shareable(&self, live_map: bool, privacy: &PrivacySettings) -> SnapshotSeries {
+        if !live_map {
+            return SnapshotSeries { interval_secs: self.interval_secs, snapshots: Vec::new() };
+        }
+        let snapshots = self.snapshots.iter()
+            .filter(|snapshot| {
+                let position = Position { x: snapshot.x, z: snapshot.z, ..Position::default() };
+                !privacy::in_zone(privacy, &position)
+            })
+            .cloned()
+            .collect();
+        SnapshotSeries { interval_secs: self.interval_secs, snapshots }
+    }
+
+    /// Drop every other snapshot and double the interval
+    fn thin(&mut self) {
+        let mut index = 0;
+        self.snapshots.retain(|_| {
+            index += 1;
+            index % 2 == 1
+        });
+        self.interval_secs *= 2;
+    }
+
+    /// Encode for `telemetry_data.snapshots`, thinning the series until it
+    /// fits the size limit; `None` when nothing was recorded
+    pub fn encode(&self) -> std::io::Result<Option<EncodedSeries>> {
+        let Some(first) = self.snapshots.first() else {
+            return Ok(None);
+        };
+        let started = first.at;
+        let mut series = self.clone();
+        loop {
+            let rows: Vec<[i64; 6]> = series.snapshots.iter()
+                .map(|snapshot| [
+                    (snapshot.at - started).num_seconds(),
+                    snapshot.x.round() as i64,
+                    snapshot.z.round() as i64,
+                    (snapshot.speed_kmh * 10.0).round() as i64,
+                    (snapshot.cargo_damage * 1000.0).round() as i64,
+                    (snapshot.chassis_damage * 1000.0).round() as i64,
+                ])
+                .collect();
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+            encoder.write_all(&serde_json::to_vec(&rows)?)?;
+            let data = BASE64.encode(encoder.finish()?);
+            if data.len() <= MAX_ENCODED_BYTES || series.snapshots.len() <= 1 {
+                return Ok(Some(EncodedSeries {
+                    v: SERIES_VERSION,
+                    encoding: "gzip+base64",
+                    started_at: started,
+                    interval_secs: series.interval_secs,
+                    count: rows.len(),
+                    fields: FIELDS,
+                    data,
+                }));
+            }
+            series.thin();
+        }
+    }
+}
+
+/// Compressed snapshot series.
+///
+/// `data` is a base64 gzip of a JSON array of rows laid out as `fields`:
+/// seconds since `startedAt`, world X and Z in metres, speed in 0.1 km/h
+/// and cargo and chassis damage in tenths of a percent.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodedSeries {
+    pub v: u8,
+    pub encoding: &'static str,
+    pub started_at: DateTime<Utc>,
+    pub interval_secs: u32,
+    pub count: usize,
+    pub fields: [&'static str; 6],
+    pub data: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use chrono::{Duration, TimeZone};
+    use crate::telemetry::Position;
+
+    fn state(x: f64) -> TelemetryState {
+        TelemetryState {
+            speed: 80.0,
+            position: Some(Position { x, y: 0.0, z: -x, heading: 0.0 }),
+            ..TelemetryState::default()
+        }
+    }
+
+    #[test]
+    fn samples_every_interval_and_thins_long_jobs() {
+        let t = Utc.with_ymd_and_hms(2025, 3, 1, 19, 0, 0).unwrap();
+        let mut series = SnapshotSeries::default();
+        for secs in 0..60 {
+            series.observe(&state(f64::from(secs) * 22.0), t + Duration::seconds(secs.into()));
+        }
+        assert_eq!(series.snapshots.len(), 6);
+
+        let encoded = series.encode().unwrap().unwrap();
+        assert_eq!((encoded.count, encoded.interval_secs), (6, 10));
+        let mut json = String::new();
+        flate2::read::GzDecoder::new(&BASE64.decode(&encoded.data).unwrap()[..]).read_to_string(&mut json).unwrap();
+        let rows: Vec<[i64; 6]> = serde_json::from_str(&json).unwrap();
+        assert_eq!(rows[1], [10, 220, -220, 800, 0, 0]);
+
+        let mut long = SnapshotSeries::default();
+        for i in 0..=MAX_SNAPSHOTS as i64 {
+            long.observe(&state(i as f64), t + Duration::seconds(i * 10));
+        }
+        assert_eq!((long.snapshots.len(), long.interval_secs), (MAX_SNAPSHOTS / 2 + 1, 20));
+        assert_eq!(SnapshotSeries::default().encode().unwrap(), None);
+    }
+
+    #[test]
+    fn shares_nothing_without_the_live_map_or_inside_privacy_zones() {
+        let t = Utc.with_ymd_and_hms(2025, 3, 1, 19, 0, 0).unwrap();
+        let mut series = SnapshotSeries::default();
+        for i in 0..10 {
+            series.observe(&state(f64::from(i) * 100.0), t + Duration::seconds(i64::from(i) * 10));
+        }
+        let privacy = PrivacySettings {
+            enabled: true,
+            zones: vec![crate::settings::PrivacyZone { name: "Home".into(), x: 0.0, z: 0.0, radius: 250.0 }],
+            ..PrivacySettings::default()
+        };
+        assert!(series.shareable(false, &PrivacySettings::default()).snapshots.is_empty());
+        let shared = series.shareable(true, &privacy);
+        assert_eq!(shared.snapshots.len(), 8);
+        assert!(shared.snapshots.iter().all(|snapshot| snapshot.x >= 200.0));
+    }
+}
@@ -0,0 +1,282 @@
+//! Speeding Module
+//!
+//! Detects overspeed episodes against the navigation speed limit using the
+//! configured tolerance profile, notices collisions from sudden damage, and
+//! aggregates them with traffic fines into a per-job conduct report.
+
+use std::collections::BTreeMap;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::OverspeedProfile;
+use crate::tachograph::TachographSummary;
+use crate::telemetry::DamageReading;
+
+/// Speed limits at or below this are treated as urban roads (km/h)
+const URBAN_LIMIT_MAX: f32 = 60.0;
+/// Episodes shorter than this are ignored as noise (overtakes, downhill creep)
+const MIN_EPISODE_SECS: i64 = 3;
+/// Damage gained between two frames that counts as a collision (fraction)
+const COLLISION_DAMAGE: f32 = 0.002;
+/// Damage jumps this close together belong to the same collision
+const COLLISION_MERGE_SECS: i64 = 2;
+/// Fine offence the game reports for running a red light
+const RED_LIGHT_OFFENCE: &str = "red_signal";
+
+/// A completed overspeed episode
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpeedingViolation {
+    pub started_at: DateTime<Utc>,
+    pub duration_secs: i64,
+    pub speed_limit: f32,
+    pub tolerance: f32,
+    pub max_speed: f32,
+    pub profile: String,
+}
+
+impl SpeedingViolation {
+    /// How far above the limit the driver went at worst (km/h)
+    pub fn max_excess(&self) -> f32 {
+        self.max_speed - self.speed_limit
+    }
+}
+
+#[derive(Debug)]
+struct Episode {
+    started_at: DateTime<Utc>,
+    speed_limit: f32,
+    tolerance: f32,
+    max_speed: f32,
+    profile: String,
+}
+
+/// Tracks whether the driver is currently over the tolerated speed
+#[derive(Debug, Default)]
+pub struct SpeedingDetector {
+    current: Option<Episode>,
+}
+
+impl SpeedingDetector {
+    /// Create a new detector
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a speed reading; returns a violation when an episode ends
+    pub fn observe(
+        &mut self,
+        speed: f32,
+        speed_limit: Option<f32>,
+        profile: &OverspeedProfile,
+        now: DateTime<Utc>,
+    ) -> Option<SpeedingViolation> {
+        let over = speed_limit
+            .filter(|limit| *limit > 0.0)
+            .map(|limit| (limit, tolerance_for(limit, profile)))
+            .filter(|(limit, tolerance)| speed > limit + tolerance);
+
+        match (over, &mut self.current) {
+            (Some((limit, _)), Some(episode)) if limit == episode.speed_limit => {
+                episode.max_speed = episode.max_speed.max(speed);
+                None
+            }
+            (Some((limit, tolerance)), _) => {
+                // New episode (or the limit changed mid-episode)
+                let finished = self.finish(now);
+                self.current = Some(Episode {
+                    started_at: now,
+                    speed_limit: limit,
+                    tolerance,
+                    max_speed: speed,
+                    profile: profile.name.clone(),
+                });
+                finished
+            }
+            (None, _) => self.finish(now),
+        }
+    }
+
+    fn finish(&mut self, now: DateTime<Utc>) -> Option<SpeedingViolation> {
+        let episode = self.current.take()?;
+        let duration_secs = (now - episode.started_at).num_seconds();
+        if duration_secs < MIN_EPISODE_SECS {
+            return None;
+        }
+
+        Some(SpeedingViolation {
+            started_at: episode.started_at,
+            duration_secs,
+            speed_limit: episode.speed_limit,
+            tolerance: episode.tolerance,
+            max_speed: episode.max_speed,
+            profile: episode.profile,
+        })
+    }
+}
+
+/// A sudden damage increase, most likely hitting something
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Collision {
+    pub at: DateTime<Utc>,
+    /// Damage added by the impact (percent)
+    pub cargo_damage: f32,
+    pub chassis_damage: f32,
+}
+
+/// Watches damage readings for impacts
+#[derive(Debug, Default)]
+pub struct CollisionDetector {
+    last: Option<DamageReading>,
+    last_impact: Option<DateTime<Utc>>,
+}
+
+impl CollisionDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a damage reading; returns a collision for a new impact
+    pub fn observe(&mut self, damage: &DamageReading, now: DateTime<Utc>) -> Option<Collision> {
+        let gained = self.last.replace(damage.clone()).map(|last| damage.since(&last))?;
+        if gained.cargo < COLLISION_DAMAGE && gained.chassis < COLLISION_DAMAGE {
+            return None;
+        }
+        let same_impact = self.last_impact.is_some_and(|at| (now - at).num_seconds() < COLLISION_MERGE_SECS);
+        self.last_impact = Some(now);
+        (!same_impact).then_some(Collision {
+            at: now,
+            cargo_damage: gained.cargo * 100.0,
+            chassis_damage: gained.chassis * 100.0,
+        })
+    }
+}
+
+/// A fine issued by the game, e.g. for a red light or a crash
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrafficOffence {
+    pub at: DateTime<Utc>,
+    /// Offence ID reported by the game (`red_signal`, `crash`, ...)
+    pub offence: String,
+    pub amount: i64,
+}
+
+/// Violations collected during one job
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ConductReport {
+    pub violations: Vec<SpeedingViolation>,
+    pub collisions: Vec<Collision>,
+    pub offences: Vec<TrafficOffence>,
+    pub tachograph: TachographSummary,
+}
+
+impl ConductReport {
+    /// Add a violation to the report
+    pub fn record(&mut self, violation: SpeedingViolation) {
+        self.violations.push(violation);
+    }
+
+    pub fn record_collision(&mut self, collision: Collision) {
+        self.collisions.push(collision);
+    }
+
+    pub fn record_offence(&mut self, offence: TrafficOffence) {
+        self.offences.push(offence);
+    }
+
+    /// Compact summary attached to job submissions
+    pub fn summary(&self) -> serde_json::Value {
+        let worst_excess = self.violations.iter()
+            .map(SpeedingViolation::max_excess)
+            .fold(0.0_f32, f32::max);
+        let total_secs: i64 = self.violations.iter().map(|v| v.duration_secs).sum();
+        let mut offences = BTreeMap::new();
+        for offence in &self.offences {
+            *offences.entry(offence.offence.as_str()).or_insert(0) += 1;
+        }
+
+        serde_json::json!({
+            "speedingCount": self.violations.len(),
+            "speedingSeconds": total_secs,
+            "worstExcessKmh": worst_excess,
+            "profiles": self.violations.iter()
+                .map(|v| v.profile.as_str())
+                .collect::<std::collections::BTreeSet<_>>(),
+            "collisionCount": self.collisions.len(),
+            "redLightCount": offences.get(RED_LIGHT_OFFENCE).copied().unwrap_or(0),
+            "offences": offences,
+            "finesTotal": self.offences.iter().map(|o| o.amount).sum::<i64>(),
+        })
+    }
+}
+
+fn tolerance_for(limit: f32, profile: &OverspeedProfile) -> f32 {
+    if limit <= URBAN_LIMIT_MAX {
+        profile.city_tolerance
+    } else {
+        profile.highway_tolerance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn profile() -> OverspeedProfile {
+        OverspeedProfile { name: "standard".into(), city_tolerance: 5.0, highway_tolerance: 10.0 }
+    }
+
+    #[test]
+    fn uses_road_specific_tolerance() {
+        let t = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let mut detector = SpeedingDetector::new();
+
+        // 58 in a 50 zone exceeds the +5 city tolerance
+        assert!(detector.observe(58.0, Some(50.0), &profile(), t).is_none());
+        let violation = detector.observe(50.0, Some(50.0), &profile(), t + Duration::seconds(5)).unwrap();
+        assert_eq!(violation.duration_secs, 5);
+        assert_eq!(violation.max_excess(), 8.0);
+
+        // 98 on a 90 road is within the +10 highway tolerance
+        assert!(detector.observe(98.0, Some(90.0), &profile(), t).is_none());
+        assert!(detector.observe(80.0, Some(90.0), &profile(), t + Duration::seconds(10)).is_none());
+    }
+
+    #[test]
+    fn counts_each_impact_once() {
+        let t = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let damage = |chassis: f32| DamageReading { chassis, ..DamageReading::default() };
+        let mut detector = CollisionDetector::new();
+
+        assert!(detector.observe(&damage(0.010), t).is_none());
+        // Wear creeping up is not an impact
+        assert!(detector.observe(&damage(0.0101), t + Duration::seconds(1)).is_none());
+        let collision = detector.observe(&damage(0.0301), t + Duration::seconds(2)).unwrap();
+        assert!((collision.chassis_damage - 2.0).abs() < 1e-3);
+        // Damage still settling from the same hit
+        assert!(detector.observe(&damage(0.0401), t + Duration::seconds(3)).is_none());
+        assert!(detector.observe(&damage(0.0601), t + Duration::seconds(30)).is_some());
+
+        let mut report = ConductReport::default();
+        report.record_collision(collision);
+        for offence in ["red_signal", "red_signal", "crash"] {
+            report.record_offence(TrafficOffence { at: t, offence: offence.into(), amount: 250 });
+        }
+        let summary = report.summary();
+        assert_eq!(summary["collisionCount"], 1);
+        assert_eq!(summary["redLightCount"], 2);
+        assert_eq!(summary["finesTotal"], 750);
+    }
+
+    #[test]
+    fn ignores_short_spikes() {
+        let t = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let mut detector = SpeedingDetector::new();
+        detector.observe(120.0, Some(90.0), &profile(), t);
+        assert!(detector.observe(85.0, Some(90.0), &profile(), t + Duration::seconds(2)).is_none());
+    }
+}
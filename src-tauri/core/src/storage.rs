@@ -0,0 +1,405 @@
+//! Secure Storage Module
+//!
+//! Handles encrypted storage using Windows DPAPI. Elsewhere files are
+//! sealed with AES-256-GCM under a random key kept in a file only the user
+//! can read, which like DPAPI ties the data to the user account.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tracing::{info, error, debug, warn};
+
+#[cfg(windows)]
+use windows::Win32::Security::Cryptography::{
+    CryptProtectData, CryptUnprotectData, CRYPTPROTECT_UI_FORBIDDEN,
+};
+#[cfg(windows)]
+use windows::Win32::Security::Cryptography::CRYPT_INTEGER_BLOB;
+#[cfg(not(windows))]
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+#[cfg(not(windows))]
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+
+/// File in the storage directory holding the encryption key (non-Windows)
+#[cfg(not(windows))]
+pub const KEY_FILE: &str = "storage.key";
+/// Marks files sealed with the storage key; older builds wrote plain JSON
+#[cfg(not(windows))]
+const SEALED_MAGIC: &[u8] = b"VTC1";
+#[cfg(not(windows))]
+const NONCE_LEN: usize = 12;
+
+/// Secure storage, encrypted per user account
+pub struct SecureStorage {
+    storage_path: PathBuf,
+    /// Keys whose files could not be decrypted and were moved aside
+    quarantined: Mutex<Vec<String>>,
+    /// Encryption key, read or created on first use
+    #[cfg(not(windows))]
+    key: Mutex<Option<Key<Aes256Gcm>>>,
+}
+
+impl SecureStorage {
+    /// Create new secure storage instance
+    pub fn new() -> Self {
+        let storage_path = dirs::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("VTCTracker");
+        
+        // Ensure directory exists
+        if let Err(e) = std::fs::create_dir_all(&storage_path) {
+            error!("Failed to create storage directory: {}", e);
+        }
+        
+        debug!("Secure storage initialized at: {:?}", storage_path);
+        
+        Self::at(storage_path)
+    }
+
+    fn at(storage_path: PathBuf) -> Self {
+        Self {
+            storage_path,
+            quarantined: Mutex::new(Vec::new()),
+            #[cfg(not(windows))]
+            key: Mutex::new(None),
+        }
+    }
+
+    /// Storage in a scratch directory, for tests
+    #[cfg(test)]
+    pub(crate) fn in_dir(storage_path: PathBuf) -> Self {
+        let _ = std::fs::create_dir_all(&storage_path);
+        Self::at(storage_path)
+    }
+
+    /// Directory holding the stored files
+    pub fn dir(&self) -> &std::path::Path {
+        &self.storage_path
+    }
+
+    /// Save data encrypted
+    pub fn save<T: Serialize>(&self, key: &str, data: &T) -> Result<(), StorageError> {
+        let json = serde_json::to_string(data)
+            .map_err(|e| StorageError::Serialization(e.to_string()))?;
+        
+        let encrypted = self.encrypt(json.as_bytes())?;
+        
+        // Write to a temp file and rename so a crash mid-write never leaves a truncated file
+        let file_path = self.storage_path.join(format!("{}.dat", key));
+        let tmp_path = self.storage_path.join(format!("{}.dat.tmp", key));
+        std::fs::write(&tmp_path, encrypted)
+            .and_then(|_| std::fs::rename(&tmp_path, &file_path))
+            .map_err(|e| StorageError::Io(e.to_string()))?;
+        
+        debug!("Saved encrypted data for key: {}", key);
+        Ok(())
+    }
+
+    /// Load and decrypt data
+    pub fn load<T: for<'de> Deserialize<'de>>(&self, key: &str) -> Result<T, StorageError> {
+        let file_path = self.storage_path.join(format!("{}.dat", key));
+        
+        let encrypted = std::fs::read(&file_path)
+            .map_err(|e| StorageError::Io(e.to_string()))?;
+        
+        // Only data that fails to decrypt is quarantined; a key that can't
+        // be read right now says nothing about the file
+        let json = self.decrypt(&encrypted)
+            .and_then(|decrypted| {
+                String::from_utf8(decrypted).map_err(|e| StorageError::Decryption(e.to_string()))
+            })
+            .map_err(|e| {
+                if matches!(e, StorageError::Decryption(_)) {
+                    self.quarantine(key);
+                }
+                e
+            })?;
+        
+        serde_json::from_str(&json)
+            .map_err(|e| StorageError::Serialization(e.to_string()))
+    }
+
+    /// Delete stored data
+    pub fn delete(&self, key: &str) -> Result<(), StorageError> {
+        let file_path = self.storage_path.join(format!("{}.dat", key));
+        
+        if file_path.exists() {
+            std::fs::remove_file(&file_path)
+                .map_err(|e| StorageError::Io(e.to_string()))?;
+            info!("Deleted stored data for key: {}", key);
+        }
+        
+        Ok(())
+    }
+
+    /// Check if key exists
+    pub fn exists(&self, key: &str) -> bool {
+        let file_path = self.storage_path.join(format!("{}.dat", key));
+        file_path.exists()
+    }
+
+    /// Whether unreadable files were quarantined and the device must be re-linked
+    pub fn reset_required(&self) -> bool {
+        self.quarantined.lock().map(|keys| !keys.is_empty()).unwrap_or(false)
+    }
+
+    /// Keys that were quarantined this run
+    pub fn quarantined_keys(&self) -> Vec<String> {
+        self.quarantined.lock().map(|keys| keys.clone()).unwrap_or_default()
+    }
+
+    /// Forget quarantined keys once the device has been re-linked
+    pub fn clear_reset_required(&self) {
+        if let Ok(mut keys) = self.quarantined.lock() {
+            keys.clear();
+        }
+    }
+
+    /// Move a file that can no longer be decrypted (profile migration, lost key)
+    /// aside, keeping it for inspection but no longer reading it
+    fn quarantine(&self, key: &str) {
+        let file_path = self.storage_path.join(format!("{}.dat", key));
+        let dir = self.storage_path.join("quarantine");
+        let target = dir.join(format!("{}-{}.dat", key, chrono::Utc::now().timestamp()));
+
+        match std::fs::create_dir_all(&dir).and_then(|_| std::fs::rename(&file_path, &target)) {
+            Ok(()) => warn!("Quarantined unreadable data for key {} at {:?}", key, target),
+            Err(e) => error!("Failed to quarantine data for key {}: {}", key, e),
+        }
+        if let Ok(mut keys) = self.quarantined.lock() {
+            if !keys.iter().any(|k| k == key) {
+                keys.push(key.to_string());
+            }
+        }
+    }
+
+    #[cfg(windows)]
+    fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, StorageError> {
+        use std::ptr::null_mut;
+        
+        let input = CRYPT_INTEGER_BLOB {
+            cbData: data.len() as u32,
+            pbData: data.as_ptr() as *mut u8,
+        };
+        
+        let mut output = CRYPT_INTEGER_BLOB {
+            cbData: 0,
+            pbData: null_mut(),
+        };
+        
+        unsafe {
+            let result = CryptProtectData(
+                &input,
+                None,
+                None,
+                None,
+                None,
+                CRYPTPROTECT_UI_FORBIDDEN,
+                &mut output,
+            );
+            
+            if result.is_err() {
+                return Err(StorageError::Encryption("DPAPI encryption failed".into()));
+            }
+            
+            let encrypted = std::slice::from_raw_parts(
+                output.pbData,
+                output.cbData as usize,
+            ).to_vec();
+            
+            // Free the memory allocated by CryptProtectData
+            windows::Win32::Foundation::LocalFree(
+                windows::Win32::Foundation::HLOCAL(output.pbData as *mut std::ffi::c_void)
+            );
+            
+            Ok(encrypted)
+        }
+    }
+
+    #[cfg(windows)]
+    fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, StorageError> {
+        use std::ptr::null_mut;
+        
+        let input = CRYPT_INTEGER_BLOB {
+            cbData: data.len() as u32,
+            pbData: data.as_ptr() as *mut u8,
+        };
+        
+        let mut output = CRYPT_INTEGER_BLOB {
+            cbData: 0,
+            pbData: null_mut(),
+        };
+        
+        unsafe {
+            let result = CryptUnprotectData(
+                &input,
+                None,
+                None,
+                None,
+                None,
+                CRYPTPROTECT_UI_FORBIDDEN,
+                &mut output,
+            );
+            
+            if result.is_err() {
+                return Err(StorageError::Decryption("DPAPI decryption failed".into()));
+            }
+            
+            let decrypted = std::slice::from_raw_parts(
+                output.pbData,
+                output.cbData as usize,
+            ).to_vec();
+            
+            // Free the memory allocated by CryptUnprotectData
+            windows::Win32::Foundation::LocalFree(
+                windows::Win32::Foundation::HLOCAL(output.pbData as *mut std::ffi::c_void)
+            );
+            
+            Ok(decrypted)
+        }
+    }
+
+    #[cfg(not(windows))]
+    fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, StorageError> {
+        let cipher = Aes256Gcm::new(&self.key()?);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let sealed = cipher.encrypt(&nonce, data)
+            .map_err(|e| StorageError::Encryption(e.to_string()))?;
+        Ok([SEALED_MAGIC, nonce.as_slice(), &sealed].concat())
+    }
+
+    #[cfg(not(windows))]
+    fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, StorageError> {
+        let Some(sealed) = data.strip_prefix(SEALED_MAGIC) else {
+            // Written unencrypted by an older build; sealed on the next save
+            return Ok(data.to_vec());
+        };
+        if sealed.len() < NONCE_LEN {
+            return Err(StorageError::Decryption("truncated data".into()));
+        }
+        let (nonce, sealed) = sealed.split_at(NONCE_LEN);
+        let cipher = Aes256Gcm::new(&self.key()?);
+        cipher.decrypt(Nonce::from_slice(nonce), sealed)
+            .map_err(|e| StorageError::Decryption(e.to_string()))
+    }
+
+    /// The storage key, created readable only by the user if there is none
+    #[cfg(not(windows))]
+    fn key(&self) -> Result<Key<Aes256Gcm>, StorageError> {
+        let mut cached = self.key.lock().map_err(|e| StorageError::Key(e.to_string()))?;
+        if let Some(key) = cached.as_ref() {
+            return Ok(*key);
+        }
+
+        let path = self.storage_path.join(KEY_FILE);
+        let key = match std::fs::read(&path) {
+            Ok(bytes) if bytes.len() == 32 => *Key::<Aes256Gcm>::from_slice(&bytes),
+            Ok(_) => return Err(StorageError::Key(format!("{:?} is not a storage key", path))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let key = Aes256Gcm::generate_key(OsRng);
+                write_private(&path, &key).map_err(|e| StorageError::Key(e.to_string()))?;
+                info!("Created storage key at {:?}", path);
+                key
+            }
+            Err(e) => return Err(StorageError::Key(format!("{:?}: {}", path, e))),
+        };
+        *cached = Some(key);
+        Ok(key)
+    }
+}
+
+/// Create `path` with `contents`, readable and writable only by the owner
+#[cfg(not(windows))]
+fn write_private(path: &std::path::Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(contents)
+}
+
+impl Default for SecureStorage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Storage errors
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("IO error: {0}")]
+    Io(String),
+    
+    #[error("Serialization error: {0}")]
+    Serialization(String),
+    
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+    
+    #[error("Decryption error: {0}")]
+    Decryption(String),
+
+    /// The storage key could not be read or created; the data may be fine
+    #[error("Storage key unavailable: {0}")]
+    Key(String),
+}
+
+impl StorageError {
+    /// Stable code for the troubleshooting panel
+    pub fn code(&self) -> &'static str {
+        match self {
+            StorageError::Io(_) => "io",
+            StorageError::Serialization(_) => "serialization",
+            StorageError::Encryption(_) => "encryption",
+            StorageError::Decryption(_) => "decryption",
+            StorageError::Key(_) => "key",
+        }
+    }
+}
+
+#[cfg(all(test, not(windows)))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seals_files_with_the_storage_key() {
+        let dir = std::env::temp_dir().join(format!("vtc-storage-{}", uuid::Uuid::new_v4()));
+        let storage = SecureStorage::in_dir(dir.clone());
+        storage.save("token", &"secret-refresh-token").unwrap();
+        let raw = std::fs::read(dir.join("token.dat")).unwrap();
+        assert!(raw.starts_with(SEALED_MAGIC));
+        assert!(!String::from_utf8_lossy(&raw).contains("secret-refresh-token"));
+        assert_eq!(SecureStorage::in_dir(dir.clone()).load::<String>("token").unwrap(), "secret-refresh-token");
+
+        // Plain JSON from older builds still loads; a lost key quarantines
+        std::fs::write(dir.join("legacy.dat"), b"\"plain\"").unwrap();
+        assert_eq!(storage.load::<String>("legacy").unwrap(), "plain");
+        std::fs::remove_file(dir.join(KEY_FILE)).unwrap();
+        let rekeyed = SecureStorage::in_dir(dir.clone());
+        assert!(matches!(rekeyed.load::<String>("token"), Err(StorageError::Decryption(_))));
+        assert!(rekeyed.reset_required());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn unreadable_key_quarantines_nothing() {
+        let dir = std::env::temp_dir().join(format!("vtc-storage-{}", uuid::Uuid::new_v4()));
+        SecureStorage::in_dir(dir.clone()).save("accounts", &"linked").unwrap();
+
+        // A key that can't be read (here a directory in its place) is not a lost key
+        let key = std::fs::read(dir.join(KEY_FILE)).unwrap();
+        std::fs::remove_file(dir.join(KEY_FILE)).unwrap();
+        std::fs::create_dir(dir.join(KEY_FILE)).unwrap();
+        let storage = SecureStorage::in_dir(dir.clone());
+        assert!(matches!(storage.load::<String>("accounts"), Err(StorageError::Key(_))));
+        assert!(!storage.reset_required());
+        assert!(storage.exists("accounts"));
+
+        std::fs::remove_dir(dir.join(KEY_FILE)).unwrap();
+        std::fs::write(dir.join(KEY_FILE), key).unwrap();
+        assert_eq!(storage.load::<String>("accounts").unwrap(), "linked");
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,526 @@
+//! API Sync Module
+//!
+//! Handles HTTP communication with the VTC Tracker API.
+
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::currency::Currency;
+use crate::distances;
+use crate::games;
+use crate::names;
+use crate::signing::DeviceKey;
+use crate::telemetry::{ActiveJob, Game, Position, TelemetryState, TrailerOwnership};
+
+mod auth;
+mod policy;
+mod realtime;
+mod limiter;
+mod network;
+mod releases;
+mod retry;
+mod schema;
+mod support;
+mod telemetry;
+mod transport;
+mod vtc;
+
+pub use auth::AuthApi;
+pub use policy::TelemetryPolicy;
+pub use realtime::{PushMessage, RealtimeClient};
+pub use releases::{ReleaseInfo, ReleasesApi};
+pub use network::{NetworkError, NetworkSettings, ProxyMode};
+pub use retry::RetryPolicy;
+pub use schema::{ServerCapabilities, CURRENT_SCHEMA};
+pub use support::{FeedbackRequest, FeedbackResponse, SupportApi};
+pub use telemetry::{TelemetryApi, MAX_BATCH_JOBS};
+pub use transport::Transport;
+pub use vtc::{AttendanceResponse, ConvoyReportResponse, VtcApi};
+
+/// API client for VTC Tracker backend, split into per-domain services
+/// sharing one transport
+pub struct ApiClient {
+    pub auth: AuthApi,
+    pub telemetry: TelemetryApi,
+    pub vtc: VtcApi,
+    pub support: SupportApi,
+    pub realtime: RealtimeClient,
+    pub releases: ReleasesApi,
+    transport: Arc<Transport>,
+}
+
+impl ApiClient {
+    /// Create a new API client
+    pub fn new(base_url: &str) -> Self {
+        let transport = Arc::new(Transport::new(base_url));
+        Self {
+            auth: AuthApi::new(transport.clone()),
+            telemetry: TelemetryApi::new(transport.clone()),
+            vtc: VtcApi::new(transport.clone()),
+            support: SupportApi::new(transport.clone()),
+            realtime: RealtimeClient::new(transport.clone()),
+            releases: ReleasesApi::new(transport.clone()),
+            transport,
+        }
+    }
+
+    /// Change how every service retries failed requests
+    pub fn set_retry_policy(&self, policy: RetryPolicy) {
+        self.transport.set_retry_policy(policy);
+    }
+
+    /// Sign job payloads with `key`; it is registered at the next device
+    /// verification or through `AuthApi::register_device_key`
+    pub fn set_device_key(&self, key: DeviceKey) {
+        self.transport.set_device_key(Some(key));
+    }
+
+    /// Public key of the device key, if one is set
+    pub fn device_public_key(&self) -> Option<String> {
+        self.transport.device_public_key()
+    }
+
+    /// Route every service's requests through the configured proxy, trusting
+    /// the extra root certificates
+    pub fn set_network(&self, settings: &NetworkSettings) -> Result<(), NetworkError> {
+        self.transport.set_network(settings)
+    }
+
+    /// Probe whether the API is reachable; returns the round trip time
+    pub async fn ping(&self, timeout: std::time::Duration) -> Result<std::time::Duration, ApiError> {
+        self.transport.ping("/api/health", timeout).await
+    }
+
+    /// Switch every service to another server
+    pub fn set_base_url(&self, base_url: &str) {
+        self.transport.set_base_url(base_url);
+        self.telemetry.forget_capabilities();
+    }
+
+    /// Receiver that sees each access token the server rejects, so the
+    /// session can be dropped as soon as the device is revoked
+    pub fn revocations(&self) -> tokio::sync::watch::Receiver<Option<String>> {
+        self.transport.revocations()
+    }
+}
+
+// Request/Response types
+
+#[derive(Serialize)]
+struct VerifyRequest<'a> {
+    code: &'a str,
+    device_name: &'a str,
+    /// Base64 Ed25519 key job payloads will be signed with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    public_key: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DeviceKeyRequest {
+    public_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeviceKeyResponse {
+    #[serde(default)]
+    pub success: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyResponse {
+    pub access_token: String,
+    pub user_id: String,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+    pub expires_at: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+}
+
+#[derive(Serialize)]
+struct RefreshRequest<'a> {
+    refresh_token: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefreshResponse {
+    pub access_token: String,
+    /// Rotated refresh token, if the server issues a new one
+    pub refresh_token: Option<String>,
+    pub expires_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TransferCodeResponse {
+    pub code: String,
+    pub expires_at: String,
+}
+
+/// What the driver is doing, sent with each heartbeat for the platform's
+/// "drivers online" page
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Presence {
+    pub game: Option<Game>,
+    pub connected: bool,
+    /// km/h
+    pub speed: f32,
+    pub job: Option<PresenceJob>,
+    /// Left out inside privacy zones or when the driver hides it
+    pub position: Option<Position>,
+}
+
+/// Route of the job in progress
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresenceJob {
+    pub cargo: String,
+    pub source_city: String,
+    pub destination_city: String,
+    pub distance_remaining: u32,
+}
+
+impl Presence {
+    pub fn from_state(state: &TelemetryState) -> Self {
+        if !state.connected {
+            return Self::default();
+        }
+        Self {
+            game: state.game,
+            connected: true,
+            speed: state.speed,
+            job: state.active_job.as_ref().map(|job| PresenceJob {
+                cargo: job.cargo.clone(),
+                source_city: job.source_city.clone(),
+                destination_city: job.destination_city.clone(),
+                distance_remaining: job.distance_remaining,
+            }),
+            position: state.position.clone(),
+        }
+    }
+
+    /// The same presence without the truck's location
+    pub fn without_position(self) -> Self {
+        Self { position: None, ..self }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct HeartbeatRequest<'a> {
+    presence: &'a Presence,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HeartbeatResponse {
+    pub success: bool,
+    pub timestamp: String,
+    pub next_heartbeat_in: u32,
+    /// Set while the backend is in maintenance mode
+    #[serde(default)]
+    pub maintenance: Option<MaintenanceNotice>,
+    /// Telemetry detail required by the driver's VTC
+    #[serde(default)]
+    pub telemetry_policy: Option<TelemetryPolicy>,
+}
+
+/// Server-signaled maintenance window
+#[derive(Debug, Clone, Deserialize)]
+pub struct MaintenanceNotice {
+    pub message: Option<String>,
+    pub retry_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PositionBatchResponse {
+    pub accepted: u32,
+    /// Seconds the server wants between batches
+    pub batch_window: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobSubmission {
+    pub game: String,
+    pub cargo: String,
+    pub source_city: String,
+    pub destination_city: String,
+    pub distance_km: u32,
+    pub revenue: f64,
+    /// Currency of `revenue`; jobs queued before it was recorded leave it out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<Currency>,
+    pub damage_percent: f64,
+    /// Truck chassis damage taken during the job
+    #[serde(default)]
+    pub chassis_damage_percent: f64,
+    pub truck_id: Option<String>,
+    pub trailer_id: Option<String>,
+    /// Company-owned or job-provided trailer, when known
+    #[serde(default)]
+    pub trailer_ownership: Option<TrailerOwnership>,
+    pub telemetry_data: Option<serde_json::Value>,
+    pub server: Option<String>,
+    /// Fuel burned during the job (litres)
+    #[serde(default)]
+    pub fuel_used: Option<f64>,
+    /// Litres per 100 km over the job
+    #[serde(default)]
+    pub average_consumption: Option<f64>,
+    /// Distance on the truck's odometer during the job; `distance_km` is the planned one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub driven_km: Option<u32>,
+    /// Route progress reported by the navigation during the job
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub navigation_km: Option<u32>,
+    /// Client-generated ID the server uses to drop repeated submissions
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+    /// Record of a job that was never delivered; `distance_km` is what was driven
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub partial: bool,
+}
+
+impl JobSubmission {
+    /// Build a submission from a job delivered in `game`
+    pub fn from_completed(job: &ActiveJob, game: Game) -> Self {
+        // Only damage taken during the job counts against the driver
+        let damage_delta = job.damage_delta().unwrap_or_default();
+        // The truck may be repaired on the way, so chassis damage uses the peak
+        let peak_delta = job.peak_damage_delta();
+        let source_city = names::canonical_city(game, &job.source_city);
+        let destination_city = names::canonical_city(game, &job.destination_city);
+        
+        let distance_check = distances::check(game, &source_city, &destination_city, job.distance_km);
+        if let Some(check) = distance_check.as_ref().filter(|check| check.flagged) {
+            warn!(
+                "Job distance {} km is far from the {} km reference for {} -> {}",
+                check.claimed_km, check.reference_km, source_city, destination_city
+            );
+        }
+        let driven_check = distances::check_driven(job.distance_km, job.driven_km(), job.odometer_km());
+        if driven_check.flagged {
+            warn!(
+                "Job planned at {} km but the odometer shows {} km ({} -> {})",
+                job.distance_km, driven_check.odometer_km.unwrap_or_default(), source_city, destination_city
+            );
+        }
+        
+        let submission = Self {
+            game: game.to_string(),
+            cargo: names::canonical_cargo(&job.cargo),
+            source_city,
+            destination_city,
+            distance_km: job.distance_km,
+            revenue: job.revenue as f64,
+            currency: Some(games::data(game).currency),
+            damage_percent: damage_delta.cargo as f64 * 100.0,
+            chassis_damage_percent: peak_delta.chassis as f64 * 100.0,
+            truck_id: None,
+            trailer_id: None,
+            trailer_ownership: job.trailer_ownership,
+            telemetry_data: Some(serde_json::json!({
+                "market": job.market,
+                "legs": job.legs,
+                "drivingSeconds": job.driving_time().num_seconds(),
+                "damage": {
+                    "pickup": job.pickup_damage,
+                    "delivery": job.delivery_damage,
+                    "delta": damage_delta,
+                    "max": job.max_damage,
+                    "peakDelta": peak_delta,
+                },
+                "truck": job.truck,
+                "trailer": job.trailer,
+                "localized": {
+                    "cargo": job.cargo,
+                    "sourceCity": job.source_city,
+                    "destinationCity": job.destination_city,
+                },
+            })),
+            server: None,
+            // Nothing burned means the tank was never read, not a free trip
+            fuel_used: Some(f64::from(job.fuel.used_liters)).filter(|liters| *liters > 0.0),
+            average_consumption: job.average_consumption().map(f64::from),
+            driven_km: job.odometer_km(),
+            navigation_km: Some(job.driven_km()).filter(|km| *km > 0),
+            idempotency_key: Some(uuid::Uuid::new_v4().to_string()),
+            partial: false,
+        };
+        
+        let integrity = job.integrity.report(Utc::now());
+        if integrity.flagged {
+            warn!("Job {} -> {} has {} integrity signals", submission.source_city, submission.destination_city, integrity.signals.len());
+        }
+        let mut submission = submission
+            .with_telemetry("drivenCheck", serde_json::json!(driven_check))
+            .with_telemetry("integrity", serde_json::json!(integrity));
+        match job.snapshots.encode() {
+            Ok(Some(series)) => submission = submission.with_telemetry("snapshots", serde_json::json!(series)),
+            Ok(None) => {}
+            Err(e) => warn!("Failed to encode job snapshots: {}", e),
+        }
+        match distance_check {
+            Some(check) => submission.with_telemetry("distanceCheck", serde_json::json!(check)),
+            None => submission,
+        }
+    }
+
+    /// Currency of `revenue`, falling back to the game's own
+    pub fn revenue_currency(&self) -> Currency {
+        self.currency
+            .or_else(|| games::from_id(&self.game).map(|game| games::data(game).currency))
+            .unwrap_or_default()
+    }
+
+    /// Attach an extra section to `telemetry_data`
+    pub fn with_telemetry(mut self, key: &str, value: serde_json::Value) -> Self {
+        let data = self.telemetry_data.get_or_insert_with(|| serde_json::json!({}));
+        if let Some(object) = data.as_object_mut() {
+            object.insert(key.to_string(), value);
+        }
+        self
+    }
+
+    /// Set the local IDs of the truck and trailer that hauled the job
+    pub fn with_vehicle_ids(mut self, truck_id: Option<String>, trailer_id: Option<String>) -> Self {
+        self.truck_id = truck_id;
+        self.trailer_id = trailer_id;
+        self
+    }
+
+    /// Key identifying this job across retries; submissions queued before
+    /// keys existed fall back to their digest
+    pub fn request_key(&self) -> String {
+        self.idempotency_key.clone().unwrap_or_else(|| self.digest())
+    }
+
+    /// SHA-256 of the submission, independent of the payload schema
+    pub fn digest(&self) -> String {
+        let payload = serde_json::to_vec(self).unwrap_or_default();
+        format!("{:x}", Sha256::digest(&payload))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JobResponse {
+    pub success: bool,
+    pub job_id: String,
+    pub message: String,
+}
+
+/// One job of a batch submission
+#[derive(Serialize)]
+struct BatchJob {
+    idempotency_key: String,
+    payload: serde_json::Value,
+    /// Device signature of `payload`'s compact JSON, as a single job
+    /// submission carries in its header
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+}
+
+#[derive(Serialize)]
+struct BatchRequest {
+    jobs: Vec<BatchJob>,
+}
+
+/// Outcome of one batch entry, matched back by its idempotency key
+#[derive(Debug, Deserialize)]
+struct BatchResult {
+    idempotency_key: String,
+    success: bool,
+    #[serde(default)]
+    job_id: String,
+    #[serde(default)]
+    message: String,
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchResponse {
+    results: Vec<BatchResult>,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// API errors
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ApiError {
+    #[error("Network error: {0}")]
+    Network(String),
+    
+    #[error("Server error: {0}")]
+    Server(String),
+    
+    /// The server refused the request itself (a 4xx other than 401 and 429)
+    #[error("Request rejected: {0}")]
+    Rejected(String),
+    
+    #[error("Not authorized: {0}")]
+    Unauthorized(String),
+    
+    #[error("Parse error: {0}")]
+    Parse(String),
+    
+    #[error("Server is under maintenance")]
+    Maintenance(Option<DateTime<Utc>>),
+
+    #[error("Too many requests, slowing down")]
+    RateLimited(Option<DateTime<Utc>>),
+
+    #[error("Missing telemetry required by your VTC: {0}")]
+    Policy(String),
+}
+
+impl ApiError {
+    /// Stable code for the troubleshooting panel
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::Network(_) => "network",
+            ApiError::Server(_) => "server",
+            ApiError::Rejected(_) => "rejected",
+            ApiError::Unauthorized(_) => "unauthorized",
+            ApiError::Parse(_) => "parse",
+            ApiError::Maintenance(_) => "maintenance",
+            ApiError::RateLimited(_) => "rate_limited",
+            ApiError::Policy(_) => "policy",
+        }
+    }
+
+    /// Retrying can't help: the server refused the job or it falls short of
+    /// the VTC's policy
+    pub fn rejects_job(&self) -> bool {
+        matches!(self, ApiError::Rejected(_) | ApiError::Policy(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presence_follows_the_game_and_can_drop_the_position() {
+        let state = TelemetryState {
+            connected: true,
+            game: Some(Game::Ets2),
+            speed: 81.5,
+            position: Some(Position { x: 1200.0, y: 40.0, z: -350.0, heading: 0.5 }),
+            ..Default::default()
+        };
+        let presence = Presence::from_state(&state);
+        assert_eq!((presence.game, presence.connected, presence.speed), (Some(Game::Ets2), true, 81.5));
+        assert_eq!(presence.position, state.position);
+        assert_eq!(presence.job, None);
+
+        let hidden = presence.clone().without_position();
+        assert_eq!(hidden, Presence { position: None, ..presence });
+
+        let offline = TelemetryState { connected: false, ..state };
+        assert_eq!(Presence::from_state(&offline), Presence::default());
+    }
+}
@@ -0,0 +1,90 @@
+//! Auth API
+//!
+//! Device linking and session transfer endpoints.
+
+use std::sync::Arc;
+use reqwest::Method;
+use tracing::{debug, info};
+
+use super::transport::Transport;
+use super::{
+    ApiError, DeviceKeyRequest, DeviceKeyResponse, RefreshRequest, RefreshResponse, TransferCodeResponse, VerifyRequest,
+    VerifyResponse,
+};
+
+/// Authentication endpoints
+pub struct AuthApi {
+    transport: Arc<Transport>,
+}
+
+impl AuthApi {
+    pub(super) fn new(transport: Arc<Transport>) -> Self {
+        Self { transport }
+    }
+
+    /// Link request, registering this install's public key when it has one
+    fn verify_request<'a>(&self, code: &'a str, device_name: &'a str) -> VerifyRequest<'a> {
+        VerifyRequest { code, device_name, public_key: self.transport.device_public_key() }
+    }
+
+    /// Verify a device code and get access token
+    pub async fn verify_code(&self, code: &str, device_name: &str) -> Result<VerifyResponse, ApiError> {
+        debug!("Verifying device code at: {}", self.transport.url("/api/auth/device/verify"));
+
+        let request = self.transport
+            .request(Method::POST, "/api/auth/device/verify", None)
+            .json(&self.verify_request(code, device_name));
+        let data: VerifyResponse = self.transport.send(request, "Unknown error").await?;
+
+        info!("Device code verified successfully");
+        Ok(data)
+    }
+
+    /// Create a one-time code for moving this device's session to another PC
+    pub async fn create_transfer_code(&self, access_token: &str) -> Result<TransferCodeResponse, ApiError> {
+        let request = self.transport.request(Method::POST, "/api/auth/device/transfer", Some(access_token));
+        let data: TransferCodeResponse = self.transport.send(request, "Transfer code request failed").await?;
+
+        info!("Session transfer code created");
+        Ok(data)
+    }
+
+    /// Redeem a transfer code issued by another linked PC
+    pub async fn redeem_transfer_code(&self, code: &str, device_name: &str) -> Result<VerifyResponse, ApiError> {
+        debug!("Redeeming transfer code at: {}", self.transport.url("/api/auth/device/transfer/redeem"));
+
+        let request = self.transport
+            .request(Method::POST, "/api/auth/device/transfer/redeem", None)
+            .json(&self.verify_request(code, device_name));
+        let data: VerifyResponse = self.transport.send(request, "Unknown error").await?;
+
+        info!("Transfer code redeemed successfully");
+        Ok(data)
+    }
+
+    /// Register this install's public key for a session linked without it;
+    /// returns the key registered, `None` when there is no key
+    pub async fn register_device_key(&self, access_token: &str) -> Result<Option<String>, ApiError> {
+        let Some(public_key) = self.transport.device_public_key() else {
+            return Ok(None);
+        };
+        let request = self.transport
+            .request(Method::POST, "/api/auth/device/key", Some(access_token))
+            .json(&DeviceKeyRequest { public_key: public_key.clone() });
+        let _: DeviceKeyResponse = self.transport.send(request, "Device key registration failed").await?;
+
+        info!("Device signing key registered");
+        Ok(Some(public_key))
+    }
+
+    /// Exchange a refresh token for a new access token before the session expires
+    pub async fn refresh_session(&self, refresh_token: &str) -> Result<RefreshResponse, ApiError> {
+        let request = self.transport
+            .request(Method::POST, "/api/auth/device/refresh", None)
+            .json(&RefreshRequest { refresh_token });
+        let data: RefreshResponse = self.transport.send(request, "Session refresh failed").await?;
+
+        info!("Session token refreshed");
+        Ok(data)
+    }
+}
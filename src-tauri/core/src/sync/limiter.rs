@@ -0,0 +1,84 @@
+//! Rate Limiter
+//!
+//! Client-side view of the server's rate limit, built from the
+//! `RateLimit-Remaining`/`RateLimit-Reset` headers and `Retry-After` on 429
+//! responses, so queued requests wait for the window instead of being
+//! rejected one after another.
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Pause after a 429 that didn't say how long to back off
+const DEFAULT_BLOCK_SECS: i64 = 30;
+/// Reset values above this are Unix timestamps rather than seconds from now
+const EPOCH_THRESHOLD: i64 = 1_000_000_000;
+
+/// When the next request may go out
+#[derive(Debug, Default)]
+pub(super) struct RateLimiter {
+    /// Set by a 429 response
+    blocked_until: Option<DateTime<Utc>>,
+    /// Requests left in the current window, if the server says
+    remaining: Option<u64>,
+    window_reset: Option<DateTime<Utc>>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How long to wait before sending, `None` to send right away
+    pub fn delay(&self, now: DateTime<Utc>) -> Option<std::time::Duration> {
+        let exhausted = self.window_reset.filter(|_| self.remaining == Some(0));
+        let until = self.blocked_until.into_iter().chain(exhausted).max()?;
+        (until - now).to_std().ok().filter(|wait| !wait.is_zero())
+    }
+
+    /// Take in the rate limit headers of a response
+    pub fn observe(&mut self, remaining: Option<&str>, reset: Option<&str>, now: DateTime<Utc>) {
+        let Some(remaining) = remaining.and_then(|value| value.trim().parse().ok()) else {
+            return;
+        };
+        self.remaining = Some(remaining);
+        self.window_reset = reset
+            .and_then(|value| value.trim().parse::<i64>().ok())
+            .and_then(|reset| match reset {
+                reset if reset > EPOCH_THRESHOLD => DateTime::from_timestamp(reset, 0),
+                secs => Some(now + Duration::seconds(secs)),
+            });
+    }
+
+    /// The server answered 429; hold requests until `retry_at`
+    pub fn block(&mut self, retry_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> DateTime<Utc> {
+        let until = retry_at
+            .filter(|at| *at > now)
+            .unwrap_or_else(|| now + Duration::seconds(DEFAULT_BLOCK_SECS));
+        self.blocked_until = Some(until);
+        until
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn waits_for_retry_after_and_exhausted_windows() {
+        let t = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let mut limiter = RateLimiter::new();
+        assert_eq!(limiter.delay(t), None);
+
+        limiter.observe(Some("3"), Some("20"), t);
+        assert_eq!(limiter.delay(t), None);
+        limiter.observe(Some("0"), Some("20"), t);
+        assert_eq!(limiter.delay(t), Some(std::time::Duration::from_secs(20)));
+        limiter.observe(Some("0"), Some(&(t.timestamp() + 5).to_string()), t);
+        assert_eq!(limiter.delay(t), Some(std::time::Duration::from_secs(5)));
+
+        limiter.observe(Some("10"), Some("60"), t);
+        assert_eq!(limiter.block(None, t), t + Duration::seconds(DEFAULT_BLOCK_SECS));
+        assert_eq!(limiter.delay(t + Duration::seconds(10)), Some(std::time::Duration::from_secs(20)));
+        assert_eq!(limiter.delay(t + Duration::seconds(DEFAULT_BLOCK_SECS)), None);
+    }
+}
@@ -0,0 +1,98 @@
+//! Telemetry Policy
+//!
+//! Server-delivered rules for how much telemetry a VTC collects. Fields are
+//! dotted payload paths such as `truck_id` or `telemetry_data.afkPeriods`;
+//! the policy is applied to every job payload before it is sent.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Payload section holding the optional telemetry detail
+const DETAIL_SECTION: &str = "telemetry_data";
+
+/// Telemetry detail a VTC requires, allows and forbids
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TelemetryPolicy {
+    /// Must be present (and not null) or the job is not submitted
+    pub required: Vec<String>,
+    /// When set, `telemetry_data` entries not listed here or in `required`
+    /// are left out
+    pub optional: Option<Vec<String>>,
+    /// Always removed
+    pub forbidden: Vec<String>,
+}
+
+impl TelemetryPolicy {
+    /// Strip what the policy doesn't allow; returns the missing required
+    /// fields as the error
+    pub fn apply(&self, payload: &mut Value) -> Result<(), Vec<String>> {
+        if let Some(optional) = &self.optional {
+            let allowed = |key: &str| {
+                let path = format!("{}.{}", DETAIL_SECTION, key);
+                self.required.contains(&path) || optional.contains(&path)
+            };
+            if let Some(detail) = payload.get_mut(DETAIL_SECTION).and_then(Value::as_object_mut) {
+                detail.retain(|key, _| allowed(key));
+            }
+        }
+
+        for path in &self.forbidden {
+            remove(payload, path);
+        }
+
+        let missing: Vec<String> = self.required.iter()
+            .filter(|path| lookup(payload, path).map_or(true, Value::is_null))
+            .cloned()
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(missing)
+        }
+    }
+}
+
+fn lookup<'a>(payload: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(payload, |value, key| value.get(key))
+}
+
+fn remove(payload: &mut Value, path: &str) {
+    let (parent, key) = match path.rsplit_once('.') {
+        Some((parent, key)) => (parent.split('.').try_fold(&mut *payload, |value, key| value.get_mut(key)), key),
+        None => (Some(payload), path),
+    };
+    if let Some(object) = parent.and_then(Value::as_object_mut) {
+        object.remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn strips_and_checks_payload_fields() {
+        let payload = json!({
+            "cargo": "Steel",
+            "truck_id": "scania.s",
+            "telemetry_data": { "conduct": {}, "afkPeriods": [], "legs": [] },
+        });
+        let policy = TelemetryPolicy {
+            required: vec!["telemetry_data.conduct".into()],
+            optional: Some(vec!["telemetry_data.legs".into()]),
+            forbidden: vec!["truck_id".into()],
+        };
+
+        let mut stripped = payload.clone();
+        assert_eq!(policy.apply(&mut stripped), Ok(()));
+        assert_eq!(stripped, json!({
+            "cargo": "Steel",
+            "telemetry_data": { "conduct": {}, "legs": [] },
+        }));
+
+        let strict = TelemetryPolicy { required: vec!["trailer_id".into()], ..TelemetryPolicy::default() };
+        assert_eq!(strict.apply(&mut payload.clone()), Err(vec!["trailer_id".to_string()]));
+    }
+}
@@ -0,0 +1,158 @@
+//! Realtime Client
+//!
+//! WebSocket to the backend for push messages such as convoy invites,
+//! dispatch assignments and forced logouts. The caller owns the reconnect
+//! loop; `run` handles a single connection, made through the configured
+//! proxy and trusting the same roots as API requests.
+
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::Connector;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+use super::transport::Transport;
+use super::ApiError;
+
+/// Path of the push endpoint
+const REALTIME_PATH: &str = "/api/realtime";
+
+/// Message pushed by the server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PushMessage {
+    ConvoyInvite {
+        convoy_id: String,
+        name: Option<String>,
+        invited_by: Option<String>,
+    },
+    DispatchAssignment {
+        assignment_id: String,
+        cargo: Option<String>,
+        source_city: Option<String>,
+        destination_city: Option<String>,
+        deadline: Option<DateTime<Utc>>,
+    },
+    /// The session was revoked on the server (e.g. from the web dashboard)
+    ForcedLogout { reason: Option<String> },
+    /// Sent by newer servers; ignored
+    #[serde(other)]
+    Unknown,
+}
+
+impl PushMessage {
+    /// Tauri event the message is forwarded as
+    pub fn event_name(&self) -> Option<&'static str> {
+        match self {
+            PushMessage::ConvoyInvite { .. } => Some("convoy_invite"),
+            PushMessage::DispatchAssignment { .. } => Some("dispatch_assignment"),
+            PushMessage::ForcedLogout { .. } => Some("forced_logout"),
+            PushMessage::Unknown => None,
+        }
+    }
+}
+
+/// Push channel from the backend
+pub struct RealtimeClient {
+    transport: Arc<Transport>,
+}
+
+impl RealtimeClient {
+    pub(super) fn new(transport: Arc<Transport>) -> Self {
+        Self { transport }
+    }
+
+    /// Connect and hand every message to `on_message` until the server closes
+    /// the socket or `cancel` fires. Errors mean the connection never opened
+    /// or broke off
+    pub async fn run(
+        &self,
+        access_token: &str,
+        cancel: &CancellationToken,
+        mut on_message: impl FnMut(PushMessage),
+    ) -> Result<(), ApiError> {
+        let mut request = websocket_url(&self.transport.url(REALTIME_PATH))
+            .into_client_request()
+            .map_err(|e| ApiError::Network(e.to_string()))?;
+        let auth = HeaderValue::from_str(&format!("Bearer {}", access_token))
+            .map_err(|e| ApiError::Parse(e.to_string()))?;
+        request.headers_mut().insert("Authorization", auth);
+
+        let network = self.transport.network();
+        let tls = network.tls_config().map_err(|e| ApiError::Network(e.to_string()))?;
+        let uri = request.uri();
+        let secure = uri.scheme_str() == Some("wss");
+        let host = uri.host()
+            .ok_or_else(|| ApiError::Network(format!("no host in {}", uri)))?
+            .trim_matches(|c| c == '[' || c == ']')
+            .to_string();
+        let port = uri.port_u16().unwrap_or(if secure { 443 } else { 80 });
+        let stream = network.connect(&host, port, secure)
+            .await
+            .map_err(|e| ApiError::Network(e.to_string()))?;
+        let (mut socket, _) = tokio_tungstenite::client_async_tls_with_config(request, stream, None, Some(Connector::Rustls(tls)))
+            .await
+            .map_err(|e| ApiError::Network(e.to_string()))?;
+        info!("Realtime channel connected");
+
+        loop {
+            let frame = tokio::select! {
+                _ = cancel.cancelled() => {
+                    let _ = socket.close(None).await;
+                    return Ok(());
+                }
+                frame = socket.next() => frame,
+            };
+            match frame {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<PushMessage>(&text) {
+                    Ok(message) => on_message(message),
+                    Err(e) => debug!("Ignoring malformed push message: {}", e),
+                },
+                Some(Ok(Message::Ping(payload))) => {
+                    socket.send(Message::Pong(payload)).await.map_err(|e| ApiError::Network(e.to_string()))?;
+                }
+                Some(Ok(Message::Close(_))) | None => {
+                    info!("Realtime channel closed by server");
+                    return Ok(());
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(ApiError::Network(e.to_string())),
+            }
+        }
+    }
+}
+
+/// `ws(s)://` URL for an `http(s)://` one
+fn websocket_url(http_url: &str) -> String {
+    if let Some(rest) = http_url.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = http_url.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        http_url.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_push_messages() {
+        let invite: PushMessage = serde_json::from_str(
+            r#"{"type":"convoy_invite","convoy_id":"c1","name":"Friday run","invited_by":null}"#,
+        ).unwrap();
+        assert_eq!(invite.event_name(), Some("convoy_invite"));
+
+        let future: PushMessage = serde_json::from_str(r#"{"type":"fleet_broadcast"}"#).unwrap();
+        assert_eq!(future, PushMessage::Unknown);
+
+        assert_eq!(websocket_url("https://api.example.com/api/realtime"), "wss://api.example.com/api/realtime");
+        assert_eq!(websocket_url("http://localhost:3000/api/realtime"), "ws://localhost:3000/api/realtime");
+    }
+}
@@ -0,0 +1,65 @@
+//! Releases API
+//!
+//! Desktop release lookup and installer downloads.
+
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+
+use super::transport::Transport;
+use super::ApiError;
+
+/// Installers are far larger than API responses
+const DOWNLOAD_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+/// Release endpoints
+pub struct ReleasesApi {
+    transport: Arc<Transport>,
+}
+
+/// A published desktop build
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReleaseInfo {
+    pub version: String,
+    /// Release notes (Markdown)
+    #[serde(default)]
+    pub notes: String,
+    pub published_at: Option<DateTime<Utc>>,
+    pub download_url: String,
+    /// Hex SHA-256 of the installer
+    pub sha256: String,
+    /// Base64 Ed25519 signature by the release signing key over the
+    /// version and `sha256`, never the installer bytes alone
+    #[serde(default)]
+    pub signature: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LatestReleaseResponse {
+    release: Option<ReleaseInfo>,
+}
+
+impl ReleasesApi {
+    pub(super) fn new(transport: Arc<Transport>) -> Self {
+        Self { transport }
+    }
+
+    /// Latest release for this platform, if any is published
+    pub async fn latest(&self) -> Result<Option<ReleaseInfo>, ApiError> {
+        let request = self.transport
+            .request(Method::GET, "/api/desktop/releases/latest", None)
+            .query(&[("platform", std::env::consts::OS), ("arch", std::env::consts::ARCH)]);
+        let data: LatestReleaseResponse = self.transport.send(request, "Release check failed").await?;
+        Ok(data.release)
+    }
+
+    /// Download a release's installer; only over https
+    pub async fn download(&self, release: &ReleaseInfo) -> Result<Vec<u8>, ApiError> {
+        let secure = reqwest::Url::parse(&release.download_url).is_ok_and(|url| url.scheme() == "https");
+        if !secure {
+            return Err(ApiError::Server(format!("Refusing non-https download URL {}", release.download_url)));
+        }
+        self.transport.download(&release.download_url, DOWNLOAD_TIMEOUT).await
+    }
+}
@@ -0,0 +1,99 @@
+//! Retry Policy
+//!
+//! Exponential backoff with jitter for transient request failures (network
+//! errors, 5xx), and a retry budget so an outage doesn't multiply the load
+//! every caller puts on the backend.
+
+use std::time::Duration;
+
+/// Budget earned per request, i.e. retries may add at most 20% traffic
+const RETRY_RATIO: f64 = 0.2;
+/// Retries available in a burst, and at startup
+const MAX_BUDGET: f64 = 10.0;
+
+/// How often and how long to retry a failed request
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts including the first; 1 disables retries
+    pub max_attempts: u32,
+    /// Wait before the first retry, doubled for each further one
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Send every request once
+    pub const NONE: Self = Self { max_attempts: 1, base_delay: Duration::ZERO, max_delay: Duration::ZERO };
+
+    /// Wait before retry number `retry` (1-based); `jitter` in [0, 1] picks a
+    /// point in the upper half of the backoff so clients don't retry in step
+    pub fn delay(&self, retry: u32, jitter: f64) -> Duration {
+        let backoff = self.base_delay
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+            .min(self.max_delay);
+        backoff.mul_f64(0.5 + 0.5 * jitter.clamp(0.0, 1.0))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+        }
+    }
+}
+
+/// Retries left across all requests of one transport
+#[derive(Debug)]
+pub(super) struct RetryBudget {
+    tokens: f64,
+}
+
+impl RetryBudget {
+    pub fn new() -> Self {
+        Self { tokens: MAX_BUDGET }
+    }
+
+    /// A new request was made
+    pub fn deposit(&mut self) {
+        self.tokens = (self.tokens + RETRY_RATIO).min(MAX_BUDGET);
+    }
+
+    /// Spend one retry; false once the budget is used up
+    pub fn withdraw(&mut self) -> bool {
+        if self.tokens < 1.0 {
+            return false;
+        }
+        self.tokens -= 1.0;
+        true
+    }
+}
+
+/// Random value in [0, 1)
+pub(super) fn jitter() -> f64 {
+    (uuid::Uuid::new_v4().as_u128() >> 75) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backs_off_exponentially_within_budget() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.delay(1, 1.0), Duration::from_millis(500));
+        assert_eq!(policy.delay(3, 1.0), Duration::from_secs(2));
+        assert_eq!(policy.delay(3, 0.0), Duration::from_secs(1));
+        assert_eq!(policy.delay(10, 1.0), policy.max_delay);
+        assert!((0.0..1.0).contains(&jitter()));
+
+        let mut budget = RetryBudget::new();
+        assert!((0..10).all(|_| budget.withdraw()));
+        assert!(!budget.withdraw());
+        (0..5).for_each(|_| budget.deposit());
+        assert!(budget.withdraw());
+        assert!(!budget.withdraw());
+    }
+}
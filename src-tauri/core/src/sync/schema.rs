@@ -0,0 +1,132 @@
+//! Payload Schemas
+//!
+//! Versioned encoders for job submissions. The two most recent schemas are
+//! kept so desktop releases and backend deploys can roll out independently;
+//! the server lists the ones it accepts at `/api/telemetry/capabilities`.
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::JobSubmission;
+
+/// Schema written by this build
+pub const CURRENT_SCHEMA: u32 = 2;
+/// Schemas this build can still encode, newest first
+const SUPPORTED_SCHEMAS: [u32; 2] = [CURRENT_SCHEMA, 1];
+/// Schema of servers that predate capability discovery
+pub const LEGACY_SCHEMA: u32 = 1;
+
+/// What the server accepts
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ServerCapabilities {
+    /// Job payload schema versions
+    #[serde(default)]
+    pub job_schemas: Vec<u32>,
+    /// Whether `/api/telemetry/jobs/batch` is available
+    #[serde(default)]
+    pub batch_jobs: bool,
+}
+
+/// Newest schema both sides understand
+pub fn negotiate(capabilities: &ServerCapabilities) -> u32 {
+    SUPPORTED_SCHEMAS.iter()
+        .copied()
+        .find(|schema| capabilities.job_schemas.contains(schema))
+        .unwrap_or_else(|| {
+            // A server that moved past us should still read its newest predecessor
+            warn!("Server accepts job schemas {:?}, sending {}", capabilities.job_schemas, CURRENT_SCHEMA);
+            CURRENT_SCHEMA
+        })
+}
+
+/// Original, unversioned payload
+#[derive(Serialize)]
+struct JobV1<'a> {
+    game: &'a str,
+    cargo: &'a str,
+    source_city: &'a str,
+    destination_city: &'a str,
+    distance_km: u32,
+    revenue: f64,
+    damage_percent: f64,
+    truck_id: Option<&'a str>,
+    trailer_id: Option<&'a str>,
+    telemetry_data: Option<&'a serde_json::Value>,
+    server: Option<&'a str>,
+}
+
+/// Adds chassis damage, trailer ownership and fuel usage
+#[derive(Serialize)]
+struct JobV2<'a> {
+    schema_version: u32,
+    #[serde(flatten)]
+    job: &'a JobSubmission,
+}
+
+/// Encode `job` in the given schema
+pub fn encode(job: &JobSubmission, schema: u32) -> serde_json::Value {
+    let encoded = match schema {
+        1 => serde_json::to_value(JobV1 {
+            game: &job.game,
+            cargo: &job.cargo,
+            source_city: &job.source_city,
+            destination_city: &job.destination_city,
+            distance_km: job.distance_km,
+            revenue: job.revenue,
+            damage_percent: job.damage_percent,
+            truck_id: job.truck_id.as_deref(),
+            trailer_id: job.trailer_id.as_deref(),
+            telemetry_data: job.telemetry_data.as_ref(),
+            server: job.server.as_deref(),
+        }),
+        _ => serde_json::to_value(JobV2 { schema_version: CURRENT_SCHEMA, job }),
+    };
+    encoded.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::TrailerOwnership;
+
+    #[test]
+    fn negotiates_and_encodes_both_schemas() {
+        let both = ServerCapabilities { job_schemas: vec![1, 2], ..Default::default() };
+        let old = ServerCapabilities { job_schemas: vec![1], ..Default::default() };
+        assert_eq!(negotiate(&both), 2);
+        assert_eq!(negotiate(&old), 1);
+        assert_eq!(negotiate(&ServerCapabilities { job_schemas: vec![3], ..Default::default() }), CURRENT_SCHEMA);
+
+        let job = JobSubmission {
+            game: "ets2".into(),
+            cargo: "Steel".into(),
+            source_city: "Berlin".into(),
+            destination_city: "Hamburg".into(),
+            distance_km: 290,
+            revenue: 10_000.0,
+            currency: None,
+            damage_percent: 2.0,
+            chassis_damage_percent: 1.5,
+            truck_id: None,
+            trailer_id: None,
+            trailer_ownership: Some(TrailerOwnership::Owned),
+            telemetry_data: None,
+            server: None,
+            fuel_used: None,
+            average_consumption: None,
+            driven_km: None,
+            navigation_km: None,
+            idempotency_key: None,
+            partial: false,
+        };
+
+        let v2 = encode(&job, 2);
+        assert_eq!(v2["schema_version"], 2);
+        assert_eq!(v2["chassis_damage_percent"], 1.5);
+
+        let v1 = encode(&job, 1);
+        assert_eq!(v1["cargo"], "Steel");
+        assert!(v1.get("schema_version").is_none());
+        assert!(v1.get("trailer_ownership").is_none());
+    }
+}
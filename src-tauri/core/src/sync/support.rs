@@ -0,0 +1,52 @@
+//! Support API
+//!
+//! Feedback and support endpoints.
+
+use std::sync::Arc;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::transport::Transport;
+use super::ApiError;
+
+/// Support endpoints
+pub struct SupportApi {
+    transport: Arc<Transport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeedbackRequest {
+    pub message: String,
+    pub app_version: String,
+    /// Recent log lines, already redacted
+    pub logs: Option<String>,
+    pub diagnostics: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeedbackResponse {
+    pub success: bool,
+    pub ticket_id: Option<String>,
+}
+
+impl SupportApi {
+    pub(super) fn new(transport: Arc<Transport>) -> Self {
+        Self { transport }
+    }
+
+    /// Send user feedback; works without a session so linking problems can be reported
+    pub async fn send_feedback(
+        &self,
+        access_token: Option<&str>,
+        feedback: &FeedbackRequest,
+    ) -> Result<FeedbackResponse, ApiError> {
+        let request = self.transport
+            .request(Method::POST, "/api/support/feedback", access_token)
+            .json(feedback);
+        let data: FeedbackResponse = self.transport.send(request, "Feedback submission failed").await?;
+
+        info!("Feedback sent");
+        Ok(data)
+    }
+}
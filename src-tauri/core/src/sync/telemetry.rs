@@ -0,0 +1,268 @@
+//! Telemetry API
+//!
+//! Heartbeat, job submission and live-map endpoints.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use reqwest::Method;
+use tracing::{debug, info, warn};
+
+use super::policy::TelemetryPolicy;
+use super::schema::{self, ServerCapabilities, LEGACY_SCHEMA};
+use super::transport::Transport;
+use super::{
+    ApiError, BatchJob, BatchRequest, BatchResponse, HeartbeatRequest, HeartbeatResponse, JobResponse,
+    JobSubmission, PositionBatchResponse, Presence,
+};
+use crate::positions::PositionBatch;
+
+/// Most jobs sent in one batch request
+pub const MAX_BATCH_JOBS: usize = 50;
+
+/// What was agreed with the server about job submission
+#[derive(Debug, Clone, Copy)]
+struct Negotiated {
+    schema: u32,
+    batch_jobs: bool,
+}
+
+/// Telemetry endpoints
+pub struct TelemetryApi {
+    transport: Arc<Transport>,
+    /// Job submission capabilities agreed with the server, once discovered
+    negotiated: RwLock<Option<Negotiated>>,
+    /// VTC telemetry policy from the last heartbeat
+    policy: RwLock<Option<TelemetryPolicy>>,
+}
+
+impl TelemetryApi {
+    pub(super) fn new(transport: Arc<Transport>) -> Self {
+        Self { transport, negotiated: RwLock::new(None), policy: RwLock::new(None) }
+    }
+
+    /// Policy currently applied to job payloads
+    pub fn policy(&self) -> Option<TelemetryPolicy> {
+        self.policy.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replace the policy; returns true if it changed
+    pub fn set_policy(&self, policy: Option<TelemetryPolicy>) -> bool {
+        let mut current = self.policy.write().unwrap_or_else(|e| e.into_inner());
+        let changed = *current != policy;
+        *current = policy;
+        changed
+    }
+
+    /// Ask the server which payload schemas it accepts
+    pub async fn capabilities(&self) -> Result<ServerCapabilities, ApiError> {
+        let request = self.transport.request(Method::GET, "/api/telemetry/capabilities", None);
+        self.transport.send(request, "Capability discovery failed").await
+    }
+
+    /// Re-discover the payload schema before the next submission
+    pub fn forget_capabilities(&self) {
+        *self.negotiated.write().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    /// Schema and batch support to submit jobs with, discovered on first use
+    async fn negotiated(&self) -> Result<Negotiated, ApiError> {
+        if let Some(negotiated) = *self.negotiated.read().unwrap_or_else(|e| e.into_inner()) {
+            return Ok(negotiated);
+        }
+        let negotiated = match self.capabilities().await {
+            Ok(capabilities) => Negotiated {
+                schema: schema::negotiate(&capabilities),
+                batch_jobs: capabilities.batch_jobs,
+            },
+            // Servers without the endpoint take the original payload, one job at a time
+            Err(ApiError::Server(e)) | Err(ApiError::Rejected(e)) | Err(ApiError::Parse(e)) => {
+                warn!("No capability discovery ({}), using job schema {}", e, LEGACY_SCHEMA);
+                Negotiated { schema: LEGACY_SCHEMA, batch_jobs: false }
+            }
+            Err(e) => return Err(e),
+        };
+        info!("Using job payload schema {} (batches: {})", negotiated.schema, negotiated.batch_jobs);
+        *self.negotiated.write().unwrap_or_else(|e| e.into_inner()) = Some(negotiated);
+        Ok(negotiated)
+    }
+
+    /// Payload for `job` in `schema`, with the VTC policy applied
+    fn encode(&self, job: &JobSubmission, schema: u32) -> Result<serde_json::Value, ApiError> {
+        let mut payload = schema::encode(job, schema);
+        if let Some(policy) = self.policy() {
+            policy.apply(&mut payload).map_err(|missing| ApiError::Policy(missing.join(", ")))?;
+        }
+        Ok(payload)
+    }
+
+    /// Send heartbeat to keep connection alive, with what the driver is doing
+    pub async fn send_heartbeat(&self, access_token: &str, presence: &Presence) -> Result<HeartbeatResponse, ApiError> {
+        let request = self.transport
+            .request(Method::POST, "/api/telemetry/heartbeat", Some(access_token))
+            .json(&HeartbeatRequest { presence });
+        let response: HeartbeatResponse = self.transport.send(request, "Heartbeat failed").await?;
+        if self.set_policy(response.telemetry_policy.clone()) {
+            info!("Telemetry policy updated: {:?}", response.telemetry_policy);
+        }
+        Ok(response)
+    }
+
+    /// Submit a telemetry job
+    pub async fn submit_job(&self, access_token: &str, job: &JobSubmission) -> Result<JobResponse, ApiError> {
+        info!("Submitting telemetry job: {} -> {}", job.source_city, job.destination_city);
+
+        let schema = self.negotiated().await?.schema;
+        let payload = self.encode(job, schema)?;
+        let request = self.transport
+            .request(Method::POST, "/api/telemetry/job", Some(access_token))
+            .header("Idempotency-Key", job.request_key());
+        let request = self.transport.signed_json(request, &payload)?;
+        let data: JobResponse = match self.transport.send(request, "Job submission failed").await {
+            Ok(data) => data,
+            Err(e) => {
+                // The backend may have been redeployed with other schemas
+                if matches!(e, ApiError::Server(_) | ApiError::Rejected(_)) {
+                    self.forget_capabilities();
+                }
+                return Err(e);
+            }
+        };
+
+        info!("Job submitted successfully: {}", data.job_id);
+        Ok(data)
+    }
+
+    /// Submit many jobs, `MAX_BATCH_JOBS` per request. Returns one result per
+    /// job, in order, so a rejected entry doesn't fail the others; servers
+    /// without the batch endpoint get the jobs one at a time
+    pub async fn submit_jobs_batch(
+        &self,
+        access_token: &str,
+        jobs: &[JobSubmission],
+    ) -> Vec<Result<JobResponse, ApiError>> {
+        let mut results = Vec::with_capacity(jobs.len());
+        let negotiated = match self.negotiated().await {
+            Ok(negotiated) => negotiated,
+            Err(e) => {
+                results.resize_with(jobs.len(), || Err(e.clone()));
+                return results;
+            }
+        };
+        let chunk_size = if negotiated.batch_jobs { MAX_BATCH_JOBS } else { 1 };
+        for chunk in jobs.chunks(chunk_size) {
+            let outcome = if negotiated.batch_jobs {
+                self.send_batch(access_token, chunk, negotiated.schema).await
+            } else {
+                match self.submit_job(access_token, &chunk[0]).await {
+                    Err(e) if aborts_batch(&e) => Err(e),
+                    result => Ok(vec![result]),
+                }
+            };
+            match outcome {
+                Ok(chunk_results) => results.extend(chunk_results),
+                // Nothing else will get through either
+                Err(e) => {
+                    results.resize_with(jobs.len(), || Err(e.clone()));
+                    break;
+                }
+            }
+        }
+        results
+    }
+
+    /// One batch request; `Err` when the request as a whole failed
+    async fn send_batch(
+        &self,
+        access_token: &str,
+        jobs: &[JobSubmission],
+        schema: u32,
+    ) -> Result<Vec<Result<JobResponse, ApiError>>, ApiError> {
+        info!("Submitting batch of {} telemetry jobs", jobs.len());
+
+        let mut encoded = Vec::with_capacity(jobs.len());
+        let mut entries = Vec::new();
+        for job in jobs {
+            match self.encode(job, schema) {
+                Ok(payload) => {
+                    let body = serde_json::to_vec(&payload).map_err(|e| ApiError::Parse(e.to_string()))?;
+                    let signature = self.transport.sign(&body);
+                    entries.push(BatchJob { idempotency_key: job.request_key(), payload, signature });
+                    encoded.push(Ok(()));
+                }
+                Err(e) => encoded.push(Err(e)),
+            }
+        }
+        let mut by_key = HashMap::new();
+        if !entries.is_empty() {
+            let request = self.transport.request(Method::POST, "/api/telemetry/jobs/batch", Some(access_token));
+            let request = request.json(&BatchRequest { jobs: entries });
+            let response: BatchResponse = match self.transport.send(request, "Batch job submission failed").await {
+                Ok(response) => response,
+                Err(e) => {
+                    if matches!(e, ApiError::Server(_) | ApiError::Rejected(_)) {
+                        self.forget_capabilities();
+                    }
+                    return Err(e);
+                }
+            };
+            by_key.extend(response.results.into_iter().map(|result| (result.idempotency_key.clone(), result)));
+        }
+
+        let results: Vec<_> = jobs.iter()
+            .zip(encoded)
+            .map(|(job, encoded)| {
+                encoded?;
+                match by_key.remove(&job.request_key()) {
+                    Some(result) if result.success => Ok(JobResponse {
+                        success: true,
+                        job_id: result.job_id,
+                        message: result.message,
+                    }),
+                    Some(result) => Err(ApiError::Rejected(result.error.unwrap_or(result.message))),
+                    None => Err(ApiError::Server("Job missing from batch response".into())),
+                }
+            })
+            .collect();
+        let accepted = results.iter().filter(|result| result.is_ok()).count();
+        info!("Batch submitted: {} of {} jobs accepted", accepted, jobs.len());
+        Ok(results)
+    }
+
+    /// Upload a batch of live-map positions
+    pub async fn upload_positions(
+        &self,
+        access_token: &str,
+        batch: &PositionBatch,
+    ) -> Result<PositionBatchResponse, ApiError> {
+        let body = batch.to_gzip_json()
+            .map_err(|e| ApiError::Parse(e.to_string()))?;
+
+        debug!("Uploading {} position samples ({} bytes)", batch.samples.len(), body.len());
+
+        let request = self.transport
+            .request(Method::POST, "/api/telemetry/positions", Some(access_token))
+            .header("Content-Type", "application/json")
+            .header("Content-Encoding", "gzip")
+            .body(body);
+        self.transport.send(request, "Position upload failed").await
+    }
+
+    /// Disconnect (set offline)
+    pub async fn disconnect(&self, access_token: &str) -> Result<(), ApiError> {
+        let _ = self.transport
+            .request(Method::DELETE, "/api/telemetry/heartbeat", Some(access_token))
+            .send()
+            .await;
+
+        info!("Disconnected from server");
+        Ok(())
+    }
+}
+
+/// Errors that would fail every other job of the batch too
+fn aborts_batch(error: &ApiError) -> bool {
+    matches!(
+        error,
+        ApiError::Network(_) | ApiError::Unauthorized(_) | ApiError::Maintenance(_) | ApiError::RateLimited(_)
+    )
+}
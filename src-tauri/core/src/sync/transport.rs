@@ -0,0 +1,351 @@
+//! Transport
+//!
+//! Shared HTTP transport for the API services: base URL, default headers,
+//! bearer auth, proxy and TLS options, retries, rate limiting and uniform error mapping. Requests
+//! go out one at a time so bursts queue up instead of hitting the server at once.
+//! A 401 on an authenticated request is published as a revoked token.
+
+use std::sync::{Mutex, RwLock};
+use chrono::{DateTime, Utc};
+use reqwest::{Method, RequestBuilder};
+use serde::de::DeserializeOwned;
+use tokio::sync::watch;
+use tracing::{debug, warn};
+
+use crate::signing::{DeviceKey, SIGNATURE_HEADER};
+use super::limiter::RateLimiter;
+use super::network::{NetworkError, NetworkSettings};
+use super::retry::{self, RetryBudget, RetryPolicy};
+use super::{ApiError, ErrorResponse};
+
+/// Longest a queued request waits out a rate limit before giving up
+const MAX_RATE_LIMIT_WAIT: std::time::Duration = std::time::Duration::from_secs(60);
+/// Longest back-off a `Retry-After` header can ask for (hours)
+const MAX_RETRY_AFTER_HOURS: i64 = 24;
+
+/// A failed attempt and whether trying again could help
+struct Failure {
+    error: ApiError,
+    retryable: bool,
+}
+
+/// Configured HTTP client shared by every API service
+pub struct Transport {
+    base_url: RwLock<String>,
+    /// Rebuilt when the network settings change
+    client: RwLock<reqwest::Client>,
+    /// Settings `client` was built with, for connections made without it
+    network: RwLock<NetworkSettings>,
+    retry_policy: RwLock<RetryPolicy>,
+    retry_budget: Mutex<RetryBudget>,
+    rate_limiter: Mutex<RateLimiter>,
+    /// Held while a request is in flight
+    queue: tokio::sync::Mutex<()>,
+    /// Last access token the server rejected with a 401
+    revoked: watch::Sender<Option<String>>,
+    /// Signs payloads that must be traceable to this install
+    device_key: RwLock<Option<DeviceKey>>,
+}
+
+impl Transport {
+    /// Create a transport for `base_url`, using the system proxy
+    pub fn new(base_url: &str) -> Self {
+        let client = NetworkSettings::default()
+            .client()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            base_url: RwLock::new(base_url.trim_end_matches('/').to_string()),
+            client: RwLock::new(client),
+            network: RwLock::new(NetworkSettings::default()),
+            retry_policy: RwLock::new(RetryPolicy::default()),
+            retry_budget: Mutex::new(RetryBudget::new()),
+            rate_limiter: Mutex::new(RateLimiter::new()),
+            queue: tokio::sync::Mutex::new(()),
+            revoked: watch::channel(None).0,
+            device_key: RwLock::new(None),
+        }
+    }
+
+    /// Full URL for an API path
+    pub fn url(&self, path: &str) -> String {
+        let base_url = self.base_url.read().unwrap_or_else(|e| e.into_inner());
+        format!("{}{}", base_url, path)
+    }
+
+    /// Point subsequent requests at another server
+    pub fn set_base_url(&self, base_url: &str) {
+        let mut current = self.base_url.write().unwrap_or_else(|e| e.into_inner());
+        *current = base_url.trim_end_matches('/').to_string();
+    }
+
+    /// Send subsequent requests with `settings`; the current client is kept
+    /// if they can't be applied
+    pub fn set_network(&self, settings: &NetworkSettings) -> Result<(), NetworkError> {
+        let client = settings.client()?;
+        settings.tls_config()?;
+        *self.client.write().unwrap_or_else(|e| e.into_inner()) = client;
+        *self.network.write().unwrap_or_else(|e| e.into_inner()) = settings.clone();
+        Ok(())
+    }
+
+    /// Network settings in use
+    pub fn network(&self) -> NetworkSettings {
+        self.network.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn client(&self) -> reqwest::Client {
+        self.client.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Change how failed requests are retried
+    pub fn set_retry_policy(&self, policy: RetryPolicy) {
+        *self.retry_policy.write().unwrap_or_else(|e| e.into_inner()) = policy;
+    }
+
+    /// Key that signs payloads from now on
+    pub fn set_device_key(&self, key: Option<DeviceKey>) {
+        *self.device_key.write().unwrap_or_else(|e| e.into_inner()) = key;
+    }
+
+    /// Public key to register with the server, if a key is set
+    pub fn device_public_key(&self) -> Option<String> {
+        self.device_key.read().unwrap_or_else(|e| e.into_inner()).as_ref().map(DeviceKey::public_key)
+    }
+
+    /// Base64 device signature of `body`, if a key is set
+    pub fn sign(&self, body: &[u8]) -> Option<String> {
+        self.device_key.read().unwrap_or_else(|e| e.into_inner()).as_ref().map(|key| key.sign(body))
+    }
+
+    /// Attach `payload` as the JSON body, signed with the device key when one is set
+    pub fn signed_json<T: serde::Serialize>(&self, request: RequestBuilder, payload: &T) -> Result<RequestBuilder, ApiError> {
+        let body = serde_json::to_vec(payload).map_err(|e| ApiError::Parse(e.to_string()))?;
+        let request = match self.sign(&body) {
+            Some(signature) => request.header(SIGNATURE_HEADER, signature),
+            None => request,
+        };
+        Ok(request.header(reqwest::header::CONTENT_TYPE, "application/json").body(body))
+    }
+
+    /// Receiver that sees each access token the server rejects
+    pub fn revocations(&self) -> watch::Receiver<Option<String>> {
+        self.revoked.subscribe()
+    }
+
+    /// Start a request, attaching the bearer token when given
+    pub fn request(&self, method: Method, path: &str, access_token: Option<&str>) -> RequestBuilder {
+        let request = self.client().request(method, self.url(path));
+        match access_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Send a request and decode the JSON response, retrying network errors
+    /// and 5xx responses per the retry policy while the budget allows.
+    /// Non-success responses become `ApiError::Server`, `Rejected` for a 4xx
+    /// (`Unauthorized` for a 401) with the server's message, or `fallback` and
+    /// the status when the body has none.
+    pub async fn send<T: DeserializeOwned>(&self, mut request: RequestBuilder, fallback: &str) -> Result<T, ApiError> {
+        let policy = *self.retry_policy.read().unwrap_or_else(|e| e.into_inner());
+        self.retry_budget.lock().unwrap_or_else(|e| e.into_inner()).deposit();
+
+        let mut attempt = 1;
+        loop {
+            // JSON and byte bodies can be replayed; a streamed body goes out once
+            let next = if attempt < policy.max_attempts { request.try_clone() } else { None };
+            let failure = match self.send_once(request, fallback).await {
+                Ok(data) => return Ok(data),
+                Err(failure) => failure,
+            };
+            let Some(next) = next.filter(|_| failure.retryable) else {
+                return Err(failure.error);
+            };
+            if !self.retry_budget.lock().unwrap_or_else(|e| e.into_inner()).withdraw() {
+                debug!("Retry budget exhausted: {}", failure.error);
+                return Err(failure.error);
+            }
+            let delay = policy.delay(attempt, retry::jitter());
+            debug!("Request failed ({}), retrying in {:?}", failure.error, delay);
+            tokio::time::sleep(delay).await;
+            request = next;
+            attempt += 1;
+        }
+    }
+
+    async fn send_once<T: DeserializeOwned>(&self, request: RequestBuilder, fallback: &str) -> Result<T, Failure> {
+        let permanent = |error| Failure { error, retryable: false };
+        let mut waited = std::time::Duration::ZERO;
+        let _turn = loop {
+            let turn = self.queue.lock().await;
+            let Some(wait) = self.limiter().delay(Utc::now()) else {
+                break turn;
+            };
+            // Wait outside the queue; whoever gets in meanwhile waits as well
+            drop(turn);
+            waited += wait;
+            if waited > MAX_RATE_LIMIT_WAIT {
+                let until = Utc::now() + chrono::Duration::from_std(wait).unwrap_or_default();
+                return Err(permanent(ApiError::RateLimited(Some(until))));
+            }
+            debug!("Rate limited, waiting {:?}", wait);
+            tokio::time::sleep(wait).await;
+        };
+
+        let (client, request) = request.build_split();
+        let request = request.map_err(|e| permanent(ApiError::Network(e.to_string())))?;
+        let token = bearer_token(&request);
+        let response = client
+            .execute(request)
+            .await
+            .map_err(|e| Failure { error: ApiError::Network(e.to_string()), retryable: true })?;
+        self.observe_rate_limit(&response);
+
+        if !response.status().is_success() {
+            check_maintenance(&response).map_err(permanent)?;
+            check_rate_limit(&response).map_err(permanent)?;
+            let status = response.status();
+            let error: ErrorResponse = response.json().await
+                .unwrap_or_else(|_| ErrorResponse { error: format!("{} (status: {})", fallback, status) });
+            if status == reqwest::StatusCode::UNAUTHORIZED {
+                if let Some(token) = token {
+                    warn!("Access token rejected: {}", error.error);
+                    self.revoked.send_replace(Some(token));
+                }
+                return Err(permanent(ApiError::Unauthorized(error.error)));
+            }
+            if status.is_client_error() {
+                return Err(permanent(ApiError::Rejected(error.error)));
+            }
+            return Err(Failure { error: ApiError::Server(error.error), retryable: status.is_server_error() });
+        }
+
+        response.json::<T>().await
+            .map_err(|e| permanent(ApiError::Parse(e.to_string())))
+    }
+
+    fn limiter(&self) -> std::sync::MutexGuard<'_, RateLimiter> {
+        self.rate_limiter.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Remember the server's rate limit window and any 429 back-off
+    fn observe_rate_limit(&self, response: &reqwest::Response) {
+        let header = |names: [&str; 2]| {
+            names.iter().find_map(|name| response.headers().get(*name).and_then(|value| value.to_str().ok()))
+        };
+        let now = Utc::now();
+        let mut limiter = self.limiter();
+        limiter.observe(
+            header(["ratelimit-remaining", "x-ratelimit-remaining"]),
+            header(["ratelimit-reset", "x-ratelimit-reset"]),
+            now,
+        );
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let until = limiter.block(retry_after(response), now);
+            debug!("Server rate limit hit, holding requests until {}", until);
+        }
+    }
+
+    /// Round trip time of a HEAD request to `path`. Skips the queue, rate
+    /// limiter and retries; any response below 500 means the API is reachable
+    pub async fn ping(&self, path: &str, timeout: std::time::Duration) -> Result<std::time::Duration, ApiError> {
+        let started = std::time::Instant::now();
+        let response = self.client().head(self.url(path))
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(|e| ApiError::Network(e.to_string()))?;
+        if response.status().is_server_error() {
+            return Err(ApiError::Server(format!("Ping failed (status: {})", response.status())));
+        }
+        Ok(started.elapsed())
+    }
+
+    /// Fetch a file from an absolute URL, e.g. a release installer on a CDN
+    pub async fn download(&self, url: &str, timeout: std::time::Duration) -> Result<Vec<u8>, ApiError> {
+        let response = self.client().get(url)
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(|e| ApiError::Network(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(ApiError::Server(format!("Download failed (status: {})", response.status())));
+        }
+        let bytes = response.bytes().await.map_err(|e| ApiError::Network(e.to_string()))?;
+        Ok(bytes.to_vec())
+    }
+}
+
+/// Access token a request carries, if any
+fn bearer_token(request: &reqwest::Request) -> Option<String> {
+    request.headers()
+        .get(reqwest::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_string)
+}
+
+/// Treat 503 responses as a maintenance signal, honouring `Retry-After`
+fn check_maintenance(response: &reqwest::Response) -> Result<(), ApiError> {
+    if response.status() != reqwest::StatusCode::SERVICE_UNAVAILABLE {
+        return Ok(());
+    }
+    Err(ApiError::Maintenance(retry_after(response)))
+}
+
+/// Treat 429 responses as a request to slow down, honouring `Retry-After`
+fn check_rate_limit(response: &reqwest::Response) -> Result<(), ApiError> {
+    if response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Ok(());
+    }
+    Err(ApiError::RateLimited(retry_after(response)))
+}
+
+fn retry_after(response: &reqwest::Response) -> Option<DateTime<Utc>> {
+    response.headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| parse_retry_after(value, Utc::now()))
+}
+
+/// Parse a `Retry-After` value given in seconds or as an HTTP date, kept
+/// between `now` and `MAX_RETRY_AFTER_HOURS` from it; whatever a server or
+/// proxy sends, this never overflows
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let latest = now + chrono::Duration::hours(MAX_RETRY_AFTER_HOURS);
+    let retry_at = match value.trim().parse::<i64>() {
+        Ok(secs) => chrono::Duration::try_seconds(secs)
+            .and_then(|delay| now.checked_add_signed(delay))
+            .unwrap_or(if secs < 0 { now } else { latest }),
+        Err(_) => DateTime::parse_from_rfc2822(value.trim())
+            .ok()?
+            .with_timezone(&Utc),
+    };
+    Some(retry_at.clamp(now, latest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn retry_after_is_kept_in_range() {
+        let now = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let latest = now + chrono::Duration::hours(MAX_RETRY_AFTER_HOURS);
+        assert_eq!(parse_retry_after(" 120 ", now), Some(now + chrono::Duration::seconds(120)));
+        assert_eq!(parse_retry_after("99999999999999999", now), Some(latest));
+        assert_eq!(parse_retry_after(&i64::MAX.to_string(), now), Some(latest));
+        assert_eq!(parse_retry_after("-30", now), Some(now));
+        assert_eq!(parse_retry_after(&i64::MIN.to_string(), now), Some(now));
+
+        assert_eq!(
+            parse_retry_after("Sun, 01 Jun 2025 12:05:00 GMT", now),
+            Some(now + chrono::Duration::minutes(5)),
+        );
+        assert_eq!(parse_retry_after("Sat, 31 May 2025 12:00:00 GMT", now), Some(now));
+        assert_eq!(parse_retry_after("Fri, 31 Dec 9999 23:59:59 GMT", now), Some(latest));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+}
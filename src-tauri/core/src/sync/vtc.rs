@@ -0,0 +1,97 @@
+//! VTC API
+//!
+//! Company endpoints such as convoy reports and event attendance.
+
+use std::sync::Arc;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::Method;
+use serde::Deserialize;
+use tracing::info;
+
+use super::transport::Transport;
+use super::ApiError;
+use crate::convoy::{AttendanceReport, ConvoyEvent, ConvoyReport};
+
+/// Characters left as they are in an ID used as a path segment; dots are
+/// encoded so an ID can't become a `..` segment
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'~');
+
+/// `id` encoded for use as one path segment
+fn segment(id: &str) -> String {
+    utf8_percent_encode(id, PATH_SEGMENT).to_string()
+}
+
+/// VTC endpoints
+pub struct VtcApi {
+    transport: Arc<Transport>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConvoyReportResponse {
+    pub success: bool,
+    pub report_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AttendanceResponse {
+    pub success: bool,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl VtcApi {
+    pub(super) fn new(transport: Arc<Transport>) -> Self {
+        Self { transport }
+    }
+
+    /// Post a finished convoy report to the event it was recorded for
+    pub async fn submit_convoy_report(
+        &self,
+        access_token: &str,
+        report: &ConvoyReport,
+    ) -> Result<ConvoyReportResponse, ApiError> {
+        let path = format!("/api/vtc/convoys/{}/report", segment(&report.session.id));
+        let request = self.transport
+            .request(Method::POST, &path, Some(access_token))
+            .json(report);
+        let data: ConvoyReportResponse = self.transport.send(request, "Convoy report failed").await?;
+
+        info!("Convoy report submitted: {}", data.report_id);
+        Ok(data)
+    }
+
+    /// Look up a scheduled event by ID
+    pub async fn get_event(&self, access_token: &str, event_id: &str) -> Result<ConvoyEvent, ApiError> {
+        let path = format!("/api/vtc/events/{}", segment(event_id));
+        let request = self.transport.request(Method::GET, &path, Some(access_token));
+        self.transport.send(request, "Event lookup failed").await
+    }
+
+    /// Post the driver's attendance once the event is over
+    pub async fn submit_attendance(
+        &self,
+        access_token: &str,
+        report: &AttendanceReport,
+    ) -> Result<AttendanceResponse, ApiError> {
+        let path = format!("/api/vtc/events/{}/attendance", segment(&report.event_id));
+        let request = self.transport
+            .request(Method::POST, &path, Some(access_token))
+            .json(report);
+        let data: AttendanceResponse = self.transport.send(request, "Attendance report failed").await?;
+
+        info!("Attendance submitted for event {}", report.event_id);
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_stay_inside_their_path_segment() {
+        assert_eq!(segment("evt-7_a~c"), "evt-7_a~c");
+        assert_eq!(segment("../admin?x=1#"), "%2E%2E%2Fadmin%3Fx%3D1%23");
+        assert_eq!(segment("Fahrt Nord"), "Fahrt%20Nord");
+    }
+}
@@ -0,0 +1,178 @@
+//! Sync Health Module
+//!
+//! Counts heartbeat and job submission outcomes so the UI and tray tooltip can
+//! say definitively whether the app is actually online.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Consecutive heartbeat failures after which the app counts as offline
+const OFFLINE_AFTER_FAILURES: u32 = 3;
+
+/// Which kind of request an outcome belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncChannel {
+    Heartbeat,
+    Submission,
+}
+
+/// Overall connection verdict
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncStatus {
+    /// Nothing has been sent yet
+    Unknown,
+    Online,
+    /// Reachable, but the latest request failed
+    Degraded,
+    Offline,
+}
+
+impl std::fmt::Display for SyncStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SyncStatus::Unknown => write!(f, "Not connected yet"),
+            SyncStatus::Online => write!(f, "Online"),
+            SyncStatus::Degraded => write!(f, "Sync problems"),
+            SyncStatus::Offline => write!(f, "Offline"),
+        }
+    }
+}
+
+/// Outcome counters for one channel
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelHealth {
+    pub successes: u64,
+    pub failures: u64,
+    pub consecutive_failures: u32,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_failure: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+impl ChannelHealth {
+    fn record(&mut self, outcome: Result<(), String>, now: DateTime<Utc>) {
+        match outcome {
+            Ok(()) => {
+                self.successes += 1;
+                self.consecutive_failures = 0;
+                self.last_success = Some(now);
+            }
+            Err(error) => {
+                self.failures += 1;
+                self.consecutive_failures += 1;
+                self.last_failure = Some(now);
+                self.last_error = Some(error);
+            }
+        }
+    }
+}
+
+/// Heartbeat and submission health
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncHealth {
+    pub heartbeat: ChannelHealth,
+    pub submissions: ChannelHealth,
+    /// Server time minus local time (ms), from the last heartbeat
+    pub clock_offset_ms: Option<i64>,
+}
+
+/// Health snapshot for the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncHealthReport {
+    pub status: SyncStatus,
+    #[serde(flatten)]
+    pub health: SyncHealth,
+}
+
+impl SyncHealth {
+    /// Create empty counters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the outcome of a request
+    pub fn record(&mut self, channel: SyncChannel, outcome: Result<(), String>, now: DateTime<Utc>) {
+        match channel {
+            SyncChannel::Heartbeat => self.heartbeat.record(outcome, now),
+            SyncChannel::Submission => self.submissions.record(outcome, now),
+        }
+    }
+
+    /// Measure the clock offset from a server timestamp received at `now`
+    pub fn record_server_time(&mut self, server_time: DateTime<Utc>, now: DateTime<Utc>) {
+        self.clock_offset_ms = Some((server_time - now).num_milliseconds());
+    }
+
+    /// Forget the offset, e.g. after the machine slept and its clock may have drifted
+    pub fn reset_clock_offset(&mut self) {
+        self.clock_offset_ms = None;
+    }
+
+    /// Overall verdict, driven mainly by heartbeats
+    pub fn status(&self) -> SyncStatus {
+        let heartbeat = &self.heartbeat;
+        if heartbeat.successes + heartbeat.failures + self.submissions.successes + self.submissions.failures == 0 {
+            SyncStatus::Unknown
+        } else if heartbeat.consecutive_failures >= OFFLINE_AFTER_FAILURES {
+            SyncStatus::Offline
+        } else if heartbeat.consecutive_failures > 0 || self.submissions.consecutive_failures > 0 {
+            SyncStatus::Degraded
+        } else {
+            SyncStatus::Online
+        }
+    }
+
+    /// Most recent successful request of any kind
+    pub fn last_success(&self) -> Option<DateTime<Utc>> {
+        self.heartbeat.last_success.max(self.submissions.last_success)
+    }
+
+    /// Snapshot for the frontend
+    pub fn report(&self) -> SyncHealthReport {
+        SyncHealthReport { status: self.status(), health: self.clone() }
+    }
+
+    /// Tray tooltip text
+    pub fn tooltip(&self, now: DateTime<Utc>) -> String {
+        let mut text = format!("VTC Tracker — {}", self.status());
+        if let Some(at) = self.last_success() {
+            let minutes = (now - at).num_minutes();
+            if minutes < 1 {
+                text.push_str(" · synced just now");
+            } else {
+                text.push_str(&format!(" · last sync {} min ago", minutes));
+            }
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn consecutive_heartbeat_failures_go_offline() {
+        let t = Utc.with_ymd_and_hms(2025, 4, 1, 10, 0, 0).unwrap();
+        let mut health = SyncHealth::new();
+        assert_eq!(health.status(), SyncStatus::Unknown);
+
+        health.record(SyncChannel::Heartbeat, Ok(()), t);
+        assert_eq!(health.status(), SyncStatus::Online);
+
+        health.record(SyncChannel::Submission, Err("Server error".into()), t);
+        assert_eq!(health.status(), SyncStatus::Degraded);
+        health.record(SyncChannel::Submission, Ok(()), t);
+
+        for _ in 0..OFFLINE_AFTER_FAILURES {
+            health.record(SyncChannel::Heartbeat, Err("Network error".into()), t);
+        }
+        assert_eq!(health.status(), SyncStatus::Offline);
+        assert_eq!(health.tooltip(t + Duration::minutes(5)), "VTC Tracker — Offline · last sync 5 min ago");
+    }
+}
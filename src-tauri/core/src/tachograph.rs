@@ -0,0 +1,195 @@
+//! Tachograph Module
+//!
+//! Tracks driving time and rest stops in game time, warns when the driver
+//! keeps going past a break or past the game's own sleep timer, and keeps a
+//! per-job summary for realism-focused VTCs.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::telemetry::TelemetryState;
+
+/// Continuous driving allowed before a break (game minutes)
+const MAX_STINT_MINUTES: u32 = 270;
+/// A stop at least this long counts as a break (game minutes)
+const MIN_BREAK_MINUTES: u32 = 45;
+/// Game time jumps larger than this in one poll are sleeping or loading (game minutes)
+const MAX_POLL_MINUTES: u32 = 30;
+/// Below this the truck is considered stopped (km/h)
+const MOVING_SPEED: f32 = 2.0;
+
+/// Rule the driver broke
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TachographRule {
+    /// Drove longer than `MAX_STINT_MINUTES` without a break
+    BreakOverdue,
+    /// Kept driving after the game's sleep timer ran out
+    RestOverdue,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TachographWarning {
+    pub rule: TachographRule,
+    pub at: DateTime<Utc>,
+    /// Driving since the last break (game minutes)
+    pub stint_minutes: u32,
+}
+
+/// A break or sleep between two stints
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rest {
+    /// When driving resumed
+    pub ended_at: DateTime<Utc>,
+    pub minutes: u32,
+    /// The driver slept, skipping game time
+    pub slept: bool,
+}
+
+/// What one poll changed
+#[derive(Debug, Clone, PartialEq)]
+pub enum TachographEvent {
+    Drove { minutes: u32, stint_minutes: u32 },
+    Rested(Rest),
+    Warning(TachographWarning),
+}
+
+/// Driving and resting since the game started
+#[derive(Debug, Default)]
+pub struct Tachograph {
+    last_game_time: Option<u32>,
+    stint_minutes: u32,
+    stopped_minutes: u32,
+    slept: bool,
+    break_warned: bool,
+    rest_warned: bool,
+}
+
+impl Tachograph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the latest telemetry
+    pub fn observe(&mut self, state: &TelemetryState, now: DateTime<Utc>) -> Vec<TachographEvent> {
+        let Some(game_time) = state.game_time.filter(|_| state.connected && !state.paused) else {
+            return Vec::new();
+        };
+        let Some(last) = self.last_game_time.replace(game_time) else {
+            return Vec::new();
+        };
+        let minutes = game_time.saturating_sub(last);
+        let moving = state.speed > MOVING_SPEED;
+        let mut events = Vec::new();
+
+        if !moving {
+            if minutes > MAX_POLL_MINUTES {
+                self.slept = true;
+            }
+            self.stopped_minutes += minutes;
+        } else if minutes <= MAX_POLL_MINUTES {
+            if self.stopped_minutes >= MIN_BREAK_MINUTES || self.slept {
+                events.push(TachographEvent::Rested(Rest { ended_at: now, minutes: self.stopped_minutes, slept: self.slept }));
+                self.stint_minutes = 0;
+                self.break_warned = false;
+            }
+            self.stopped_minutes = 0;
+            self.slept = false;
+            if minutes > 0 {
+                self.stint_minutes += minutes;
+                events.push(TachographEvent::Drove { minutes, stint_minutes: self.stint_minutes });
+            }
+        }
+
+        if moving && self.stint_minutes > MAX_STINT_MINUTES && !self.break_warned {
+            self.break_warned = true;
+            events.push(self.warning(TachographRule::BreakOverdue, now));
+        }
+        match state.rest_stop_minutes {
+            Some(left) if left < 0 && moving && !self.rest_warned => {
+                self.rest_warned = true;
+                events.push(self.warning(TachographRule::RestOverdue, now));
+            }
+            Some(left) if left >= 0 => self.rest_warned = false,
+            _ => {}
+        }
+        events
+    }
+
+    fn warning(&self, rule: TachographRule, at: DateTime<Utc>) -> TachographEvent {
+        TachographEvent::Warning(TachographWarning { rule, at, stint_minutes: self.stint_minutes })
+    }
+}
+
+/// Driving and rests during one job
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TachographSummary {
+    pub driving_minutes: u32,
+    pub longest_stint_minutes: u32,
+    pub rests: Vec<Rest>,
+    pub warnings: Vec<TachographWarning>,
+}
+
+impl TachographSummary {
+    pub fn record(&mut self, event: &TachographEvent) {
+        match event {
+            TachographEvent::Drove { minutes, stint_minutes } => {
+                self.driving_minutes += minutes;
+                self.longest_stint_minutes = self.longest_stint_minutes.max(*stint_minutes);
+            }
+            TachographEvent::Rested(rest) => self.rests.push(rest.clone()),
+            TachographEvent::Warning(warning) => self.warnings.push(warning.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(game_time: u32, speed: f32, rest_stop_minutes: i32) -> TelemetryState {
+        TelemetryState {
+            connected: true,
+            speed,
+            game_time: Some(game_time),
+            rest_stop_minutes: Some(rest_stop_minutes),
+            ..TelemetryState::default()
+        }
+    }
+
+    #[test]
+    fn warns_past_the_limits_and_resets_after_a_rest() {
+        let now = Utc::now();
+        let mut tachograph = Tachograph::new();
+        let mut summary = TachographSummary::default();
+        let mut warnings = Vec::new();
+        let mut drive = |tachograph: &mut Tachograph, summary: &mut TachographSummary, state: TelemetryState| {
+            for event in tachograph.observe(&state, now) {
+                if let TachographEvent::Warning(warning) = &event {
+                    warnings.push(warning.rule);
+                }
+                summary.record(&event);
+            }
+        };
+
+        // Five hours at the wheel, the sleep timer running out on the way
+        for minute in 0..=300 {
+            drive(&mut tachograph, &mut summary, state(minute, 80.0, 280 - minute as i32));
+        }
+        // A short stop doesn't reset the stint; sleeping does
+        drive(&mut tachograph, &mut summary, state(310, 0.0, -30));
+        drive(&mut tachograph, &mut summary, state(311, 80.0, -31));
+        drive(&mut tachograph, &mut summary, state(312, 0.0, -32));
+        drive(&mut tachograph, &mut summary, state(900, 0.0, 660));
+        drive(&mut tachograph, &mut summary, state(901, 80.0, 659));
+
+        assert_eq!(warnings, [TachographRule::BreakOverdue, TachographRule::RestOverdue]);
+        assert_eq!(summary.driving_minutes, 302);
+        assert_eq!(summary.longest_stint_minutes, 301);
+        assert_eq!(summary.rests.len(), 1);
+        assert!(summary.rests[0].slept && summary.rests[0].minutes == 589);
+    }
+}
@@ -0,0 +1,1604 @@
+//! Telemetry Module
+//!
+//! Reads ETS2/ATS telemetry from the plugin's shared memory, on Windows or
+//! on Linux when the game runs under Proton.
+//! This manual implementation avoids external crate dependency issues (bindgen/libclang).
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::dedup::{EventDeduplicator, EventKey};
+use crate::integrity::{IntegrityMonitor, Sample};
+use crate::scs::{ScsError, ScsFrame, SpecialFlags};
+use crate::snapshots::SnapshotSeries;
+use self::simulator::Simulator;
+#[cfg(any(windows, target_os = "linux"))]
+use self::shm::SharedMemory;
+#[cfg(any(windows, target_os = "linux"))]
+use crate::reconnect::{Liveness, ReconnectSchedule};
+#[cfg(any(windows, target_os = "linux"))]
+use crate::scs;
+#[cfg(any(windows, target_os = "linux"))]
+use tracing::info;
+
+#[cfg(any(windows, target_os = "linux"))]
+pub mod shm;
+pub mod simulator;
+pub mod thread;
+
+/// Game type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Game {
+    Ets2,
+    Ats,
+}
+
+impl std::fmt::Display for Game {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Game::Ets2 => write!(f, "ets2"),
+            Game::Ats => write!(f, "ats"),
+        }
+    }
+}
+
+impl Game {
+    /// Full title, for user-facing messages
+    pub fn title(self) -> &'static str {
+        match self {
+            Game::Ets2 => "Euro Truck Simulator 2",
+            Game::Ats => "American Truck Simulator",
+        }
+    }
+
+    /// Identify the game from its executable name
+    pub fn from_process_name(name: &str) -> Option<Self> {
+        crate::games::from_process_name(name)
+    }
+
+    /// Find a running game process (fallback for plugins without a game ID)
+    pub fn detect_running() -> Option<Self> {
+        crate::processes::running().iter().find_map(|process| Game::from_process_name(&process.name))
+    }
+}
+
+/// Current telemetry state
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryState {
+    pub connected: bool,
+    /// Game paused (menu, map, pause key)
+    #[serde(default)]
+    pub paused: bool,
+    /// Job tracking paused by the driver, e.g. for a personal job
+    #[serde(default)]
+    pub tracking_paused: bool,
+    /// Frames come from the simulator, not a game; its jobs are never submitted
+    #[serde(default)]
+    pub simulated: bool,
+    pub game: Option<Game>,
+    pub speed: f32,
+    /// Navigation speed limit (km/h), if the road has one
+    pub speed_limit: Option<f32>,
+    pub engine_rpm: f32,
+    pub fuel: Option<FuelLevel>,
+    pub multiplayer: bool,
+    /// Multiplayer client the game runs on (e.g. TruckersMP)
+    #[serde(default)]
+    pub server: Option<String>,
+    pub current_city: Option<String>,
+    pub active_job: Option<ActiveJob>,
+    pub job_phase: Option<JobPhase>,
+    pub truck_wear: Option<TruckWear>,
+    pub damage: DamageReading,
+    pub position: Option<Position>,
+    /// Truck odometer (km)
+    #[serde(default)]
+    pub odometer_km: Option<f32>,
+    /// In-game time (minutes)
+    #[serde(default)]
+    pub game_time: Option<u32>,
+    /// Game minutes until the driver must sleep; negative when overdue
+    #[serde(default)]
+    pub rest_stop_minutes: Option<i32>,
+    /// Data the telemetry plugin doesn't publish; shown as unknown rather
+    /// than zero
+    #[serde(default)]
+    pub unknown: Vec<TelemetryField>,
+}
+
+impl TelemetryState {
+    /// Whether the plugin publishes `field`
+    pub fn is_known(&self, field: TelemetryField) -> bool {
+        !self.unknown.contains(&field)
+    }
+}
+
+/// Telemetry blocks an older plugin revision may leave out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TelemetryField {
+    /// Speed limit and remaining route distance; without it ETA and job
+    /// progress are unavailable
+    Navigation,
+    TruckWear,
+    Trailer,
+}
+
+impl Default for TelemetryState {
+    fn default() -> Self {
+        Self {
+            connected: false,
+            paused: false,
+            tracking_paused: false,
+            simulated: false,
+            game: None,
+            speed: 0.0,
+            speed_limit: None,
+            engine_rpm: 0.0,
+            fuel: None,
+            multiplayer: false,
+            server: None,
+            current_city: None,
+            active_job: None,
+            job_phase: None,
+            truck_wear: None,
+            damage: DamageReading::default(),
+            position: None,
+            odometer_km: None,
+            game_time: None,
+            rest_stop_minutes: None,
+            unknown: Vec::new(),
+        }
+    }
+}
+
+/// Drives the telemetry poll rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollActivity {
+    /// No game; only looking for one to start
+    Disconnected,
+    /// The game just started; waiting for the plugin to come up
+    Connecting,
+    /// Nothing changes until the game resumes
+    Paused,
+    Active,
+}
+
+/// Active job information from telemetry
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveJob {
+    pub cargo: String,
+    pub source_city: String,
+    pub destination_city: String,
+    #[serde(default)]
+    pub source_company: Option<String>,
+    #[serde(default)]
+    pub destination_company: Option<String>,
+    pub distance_km: u32,
+    pub distance_remaining: u32,
+    pub revenue: u64,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    /// Damage already present when the job was picked up
+    #[serde(default)]
+    pub pickup_damage: DamageReading,
+    /// Damage at the moment of delivery
+    #[serde(default)]
+    pub delivery_damage: Option<DamageReading>,
+    /// Highest damage seen while the job was in progress
+    #[serde(default)]
+    pub max_damage: DamageReading,
+    /// Job market reported by the SDK
+    #[serde(default)]
+    pub market: Option<String>,
+    #[serde(default)]
+    pub trailer_ownership: Option<TrailerOwnership>,
+    #[serde(default)]
+    pub truck: Option<TruckInfo>,
+    #[serde(default)]
+    pub trailer: Option<TrailerInfo>,
+    /// Stretches driven between game sessions; more than one when the job
+    /// was saved mid-delivery and resumed later
+    #[serde(default)]
+    pub legs: Vec<JobLeg>,
+    #[serde(default)]
+    pub fuel: FuelUsage,
+    #[serde(default)]
+    pub odometer: OdometerUsage,
+    /// Tampering signals seen during the job
+    #[serde(default)]
+    pub integrity: IntegrityMonitor,
+    /// Route replay samples; left out of checkpoints and frontend events
+    #[serde(skip)]
+    pub snapshots: SnapshotSeries,
+}
+
+/// Fuel burned during a job; refuelling doesn't count against it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuelUsage {
+    pub used_liters: f32,
+    /// Tank level at the previous frame
+    #[serde(default)]
+    pub last_liters: Option<f32>,
+}
+
+impl FuelUsage {
+    /// Fold in the current tank level
+    pub fn observe(&mut self, liters: f32) {
+        if let Some(last) = self.last_liters.filter(|last| liters < *last) {
+            self.used_liters += last - liters;
+        }
+        self.last_liters = Some(liters);
+    }
+}
+
+/// Longest odometer advance between two polls still counted as driving (km);
+/// bigger jumps come from switching trucks
+const MAX_ODOMETER_STEP_KM: f32 = 25.0;
+
+/// Distance the truck's odometer advanced during a job; switching trucks
+/// or teleporting doesn't add to it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OdometerUsage {
+    pub driven_km: f32,
+    /// Odometer at the previous frame
+    #[serde(default)]
+    pub last_km: Option<f32>,
+}
+
+impl OdometerUsage {
+    /// Fold in the current odometer reading
+    pub fn observe(&mut self, km: f32) {
+        if let Some(last) = self.last_km {
+            let step = km - last;
+            if step > 0.0 && step <= MAX_ODOMETER_STEP_KM {
+                self.driven_km += step;
+            }
+        }
+        self.last_km = Some(km);
+    }
+}
+
+/// One uninterrupted stretch of a job within a single game session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobLeg {
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub ended_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Route distance left when the leg started and ended (km)
+    pub start_remaining_km: u32,
+    pub end_remaining_km: Option<u32>,
+}
+
+impl JobLeg {
+    /// Start a leg with `remaining_km` still to drive
+    pub fn open(at: chrono::DateTime<chrono::Utc>, remaining_km: u32) -> Self {
+        Self { started_at: at, ended_at: None, start_remaining_km: remaining_km, end_remaining_km: None }
+    }
+
+    /// Distance covered in this leg (km)
+    pub fn distance_km(&self) -> u32 {
+        self.end_remaining_km.map_or(0, |end| self.start_remaining_km.saturating_sub(end))
+    }
+}
+
+/// Truck as reported by the game
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TruckInfo {
+    /// Brand ID, e.g. `scania`
+    pub brand_id: String,
+    pub brand: String,
+    /// Model ID, e.g. `vehicle.scania.r_2016`
+    pub model_id: String,
+    pub model: String,
+    pub license_plate: String,
+    /// Country ID of the license plate
+    pub license_plate_country: String,
+}
+
+/// Trailer as reported by the game
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrailerInfo {
+    /// Trailer definition ID, e.g. `trailer.scs.box`
+    pub id: String,
+    /// Body type, e.g. `dryvan` or `reefer`
+    pub body_type: String,
+    pub license_plate: String,
+}
+
+/// Whether the trailer belongs to the driver's company or came with the job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrailerOwnership {
+    Owned,
+    JobProvided,
+}
+
+impl TrailerOwnership {
+    /// Derive ownership from the job market; only cargo market jobs are
+    /// hauled with the company's own trailer
+    pub fn from_market(market: &str) -> Option<Self> {
+        match market {
+            "cargo_market" | "external_market" => Some(TrailerOwnership::Owned),
+            "quick_job" | "freight_market" | "external_contracts" => Some(TrailerOwnership::JobProvided),
+            _ => None,
+        }
+    }
+}
+
+impl ActiveJob {
+    /// Damage taken during this job, excluding pre-existing damage
+    pub fn damage_delta(&self) -> Option<DamageReading> {
+        self.delivery_damage.as_ref().map(|delivery| delivery.since(&self.pickup_damage))
+    }
+
+    /// Worst damage taken at any point during this job, even if repaired before delivery
+    pub fn peak_damage_delta(&self) -> DamageReading {
+        self.max_damage.since(&self.pickup_damage)
+    }
+
+    /// Distance driven across all closed legs (km)
+    pub fn driven_km(&self) -> u32 {
+        self.legs.iter().map(JobLeg::distance_km).sum()
+    }
+
+    /// Distance on the truck's odometer, if it was read (km)
+    pub fn odometer_km(&self) -> Option<u32> {
+        Some(self.odometer.driven_km.round() as u32).filter(|km| *km > 0)
+    }
+
+    /// Average fuel consumption over the job (litres per 100 km)
+    pub fn average_consumption(&self) -> Option<f32> {
+        let km = Some(self.driven_km()).filter(|km| *km > 0).unwrap_or(self.distance_km);
+        (km > 0 && self.fuel.used_liters > 0.0).then(|| self.fuel.used_liters / km as f32 * 100.0)
+    }
+
+    /// Whether `other` is this job picked up again in a later game session
+    pub fn is_same_job(&self, other: &ActiveJob) -> bool {
+        self.cargo == other.cargo
+            && self.source_city == other.source_city
+            && self.destination_city == other.destination_city
+            && self.source_company == other.source_company
+            && self.destination_company == other.destination_company
+            && self.distance_km == other.distance_km
+    }
+
+    /// Time spent driving across all legs, excluding breaks between sessions
+    pub fn driving_time(&self) -> chrono::Duration {
+        self.legs.iter()
+            .filter_map(|leg| leg.ended_at.map(|end| end - leg.started_at))
+            .fold(chrono::Duration::zero(), |total, leg| total + leg)
+    }
+}
+
+/// Longest break between sessions after which a job can still be resumed
+const RESUME_WINDOW_DAYS: i64 = 7;
+
+/// One-off gameplay event reported by the SDK
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameplayEvent {
+    /// Game timestamp the SDK attached to the event
+    pub game_timestamp: u64,
+    #[serde(flatten)]
+    pub kind: GameplayEventKind,
+}
+
+/// Gameplay event payloads (amounts in game currency)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum GameplayEventKind {
+    Fine { offence: String, amount: i64 },
+    Toll { amount: i64 },
+    Ferry { amount: i64 },
+    Train { amount: i64 },
+    JobDelivered { revenue: i64 },
+    JobCancelled { penalty: i64 },
+}
+
+impl GameplayEventKind {
+    /// Stable name used for deduplication and logging
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameplayEventKind::Fine { .. } => "fine",
+            GameplayEventKind::Toll { .. } => "toll",
+            GameplayEventKind::Ferry { .. } => "ferry",
+            GameplayEventKind::Train { .. } => "train",
+            GameplayEventKind::JobDelivered { .. } => "job_delivered",
+            GameplayEventKind::JobCancelled { .. } => "job_cancelled",
+        }
+    }
+}
+
+/// Cargo, trailer and truck chassis damage (0.0 = pristine, 1.0 = destroyed)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DamageReading {
+    pub cargo: f32,
+    pub trailer: f32,
+    #[serde(default)]
+    pub chassis: f32,
+}
+
+impl DamageReading {
+    /// Damage added since `earlier`; repairs in between never yield negative values
+    pub fn since(&self, earlier: &DamageReading) -> DamageReading {
+        DamageReading {
+            cargo: (self.cargo - earlier.cargo).max(0.0),
+            trailer: (self.trailer - earlier.trailer).max(0.0),
+            chassis: (self.chassis - earlier.chassis).max(0.0),
+        }
+    }
+
+    /// Per-field maximum of two readings
+    pub fn max(&self, other: &DamageReading) -> DamageReading {
+        DamageReading {
+            cargo: self.cargo.max(other.cargo),
+            trailer: self.trailer.max(other.trailer),
+            chassis: self.chassis.max(other.chassis),
+        }
+    }
+}
+
+/// Truck placement in world coordinates
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Position {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    /// Heading as a fraction of a full turn (0.0–1.0)
+    pub heading: f32,
+}
+
+/// Fuel tank state
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FuelLevel {
+    pub liters: f32,
+    pub capacity: f32,
+    /// Average consumption (litres per km)
+    pub avg_consumption: f32,
+    pub range_km: f32,
+}
+
+/// Truck component wear (0.0 = new, 1.0 = destroyed)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TruckWear {
+    pub engine: f32,
+    pub transmission: f32,
+    pub cabin: f32,
+    pub chassis: f32,
+    pub wheels: f32,
+}
+
+/// Lifecycle phase of a job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobPhase {
+    Offered,
+    Accepted,
+    InTransit,
+    Delivered,
+    Cancelled,
+    Failed,
+}
+
+impl JobPhase {
+    /// Whether the job has ended in this phase
+    pub fn is_terminal(self) -> bool {
+        matches!(self, JobPhase::Delivered | JobPhase::Cancelled | JobPhase::Failed)
+    }
+
+    /// Whether moving from this phase to `next` is allowed
+    pub fn can_transition_to(self, next: JobPhase) -> bool {
+        use JobPhase::*;
+        matches!(
+            (self, next),
+            (Offered, Accepted)
+                | (Offered, Cancelled)
+                | (Accepted, InTransit)
+                | (Accepted, Cancelled)
+                | (Accepted, Failed)
+                | (InTransit, Delivered)
+                | (InTransit, Cancelled)
+                | (InTransit, Failed)
+        )
+    }
+}
+
+/// Rejected job phase transition
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid job transition: {from:?} -> {to:?}")]
+pub struct JobTransitionError {
+    pub from: Option<JobPhase>,
+    pub to: JobPhase,
+}
+
+/// Tracks a single job through its lifecycle with validated transitions
+#[derive(Debug, Default)]
+pub struct JobLifecycle {
+    phase: Option<JobPhase>,
+    job: Option<ActiveJob>,
+}
+
+impl JobLifecycle {
+    /// Current phase, if a job has been seen
+    pub fn phase(&self) -> Option<JobPhase> {
+        self.phase
+    }
+
+    /// Latest snapshot of the tracked job
+    pub fn job(&self) -> Option<&ActiveJob> {
+        self.job.as_ref()
+    }
+
+    /// Whether a job is currently in progress
+    pub fn in_progress(&self) -> bool {
+        self.phase.is_some_and(|phase| !phase.is_terminal())
+    }
+
+    /// Start tracking a new job in the `Offered` or `Accepted` phase
+    pub fn begin(&mut self, job: ActiveJob, phase: JobPhase) -> Result<TelemetryEvent, JobTransitionError> {
+        if self.in_progress() || !matches!(phase, JobPhase::Offered | JobPhase::Accepted) {
+            return Err(JobTransitionError { from: self.phase, to: phase });
+        }
+
+        let from = self.phase.take();
+        self.phase = Some(phase);
+        self.job = Some(job);
+        Ok(TelemetryEvent::JobPhaseChanged { from, to: phase })
+    }
+
+    /// Move the current job to `next`
+    pub fn transition(&mut self, next: JobPhase) -> Result<TelemetryEvent, JobTransitionError> {
+        match self.phase {
+            Some(current) if current.can_transition_to(next) => {
+                self.phase = Some(next);
+                Ok(TelemetryEvent::JobPhaseChanged { from: Some(current), to: next })
+            }
+            from => Err(JobTransitionError { from, to: next }),
+        }
+    }
+
+    /// Refresh the snapshot of the in-progress job, keeping its start time and pickup damage
+    pub fn update_job(&mut self, job: &ActiveJob) {
+        if !self.in_progress() {
+            return;
+        }
+        if let Some(current) = &mut self.job {
+            let pickup_damage = std::mem::take(&mut current.pickup_damage);
+            let max_damage = std::mem::take(&mut current.max_damage);
+            let legs = std::mem::take(&mut current.legs);
+            let fuel = std::mem::take(&mut current.fuel);
+            let odometer = std::mem::take(&mut current.odometer);
+            let integrity = std::mem::take(&mut current.integrity);
+            let snapshots = std::mem::take(&mut current.snapshots);
+            *current = ActiveJob {
+                pickup_damage,
+                max_damage,
+                legs,
+                fuel,
+                odometer,
+                integrity,
+                snapshots,
+                started_at: current.started_at,
+                ..job.clone()
+            };
+        }
+    }
+
+    /// Fold the latest damage reading into the job's peak damage
+    pub fn record_damage(&mut self, damage: &DamageReading) {
+        if !self.in_progress() {
+            return;
+        }
+        if let Some(job) = &mut self.job {
+            job.max_damage = job.max_damage.max(damage);
+        }
+    }
+
+    /// Fold the latest tank level into the job's fuel usage
+    pub fn record_fuel(&mut self, liters: f32) {
+        if !self.in_progress() {
+            return;
+        }
+        if let Some(job) = &mut self.job {
+            job.fuel.observe(liters);
+        }
+    }
+
+    /// Fold the latest odometer reading into the job's driven distance
+    pub fn record_odometer(&mut self, km: f32) {
+        if !self.in_progress() {
+            return;
+        }
+        if let Some(job) = &mut self.job {
+            job.odometer.observe(km);
+        }
+    }
+
+    /// Check the latest poll and the job's terms for signs of tampering
+    pub fn record_integrity(&mut self, sample: Sample, now: chrono::DateTime<chrono::Utc>) {
+        if !self.in_progress() {
+            return;
+        }
+        if let Some(job) = &mut self.job {
+            job.integrity.observe_terms(job.revenue, job.distance_km, now);
+            job.integrity.observe(sample, now);
+        }
+    }
+
+    /// Take a route snapshot when one is due
+    pub fn record_snapshot(&mut self, state: &TelemetryState, now: chrono::DateTime<chrono::Utc>) {
+        if !self.in_progress() {
+            return;
+        }
+        if let Some(job) = &mut self.job {
+            job.snapshots.observe(state, now);
+        }
+    }
+
+    /// Reinstate a job recovered from a checkpoint; finished jobs are ignored,
+    /// interrupted ones are kept so they can be resumed
+    pub fn restore(&mut self, job: ActiveJob, phase: JobPhase) {
+        if phase.is_terminal() && phase != JobPhase::Failed {
+            return;
+        }
+        self.phase = Some(phase);
+        self.job = Some(job);
+    }
+
+    /// Stop tracking the current job without recording an outcome
+    pub fn abandon(&mut self) -> Option<ActiveJob> {
+        self.phase = None;
+        self.job.take()
+    }
+
+    /// The job interrupted by the game closing, if it can still be resumed
+    pub fn interrupted(&self, now: chrono::DateTime<chrono::Utc>) -> Option<&ActiveJob> {
+        if self.phase != Some(JobPhase::Failed) {
+            return None;
+        }
+        self.job.as_ref().filter(|job| {
+            let last_seen = job.legs.last().and_then(|leg| leg.ended_at).unwrap_or(job.started_at);
+            now - last_seen <= chrono::Duration::days(RESUME_WINDOW_DAYS)
+        })
+    }
+
+    /// Continue the interrupted job as a new leg, keeping its start time and damage
+    pub fn resume(&mut self, job: &ActiveJob, now: chrono::DateTime<chrono::Utc>) -> Result<TelemetryEvent, JobTransitionError> {
+        let Some(current) = self.interrupted(now).filter(|current| current.is_same_job(job)) else {
+            return Err(JobTransitionError { from: self.phase, to: JobPhase::Accepted });
+        };
+
+        let mut legs = current.legs.clone();
+        legs.push(JobLeg::open(now, job.distance_remaining));
+        let resumed = ActiveJob {
+            started_at: current.started_at,
+            pickup_damage: current.pickup_damage.clone(),
+            max_damage: current.max_damage.max(&job.max_damage),
+            legs,
+            // The tank may have been topped up between sessions
+            fuel: FuelUsage { last_liters: job.fuel.last_liters, ..current.fuel.clone() },
+            odometer: OdometerUsage { last_km: job.odometer.last_km, ..current.odometer.clone() },
+            // Render time restarts with the game
+            integrity: IntegrityMonitor { last: None, ..current.integrity.clone() },
+            snapshots: current.snapshots.clone(),
+            ..job.clone()
+        };
+        self.job = Some(resumed);
+        self.phase = Some(JobPhase::Accepted);
+        Ok(TelemetryEvent::JobPhaseChanged { from: Some(JobPhase::Failed), to: JobPhase::Accepted })
+    }
+
+    /// End the current leg at the job's latest remaining distance
+    pub fn close_leg(&mut self, now: chrono::DateTime<chrono::Utc>) {
+        if let Some(job) = &mut self.job {
+            let remaining = job.distance_remaining;
+            if let Some(leg) = job.legs.last_mut().filter(|leg| leg.ended_at.is_none()) {
+                leg.ended_at = Some(now);
+                leg.end_remaining_km = Some(remaining);
+            }
+        }
+    }
+
+    /// Start a new leg if the job is in progress and its last leg was closed
+    pub fn open_leg(&mut self, now: chrono::DateTime<chrono::Utc>) {
+        if !self.in_progress() {
+            return;
+        }
+        if let Some(job) = &mut self.job {
+            if job.legs.last().map_or(true, |leg| leg.ended_at.is_some()) {
+                job.legs.push(JobLeg::open(now, job.distance_remaining));
+            }
+        }
+    }
+
+    /// Record damage at the moment of delivery
+    pub fn record_delivery_damage(&mut self, damage: DamageReading) {
+        if let Some(job) = &mut self.job {
+            job.max_damage = job.max_damage.max(&damage);
+            job.delivery_damage = Some(damage);
+        }
+    }
+
+    /// Replace the planned income with what the game actually paid out
+    pub fn record_revenue(&mut self, revenue: i64) {
+        if let (Some(job), Ok(revenue)) = (&mut self.job, u64::try_from(revenue)) {
+            if revenue > 0 {
+                job.revenue = revenue;
+            }
+        }
+    }
+}
+
+/// Speed above which the truck counts as moving (km/h)
+const MOVING_SPEED: f32 = 1.0;
+/// Remaining distance at which a vanished job counts as delivered (km)
+const DELIVERY_TOLERANCE_KM: u32 = 1;
+/// How often to look for a running game while the simulator stands in
+#[cfg(any(windows, target_os = "linux"))]
+const GAME_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+pub struct TelemetryReader {
+    state: TelemetryState,
+    /// The plugin's shared memory, while mapped
+    #[cfg(any(windows, target_os = "linux"))]
+    map: Option<SharedMemory>,
+    /// When to retry opening the shared memory while disconnected
+    #[cfg(any(windows, target_os = "linux"))]
+    reconnect: ReconnectSchedule,
+    /// Detects a map left behind by a game that crashed or was killed
+    #[cfg(any(windows, target_os = "linux"))]
+    liveness: Liveness,
+    job: JobLifecycle,
+    dedup: EventDeduplicator,
+    /// Special event flags from the previous frame, for edge detection
+    flags: SpecialFlags,
+    /// Latest frame for the integrity checks, until the job tracker takes it
+    sample: Option<Sample>,
+    /// Delivery/cancellation reported by the SDK for the current job
+    job_outcome: Option<GameplayEventKind>,
+    /// Revision of the connected telemetry plugin
+    plugin_revision: Option<u32>,
+    /// Why the last shared memory read failed, until one succeeds
+    read_error: Option<ScsError>,
+    /// Fake telemetry used while no game is running
+    simulator: Option<Simulator>,
+    /// Whether the current connection is the simulator's
+    simulated: bool,
+    /// Job that was already running when tracking resumed, ignored until it ends
+    untracked_job: Option<ActiveJob>,
+    /// When to next look for a running game, and whether one was found
+    #[cfg(any(windows, target_os = "linux"))]
+    game_check: (std::time::Instant, bool),
+}
+
+impl TelemetryReader {
+    pub fn new() -> Self {
+        Self {
+            state: TelemetryState::default(),
+            #[cfg(any(windows, target_os = "linux"))]
+            map: None,
+            #[cfg(any(windows, target_os = "linux"))]
+            reconnect: ReconnectSchedule::new(),
+            #[cfg(any(windows, target_os = "linux"))]
+            liveness: Liveness::new(),
+            job: JobLifecycle::default(),
+            dedup: EventDeduplicator::new(),
+            flags: SpecialFlags::default(),
+            sample: None,
+            job_outcome: None,
+            plugin_revision: None,
+            read_error: None,
+            simulator: None,
+            simulated: false,
+            untracked_job: None,
+            #[cfg(any(windows, target_os = "linux"))]
+            game_check: (std::time::Instant::now(), false),
+        }
+    }
+
+    pub fn get_state(&self) -> &TelemetryState {
+        &self.state
+    }
+
+    /// What the telemetry loop is currently watching
+    pub fn poll_activity(&self) -> PollActivity {
+        match (self.state.connected, self.state.paused) {
+            (false, _) if self.reconnecting_fast() => PollActivity::Connecting,
+            (false, _) => PollActivity::Disconnected,
+            (true, true) => PollActivity::Paused,
+            (true, false) => PollActivity::Active,
+        }
+    }
+
+    fn reconnecting_fast(&self) -> bool {
+        #[cfg(any(windows, target_os = "linux"))]
+        {
+            self.reconnect.is_fast(std::time::Instant::now())
+        }
+
+        #[cfg(not(any(windows, target_os = "linux")))]
+        {
+            false
+        }
+    }
+
+    /// Why the shared memory can't be read, e.g. an unsupported plugin
+    pub fn read_error(&self) -> Option<&ScsError> {
+        self.read_error.as_ref()
+    }
+
+    /// Revision of the telemetry plugin, once the game has connected
+    pub fn plugin_revision(&self) -> Option<u32> {
+        self.plugin_revision
+    }
+
+    /// The machine is going to sleep: end the job's current leg so the time
+    /// asleep doesn't count as driving
+    pub fn suspend(&mut self, now: chrono::DateTime<chrono::Utc>) {
+        self.job.close_leg(now);
+    }
+
+    /// The machine woke up: reopen the shared memory, whose handle may be
+    /// stale, and continue the job in a new leg
+    pub fn resume(&mut self, now: chrono::DateTime<chrono::Utc>) {
+        #[cfg(any(windows, target_os = "linux"))]
+        {
+            self.map = None;
+            self.liveness = Liveness::new();
+            self.reconnect = ReconnectSchedule::new();
+            // Still connected as far as the frontend knows; if the game is
+            // gone, the next update reports the disconnect as usual
+            if self.state.connected && !self.connect() {
+                debug!("Telemetry map not available after resume");
+            }
+        }
+        self.job.open_leg(now);
+    }
+
+    /// Stop or restart job detection while staying connected. A job picked
+    /// up during the pause is left untracked until it ends; a tracked job
+    /// continues in a new leg
+    pub fn set_tracking_paused(&mut self, paused: bool, now: chrono::DateTime<chrono::Utc>) {
+        if self.state.tracking_paused == paused {
+            return;
+        }
+        self.state.tracking_paused = paused;
+        if paused {
+            self.job.close_leg(now);
+            return;
+        }
+        self.job.open_leg(now);
+        if !self.job.in_progress() {
+            self.untracked_job = self.state.active_job.clone();
+        }
+    }
+
+    pub fn connect(&mut self) -> bool {
+        #[cfg(any(windows, target_os = "linux"))]
+        {
+            if self.map.is_some() {
+                return true;
+            }
+            self.map = SharedMemory::open(scs::map_name());
+            self.state.connected = self.map.is_some();
+            if self.state.connected {
+                info!("Connected to SCS Telemetry Shared Memory");
+            }
+            self.state.connected
+        }
+        #[cfg(not(any(windows, target_os = "linux")))]
+        {
+            false
+        }
+    }
+
+    /// Turn the telemetry simulator on or off; a running game takes
+    /// precedence over it
+    pub fn set_simulator(&mut self, simulator: Option<Simulator>) {
+        self.simulator = simulator;
+    }
+
+    /// Whether a real game should be read instead of the simulator
+    fn game_running(&mut self) -> bool {
+        #[cfg(any(windows, target_os = "linux"))]
+        {
+            if self.state.connected && !self.simulated {
+                return true;
+            }
+            let now = std::time::Instant::now();
+            if now >= self.game_check.0 {
+                self.game_check = (now + GAME_CHECK_INTERVAL, Game::detect_running().is_some());
+            }
+            self.game_check.1
+        }
+
+        #[cfg(not(any(windows, target_os = "linux")))]
+        {
+            false
+        }
+    }
+
+    pub fn update(&mut self) -> Vec<TelemetryEvent> {
+        if self.simulator.is_some() && !self.game_running() {
+            let frame = self.simulator.as_mut().map(|simulator| simulator.frame(std::time::Instant::now()));
+            self.simulated = true;
+            let events = self.feed(frame.as_ref());
+            self.state.simulated = self.state.connected;
+            return events;
+        }
+        if self.simulated {
+            // The simulator was turned off or a game started: end its session
+            self.simulated = false;
+            self.state.simulated = false;
+            return self.feed(None);
+        }
+
+        let mut events = Vec::new();
+
+        #[cfg(any(windows, target_os = "linux"))]
+        {
+            let newly_connected = !self.state.connected;
+            if newly_connected {
+                let due = self.reconnect.attempt_due(std::time::Instant::now(), || Game::detect_running().is_some());
+                if !due || !self.connect() {
+                    return events;
+                }
+            }
+
+            let frame = match self.map.as_ref().map(|map| scs::parse(map.bytes())) {
+                None => None,
+                Some(Ok(frame)) => {
+                    self.read_error = None;
+                    Some(frame)
+                }
+                Some(Err(e)) => {
+                    if newly_connected {
+                        warn!("Cannot read telemetry: {}", e);
+                    }
+                    self.read_error = Some(e);
+                    return events;
+                }
+            };
+
+            // The plugin clears sdkActive when the game shuts down; a crash
+            // leaves it set, but the render timestamp stops moving. Unmapping
+            // releases the old map so a restarted game gets a fresh one
+            let now = std::time::Instant::now();
+            let live = |frame: &ScsFrame| frame.sdk_active && self.liveness.observe(frame.render_time, now);
+            let Some(frame) = frame.filter(live) else {
+                self.map = None;
+                self.state.connected = false;
+                self.state.multiplayer = false;
+                self.state.server = None;
+                if !newly_connected {
+                    events.extend(self.fail_active_job());
+                    events.push(TelemetryEvent::Disconnected);
+                }
+                return events;
+            };
+
+            if newly_connected {
+                // Older plugin builds report no game ID; check which game is
+                // running, and fall back to ETS2 as by far the common case
+                let game = frame.game
+                    .or_else(Game::detect_running)
+                    .unwrap_or(Game::Ets2);
+                info!("Telemetry plugin revision {} ({})", frame.revision, game);
+                if !frame.unknown.is_empty() {
+                    warn!("Telemetry plugin revision {} doesn't publish {:?}", frame.revision, frame.unknown);
+                }
+                self.plugin_revision = Some(frame.revision);
+                self.state.game = Some(game);
+                self.state.server = crate::multiplayer::detect(game);
+                self.state.multiplayer = self.state.server.is_some();
+                if let Some(server) = &self.state.server {
+                    info!("Playing on {}", server);
+                }
+                self.reconnect.connected();
+                events.push(TelemetryEvent::Connected(game));
+            }
+            events.extend(self.apply_frame(&frame));
+        }
+
+        if self.state.connected {
+            events.extend(self.track_job());
+        }
+        self.dedupe(&mut events);
+        events
+    }
+
+    /// Stand-in for `update` that takes the frame instead of reading the
+    /// shared memory; `None` is a game that went away
+    pub(crate) fn feed(&mut self, frame: Option<&ScsFrame>) -> Vec<TelemetryEvent> {
+        let mut events = Vec::new();
+        match frame.filter(|frame| frame.sdk_active) {
+            Some(frame) => {
+                if !self.state.connected {
+                    let game = frame.game.unwrap_or(Game::Ets2);
+                    self.state.connected = true;
+                    self.state.game = Some(game);
+                    events.push(TelemetryEvent::Connected(game));
+                }
+                events.extend(self.apply_frame(frame));
+                events.extend(self.track_job());
+            }
+            None if self.state.connected => {
+                self.state.connected = false;
+                events.extend(self.fail_active_job());
+                events.push(TelemetryEvent::Disconnected);
+            }
+            None => {}
+        }
+        self.dedupe(&mut events);
+        events
+    }
+
+    /// Copy a decoded frame into the state and emit gameplay events on flag edges
+    fn apply_frame(&mut self, frame: &ScsFrame) -> Vec<TelemetryEvent> {
+        let state = &mut self.state;
+        state.game = frame.game.or(state.game);
+        state.paused = frame.paused;
+        state.speed = frame.speed_kmh;
+        state.speed_limit = frame.speed_limit_kmh;
+        state.engine_rpm = frame.engine_rpm;
+        state.fuel = Some(frame.fuel.clone());
+        state.truck_wear = frame.wear.clone();
+        state.unknown = frame.unknown.clone();
+        // Only the trailer's identity is read from the trailer zone, not its damage
+        state.damage.cargo = frame.cargo_damage;
+        state.damage.chassis = frame.wear.as_ref().map_or(0.0, |wear| wear.chassis);
+        state.position = Some(frame.position.clone());
+        state.odometer_km = Some(frame.odometer_km).filter(|km| *km > 0.0);
+        state.game_time = Some(frame.game_time);
+        state.rest_stop_minutes = Some(frame.rest_stop_minutes);
+        state.active_job = frame.job.as_ref().map(|job| ActiveJob {
+            cargo: job.cargo.clone(),
+            source_city: job.source_city.clone(),
+            destination_city: job.destination_city.clone(),
+            source_company: Some(job.source_company.clone()).filter(|c| !c.is_empty()),
+            destination_company: Some(job.destination_company.clone()).filter(|c| !c.is_empty()),
+            distance_km: job.planned_distance_km,
+            // Without navigation nothing is known to have been driven
+            distance_remaining: if frame.unknown.contains(&TelemetryField::Navigation) {
+                job.planned_distance_km
+            } else {
+                job.distance_remaining_km
+            },
+            revenue: job.income,
+            // Kept from the first sighting by JobLifecycle::update_job
+            started_at: chrono::Utc::now(),
+            pickup_damage: DamageReading::default(),
+            delivery_damage: None,
+            max_damage: DamageReading::default(),
+            market: Some(job.market.clone()).filter(|market| !market.is_empty()),
+            trailer_ownership: TrailerOwnership::from_market(&job.market),
+            truck: frame.truck.clone(),
+            trailer: frame.trailer.clone(),
+            legs: Vec::new(),
+            fuel: FuelUsage::default(),
+            odometer: OdometerUsage::default(),
+            integrity: IntegrityMonitor::default(),
+            snapshots: SnapshotSeries::default(),
+        });
+        self.sample = Some(Sample {
+            render_time: frame.render_time,
+            game_time: frame.game_time,
+            paused: frame.paused,
+            speed_kmh: frame.speed_kmh,
+            position: Some(frame.position.clone()),
+            transported: frame.flags.ferry || frame.flags.train,
+            simulated: self.simulated,
+        });
+
+        let (now, before, amounts) = (frame.flags, self.flags, &frame.amounts);
+        let mut kinds = Vec::new();
+        if now.fined && !before.fined {
+            kinds.push(GameplayEventKind::Fine { offence: amounts.fine_offence.clone(), amount: amounts.fine });
+        }
+        if now.tollgate && !before.tollgate {
+            kinds.push(GameplayEventKind::Toll { amount: amounts.toll });
+        }
+        if now.ferry && !before.ferry {
+            kinds.push(GameplayEventKind::Ferry { amount: amounts.ferry });
+        }
+        if now.train && !before.train {
+            kinds.push(GameplayEventKind::Train { amount: amounts.train });
+        }
+        if now.job_delivered && !before.job_delivered {
+            kinds.push(GameplayEventKind::JobDelivered { revenue: amounts.delivered_revenue });
+        }
+        if now.job_cancelled && !before.job_cancelled {
+            kinds.push(GameplayEventKind::JobCancelled { penalty: amounts.cancel_penalty });
+        }
+        self.flags = now;
+        if let Some(outcome) = kinds.iter().rev().find(|kind| {
+            matches!(kind, GameplayEventKind::JobDelivered { .. } | GameplayEventKind::JobCancelled { .. })
+        }) {
+            self.job_outcome = Some(outcome.clone());
+        }
+
+        kinds.into_iter()
+            .map(|kind| TelemetryEvent::Gameplay(GameplayEvent {
+                game_timestamp: u64::from(frame.game_time),
+                kind,
+            }))
+            .collect()
+    }
+
+    /// Drop gameplay events the SDK repeated across frames
+    fn dedupe(&mut self, events: &mut Vec<TelemetryEvent>) {
+        events.retain(|event| match event.dedup_key() {
+            Some(key) => {
+                let fresh = self.dedup.admit(key);
+                if !fresh {
+                    debug!(target: crate::logging::FRAME_TARGET, "Dropping repeated event: {:?}", event);
+                }
+                fresh
+            }
+            None => true,
+        });
+    }
+
+    /// Snapshot of the in-progress job for checkpointing
+    pub fn job_snapshot(&self) -> Option<(ActiveJob, JobPhase)> {
+        if !self.job.in_progress() && self.job.interrupted(chrono::Utc::now()).is_none() {
+            return None;
+        }
+        Some((self.job.job()?.clone(), self.job.phase()?))
+    }
+
+    /// Resume tracking a job recovered from a checkpoint
+    pub fn restore_job(&mut self, job: ActiveJob, phase: JobPhase) {
+        self.job.restore(job, phase);
+        self.state.job_phase = self.job.phase();
+    }
+
+    /// Drop a recovered job the driver chose not to resume
+    pub fn abandon_job(&mut self) -> Option<ActiveJob> {
+        let job = self.job.abandon();
+        self.state.job_phase = None;
+        job
+    }
+
+    /// Advance the job lifecycle from the latest telemetry state
+    fn track_job(&mut self) -> Vec<TelemetryEvent> {
+        let mut events = Vec::new();
+        if self.state.tracking_paused {
+            return events;
+        }
+        if let Some(untracked) = &self.untracked_job {
+            if self.state.active_job.as_ref().is_some_and(|job| job.is_same_job(untracked)) {
+                return events;
+            }
+            self.untracked_job = None;
+            self.job_outcome = None;
+        }
+        let current = self.job.phase().filter(|phase| !phase.is_terminal());
+
+        match (self.state.active_job.clone(), current) {
+            (Some(job), None) => {
+                let now = chrono::Utc::now();
+                self.job_outcome = None;
+                let resumable = self.job.interrupted(now).is_some_and(|interrupted| interrupted.is_same_job(&job));
+                let fuel = FuelUsage { used_liters: 0.0, last_liters: self.state.fuel.as_ref().map(|fuel| fuel.liters) };
+                let odometer = OdometerUsage { driven_km: 0.0, last_km: self.state.odometer_km };
+                if resumable {
+                    let job = ActiveJob { max_damage: self.state.damage.clone(), fuel, odometer, ..job };
+                    if let Some(event) = self.apply(|lifecycle| lifecycle.resume(&job, now)) {
+                        events.push(event);
+                        events.extend(self.job.job().cloned().map(TelemetryEvent::JobResumed));
+                    }
+                } else {
+                    let job = ActiveJob {
+                        pickup_damage: self.state.damage.clone(),
+                        max_damage: self.state.damage.clone(),
+                        legs: vec![JobLeg::open(now, job.distance_remaining)],
+                        fuel,
+                        odometer,
+                        ..job
+                    };
+                    if let Some(event) = self.apply(|lifecycle| lifecycle.begin(job.clone(), JobPhase::Accepted)) {
+                        events.push(event);
+                        events.push(TelemetryEvent::JobStarted(job));
+                    }
+                }
+            }
+            (Some(job), Some(phase)) => {
+                self.job.update_job(&job);
+                self.job.record_damage(&self.state.damage);
+                if let Some(fuel) = &self.state.fuel {
+                    self.job.record_fuel(fuel.liters);
+                }
+                if let Some(km) = self.state.odometer_km {
+                    self.job.record_odometer(km);
+                }
+                if let Some(sample) = self.sample.take() {
+                    self.job.record_integrity(sample, chrono::Utc::now());
+                }
+                self.job.record_snapshot(&self.state, chrono::Utc::now());
+                if phase == JobPhase::Accepted && self.state.speed > MOVING_SPEED {
+                    events.extend(self.apply(|lifecycle| lifecycle.transition(JobPhase::InTransit)));
+                }
+            }
+            (None, Some(phase)) => {
+                // Trust the SDK's delivery/cancel signal; without one, only a job that
+                // was actually driven to its destination counts as delivered
+                let outcome = self.job_outcome.take();
+                let delivered = match &outcome {
+                    Some(GameplayEventKind::JobDelivered { .. }) => true,
+                    Some(_) => false,
+                    None => {
+                        phase == JobPhase::InTransit
+                            && self.state.is_known(TelemetryField::Navigation)
+                            && self.job.job().is_some_and(|job| job.distance_remaining <= DELIVERY_TOLERANCE_KM)
+                    }
+                };
+
+                if delivered {
+                    if phase == JobPhase::Accepted {
+                        events.extend(self.apply(|lifecycle| lifecycle.transition(JobPhase::InTransit)));
+                    }
+                    if let Some(event) = self.apply(|lifecycle| lifecycle.transition(JobPhase::Delivered)) {
+                        events.push(event);
+                        self.job.close_leg(chrono::Utc::now());
+                        self.job.record_delivery_damage(self.state.damage.clone());
+                        if let Some(GameplayEventKind::JobDelivered { revenue }) = outcome {
+                            self.job.record_revenue(revenue);
+                        }
+                        if let Some(job) = self.job.job() {
+                            events.push(TelemetryEvent::JobCompleted(job.clone()));
+                        }
+                    }
+                } else if let Some(event) = self.apply(|lifecycle| lifecycle.transition(JobPhase::Cancelled)) {
+                    events.push(event);
+                    self.job.close_leg(chrono::Utc::now());
+                    let penalty = match outcome {
+                        Some(GameplayEventKind::JobCancelled { penalty }) => Some(penalty),
+                        _ => None,
+                    };
+                    if let Some(job) = self.job.job() {
+                        events.push(TelemetryEvent::JobCancelled { job: job.clone(), penalty });
+                    }
+                }
+            }
+            (None, None) => {}
+        }
+
+        self.state.job_phase = self.job.phase();
+        events
+    }
+
+    /// Mark an in-progress job as failed (e.g. the game went away mid-delivery)
+    fn fail_active_job(&mut self) -> Option<TelemetryEvent> {
+        if !self.job.in_progress() {
+            return None;
+        }
+        let event = self.apply(|lifecycle| lifecycle.transition(JobPhase::Failed));
+        // Kept so the job can be resumed when the save is loaded again
+        self.job.close_leg(chrono::Utc::now());
+        self.state.job_phase = self.job.phase();
+        event
+    }
+
+    fn apply(
+        &mut self,
+        step: impl FnOnce(&mut JobLifecycle) -> Result<TelemetryEvent, JobTransitionError>,
+    ) -> Option<TelemetryEvent> {
+        match step(&mut self.job) {
+            Ok(event) => {
+                debug!("Job lifecycle: {:?}", event);
+                Some(event)
+            }
+            Err(e) => {
+                warn!("{}", e);
+                None
+            }
+        }
+    }
+}
+
+impl Default for TelemetryReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
+pub enum TelemetryEvent {
+    Connected(Game),
+    Disconnected,
+    /// A new job was accepted (start time and pickup damage filled in)
+    JobStarted(ActiveJob),
+    /// A job interrupted by the game closing was picked up again
+    JobResumed(ActiveJob),
+    JobPhaseChanged {
+        from: Option<JobPhase>,
+        to: JobPhase,
+    },
+    JobCompleted(ActiveJob),
+    /// The job ended without delivery; `penalty` is set when the SDK reported one
+    JobCancelled {
+        job: ActiveJob,
+        penalty: Option<i64>,
+    },
+    Gameplay(GameplayEvent),
+}
+
+impl TelemetryEvent {
+    /// Whether the event changes the tracked job enough to checkpoint at once
+    pub fn changes_job(&self) -> bool {
+        !matches!(self, TelemetryEvent::Connected(_) | TelemetryEvent::Gameplay(_))
+    }
+
+    /// Key identifying repeats of the same gameplay event
+    pub fn dedup_key(&self) -> Option<EventKey> {
+        match self {
+            TelemetryEvent::Gameplay(event) => Some(EventKey {
+                kind: event.kind.name(),
+                game_timestamp: event.game_timestamp,
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(distance_remaining: u32) -> ActiveJob {
+        ActiveJob {
+            cargo: "Machinery".into(),
+            source_city: "Berlin".into(),
+            destination_city: "Hamburg".into(),
+            source_company: None,
+            destination_company: None,
+            distance_km: 290,
+            distance_remaining,
+            revenue: 12_000,
+            started_at: chrono::Utc::now(),
+            pickup_damage: DamageReading::default(),
+            delivery_damage: None,
+            max_damage: DamageReading::default(),
+            market: None,
+            trailer_ownership: None,
+            truck: None,
+            trailer: None,
+            legs: Vec::new(),
+            fuel: FuelUsage::default(),
+            odometer: OdometerUsage::default(),
+            integrity: IntegrityMonitor::default(),
+            snapshots: SnapshotSeries::default(),
+        }
+    }
+
+    #[test]
+    fn lifecycle_rejects_invalid_transitions() {
+        let mut lifecycle = JobLifecycle::default();
+        assert!(lifecycle.transition(JobPhase::Delivered).is_err());
+
+        lifecycle.begin(job(290), JobPhase::Accepted).unwrap();
+        assert!(lifecycle.transition(JobPhase::Delivered).is_err());
+        assert!(lifecycle.begin(job(290), JobPhase::Accepted).is_err());
+
+        lifecycle.transition(JobPhase::InTransit).unwrap();
+        lifecycle.transition(JobPhase::Delivered).unwrap();
+        assert!(!lifecycle.in_progress());
+        assert!(lifecycle.transition(JobPhase::Cancelled).is_err());
+    }
+
+    #[test]
+    fn interrupted_job_resumes_as_second_leg() {
+        let mut reader = TelemetryReader::new();
+        reader.state.connected = true;
+        reader.state.active_job = Some(job(290));
+        reader.track_job();
+        let started_at = reader.job.job().unwrap().started_at;
+
+        // Saved 100 km in, game closed
+        reader.state.active_job = Some(job(190));
+        reader.track_job();
+        assert!(reader.fail_active_job().is_some());
+
+        // Save loaded again later
+        reader.state.active_job = Some(job(190));
+        let events = reader.track_job();
+        assert!(events.iter().any(|event| matches!(event, TelemetryEvent::JobResumed(_))));
+        assert_eq!(reader.state.job_phase, Some(JobPhase::Accepted));
+
+        let resumed = reader.job.job().unwrap();
+        assert_eq!(resumed.started_at, started_at);
+        assert_eq!(resumed.legs.len(), 2);
+        assert_eq!(resumed.legs[0].distance_km(), 100);
+    }
+
+    #[test]
+    fn job_taken_while_paused_stays_untracked_after_resuming() {
+        let now = chrono::Utc::now();
+        let mut reader = TelemetryReader::new();
+        reader.state.connected = true;
+        reader.set_tracking_paused(true, now);
+        reader.state.active_job = Some(job(290));
+        assert!(reader.track_job().is_empty());
+
+        reader.set_tracking_paused(false, now);
+        reader.state.active_job = Some(job(200));
+        assert!(reader.track_job().is_empty());
+        assert_eq!(reader.state.job_phase, None);
+
+        // The personal job was delivered; the next one is tracked again
+        reader.state.active_job = None;
+        reader.track_job();
+        reader.state.active_job = Some(ActiveJob { cargo: "Apples".into(), ..job(400) });
+        let events = reader.track_job();
+        assert!(events.iter().any(|event| matches!(event, TelemetryEvent::JobStarted(_))));
+    }
+
+    #[test]
+    fn fuel_usage_ignores_refuelling() {
+        let mut fuel = FuelUsage::default();
+        for liters in [400.0, 390.0, 385.0, 600.0, 590.0] {
+            fuel.observe(liters);
+        }
+        assert_eq!(fuel.used_liters, 25.0);
+
+        let mut delivered = ActiveJob { fuel, ..job(0) };
+        assert_eq!(delivered.average_consumption(), Some(25.0 / 290.0 * 100.0));
+        delivered.legs = vec![JobLeg { end_remaining_km: Some(190), ..JobLeg::open(chrono::Utc::now(), 290) }];
+        assert_eq!(delivered.average_consumption(), Some(25.0));
+    }
+
+    #[test]
+    fn job_vanishing_at_destination_is_delivered() {
+        let mut reader = TelemetryReader::new();
+        reader.state.active_job = Some(job(290));
+        reader.track_job();
+
+        reader.state.speed = 80.0;
+        reader.track_job();
+        assert_eq!(reader.state.job_phase, Some(JobPhase::InTransit));
+
+        reader.state.active_job = Some(job(0));
+        reader.track_job();
+        reader.state.active_job = None;
+        let events = reader.track_job();
+
+        assert!(events.iter().any(|e| matches!(e, TelemetryEvent::JobCompleted(_))));
+        assert_eq!(reader.state.job_phase, Some(JobPhase::Delivered));
+    }
+
+    #[test]
+    fn delivery_reports_damage_taken_since_pickup() {
+        let mut reader = TelemetryReader::new();
+        reader.state.damage = DamageReading { cargo: 0.0, trailer: 0.25, chassis: 0.10 };
+        reader.state.active_job = Some(job(290));
+        reader.state.speed = 80.0;
+        reader.track_job();
+        reader.track_job();
+
+        reader.state.damage = DamageReading { cargo: 0.02, trailer: 0.30, chassis: 0.30 };
+        reader.track_job();
+
+        // The truck was repaired on the way
+        reader.state.damage = DamageReading { cargo: 0.04, trailer: 0.30, chassis: 0.0 };
+        reader.state.active_job = Some(job(0));
+        reader.track_job();
+        reader.state.active_job = None;
+        let events = reader.track_job();
+
+        let job = events.iter()
+            .find_map(|e| match e {
+                TelemetryEvent::JobCompleted(job) => Some(job),
+                _ => None,
+            })
+            .unwrap();
+        let delta = job.damage_delta().unwrap();
+        assert!((delta.cargo - 0.04).abs() < 1e-6);
+        assert!((delta.trailer - 0.05).abs() < 1e-6);
+        assert_eq!(delta.chassis, 0.0);
+        assert!((job.peak_damage_delta().chassis - 0.20).abs() < 1e-6);
+    }
+
+    #[test]
+    fn job_vanishing_mid_route_is_cancelled() {
+        let mut reader = TelemetryReader::new();
+        reader.state.active_job = Some(job(290));
+        reader.state.speed = 80.0;
+        reader.track_job();
+        reader.track_job();
+
+        reader.state.active_job = None;
+        let events = reader.track_job();
+
+        assert!(!events.iter().any(|e| matches!(e, TelemetryEvent::JobCompleted(_))));
+        assert!(events.iter().any(|e| matches!(e, TelemetryEvent::JobCancelled { penalty: None, .. })));
+        assert_eq!(reader.state.job_phase, Some(JobPhase::Cancelled));
+    }
+
+    #[test]
+    fn sdk_delivery_signal_wins_over_distance() {
+        let mut reader = TelemetryReader::new();
+        reader.state.active_job = Some(job(290));
+        reader.state.speed = 80.0;
+        reader.track_job();
+        reader.track_job();
+
+        // The SDK reports the delivery before the route distance reached zero
+        reader.job_outcome = Some(GameplayEventKind::JobDelivered { revenue: 9_800 });
+        reader.state.active_job = None;
+        let events = reader.track_job();
+
+        let job = events.iter()
+            .find_map(|e| match e {
+                TelemetryEvent::JobCompleted(job) => Some(job),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(job.revenue, 9_800);
+    }
+
+    #[test]
+    fn restored_job_resumes_without_restarting() {
+        let mut reader = TelemetryReader::new();
+        reader.restore_job(job(120), JobPhase::InTransit);
+        assert!(reader.job_snapshot().is_some());
+
+        reader.state.active_job = Some(job(0));
+        let events = reader.track_job();
+        assert!(!events.iter().any(|e| matches!(e, TelemetryEvent::JobStarted(_))));
+
+        reader.state.active_job = None;
+        let events = reader.track_job();
+        assert!(events.iter().any(|e| matches!(e, TelemetryEvent::JobCompleted(_))));
+    }
+
+    #[test]
+    fn game_from_process_name() {
+        assert_eq!(Game::from_process_name("amtrucks.exe"), Some(Game::Ats));
+        assert_eq!(Game::from_process_name("eurotrucks2.EXE"), Some(Game::Ets2));
+        assert_eq!(Game::from_process_name("explorer.exe"), None);
+    }
+
+    #[test]
+    fn repeated_gameplay_events_are_dropped() {
+        let fine = || TelemetryEvent::Gameplay(GameplayEvent {
+            game_timestamp: 4_200,
+            kind: GameplayEventKind::Fine { offence: "speeding".into(), amount: 350 },
+        });
+        let mut reader = TelemetryReader::new();
+
+        let mut events = vec![fine(), fine(), TelemetryEvent::Disconnected];
+        reader.dedupe(&mut events);
+        assert_eq!(events.len(), 2);
+
+        let mut events = vec![fine()];
+        reader.dedupe(&mut events);
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn frame_populates_state_and_fires_events_once() {
+        let frame = crate::scs::parse(&crate::scs::tests::sample_map()).unwrap();
+        let mut reader = TelemetryReader::new();
+
+        let events = reader.apply_frame(&frame);
+        assert!(matches!(events.as_slice(), [TelemetryEvent::Gameplay(_)]));
+        assert_eq!(reader.state.speed_limit.map(f32::round), Some(80.0));
+        assert_eq!(reader.state.active_job.as_ref().unwrap().source_company.as_deref(), Some("Posped"));
+        assert_eq!(reader.state.active_job.as_ref().unwrap().trailer_ownership, Some(TrailerOwnership::Owned));
+
+        // The plugin holds the flag for several frames
+        assert!(reader.apply_frame(&frame).is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+//! Shared Memory Module
+//!
+//! Maps the telemetry plugin's shared memory: a named file mapping on
+//! Windows, and on Linux the file the plugin leaves in `/dev/shm` when the
+//! game runs under Proton/Wine.
+
+#[cfg(target_os = "linux")]
+use std::path::{Path, PathBuf};
+
+use crate::scs;
+
+/// Environment variable pointing at the map file, for Wine setups that put it elsewhere
+#[cfg(target_os = "linux")]
+pub const PATH_VAR: &str = "VTC_TELEMETRY_SHM";
+/// Where shared memory files live on Linux
+#[cfg(target_os = "linux")]
+const SHM_DIR: &str = "/dev/shm";
+
+/// Read-only view of the plugin's map, unmapped on drop
+pub struct SharedMemory {
+    #[cfg(windows)]
+    handle: windows::Win32::Foundation::HANDLE,
+    #[cfg(windows)]
+    view: *const std::ffi::c_void,
+    #[cfg(target_os = "linux")]
+    map: memmap2::Mmap,
+}
+
+impl SharedMemory {
+    /// Open the map named `name`, if the game has created it
+    #[cfg(windows)]
+    pub fn open(name: &str) -> Option<Self> {
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::System::Memory::{MapViewOfFile, OpenFileMappingA, FILE_MAP_READ};
+
+        let name = std::ffi::CString::new(name).ok()?;
+        unsafe {
+            let handle = OpenFileMappingA(
+                FILE_MAP_READ.0, // Read access
+                false,
+                windows::core::PCSTR(name.as_ptr() as *const u8),
+            ).ok()?;
+            if handle.is_invalid() {
+                return None;
+            }
+
+            let view = MapViewOfFile(handle, FILE_MAP_READ, 0, 0, 0); // Map entire file
+            if view.Value.is_null() {
+                let _ = CloseHandle(handle);
+                return None;
+            }
+            Some(Self { handle, view: view.Value })
+        }
+    }
+
+    /// Open the map named `name`, if the game has created it
+    #[cfg(target_os = "linux")]
+    pub fn open(name: &str) -> Option<Self> {
+        let path = std::env::var_os(PATH_VAR)
+            .map(PathBuf::from)
+            .or_else(|| locate(Path::new(SHM_DIR), name))?;
+        let file = std::fs::File::open(&path).ok()?;
+        // Safety: the map is only read, and a plugin that goes away leaves
+        // the pages in place until they are unmapped
+        let map = unsafe { memmap2::Mmap::map(&file) }.ok()?;
+        if map.len() < scs::MAP_SIZE {
+            return None;
+        }
+        tracing::debug!("Mapped telemetry from {:?}", path);
+        Some(Self { map })
+    }
+
+    /// The first `MAP_SIZE` bytes of the map
+    pub fn bytes(&self) -> &[u8] {
+        #[cfg(windows)]
+        {
+            // Safety: the plugin maps MAP_SIZE bytes and the view stays valid until drop
+            unsafe { std::slice::from_raw_parts(self.view as *const u8, scs::MAP_SIZE) }
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            &self.map[..scs::MAP_SIZE]
+        }
+    }
+}
+
+// Safety: the view is read-only and lives until drop; access is
+// synchronized via the Mutex wrapper in AppState
+#[cfg(windows)]
+unsafe impl Send for SharedMemory {}
+#[cfg(windows)]
+unsafe impl Sync for SharedMemory {}
+
+#[cfg(windows)]
+impl Drop for SharedMemory {
+    fn drop(&mut self) {
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::System::Memory::{UnmapViewOfFile, MEMORY_MAPPED_VIEW_ADDRESS};
+
+        unsafe {
+            let _ = UnmapViewOfFile(MEMORY_MAPPED_VIEW_ADDRESS { Value: self.view as *mut _ });
+            let _ = CloseHandle(self.handle);
+        }
+    }
+}
+
+/// File in `dir` backing the map `name`; Wine drops the `Local\` namespace
+/// and may add its own prefix to the file name
+#[cfg(target_os = "linux")]
+fn locate(dir: &Path, name: &str) -> Option<PathBuf> {
+    let base = name.rsplit('\\').next().unwrap_or(name);
+    let exact = dir.join(base);
+    if exact.is_file() {
+        return Some(exact);
+    }
+    std::fs::read_dir(dir).ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|path| path.is_file() && path.file_name().and_then(|file| file.to_str()).is_some_and(|file| file.ends_with(base)))
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_map_file_behind_the_windows_name() {
+        let dir = std::env::temp_dir().join(format!("vtc-shm-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(locate(&dir, "Local\\SCSTelemetry"), None);
+
+        std::fs::write(dir.join("wine-SCSTelemetry"), vec![0u8; 16]).unwrap();
+        assert_eq!(locate(&dir, "Local\\SCSTelemetry"), Some(dir.join("wine-SCSTelemetry")));
+        std::fs::write(dir.join("SCSTelemetry"), vec![0u8; 16]).unwrap();
+        assert_eq!(locate(&dir, "Local\\SCSTelemetry"), Some(dir.join("SCSTelemetry")));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,298 @@
+//! Telemetry Simulator Module
+//!
+//! Generates plausible telemetry without the game: the truck takes jobs
+//! between cities from the bundled route table, speeds up, cruises, brakes
+//! into the destination and delivers. Lets the frontend and sync pipeline be
+//! exercised on machines without ETS2/ATS, e.g. for development and demos.
+
+use std::time::{Duration, Instant};
+
+use crate::currency::Currency;
+use crate::games;
+use crate::scs::{JobFrame, ScsFrame, SpecialFlags};
+use crate::telemetry::{FuelLevel, Game, Position, TrailerInfo, TruckInfo, TruckWear};
+
+/// Environment variable that turns the simulator on (`1`, `ets2` or `ats`)
+pub const ENV_VAR: &str = "VTC_SIMULATOR";
+/// How much faster than real time the truck covers distance, so a job
+/// takes minutes instead of hours
+const TIME_SCALE: f64 = 20.0;
+/// Cruise speed range (km/h); each job picks one
+const CRUISE_KMH: (f32, f32) = (78.0, 90.0);
+/// Speed change per second of driving (km/h)
+const ACCELERATION: f32 = 6.0;
+/// Distance before the destination at which the truck starts braking (km)
+const BRAKING_KM: f64 = 4.0;
+/// Slowest speed while still rolling towards the destination (km/h)
+const CRAWL_KMH: f32 = 15.0;
+/// Time parked at a company before taking the next job
+const LAYOVER: Duration = Duration::from_secs(8);
+/// How long the delivered flag stays up, like the SDK's
+const DELIVERY_FLAG: Duration = Duration::from_secs(2);
+/// Fuel burned per km (litres)
+const CONSUMPTION: f32 = 0.32;
+/// Game minutes between the sleeps the game asks for; the simulated driver
+/// sleeps on time, so the timer just wraps around
+const REST_INTERVAL_MINUTES: i32 = 660;
+const COMPANIES: &[&str] = &["Posped", "Tradeaux", "Transinet", "Stokes", "Kaarfor", "Wilnet Trans"];
+
+/// What the simulated driver is doing
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Phase {
+    /// Parked, waiting to take the next job
+    Parked { left: Duration },
+    /// On the road with `remaining_km` to go at `cruise_kmh`
+    Driving { remaining_km: f64, cruise_kmh: f32 },
+    /// Cargo handed over; the delivered flag is still up
+    Delivered { left: Duration },
+}
+
+/// Fake telemetry source for one game
+#[derive(Debug)]
+pub struct Simulator {
+    frame: ScsFrame,
+    phase: Phase,
+    /// In-game minutes, kept fractional so short frames still add up
+    game_minutes: f64,
+    /// Wall-clock time of the previous frame
+    last: Option<Instant>,
+    rng: u64,
+}
+
+impl Simulator {
+    pub fn new(game: Game) -> Self {
+        let seed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |since| since.as_nanos() as u64);
+        Self::with_seed(game, seed)
+    }
+
+    /// Simulator whose routes and speeds repeat for the same `seed`
+    pub fn with_seed(game: Game, seed: u64) -> Self {
+        Self {
+            frame: ScsFrame {
+                revision: 12,
+                sdk_active: true,
+                paused: false,
+                render_time: 0,
+                game: Some(game),
+                game_time: 0,
+                rest_stop_minutes: REST_INTERVAL_MINUTES,
+                speed_kmh: 0.0,
+                speed_limit_kmh: Some(90.0),
+                engine_rpm: 0.0,
+                fuel: FuelLevel { liters: 500.0, capacity: 600.0, avg_consumption: CONSUMPTION, range_km: 500.0 / CONSUMPTION },
+                wear: Some(TruckWear::default()),
+                cargo_damage: 0.0,
+                odometer_km: 84_000.0,
+                position: Position { x: 0.0, y: 0.0, z: 0.0, heading: 0.0 },
+                truck: Some(truck(game)),
+                trailer: None,
+                job: None,
+                flags: SpecialFlags::default(),
+                amounts: Default::default(),
+                unknown: Vec::new(),
+            },
+            phase: Phase::Parked { left: Duration::ZERO },
+            game_minutes: 0.0,
+            last: None,
+            rng: seed | 1,
+        }
+    }
+
+    /// Frame for wall-clock time `now`
+    pub fn frame(&mut self, now: Instant) -> ScsFrame {
+        let elapsed = self.last.map_or(Duration::ZERO, |last| now.saturating_duration_since(last));
+        self.last = Some(now);
+        self.advance(elapsed)
+    }
+
+    /// Move the simulation forward by `elapsed` of real time
+    pub fn advance(&mut self, elapsed: Duration) -> ScsFrame {
+        let seconds = elapsed.as_secs_f32();
+        self.frame.render_time += elapsed.as_micros() as u64;
+        self.game_minutes += elapsed.as_secs_f64() * TIME_SCALE / 60.0;
+        self.frame.game_time = self.game_minutes as u32;
+        self.frame.rest_stop_minutes = REST_INTERVAL_MINUTES - self.frame.game_time as i32 % REST_INTERVAL_MINUTES;
+
+        self.phase = match self.phase {
+            Phase::Parked { left } if left > elapsed => Phase::Parked { left: left - elapsed },
+            Phase::Parked { .. } => self.take_job(),
+            Phase::Delivered { left } if left > elapsed => Phase::Delivered { left: left - elapsed },
+            Phase::Delivered { .. } => {
+                self.frame.flags.job_delivered = false;
+                self.frame.amounts.delivered_revenue = 0;
+                Phase::Parked { left: LAYOVER }
+            }
+            Phase::Driving { remaining_km, cruise_kmh } => {
+                // Full speed on the open road, easing off over the last few km
+                let target = if remaining_km < BRAKING_KM {
+                    (cruise_kmh * (remaining_km / BRAKING_KM) as f32).max(CRAWL_KMH)
+                } else {
+                    cruise_kmh
+                };
+                let step = ACCELERATION * seconds;
+                let speed = self.frame.speed_kmh;
+                self.frame.speed_kmh = if speed < target { (speed + step).min(target) } else { (speed - step).max(target) };
+
+                let km = f64::from(self.frame.speed_kmh) * elapsed.as_secs_f64() / 3600.0 * TIME_SCALE;
+                self.drive(km);
+                let remaining_km = remaining_km - km;
+                if remaining_km <= 0.0 {
+                    self.deliver()
+                } else {
+                    if let Some(job) = self.frame.job.as_mut() {
+                        job.distance_remaining_km = remaining_km.ceil() as u32;
+                    }
+                    Phase::Driving { remaining_km, cruise_kmh }
+                }
+            }
+        };
+        self.frame.engine_rpm = if self.frame.speed_kmh > 0.0 { (650.0 + self.frame.speed_kmh * 13.0).min(1900.0) } else { 600.0 };
+        self.frame.clone()
+    }
+
+    /// Accept a job on a random route from the game's route table
+    fn take_job(&mut self) -> Phase {
+        let game = self.frame.game.unwrap_or(Game::Ets2);
+        let data = games::data(game);
+        let Some((from, to, km)) = self.pick(&data.routes).cloned() else {
+            return Phase::Parked { left: LAYOVER };
+        };
+        let (from, to) = if self.next() % 2 == 0 { (from, to) } else { (to, from) };
+        let cargo = self.pick_name(games::cargo()).unwrap_or_else(|| "Machinery".into());
+        let per_km = match data.currency {
+            Currency::Eur => 45,
+            Currency::Usd => 4,
+        };
+
+        if self.frame.fuel.liters < self.frame.fuel.capacity * 0.25 {
+            self.frame.fuel.liters = self.frame.fuel.capacity;
+        }
+        self.frame.position.heading = (self.next() % 1000) as f32 / 1000.0;
+        self.frame.trailer = Some(TrailerInfo {
+            id: "trailer.scs.box".into(),
+            body_type: "dryvan".into(),
+            license_plate: format!("SIM {:04}", self.next() % 10_000),
+        });
+        self.frame.job = Some(JobFrame {
+            cargo,
+            source_city: display_name(&data.cities, &from),
+            source_company: self.pick(COMPANIES).map_or_else(String::new, |company| company.to_string()),
+            destination_city: display_name(&data.cities, &to),
+            destination_company: self.pick(COMPANIES).map_or_else(String::new, |company| company.to_string()),
+            planned_distance_km: km,
+            distance_remaining_km: km,
+            income: u64::from(km) * per_km,
+            market: "freight_market".into(),
+        });
+        let (low, high) = CRUISE_KMH;
+        let cruise_kmh = low + (self.next() % 1000) as f32 / 1000.0 * (high - low);
+        Phase::Driving { remaining_km: f64::from(km), cruise_kmh }
+    }
+
+    fn deliver(&mut self) -> Phase {
+        let revenue = self.frame.job.take().map_or(0, |job| job.income as i64);
+        self.frame.speed_kmh = 0.0;
+        self.frame.trailer = None;
+        self.frame.flags.job_delivered = true;
+        self.frame.amounts.delivered_revenue = revenue;
+        Phase::Delivered { left: DELIVERY_FLAG }
+    }
+
+    /// Move `km` along the current heading, burning fuel and wearing the truck
+    fn drive(&mut self, km: f64) {
+        let angle = f64::from(self.frame.position.heading) * std::f64::consts::TAU;
+        self.frame.position.x += angle.cos() * km * 1000.0;
+        self.frame.position.z += angle.sin() * km * 1000.0;
+        self.frame.odometer_km += km as f32;
+
+        let fuel = &mut self.frame.fuel;
+        fuel.liters = (fuel.liters - km as f32 * CONSUMPTION).max(0.0);
+        fuel.range_km = fuel.liters / fuel.avg_consumption;
+        if let Some(wear) = self.frame.wear.as_mut() {
+            wear.engine = (wear.engine + km as f32 * 0.000_01).min(1.0);
+            wear.chassis = (wear.chassis + km as f32 * 0.000_005).min(1.0);
+        }
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+        let index = (self.next() % items.len() as u64) as usize;
+        items.get(index)
+    }
+
+    fn pick_name(&mut self, table: &games::NameTable) -> Option<String> {
+        let index = (self.next() % table.len().max(1) as u64) as usize;
+        table.values().nth(index).and_then(|aliases| aliases.first().cloned())
+    }
+
+    /// xorshift64; good enough for picking routes
+    fn next(&mut self) -> u64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
+}
+
+/// The English name of a city, which the name tables list first
+fn display_name(cities: &games::NameTable, id: &str) -> String {
+    cities.get(id)
+        .and_then(|aliases| aliases.first().cloned())
+        .unwrap_or_else(|| id.to_string())
+}
+
+fn truck(game: Game) -> TruckInfo {
+    match game {
+        Game::Ets2 => TruckInfo {
+            brand_id: "scania".into(),
+            brand: "Scania".into(),
+            model_id: "vehicle.scania.r_2016".into(),
+            model: "R 2016".into(),
+            license_plate: "SIM 001".into(),
+            license_plate_country: "germany".into(),
+        },
+        Game::Ats => TruckInfo {
+            brand_id: "peterbilt".into(),
+            brand: "Peterbilt".into(),
+            model_id: "vehicle.peterbilt.579".into(),
+            model: "579".into(),
+            license_plate: "SIM 001".into(),
+            license_plate_country: "california".into(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::{TelemetryEvent, TelemetryReader};
+
+    #[test]
+    fn drives_jobs_from_pickup_to_delivery() {
+        let mut simulator = Simulator::with_seed(Game::Ets2, 7);
+        let mut reader = TelemetryReader::new();
+        let mut events = Vec::new();
+        let mut top_speed: f32 = 0.0;
+        // An hour of simulated driving in one-second frames
+        for _ in 0..3600 {
+            let frame = simulator.advance(Duration::from_secs(1));
+            top_speed = top_speed.max(frame.speed_kmh);
+            events.extend(reader.feed(Some(&frame)));
+        }
+
+        assert!(matches!(events.first(), Some(TelemetryEvent::Connected(Game::Ets2))));
+        let completed: Vec<_> = events.iter()
+            .filter_map(|event| match event {
+                TelemetryEvent::JobCompleted(job) => Some(job),
+                _ => None,
+            })
+            .collect();
+        assert!(!completed.is_empty());
+        assert!(completed.iter().all(|job| job.revenue > 0 && job.source_city != job.destination_city));
+        assert!((CRUISE_KMH.0..=CRUISE_KMH.1).contains(&top_speed));
+    }
+}
@@ -0,0 +1,283 @@
+//! Reader Thread Module
+//!
+//! Runs the TelemetryReader on a dedicated OS thread that owns the shared
+//! memory map. Each poll publishes a snapshot on a watch channel, so commands
+//! read the latest state without waiting on the reader, and queues the
+//! poll's events for the telemetry loop. The queue is bounded: while it is
+//! full, polls are merged into one that keeps every event.
+
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc as queue, oneshot, watch};
+use tracing::{debug, warn};
+
+use crate::scs::ScsError;
+use crate::telemetry::{ActiveJob, JobPhase, PollActivity, TelemetryEvent, TelemetryReader, TelemetryState};
+
+/// Poll interval until the telemetry loop picks one
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(100);
+/// Polls queued for the telemetry loop before they are merged
+const POLL_QUEUE: usize = 32;
+
+/// Reader state after a poll
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub state: TelemetryState,
+    pub activity: PollActivity,
+    /// Why the shared memory can't be read, if it can't
+    pub read_error: Option<ScsError>,
+    pub plugin_revision: Option<u32>,
+    /// The tracked job, for checkpoints
+    pub job: Option<(ActiveJob, JobPhase)>,
+}
+
+impl Snapshot {
+    fn of(reader: &TelemetryReader) -> Self {
+        Self {
+            state: reader.get_state().clone(),
+            activity: reader.poll_activity(),
+            read_error: reader.read_error().cloned(),
+            plugin_revision: reader.plugin_revision(),
+            job: reader.job_snapshot(),
+        }
+    }
+}
+
+/// One poll: the events it produced and the state after them
+#[derive(Debug)]
+pub struct Poll {
+    pub events: Vec<TelemetryEvent>,
+    pub snapshot: Snapshot,
+}
+
+type Task = Box<dyn FnOnce(&mut TelemetryReader) + Send>;
+
+enum Request {
+    /// Run on the reader between polls
+    Run(Task),
+    Interval(Duration),
+    /// Start or stop polling the game
+    Polling(bool),
+    /// Stop polling and hand over the merged poll not yet queued
+    Drain(oneshot::Sender<Option<Poll>>),
+}
+
+/// Handle to the reader thread; the thread exits when this is dropped
+pub struct TelemetryThread {
+    requests: Mutex<mpsc::Sender<Request>>,
+    snapshots: watch::Receiver<Snapshot>,
+    /// Held by the telemetry loop while it runs
+    polls: tokio::sync::Mutex<queue::Receiver<Poll>>,
+}
+
+impl TelemetryThread {
+    /// Move `reader` to its own thread; it polls once `start` is called
+    pub fn spawn(reader: TelemetryReader) -> std::io::Result<Self> {
+        let (requests, rx) = mpsc::channel();
+        let (publish, snapshots) = watch::channel(Snapshot::of(&reader));
+        let (send, polls) = queue::channel(POLL_QUEUE);
+        std::thread::Builder::new()
+            .name("telemetry".into())
+            .spawn(move || run(reader, rx, publish, send))?;
+        Ok(Self {
+            requests: Mutex::new(requests),
+            snapshots,
+            polls: tokio::sync::Mutex::new(polls),
+        })
+    }
+
+    /// Latest published state
+    pub fn snapshot(&self) -> Snapshot {
+        self.snapshots.borrow().clone()
+    }
+
+    /// Receiver that sees every published snapshot
+    pub fn subscribe(&self) -> watch::Receiver<Snapshot> {
+        self.snapshots.clone()
+    }
+
+    /// Run `task` on the reader and wait for its result; `None` if the
+    /// thread is gone. Blocks the calling thread, so async code and the UI
+    /// thread use `run_async` or `post`. Must not be called from the reader thread
+    pub fn run<R, F>(&self, task: F) -> Option<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut TelemetryReader) -> R + Send + 'static,
+    {
+        let (reply, result) = mpsc::sync_channel(1);
+        self.send(Request::Run(Box::new(move |reader| {
+            let _ = reply.send(task(reader));
+        })));
+        result.recv().ok()
+    }
+
+    /// `run` without blocking the async runtime
+    pub async fn run_async<R, F>(&self, task: F) -> Option<R>
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut TelemetryReader) -> R + Send + 'static,
+    {
+        let (reply, result) = oneshot::channel();
+        self.send(Request::Run(Box::new(move |reader| {
+            let _ = reply.send(task(reader));
+        })));
+        result.await.ok()
+    }
+
+    /// Queue `task` on the reader without waiting for it; `false` if the
+    /// thread is gone
+    pub fn post<F>(&self, task: F) -> bool
+    where
+        F: FnOnce(&mut TelemetryReader) + Send + 'static,
+    {
+        self.send(Request::Run(Box::new(task)))
+    }
+
+    /// Time between polls
+    pub fn set_interval(&self, interval: Duration) {
+        self.send(Request::Interval(interval));
+    }
+
+    /// Start polling the game
+    pub fn start(&self) {
+        self.send(Request::Polling(true));
+    }
+
+    /// Stop polling; the last snapshot stays published
+    pub fn stop(&self) {
+        self.send(Request::Polling(false));
+    }
+
+    /// Wait for the next poll; `None` once the thread has exited
+    pub async fn next_poll(&self) -> Option<Poll> {
+        self.polls.lock().await.recv().await
+    }
+
+    /// Stop polling and take every poll the telemetry loop hasn't seen yet,
+    /// oldest first, so no event is lost on shutdown
+    pub async fn stop_and_drain(&self) -> Vec<Poll> {
+        let (reply, merged) = oneshot::channel();
+        self.send(Request::Drain(reply));
+        // The thread answers after every poll it queued before stopping
+        let merged = merged.await.ok().flatten();
+        let mut polls = Vec::new();
+        let mut queue = self.polls.lock().await;
+        while let Ok(poll) = queue.try_recv() {
+            polls.push(poll);
+        }
+        polls.extend(merged);
+        polls
+    }
+
+    fn send(&self, request: Request) -> bool {
+        let sent = self.requests.lock().is_ok_and(|requests| requests.send(request).is_ok());
+        if !sent {
+            warn!("Telemetry thread is not running");
+        }
+        sent
+    }
+}
+
+fn run(
+    mut reader: TelemetryReader,
+    requests: mpsc::Receiver<Request>,
+    publish: watch::Sender<Snapshot>,
+    polls: queue::Sender<Poll>,
+) {
+    let mut interval = DEFAULT_INTERVAL;
+    let mut polling = false;
+    let mut next_poll = Instant::now();
+    // Polls merged while the queue was full
+    let mut backlog: Option<Poll> = None;
+    loop {
+        let request = if polling {
+            requests.recv_timeout(next_poll.saturating_duration_since(Instant::now()))
+        } else {
+            requests.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected)
+        };
+        match request {
+            Ok(Request::Run(task)) => {
+                task(&mut reader);
+                publish.send_replace(Snapshot::of(&reader));
+            }
+            Ok(Request::Interval(wanted)) => {
+                next_poll = next_poll.min(Instant::now() + wanted);
+                interval = wanted;
+            }
+            Ok(Request::Polling(on)) => {
+                debug!("Telemetry polling {}", if on { "started" } else { "stopped" });
+                polling = on;
+                next_poll = Instant::now();
+            }
+            Ok(Request::Drain(reply)) => {
+                polling = false;
+                let _ = reply.send(backlog.take());
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                let events = reader.update();
+                let snapshot = Snapshot::of(&reader);
+                publish.send_replace(snapshot.clone());
+                let poll = match backlog.take() {
+                    Some(mut merged) => {
+                        merged.events.extend(events);
+                        merged.snapshot = snapshot;
+                        merged
+                    }
+                    None => Poll { events, snapshot },
+                };
+                // A stalled telemetry loop gets one merged poll later rather
+                // than an ever-growing queue; the events are all kept
+                if let Err(queue::error::TrySendError::Full(poll)) = polls.try_send(poll) {
+                    backlog = Some(poll);
+                }
+                next_poll = (next_poll + interval).max(Instant::now());
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    debug!("Telemetry thread exiting");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::simulator::Simulator;
+    use crate::telemetry::Game;
+
+    #[test]
+    fn publishes_polls_from_its_own_thread() {
+        let thread = TelemetryThread::spawn(TelemetryReader::new()).unwrap();
+        assert!(!thread.snapshot().state.connected);
+        thread.run(|reader| reader.set_simulator(Some(Simulator::with_seed(Game::Ats, 3)))).unwrap();
+        thread.set_interval(Duration::from_millis(1));
+        thread.start();
+
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let poll = runtime.block_on(thread.next_poll()).unwrap();
+        assert!(matches!(poll.events.first(), Some(TelemetryEvent::Connected(Game::Ats))));
+        assert!(poll.snapshot.state.connected);
+        assert_eq!(thread.run(|reader| reader.get_state().game), Some(Some(Game::Ats)));
+        thread.stop();
+        assert!(thread.snapshot().state.connected);
+    }
+
+    #[test]
+    fn merges_polls_while_the_queue_is_full_and_drains_them_all() {
+        let thread = TelemetryThread::spawn(TelemetryReader::new()).unwrap();
+        thread.run(|reader| reader.set_simulator(Some(Simulator::with_seed(Game::Ets2, 5)))).unwrap();
+        thread.set_interval(Duration::from_millis(1));
+        thread.start();
+        // Nobody takes polls for a while
+        std::thread::sleep(Duration::from_millis(200));
+
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let polls = runtime.block_on(thread.stop_and_drain());
+        assert_eq!(polls.len(), POLL_QUEUE + 1);
+        // The very first event is still there
+        assert!(matches!(polls[0].events.first(), Some(TelemetryEvent::Connected(Game::Ets2))));
+        let last = thread.snapshot();
+        assert_eq!(polls[POLL_QUEUE].snapshot.state.speed, last.state.speed);
+        assert!(runtime.block_on(thread.stop_and_drain()).is_empty());
+        assert_eq!(runtime.block_on(thread.run_async(|reader| reader.get_state().game)), Some(Some(Game::Ets2)));
+    }
+}
@@ -0,0 +1,108 @@
+//! Vehicles Module
+//!
+//! Small local cache giving each truck and trailer the driver uses a stable
+//! ID, so submissions can say which vehicle hauled a job even though the game
+//! only reports model and license plate.
+
+use std::collections::BTreeMap;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::storage::{SecureStorage, StorageError};
+use crate::telemetry::{TrailerInfo, TruckInfo};
+
+/// Storage key for the vehicle cache
+const VEHICLES_KEY: &str = "vehicles";
+/// Vehicles remembered; the least recently used are forgotten first
+const MAX_VEHICLES: usize = 50;
+
+/// Local ID of one vehicle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VehicleEntry {
+    id: String,
+    last_used: DateTime<Utc>,
+}
+
+/// Vehicle IDs by model and license plate
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct VehicleCache {
+    vehicles: BTreeMap<String, VehicleEntry>,
+}
+
+impl VehicleCache {
+    /// Load the cache, starting empty if it is missing or unreadable
+    pub fn load(storage: &SecureStorage) -> Self {
+        if !storage.exists(VEHICLES_KEY) {
+            return Self::default();
+        }
+        storage.load(VEHICLES_KEY).unwrap_or_else(|e| {
+            warn!("Failed to load vehicle cache: {}", e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, storage: &SecureStorage) -> Result<(), StorageError> {
+        storage.save(VEHICLES_KEY, self)
+    }
+
+    /// Stable ID for the truck, assigned on first use
+    pub fn truck_id(&mut self, truck: &TruckInfo, now: DateTime<Utc>) -> String {
+        self.id_for(format!("truck:{}:{}", truck.model_id, truck.license_plate), now)
+    }
+
+    /// Stable ID for the trailer, assigned on first use
+    pub fn trailer_id(&mut self, trailer: &TrailerInfo, now: DateTime<Utc>) -> String {
+        self.id_for(format!("trailer:{}:{}", trailer.id, trailer.license_plate), now)
+    }
+
+    fn id_for(&mut self, key: String, now: DateTime<Utc>) -> String {
+        if !self.vehicles.contains_key(&key) && self.vehicles.len() >= MAX_VEHICLES {
+            let oldest = self.vehicles.iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                self.vehicles.remove(&oldest);
+            }
+        }
+        let entry = self.vehicles.entry(key).or_insert_with(|| VehicleEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            last_used: now,
+        });
+        entry.last_used = now;
+        entry.id.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn truck(plate: &str) -> TruckInfo {
+        TruckInfo {
+            brand_id: "scania".into(),
+            brand: "Scania".into(),
+            model_id: "vehicle.scania.r_2016".into(),
+            model: "R".into(),
+            license_plate: plate.into(),
+            license_plate_country: "germany".into(),
+        }
+    }
+
+    #[test]
+    fn keeps_ids_stable_and_forgets_least_recent() {
+        let t = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let mut cache = VehicleCache::default();
+        let first = cache.truck_id(&truck("B VT 1"), t);
+        assert_eq!(cache.truck_id(&truck("B VT 1"), t + Duration::hours(1)), first);
+        assert_ne!(cache.truck_id(&truck("B VT 2"), t), first);
+
+        for n in 0..MAX_VEHICLES {
+            cache.truck_id(&truck(&format!("X {}", n)), t + Duration::hours(2));
+        }
+        assert_eq!(cache.vehicles.len(), MAX_VEHICLES);
+        assert_ne!(cache.truck_id(&truck("B VT 1"), t + Duration::hours(3)), first);
+    }
+}
@@ -0,0 +1,152 @@
+//! Audio Module
+//!
+//! Plays short sounds for gameplay and sync events from the backend, so they
+//! are heard while the window is minimized to the tray.
+
+use std::path::PathBuf;
+use std::sync::{mpsc, Mutex};
+use std::time::Duration;
+use rodio::{Decoder, OutputStream, Sink, Source};
+use tracing::{debug, warn};
+
+use crate::settings::SoundSettings;
+
+/// Events that can play a sound
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundEvent {
+    JobDelivered,
+    FineReceived,
+    SyncFailed,
+}
+
+impl SoundEvent {
+    /// Built-in tone sequence: (frequency Hz, duration ms)
+    fn tones(self) -> &'static [(f32, u64)] {
+        match self {
+            SoundEvent::JobDelivered => &[(660.0, 140), (880.0, 220)],
+            SoundEvent::FineReceived => &[(440.0, 160), (330.0, 260)],
+            SoundEvent::SyncFailed => &[(220.0, 350)],
+        }
+    }
+}
+
+/// Volume to play `event` at, or `None` if it should stay silent
+pub fn volume_for(settings: &SoundSettings, event: SoundEvent, in_game: bool) -> Option<f32> {
+    let enabled = match event {
+        SoundEvent::JobDelivered => settings.job_delivered.enabled,
+        SoundEvent::FineReceived => settings.fine_received.enabled,
+        SoundEvent::SyncFailed => settings.sync_failed.enabled,
+    };
+    if !settings.enabled || !enabled || (settings.mute_in_game && in_game) {
+        return None;
+    }
+    Some(settings.volume.clamp(0.0, 1.0)).filter(|volume| *volume > 0.0)
+}
+
+struct Request {
+    event: SoundEvent,
+    file: Option<PathBuf>,
+    volume: f32,
+}
+
+/// Plays sounds on a dedicated audio thread (output streams are not `Send`)
+pub struct SoundPlayer {
+    requests: Mutex<mpsc::Sender<Request>>,
+}
+
+impl SoundPlayer {
+    /// Start the audio thread
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::channel();
+        if let Err(e) = std::thread::Builder::new()
+            .name("audio".into())
+            .spawn(move || run(rx))
+        {
+            warn!("Failed to start audio thread: {}", e);
+        }
+        Self { requests: Mutex::new(tx) }
+    }
+
+    /// Play the sound configured for `event`
+    pub fn play(&self, event: SoundEvent, settings: &SoundSettings, in_game: bool) {
+        let Some(volume) = volume_for(settings, event, in_game) else {
+            return;
+        };
+        let file = match event {
+            SoundEvent::JobDelivered => &settings.job_delivered.file,
+            SoundEvent::FineReceived => &settings.fine_received.file,
+            SoundEvent::SyncFailed => &settings.sync_failed.file,
+        };
+
+        if let Ok(requests) = self.requests.lock() {
+            // Fails only when no output device was available
+            let _ = requests.send(Request { event, file: file.as_ref().map(PathBuf::from), volume });
+        }
+    }
+}
+
+impl Default for SoundPlayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn run(requests: mpsc::Receiver<Request>) {
+    let (_stream, handle) = match OutputStream::try_default() {
+        Ok(output) => output,
+        Err(e) => {
+            warn!("No audio output available, sounds disabled: {}", e);
+            return;
+        }
+    };
+
+    for request in requests {
+        let sink = match Sink::try_new(&handle) {
+            Ok(sink) => sink,
+            Err(e) => {
+                debug!("Failed to create audio sink: {}", e);
+                continue;
+            }
+        };
+        sink.set_volume(request.volume);
+
+        let custom = request.file.as_ref().and_then(|path| {
+            std::fs::File::open(path)
+                .map_err(|e| e.to_string())
+                .and_then(|file| Decoder::new(std::io::BufReader::new(file)).map_err(|e| e.to_string()))
+                .map_err(|e| warn!("Cannot play {:?}, using built-in sound: {}", path, e))
+                .ok()
+        });
+        match custom {
+            Some(decoder) => sink.append(decoder),
+            None => {
+                for (freq, ms) in request.event.tones() {
+                    sink.append(
+                        rodio::source::SineWave::new(*freq)
+                            .take_duration(Duration::from_millis(*ms))
+                            .amplify(0.3),
+                    );
+                }
+            }
+        }
+        sink.detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn respects_mute_and_per_event_toggles() {
+        let mut settings = SoundSettings::default();
+        assert!(volume_for(&settings, SoundEvent::JobDelivered, false).is_some());
+
+        settings.mute_in_game = true;
+        assert!(volume_for(&settings, SoundEvent::JobDelivered, true).is_none());
+        assert!(volume_for(&settings, SoundEvent::JobDelivered, false).is_some());
+
+        settings.fine_received.enabled = false;
+        assert!(volume_for(&settings, SoundEvent::FineReceived, false).is_none());
+    }
+}
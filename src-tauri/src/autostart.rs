@@ -0,0 +1,135 @@
+//! Autostart Module
+//!
+//! Registers the app under the per-user Windows `Run` key so it starts with
+//! Windows, minimized to the tray.
+
+/// Argument passed when launched at login, to start hidden in the tray
+pub const LAUNCH_ARG: &str = "--autostart";
+
+/// Autostart errors
+#[derive(Debug, thiserror::Error)]
+pub enum AutostartError {
+    #[error("Failed to locate executable: {0}")]
+    Executable(String),
+
+    #[error("Registry update failed: {0}")]
+    Registry(String),
+
+    #[error("Autostart is only supported on Windows")]
+    Unsupported,
+}
+
+/// Whether this process was started by the `Run` key
+pub fn launched_at_login() -> bool {
+    std::env::args().any(|arg| arg == LAUNCH_ARG)
+}
+
+/// Command line stored in the `Run` key
+#[cfg(windows)]
+fn command_line() -> Result<String, AutostartError> {
+    let exe = std::env::current_exe().map_err(|e| AutostartError::Executable(e.to_string()))?;
+    Ok(format!("\"{}\" {}", exe.display(), LAUNCH_ARG))
+}
+
+#[cfg(windows)]
+mod registry {
+    use windows::core::{w, HSTRING, PCWSTR};
+    use windows::Win32::Foundation::ERROR_FILE_NOT_FOUND;
+    use windows::Win32::System::Registry::{
+        RegDeleteKeyValueW, RegGetValueW, RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ, RRF_RT_REG_SZ,
+    };
+
+    use super::AutostartError;
+
+    const RUN_KEY: PCWSTR = w!("Software\\Microsoft\\Windows\\CurrentVersion\\Run");
+    const VALUE_NAME: PCWSTR = w!("VTC Tracker");
+
+    /// Current `Run` entry, if any
+    pub fn read() -> Option<String> {
+        unsafe {
+            let mut size = 0u32;
+            RegGetValueW(HKEY_CURRENT_USER, RUN_KEY, VALUE_NAME, RRF_RT_REG_SZ, None, None, Some(&mut size)).ok().ok()?;
+            let mut buffer = vec![0u16; (size as usize + 1) / 2];
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                RUN_KEY,
+                VALUE_NAME,
+                RRF_RT_REG_SZ,
+                None,
+                Some(buffer.as_mut_ptr().cast()),
+                Some(&mut size),
+            )
+            .ok()
+            .ok()?;
+            let len = buffer.iter().position(|c| *c == 0).unwrap_or(buffer.len());
+            Some(String::from_utf16_lossy(&buffer[..len]))
+        }
+    }
+
+    pub fn write(command_line: &str) -> Result<(), AutostartError> {
+        let data = HSTRING::from(command_line);
+        // Size in bytes, including the terminating NUL
+        let size = ((data.len() + 1) * 2) as u32;
+        unsafe {
+            RegSetKeyValueW(HKEY_CURRENT_USER, RUN_KEY, VALUE_NAME, REG_SZ.0, Some(data.as_ptr().cast()), size)
+                .ok()
+                .map_err(|e| AutostartError::Registry(e.to_string()))
+        }
+    }
+
+    pub fn delete() -> Result<(), AutostartError> {
+        match unsafe { RegDeleteKeyValueW(HKEY_CURRENT_USER, RUN_KEY, VALUE_NAME) } {
+            ERROR_FILE_NOT_FOUND => Ok(()),
+            result => result.ok().map_err(|e| AutostartError::Registry(e.to_string())),
+        }
+    }
+}
+
+/// Whether the app is registered to start with Windows
+pub fn is_enabled() -> bool {
+    #[cfg(windows)]
+    {
+        registry::read().is_some()
+    }
+
+    #[cfg(not(windows))]
+    {
+        false
+    }
+}
+
+/// Register or unregister the app in the `Run` key
+pub fn set(enabled: bool) -> Result<(), AutostartError> {
+    #[cfg(windows)]
+    {
+        if enabled {
+            registry::write(&command_line()?)
+        } else {
+            registry::delete()
+        }
+    }
+
+    #[cfg(not(windows))]
+    {
+        if enabled {
+            Err(AutostartError::Unsupported)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Re-register when the stored entry points at an old install location
+pub fn repair(enabled: bool) -> Result<(), AutostartError> {
+    #[cfg(windows)]
+    if enabled {
+        let expected = command_line()?;
+        if registry::read().as_ref() != Some(&expected) {
+            return registry::write(&expected);
+        }
+    }
+
+    #[cfg(not(windows))]
+    let _ = enabled;
+    Ok(())
+}
@@ -3,28 +3,83 @@
 //! IPC commands exposed to the frontend.
 
 use tauri::{command, State, Manager, AppHandle, WebviewWindow, Emitter};
-use serde::{Deserialize, Serialize};
-use tracing::{info, error, debug};
+use serde::Serialize;
+use tracing::{info, error, debug, warn};
+use tokio_util::sync::CancellationToken;
 
 use crate::AppState;
-use crate::auth::Session;
+use crate::error::AppError;
+use crate::app_health::{self, AppHealth, HealthInputs};
+use crate::audit::{AuditEvent, AuditVerification};
+use crate::audio::SoundEvent;
+use crate::autostart;
+use crate::checkpoint::{self, Checkpoint, RecoveredJob, RecoveryAction};
+use crate::connectivity::{self, ConnectivityReport};
+use crate::convoy::{ConvoyReport, ConvoySession};
+use crate::events::{FrameThrottle, FrontendEvent};
+use crate::focus::{self, FocusTracker};
+use crate::games::{self, GameData};
+use crate::heartbeat::{self as schedule, ConnectionStatus, HeartbeatSchedule};
+use crate::history::{HistoryFilter, HistoryPage, HistoryStatus, JobStats, LocalStats, StatsPeriod};
+use crate::import::{self, LogbookFormat};
+use crate::job_queue::JobQueue;
+use crate::local_api;
+use crate::local_auth::LocalAccessToken;
+use crate::logging;
+use crate::maintenance::MaintenanceTracker;
+use crate::manual::ManualJob;
+use crate::minimap::{MapPosition, MiniMapFeed};
+use crate::notifications::{self, Notification, NotificationEvent, ToastAction};
+use crate::positions::{PositionBatch, PositionSample};
+use crate::power::{self, PowerEvent, SleepDetector};
+use crate::preflight::{self, PreflightInputs, PreflightReport};
+use crate::privacy::PrivacyGuard;
+use crate::progress::ProgressTracker;
+use crate::route::{self, RouteFormat, RoutePoint};
+use crate::server_status::MaintenanceStatus;
+use crate::services::RestartPolicy;
+use crate::last_errors::{Subsystem, SubsystemError};
+use crate::overlay::{OverlayError, OverlayPreset, OverlaySettings};
+use crate::settings::{FocusSettings, Settings, SpeedingSettings};
+use crate::speeding::{CollisionDetector, ConductReport, SpeedingDetector, TrafficOffence};
+use crate::stats_card::{self, StatsSummary};
+use crate::tachograph::{Tachograph, TachographEvent};
+use crate::sync_health::{SyncChannel, SyncHealthReport, SyncStatus};
+use crate::taskbar::{TaskbarIndicator, TaskbarProgress};
+use crate::tray::{TrayView, TRAY_ID};
+use crate::telemetry::{simulator::Simulator, thread::Poll, ActiveJob, Game, TelemetryState};
+use crate::updates::{self, UpdateError, UpdateInfo};
+use crate::auth::{AccountSummary, Accounts, Session};
+use crate::storage::{SecureStorage, StorageError};
+use crate::sync::{
+    ApiError, FeedbackRequest, JobResponse, JobSubmission, Presence, PushMessage, TelemetryPolicy,
+    VerifyResponse, MAX_BATCH_JOBS,
+};
 
 // Response types for frontend
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SessionResponse {
     pub access_token: String,
     pub user_id: String,
     pub display_name: String,
 }
 
+impl From<Session> for SessionResponse {
+    fn from(session: Session) -> Self {
+        Self {
+            access_token: session.access_token,
+            user_id: session.user_id,
+            display_name: session.display_name,
+        }
+    }
+}
+
+/// One-time code for linking another PC
 #[derive(Debug, Serialize)]
-pub struct VerifyResult {
-    pub success: bool,
-    pub access_token: Option<String>,
-    pub user_id: Option<String>,
-    pub display_name: Option<String>,
-    pub error: Option<String>,
+pub struct TransferCode {
+    pub code: String,
+    pub expires_at: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -32,19 +87,71 @@ pub struct HeartbeatResult {
     pub success: bool,
 }
 
+#[derive(Debug, Serialize)]
+pub struct RetryResult {
+    pub attempted: usize,
+    pub succeeded: usize,
+    pub remaining: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResubmitResult {
+    /// `None` while offline: the job was queued instead
+    pub job_id: Option<String>,
+    /// Jobs still waiting in the retry queue
+    pub queued_jobs: usize,
+}
+
+// Guards
+
+/// Get the current access token, if any
+fn current_token(state: &AppState) -> Option<String> {
+    state.tokens.get().map(|token| token.to_string())
+}
+
+/// Require a valid session for commands that act on the user's behalf.
+/// There is no dispatch command to guard: assignments only arrive over the
+/// push channel, which `run_realtime` opens only while signed in
+fn require_auth(state: &AppState) -> Result<String, AppError> {
+    current_token(state).ok_or(AppError::AuthRequired)
+}
+
+/// User ID and access token of the signed-in account, if any
+fn current_account(state: &AppState) -> Option<(String, String)> {
+    let auth = state.auth.lock().ok()?;
+    let session = auth.get_session()?;
+    Some((session.user_id.clone(), session.access_token.clone()))
+}
+
+/// User ID of the signed-in account, if any
+fn current_user(state: &AppState) -> Option<String> {
+    state.auth.lock().ok()?.get_session().map(|session| session.user_id.clone())
+}
+
+/// `require_auth` for commands that queue jobs for the account
+fn require_account(state: &AppState) -> Result<(String, String), AppError> {
+    current_account(state).ok_or(AppError::AuthRequired)
+}
+
 // Commands
 
 /// Get stored session from secure storage
 #[command]
-pub fn get_stored_session(state: State<'_, AppState>) -> Option<SessionResponse> {
+pub fn get_stored_session(app: AppHandle, state: State<'_, AppState>) -> Option<SessionResponse> {
     debug!("Getting stored session");
     
     // Try to load from secure storage
-    match state.storage.load::<Session>("session") {
-        Ok(session) => {
-            if session.is_expired() {
+    match Accounts::load(&state.storage) {
+        Ok(mut accounts) => {
+            let Some(session) = accounts.active_session().cloned() else {
+                debug!("No stored session found");
+                return None;
+            };
+            if session.is_expired_at(state.clock.now()) {
                 info!("Stored session is expired");
-                let _ = state.storage.delete("session");
+                accounts.remove(&session.user_id);
+                let _ = accounts.save(&state.storage);
                 return None;
             }
             
@@ -53,222 +160,2810 @@ pub fn get_stored_session(state: State<'_, AppState>) -> Option<SessionResponse>
                 auth.set_session(session.clone());
             }
             
-            Some(SessionResponse {
-                access_token: session.access_token,
-                user_id: session.user_id,
-                display_name: session.display_name,
-            })
-        }
-        Err(_) => {
-            debug!("No stored session found");
+            Some(SessionResponse::from(session))
+        }
+        Err(StorageError::Decryption(e)) => {
+            error!("Stored session could not be decrypted: {}", e);
+            record_error(&state, Subsystem::Storage, "decryption", &e);
+            emit_storage_reset(&app, &state);
+            None
+        }
+        Err(e) => {
+            warn!("Failed to load stored accounts: {}", e);
+            record_error(&state, Subsystem::Storage, e.code(), &e.to_string());
             None
         }
     }
 }
 
+/// Secure storage health, used to drive the re-link flow
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageStatus {
+    pub reset_required: bool,
+    pub quarantined: Vec<String>,
+}
+
+impl StorageStatus {
+    fn of(storage: &SecureStorage) -> Self {
+        Self {
+            reset_required: storage.reset_required(),
+            quarantined: storage.quarantined_keys(),
+        }
+    }
+}
+
+/// Tell the frontend that encrypted data was lost and the device must be re-linked
+pub fn emit_storage_reset(app: &AppHandle, state: &AppState) {
+    if !state.storage.reset_required() {
+        return;
+    }
+    let _ = app.emit("secure_storage_reset_required", StorageStatus::of(&state.storage));
+}
+
+/// Get secure storage health
+#[command]
+pub fn get_storage_status(state: State<'_, AppState>) -> StorageStatus {
+    StorageStatus::of(&state.storage)
+}
+
 /// Verify device code and authenticate
 #[command]
 pub async fn verify_device_code(
     code: String,
     state: State<'_, AppState>,
-) -> Result<VerifyResult, String> {
-    info!("Verifying device code: {}", &code[..2]); // Only log first 2 chars
+) -> Result<SessionResponse, AppError> {
+    // Only log the first 2 characters
+    info!("Verifying device code: {}", code.chars().take(2).collect::<String>());
     
     // Get device name
     let device_name = whoami::fallible::hostname()
         .unwrap_or_else(|_| "VTC Desktop".to_string());
     
-    match state.api.verify_code(&code, &device_name).await {
-        Ok(response) => {
-            // Parse expiration
-            let expires_at = chrono::DateTime::parse_from_rfc3339(&response.expires_at)
-                .map(|dt| dt.with_timezone(&chrono::Utc))
-                .unwrap_or_else(|_| {
-                    chrono::Utc::now() + chrono::Duration::days(30)
-                });
-            
-            // Create session
-            let session = Session {
-                access_token: response.access_token.clone(),
-                user_id: response.user_id.clone(),
-                display_name: response.display_name.clone(),
-                avatar_url: response.avatar_url,
-                expires_at,
-            };
-            
-            // Update auth manager
-            if let Ok(mut auth) = state.auth.lock() {
-                auth.set_session(session.clone());
+    match state.api.auth.verify_code(&code, &device_name).await {
+        Ok(response) => Ok(establish_session(&state, response)),
+        Err(e) => {
+            error!("Code verification failed: {}", e);
+            record_api_error(&state, Subsystem::Auth, &e);
+            Err(e.into())
+        }
+    }
+}
+
+/// Create a one-time code for linking another PC to this session
+#[command]
+pub async fn create_transfer_code(
+    state: State<'_, AppState>,
+) -> Result<TransferCode, AppError> {
+    let token = require_auth(&state)?;
+    
+    match state.api.auth.create_transfer_code(&token).await {
+        Ok(response) => Ok(TransferCode {
+            code: response.code,
+            expires_at: response.expires_at,
+        }),
+        Err(e) => {
+            error!("Failed to create transfer code: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+/// Redeem a transfer code from another PC and authenticate
+#[command]
+pub async fn redeem_transfer_code(
+    code: String,
+    state: State<'_, AppState>,
+) -> Result<SessionResponse, AppError> {
+    info!("Redeeming transfer code");
+    
+    let device_name = whoami::fallible::hostname()
+        .unwrap_or_else(|_| "VTC Desktop".to_string());
+    
+    match state.api.auth.redeem_transfer_code(&code, &device_name).await {
+        Ok(response) => Ok(establish_session(&state, response)),
+        Err(e) => {
+            error!("Transfer code redemption failed: {}", e);
+            record_api_error(&state, Subsystem::Auth, &e);
+            Err(e.into())
+        }
+    }
+}
+
+/// Store a freshly issued device session and build the frontend result
+fn establish_session(state: &AppState, response: VerifyResponse) -> SessionResponse {
+    // Create session
+    let session = Session {
+        access_token: response.access_token.clone(),
+        user_id: response.user_id.clone(),
+        display_name: response.display_name.clone(),
+        avatar_url: response.avatar_url,
+        expires_at: parse_expiry(&response.expires_at, state.clock.now()),
+        refresh_token: response.refresh_token,
+        // Verification registers the key
+        device_key: state.api.device_public_key(),
+    };
+    
+    activate_session(state, session.clone());
+    audit(state, AuditEvent::Login { user_id: session.user_id.clone() });
+    resolve_error(state, Subsystem::Auth);
+    
+    // Save to secure storage, next to any other linked accounts
+    if let Err(e) = update_accounts(state, |accounts| accounts.insert(session)) {
+        error!("Failed to save session: {}", e);
+        record_error(state, Subsystem::Storage, e.code(), &e.to_string());
+    } else {
+        resolve_error(state, Subsystem::Storage);
+        // Fresh credentials replace anything that was quarantined
+        state.storage.clear_reset_required();
+    }
+    
+    SessionResponse {
+        access_token: response.access_token,
+        user_id: response.user_id,
+        display_name: response.display_name,
+    }
+}
+
+/// Make `session` the one requests use, stopping work done for another account
+fn activate_session(state: &AppState, session: Session) {
+    let user_id = session.user_id.clone();
+    let previous = state.auth.lock().ok().and_then(|mut auth| {
+        let previous = auth.get_session().map(|session| session.user_id.clone());
+        auth.set_session(session);
+        previous
+    });
+    if previous.is_some_and(|previous| previous != user_id) {
+        state.api.telemetry.set_policy(None);
+        state.shutdown.end_session();
+    }
+}
+
+/// Apply a change to the stored accounts and persist it
+fn update_accounts<R>(state: &AppState, change: impl FnOnce(&mut Accounts) -> R) -> Result<R, StorageError> {
+    let mut accounts = Accounts::load(&state.storage)?;
+    let result = change(&mut accounts);
+    accounts.save(&state.storage)?;
+    Ok(result)
+}
+
+/// Parse a server expiry timestamp, defaulting to the usual 30-day lifetime from `now`
+fn parse_expiry(expires_at: &str, now: chrono::DateTime<chrono::Utc>) -> chrono::DateTime<chrono::Utc> {
+    chrono::DateTime::parse_from_rfc3339(expires_at)
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+        .unwrap_or_else(|_| now + chrono::Duration::days(30))
+}
+
+/// Background services, by name
+pub const TELEMETRY_SERVICE: &str = "telemetry";
+const SESSION_REFRESH_SERVICE: &str = "session_refresh";
+const HEARTBEAT_SERVICE: &str = "heartbeat";
+const REALTIME_SERVICE: &str = "realtime";
+const HEALTH_SERVICE: &str = "health_reporter";
+const QUEUE_DRAIN_SERVICE: &str = "queue_drain";
+const UPDATE_SERVICE: &str = "update_checker";
+const POWER_SERVICE: &str = "power_monitor";
+const REVOCATION_SERVICE: &str = "revocation_watch";
+const CONNECTIVITY_SERVICE: &str = "connectivity";
+const LOCAL_API_SERVICE: &str = "local_api";
+const CHECKPOINT_SERVICE: &str = "checkpointer";
+
+/// First restart delay of a background service that stopped on its own
+const SERVICE_RESTART_DELAY: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Start every background service; only the first call does anything
+pub fn start_services(app: &AppHandle) {
+    app.state::<AppState>().services.init(|_| {
+        start_service(app, TELEMETRY_SERVICE, run_telemetry);
+        start_service(app, SESSION_REFRESH_SERVICE, run_session_refresh);
+        start_service(app, HEARTBEAT_SERVICE, run_heartbeat_scheduler);
+        start_service(app, REALTIME_SERVICE, run_realtime);
+        start_service(app, HEALTH_SERVICE, run_health_reporter);
+        start_service(app, QUEUE_DRAIN_SERVICE, run_queue_drain);
+        start_service(app, UPDATE_SERVICE, run_update_checker);
+        start_service(app, POWER_SERVICE, run_power_monitor);
+        start_service(app, REVOCATION_SERVICE, run_revocation_watch);
+        start_service(app, CONNECTIVITY_SERVICE, run_connectivity_monitor);
+        start_service(app, LOCAL_API_SERVICE, run_local_api);
+        start_service(app, CHECKPOINT_SERVICE, run_checkpointer);
+    });
+}
+
+/// Start one app-scoped service, restarted with backoff if it stops on its own
+fn start_service<F, Fut>(app: &AppHandle, name: &'static str, run: F) -> bool
+where
+    F: Fn(AppHandle, CancellationToken) -> Fut + Send + Sync + 'static,
+    Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+    let state = app.state::<AppState>();
+    let app = app.clone();
+    state.services.start(
+        name,
+        RestartPolicy::Backoff(SERVICE_RESTART_DELAY),
+        state.shutdown.app_token(),
+        move |cancel| Box::pin(run(app.clone(), cancel)),
+    )
+}
+
+/// Longest quitting waits for the background services to clean up
+const QUIT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Stop the background services, waiting for them to save their state, then
+/// exit; every quit path goes through here. Later calls while a quit is
+/// under way do nothing
+pub async fn quit(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    if !state.shutdown.start_quit() {
+        return;
+    }
+    info!("Quitting, stopping background services");
+    let stopped = tokio::time::timeout(QUIT_TIMEOUT, async {
+        // Telemetry first: the polls it drains may still complete a job,
+        // which the other services must be around to handle
+        state.services.stop(TELEMETRY_SERVICE).await;
+        state.services.stop_all().await;
+    });
+    if stopped.await.is_err() {
+        warn!("Background services did not stop within {:?}, exiting anyway", QUIT_TIMEOUT);
+    }
+    // Submissions still in flight are saved as queued
+    write_checkpoint(&state);
+    state.shutdown.quit();
+    app.exit(0);
+}
+
+/// How often queued jobs are retried without being asked to
+const QUEUE_DRAIN_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+/// Retry queued jobs at startup and every few minutes while signed in
+async fn run_queue_drain(app: AppHandle, cancel: CancellationToken) {
+    let mut interval = tokio::time::interval(QUEUE_DRAIN_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = interval.tick() => {}
+        }
+        let session = app.state::<AppState>().shutdown.session_token();
+        if let Some(result) = drain_failed_jobs(&app, &session).await {
+            debug!("Queue drain: {} of {} jobs submitted", result.succeeded, result.attempted);
+        }
+    }
+    debug!("Queue drain stopped");
+}
+
+/// Probe the API and emit `connectivity_changed`; coming back online drains
+/// the jobs queued while offline
+async fn run_connectivity_monitor(app: AppHandle, cancel: CancellationToken) {
+    loop {
+        let state = app.state::<AppState>();
+        let outcome = state.api.ping(connectivity::PROBE_TIMEOUT).await.map_err(|e| e.to_string());
+        let (was_offline, change, delay) = match state.connectivity.lock() {
+            Ok(mut monitor) => {
+                let was_offline = monitor.is_offline();
+                let change = monitor.record(outcome, state.clock.now());
+                (was_offline, change, monitor.next_probe())
             }
-            
-            // Save to secure storage
-            if let Err(e) = state.storage.save("session", &session) {
-                error!("Failed to save session: {}", e);
+            Err(_) => (false, None, connectivity::PROBE_INTERVAL),
+        };
+        if let Some(report) = change {
+            info!("Connectivity: {:?} ({:?} ms)", report.state, report.latency_ms);
+            let _ = app.emit("connectivity_changed", &report);
+            if was_offline && !is_offline(&state) {
+                spawn_failed_job_retry(&app);
             }
-            
-            Ok(VerifyResult {
-                success: true,
-                access_token: Some(response.access_token),
-                user_id: Some(response.user_id),
-                display_name: Some(response.display_name),
-                error: None,
-            })
         }
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = tokio::time::sleep(delay) => {}
+        }
+    }
+    debug!("Connectivity monitor stopped");
+}
+
+/// Local API contents: the install token and the presence heartbeats send,
+/// which already leaves out positions inside privacy zones
+struct LocalClients(AppHandle);
+
+impl local_api::LocalSource for LocalClients {
+    fn token(&self) -> LocalAccessToken {
+        let state = self.0.state::<AppState>();
+        let token = state.local_token.lock().map(|token| token.clone());
+        token.unwrap_or_else(|e| e.into_inner().clone())
+    }
+
+    fn telemetry(&self) -> serde_json::Value {
+        let state = self.0.state::<AppState>();
+        let presence = state.presence.lock().map(|presence| presence.clone()).unwrap_or_default();
+        serde_json::to_value(presence).unwrap_or_default()
+    }
+}
+
+/// Serve the overlay and local integrations, token required
+async fn run_local_api(app: AppHandle, cancel: CancellationToken) {
+    let listener = match tokio::net::TcpListener::bind(local_api::LOCAL_API_ADDR).await {
+        Ok(listener) => listener,
         Err(e) => {
-            error!("Code verification failed: {}", e);
-            Ok(VerifyResult {
-                success: false,
-                access_token: None,
-                user_id: None,
-                display_name: None,
-                error: Some(e.to_string()),
-            })
+            warn!("Local API unavailable on {}: {}", local_api::LOCAL_API_ADDR, e);
+            cancel.cancelled().await;
+            return;
+        }
+    };
+    info!("Local API listening on {}", local_api::LOCAL_API_ADDR);
+    local_api::serve(listener, std::sync::Arc::new(LocalClients(app)), cancel).await;
+}
+
+/// The API is unreachable and work is queued instead of sent
+fn is_offline(state: &AppState) -> bool {
+    state.connectivity.lock()
+        .map(|monitor| monitor.is_offline())
+        .unwrap_or(false)
+}
+
+/// Latest connectivity verdict, latency and failure streak
+#[command]
+pub fn get_connectivity(state: State<'_, AppState>) -> Result<ConnectivityReport, AppError> {
+    Ok(state.connectivity.lock()?.report())
+}
+
+/// How often the background task checks whether the session needs refreshing
+const REFRESH_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+/// Refresh the session in the background shortly before it expires
+async fn run_session_refresh(app: AppHandle, cancel: CancellationToken) {
+    let mut interval = tokio::time::interval(REFRESH_CHECK_INTERVAL);
+    let mut warned_expiry = None;
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = interval.tick() => {}
+        }
+        refresh_session_if_due(&app).await;
+        register_device_key_if_needed(&app).await;
+        warn_session_expiring(&app, &mut warned_expiry);
+    }
+}
+
+/// Register the device key for a session the server doesn't know it for:
+/// one linked before signing, or after the key was replaced
+async fn register_device_key_if_needed(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let Some(public_key) = state.api.device_public_key() else {
+        return;
+    };
+    let due = state.auth.lock().ok().and_then(|auth| {
+        let session = auth.get_session()?;
+        (session.device_key.as_deref() != Some(public_key.as_str()))
+            .then(|| (session.user_id.clone(), session.access_token.clone()))
+    });
+    let Some((user_id, token)) = due else {
+        return;
+    };
+    
+    let registered = match state.api.auth.register_device_key(&token).await {
+        Ok(Some(registered)) => registered,
+        Ok(None) => return,
+        Err(e) => {
+            warn!("Device key registration failed: {}", e);
+            record_api_error(&state, Subsystem::Auth, &e);
+            return;
+        }
+    };
+    let session = state.auth.lock().ok().and_then(|mut auth| {
+        if auth.get_session()?.user_id != user_id {
+            return None;
+        }
+        auth.set_device_key(registered)
+    });
+    if let Some(session) = session {
+        if let Err(e) = update_accounts(&state, |accounts| accounts.insert(session)) {
+            error!("Failed to save session: {}", e);
+        }
+    }
+}
+
+/// Notify once per expiry that the session is about to end
+fn warn_session_expiring(app: &AppHandle, warned_expiry: &mut Option<chrono::DateTime<chrono::Utc>>) {
+    let state = app.state::<AppState>();
+    let expires_at = state.auth.lock().ok().and_then(|auth| auth.expiring_soon());
+    let Some(expires_at) = expires_at else {
+        return;
+    };
+    if warned_expiry.replace(expires_at) == Some(expires_at) {
+        return;
+    }
+    let minutes = (expires_at - state.clock.now()).num_minutes().max(0);
+    notify(
+        app,
+        NotificationEvent::SessionExpiring,
+        Notification::new(
+            "Session expiring",
+            format!("Your session ends in {} h {} min, link the account again to keep syncing", minutes / 60, minutes % 60),
+        ),
+    );
+}
+
+/// Exchange the refresh token if the session is within the refresh window
+async fn refresh_session_if_due(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let due = state.auth.lock().ok().and_then(|auth| {
+        Some((auth.get_session()?.user_id.clone(), auth.refresh_due()?))
+    });
+    let Some((user_id, refresh_token)) = due else {
+        return;
+    };
+    
+    let response = match state.api.auth.refresh_session(&refresh_token).await {
+        Ok(response) => response,
+        Err(ApiError::Unauthorized(reason)) => {
+            // The refresh token was revoked along with the device
+            let current = state.auth.lock().ok()
+                .and_then(|auth| auth.get_session().map(|session| session.user_id == user_id));
+            if current == Some(true) {
+                revoke_session(app, &reason);
+            }
+            return;
+        }
+        Err(e) => {
+            warn!("Session refresh failed: {}", e);
+            record_api_error(&state, Subsystem::Auth, &e);
+            return;
+        }
+    };
+    
+    // None if the user logged out or switched accounts while the request was in flight
+    let session = state.auth.lock().ok().and_then(|mut auth| {
+        if auth.get_session()?.user_id != user_id {
+            return None;
+        }
+        auth.refresh_session(response.access_token, response.refresh_token, parse_expiry(&response.expires_at, state.clock.now()))
+    });
+    if let Some(session) = session {
+        audit(&state, AuditEvent::SessionRefreshed { user_id: session.user_id.clone() });
+        resolve_error(&state, Subsystem::Auth);
+        if let Err(e) = update_accounts(&state, |accounts| accounts.insert(session.clone())) {
+            error!("Failed to save refreshed session: {}", e);
+            record_error(&state, Subsystem::Storage, e.code(), &e.to_string());
+        }
+        let _ = app.emit("session_refreshed", SessionResponse::from(session));
+    }
+}
+
+/// Sign out as soon as the server rejects the current access token
+async fn run_revocation_watch(app: AppHandle, cancel: CancellationToken) {
+    let mut revocations = app.state::<AppState>().api.revocations();
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            changed = revocations.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+            }
+        }
+        let revoked = revocations.borrow_and_update().clone();
+        // A token replaced by a refresh or another sign-in is no loss
+        if revoked.is_some() && revoked == current_token(&app.state::<AppState>()) {
+            revoke_session(&app, "Access token rejected");
         }
     }
+    debug!("Revocation watch stopped");
+}
+
+/// Drop a session the server no longer accepts and send the UI back to sign in
+fn revoke_session(app: &AppHandle, reason: &str) {
+    warn!("Session revoked by the server: {}", reason);
+    end_session(&app.state::<AppState>());
+    publish_connection_status(app);
+    let _ = app.emit("session_revoked", reason);
 }
 
 /// Logout and clear session
 #[command]
-pub async fn logout(state: State<'_, AppState>) -> Result<(), String> {
+pub async fn logout(state: State<'_, AppState>) -> Result<(), AppError> {
     info!("Logging out");
     
     // Get token before clearing
-    let token = state.auth.lock()
-        .ok()
-        .and_then(|auth| auth.get_access_token().map(|s| s.to_string()));
+    let token = current_token(&state);
     
     // Notify server
     if let Some(token) = token {
-        let _ = state.api.disconnect(&token).await;
+        let _ = state.api.telemetry.disconnect(&token).await;
     }
     
+    end_session(&state);
+    Ok(())
+}
+
+/// Forget the signed-in user locally
+fn end_session(state: &AppState) {
     // Clear auth manager
-    if let Ok(mut auth) = state.auth.lock() {
+    let user_id = state.auth.lock().ok().and_then(|mut auth| {
+        let user_id = auth.get_session().map(|session| session.user_id.clone());
         auth.clear_session();
+        user_id
+    });
+    audit(state, AuditEvent::Logout { user_id });
+    state.api.telemetry.set_policy(None);
+    
+    // Stop uploads and retries running for the old session
+    state.shutdown.end_session();
+    
+    // Forget the active account's stored session; other linked accounts stay
+    let removed = update_accounts(state, |accounts| {
+        if let Some(active) = accounts.active.clone() {
+            accounts.remove(&active);
+        }
+    });
+    if let Err(e) = removed {
+        error!("Failed to remove stored session: {}", e);
+    }
+}
+
+/// Accounts linked on this PC
+#[command]
+pub fn list_accounts(state: State<'_, AppState>) -> Result<Vec<AccountSummary>, AppError> {
+    let accounts = Accounts::load(&state.storage)?;
+    Ok(accounts.summaries(state.clock.now()))
+}
+
+/// Make another linked account the one submissions are sent for
+#[command]
+pub async fn switch_account(
+    user_id: String,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<SessionResponse, AppError> {
+    let accounts = Accounts::load(&state.storage)?;
+    let session = accounts.sessions.get(&user_id).cloned()
+        .ok_or_else(|| AppError::NotFound(format!("No linked account {}", user_id)))?;
+    if session.is_expired_at(state.clock.now()) {
+        return Err(AppError::AuthExpired(format!("session of {} has expired, link the account again", session.display_name)));
+    }
+    info!("Switching to account {}", user_id);
+    update_accounts(&state, |accounts| {
+        accounts.switch(&user_id);
+    })?;
+    
+    // Let the server know this device left the previous account
+    if let Some(token) = current_token(&state).filter(|token| *token != session.access_token) {
+        let _ = state.api.telemetry.disconnect(&token).await;
     }
     
-    // Delete stored session
-    let _ = state.storage.delete("session");
+    activate_session(&state, session.clone());
+    audit(&state, AuditEvent::Login { user_id });
     
-    Ok(())
+    publish_connection_status(&app);
+    let response = SessionResponse::from(session);
+    let _ = app.emit("account_switched", &response);
+    Ok(response)
 }
 
-/// Start telemetry reader
+/// Unlink an account from this PC, signing out if it is the active one
 #[command]
-pub fn start_telemetry(app: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
-    debug!("Starting telemetry");
+pub async fn remove_account(user_id: String, app: AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    let active = state.auth.lock()
+        .map(|auth| auth.get_session().is_some_and(|session| session.user_id == user_id))
+        .unwrap_or(false);
+    if active {
+        logout(state).await?;
+        publish_connection_status(&app);
+        return Ok(());
+    }
     
-    // Check if already running?
-    // For simplicity, we just spawn. A better way uses atomic bool or similar.
-    // But since this is usually called once on mount...
+    info!("Removing account {}", user_id);
+    update_accounts(&state, |accounts| accounts.remove(&user_id))?
+        .ok_or_else(|| AppError::NotFound(format!("No linked account {}", user_id)))?;
+    Ok(())
+}
+
+/// Start the telemetry reader after `stop_telemetry`; it starts with the app
+/// and does nothing if it is already running
+#[command]
+pub fn start_telemetry(app: AppHandle) {
+    start_service(&app, TELEMETRY_SERVICE, run_telemetry);
+}
+
+/// Poll the game and act on what changed until `cancel` fires
+async fn run_telemetry(app_handle: AppHandle, cancel: CancellationToken) {
+    let mut poll_interval = app_handle.state::<AppState>().settings.lock()
+        .map(|settings| settings.general.poll_interval())
+        .unwrap_or(std::time::Duration::from_millis(100));
+    let telemetry = &app_handle.state::<AppState>().telemetry;
+    telemetry.set_interval(poll_interval);
+    telemetry.start();
+    let mut throttle = FrameThrottle::new();
+    let mut maintenance = MaintenanceTracker::new();
+    let mut speeding = SpeedingDetector::new();
+    let mut collisions = CollisionDetector::new();
+    let mut tachograph = Tachograph::new();
+    let mut taskbar = TaskbarIndicator::new();
+    let mut privacy = PrivacyGuard::new();
+    let mut focus = FocusTracker::new();
+    let mut minimap = MiniMapFeed::new();
     
-    let app_handle = app.clone();
-    let state_handle = state.inner().clone(); // AppState likely needs to be Clone or wrapped in Arc? 
-    // AppState fields are Mutex/Arc safe. But AppState struct itself is not Clone/Arc'd by default in Tauri management?
-    // Actually `State` wraps it. `state.inner()` gives reference.
-    // We need to clone the Arcs inside AppState. "inner().clone()" works if AppState implements Clone.
-    // Let's check lib.rs for AppState definition. It has Mutex field. Mutex is not Clone.
-    // We need Arc<Mutex<...>>.
-    // In lib.rs: pub auth: Mutex<AuthManager>. NOT Arc.
-    // This is a problem for spawning tasks. The State stays alive, but we can't move reference into 'static task.
-    // We can use `app_handle.state::<AppState>()` inside the task? Yes.
+    // Polls queued when the loop was cancelled, still to be handled
+    let mut drained: Option<std::vec::IntoIter<Poll>> = None;
     
-    tauri::async_runtime::spawn(async move {
-        let mut interval = tokio::time::interval(std::time::Duration::from_millis(100));
+    loop {
+        // 1. Take the reader thread's next poll; once cancelled, stop the
+        // reader and finish the polls it already queued (completed jobs!)
+        let poll = match drained.as_mut() {
+            Some(queued) => queued.next(),
+            None => tokio::select! {
+                _ = cancel.cancelled() => {
+                    drained = Some(telemetry.stop_and_drain().await.into_iter());
+                    continue;
+                }
+                poll = telemetry.next_poll() => {
+                    if poll.is_none() {
+                        warn!("Telemetry thread stopped");
+                    }
+                    poll
+                }
+            },
+        };
+        let Some(Poll { events, snapshot }) = poll else {
+            break;
+        };
         
-        loop {
-            interval.tick().await;
-            
-            let state = app_handle.state::<AppState>();
-            let mut event_to_emit: Option<crate::telemetry::TelemetryEvent> = None;
-            let mut telemetry_data: Option<crate::telemetry::TelemetryState> = None;
+        let state = app_handle.state::<AppState>();
+        
+        if events.iter().any(crate::telemetry::TelemetryEvent::changes_job) {
+            request_checkpoint(&state);
+        }
+        let activity = snapshot.activity;
+        match &snapshot.read_error {
+            Some(e) => record_error(&state, Subsystem::Telemetry, e.code(), &e.to_string()),
+            None if snapshot.state.connected => resolve_error(&state, Subsystem::Telemetry),
+            None => {}
+        }
+        // Game and multiplayer server the poll's jobs were driven on, and
+        // whether they were only simulated
+        let session = (snapshot.state.game.unwrap_or(Game::Ets2), snapshot.state.server.clone(), snapshot.state.simulated);
+        let mut telemetry_data = Some(snapshot.state);
+        
+        // Adapt the poll rate: settings-driven while driving, slow while
+        // the game is closed or paused
+        if let Ok(settings) = state.settings.lock() {
+            let wanted = settings.general.poll_interval_for(activity);
+            if wanted != poll_interval {
+                debug!("Polling telemetry every {:?} ({:?})", wanted, activity);
+                poll_interval = wanted;
+                telemetry.set_interval(poll_interval);
+            }
+        }
+        
+        // 2. Maintenance reminders
+        if let Some(wear) = telemetry_data.as_ref().and_then(|data| data.truck_wear.as_ref()) {
+            let thresholds = state.settings.lock()
+                .map(|settings| settings.maintenance.clone())
+                .unwrap_or_default();
+            for reminder in maintenance.observe(wear, &thresholds) {
+                info!("Maintenance reminder: {}", reminder.message);
+                let _ = app_handle.emit("maintenance_reminder", &reminder);
+            }
+        }
+        
+        // 3. Speeding and collision detection
+        let on_job = telemetry_data.as_ref().is_some_and(|data| data.active_job.is_some());
+        if let Some(data) = telemetry_data.as_ref() {
+            let profile = state.settings.lock()
+                .map(|settings| settings.speeding.active_profile(data.multiplayer))
+                .unwrap_or_else(|_| SpeedingSettings::default().active_profile(data.multiplayer));
+            let violation = speeding.observe(data.speed, data.speed_limit, &profile, state.clock.now());
+            if let Some(violation) = violation {
+                debug!("Speeding: {:.0} km/h in a {:.0} zone", violation.max_speed, violation.speed_limit);
+                let _ = app_handle.emit("speeding_violation", &violation);
+                if on_job {
+                    record_conduct(&state, |conduct| conduct.record(violation));
+                    request_checkpoint(&state);
+                }
+            }
+            if let Some(collision) = collisions.observe(&data.damage, state.clock.now()) {
+                debug!("Collision: +{:.1}% chassis, +{:.1}% cargo", collision.chassis_damage, collision.cargo_damage);
+                let _ = app_handle.emit("collision", &collision);
+                if on_job {
+                    record_conduct(&state, |conduct| conduct.record_collision(collision));
+                    request_checkpoint(&state);
+                }
+            }
+            for event in tachograph.observe(data, state.clock.now()) {
+                if let TachographEvent::Warning(warning) = &event {
+                    info!("Tachograph: {:?} after {} minutes of driving", warning.rule, warning.stint_minutes);
+                    let _ = app_handle.emit("tachograph_warning", warning);
+                }
+                if on_job {
+                    record_conduct(&state, |conduct| conduct.tachograph.record(&event));
+                }
+            }
+        }
+        
+        // 4. Live map positions (opt-in) and convoy route, paused inside privacy zones
+        if let Some(data) = telemetry_data.as_mut() {
+            let zones = state.settings.lock()
+                .map(|settings| settings.privacy.clone())
+                .unwrap_or_default();
+            if let Some(change) = privacy.observe(data.position.as_ref(), &zones) {
+                info!("Privacy zone {}", if change.active { "entered" } else { "left" });
+                let _ = app_handle.emit("privacy_zone", &change);
+                if change.active {
+                    // Drop the unsent approach to the zone as well
+                    if let Ok(mut batcher) = state.positions.lock() {
+                        batcher.clear();
+                    }
+                }
+            }
             
-            // 1. Update Telemetry
-            if let Ok(mut telemetry) = state.telemetry.lock() {
-                 if let Some(event) = telemetry.update() {
-                     event_to_emit = Some(event);
-                 }
-                 telemetry_data = Some(telemetry.get_state().clone());
+            if privacy.is_active() {
+                // Keep the location out of the overlay and presence too
+                data.position = None;
+            } else {
+                record_position(&app_handle, &state, data);
+                if let (Some(position), Ok(mut convoy)) = (&data.position, state.convoy.lock()) {
+                    if let Some(convoy) = convoy.as_mut() {
+                        convoy.record_position(position.x, position.z, state.clock.now());
+                    }
+                }
             }
+            record_convoy_attendance(&app_handle, &state, data);
             
-            // 2. Emit to Frontend
-            if let Some(data) = telemetry_data {
-                // Emit raw state, or specific event? 
-                // Dashboard expects current state.
-                let _ = app_handle.emit("telemetry_update", &data);
+            if let Some(position) = minimap.next(data.game, data.position.as_ref(), std::time::Instant::now()) {
+                if let Ok(mut current) = state.map_position.lock() {
+                    current.clone_from(&position);
+                }
+                let _ = app_handle.emit("map_position", &position);
+            }
+        }
+        
+        // 5. Game window focus (overlay, toasts, AFK)
+        if focus.poll_due(std::time::Instant::now()) {
+            let connected = telemetry_data.as_ref().is_some_and(|data| data.connected);
+            let settings = state.settings.lock()
+                .map(|settings| settings.focus.clone())
+                .unwrap_or_default();
+            let now = state.clock.now();
+            let changed = match focus::sample().filter(|_| connected) {
+                Some(sample) => focus.observe(sample, &settings, now).is_some(),
+                None => focus.reset(now),
+            };
+            if changed {
+                apply_focus(&app_handle, &state, &focus, &settings);
+            }
+        }
+        
+        // 6. Emit to Frontend (coalesced when the webview can't keep up)
+        if let Some(data) = telemetry_data {
+            if let Ok(mut presence) = state.presence.lock() {
+                *presence = Presence::from_state(&data);
+            }
+            let window = app_handle.get_webview_window("main");
+            if let Some(window) = &window {
+                taskbar.update(window, TaskbarProgress::from_state(&data, state.clock.now()));
+            }
+            let sync = state.sync_health.lock().map(|health| health.status()).unwrap_or(SyncStatus::Unknown);
+            let status_changed = state.tray.lock()
+                .map(|mut tray| tray.update(&app_handle, TrayView::from_state(&data, sync)))
+                .unwrap_or(false);
+            if status_changed {
+                refresh_tray_tooltip(&app_handle);
             }
+            let window_active = window
+                .map(|w| {
+                    w.is_visible().unwrap_or(false) && !w.is_minimized().unwrap_or(false)
+                })
+                .unwrap_or(false);
+            let now = std::time::Instant::now();
             
-            // 3. Handle Events (Sync)
-            if let Some(event) = event_to_emit {
-                match event {
-                    crate::telemetry::TelemetryEvent::Connected(game) => {
-                         info!("Game connected: {}", game);
+            if throttle.should_emit(&data, window_active, now) {
+                match app_handle.emit("telemetry_update", &data) {
+                    Ok(()) => throttle.record_success(data, now),
+                    Err(e) => {
+                        debug!(target: logging::FRAME_TARGET, "Failed to emit telemetry update: {}", e);
+                        throttle.record_failure(now);
                     }
-                    crate::telemetry::TelemetryEvent::Disconnected => {
-                        info!("Game disconnected");
+                }
+            }
+        }
+        
+        // 7. Handle Events (Sync)
+        let window_ended = state.server_status.lock()
+            .map(|mut status| status.expire(state.clock.now()))
+            .unwrap_or(false);
+        if window_ended {
+            leave_maintenance(&app_handle);
+        }
+        for event in events {
+            if let Some(typed) = FrontendEvent::from_telemetry(&event) {
+                let _ = app_handle.emit(typed.name(), &typed);
+            }
+            match event {
+                crate::telemetry::TelemetryEvent::Connected(game) => {
+                     info!("Game connected: {}", game);
+                     notify(
+                         &app_handle,
+                         NotificationEvent::GameConnected,
+                         Notification::new("Game connected", format!("Tracking {}", game.title())),
+                     );
+                }
+                crate::telemetry::TelemetryEvent::Disconnected => {
+                    info!("Game disconnected");
+                    // The next session may load a save with other damage
+                    collisions = CollisionDetector::new();
+                }
+                crate::telemetry::TelemetryEvent::JobStarted(job) => {
+                    info!("Job started: {} -> {}", job.source_city, job.destination_city);
+                    forget_recovered_job(&state);
+                    record_conduct(&state, |conduct| *conduct = ConductReport::default());
+                    focus.take_afk_periods(state.clock.now());
+                }
+                crate::telemetry::TelemetryEvent::JobResumed(job) => {
+                    info!("Job resumed: {} -> {} (leg {})", job.source_city, job.destination_city, job.legs.len());
+                    forget_recovered_job(&state);
+                }
+                crate::telemetry::TelemetryEvent::JobCancelled { job, .. } => {
+                    info!("Job cancelled: {} -> {}", job.source_city, job.destination_city);
+                    forget_recovered_job(&state);
+                    record_conduct(&state, |conduct| *conduct = ConductReport::default());
+                    focus.take_afk_periods(state.clock.now());
+                }
+                crate::telemetry::TelemetryEvent::JobCompleted(job) => {
+                    info!("Job completed: {} -> {}", job.source_city, job.destination_city);
+                    forget_recovered_job(&state);
+                    play_sound(&state, SoundEvent::JobDelivered);
+                    
+                    let convoy_id = state.convoy.lock().ok().and_then(|mut convoy| {
+                        convoy.as_mut().map(|convoy| {
+                            convoy.record_job(&job, state.clock.now());
+                            convoy.id.clone()
+                        })
+                    });
+                    
+                    let (game, server, simulated) = session.clone();
+                    // Kept for the violations report until the next job starts
+                    let (conduct, tachograph) = state.conduct.lock()
+                        .map(|conduct| (conduct.summary(), serde_json::json!(conduct.tachograph)))
+                        .unwrap_or_default();
+                    // The local route keeps every point; the upload only
+                    // what the live map and privacy zones allow
+                    let route = route::from_snapshots(&job.snapshots);
+                    let (live_map, privacy) = state.settings.lock()
+                        .map(|settings| (settings.live_map.enabled, settings.privacy.clone()))
+                        .unwrap_or_default();
+                    let shared = crate::telemetry::ActiveJob {
+                        snapshots: job.snapshots.shareable(live_map, &privacy),
+                        ..job.clone()
+                    };
+                    let mut submission = crate::sync::JobSubmission::from_completed(&shared, game)
+                        .with_telemetry("conduct", conduct)
+                        .with_telemetry("tachograph", tachograph);
+                    submission = with_vehicle_ids(&state, &job, submission);
+                    submission.server = server;
+                    if simulated {
+                        submission = submission.with_telemetry("simulated", true.into());
+                    }
+                    if let Some(convoy_id) = convoy_id {
+                        submission = submission.with_telemetry("convoyId", convoy_id.into());
                     }
-                    crate::telemetry::TelemetryEvent::JobCompleted(job) => {
-                        info!("Job completed: {} -> {}", job.source_city, job.destination_city);
-                        
-                        // Submit to API
-                        // We need token
-                        let token = state.auth.lock()
-                            .ok()
-                            .and_then(|auth| auth.get_access_token().map(|s| s.to_string()));
-                            
-                        if let Some(token) = token {
-                            // Construct submission
-                            // We need to map ActiveJob to JobSubmission
-                             let submission = crate::sync::JobSubmission {
-                                 game: "ets2".to_string(), // TODO: Get from telemetry state
-                                 cargo: job.cargo.clone(),
-                                 source_city: job.source_city.clone(),
-                                 destination_city: job.destination_city.clone(),
-                                 distance_km: job.distance_km,
-                                 revenue: job.revenue as f64,
-                                 damage_percent: 0.0, // TODO: Read damage
-                                 truck_id: None,
-                                 trailer_id: None,
-                                 telemetry_data: None,
-                                 server: None,
-                             };
-                             
-                             // Spawn sync to avoid blocking loop?
-                             // submit_job is async, we are in async task.
-                             if let Err(e) = state.api.submit_job(&token, &submission).await {
-                                 error!("Failed to submit job: {}", e);
-                             }
+                    let afk_periods = focus.take_afk_periods(state.clock.now());
+                    if !afk_periods.is_empty() {
+                        submission = submission.with_telemetry("afkPeriods", serde_json::json!(afk_periods));
+                    }
+                    spawn_job_submission(&app_handle, submission, route);
+                }
+                crate::telemetry::TelemetryEvent::Gameplay(event) => {
+                    info!("Gameplay event: {}", event.kind.name());
+                    if let crate::telemetry::GameplayEventKind::Fine { offence, amount } = &event.kind {
+                        play_sound(&state, SoundEvent::FineReceived);
+                        if on_job {
+                            let offence = TrafficOffence { at: state.clock.now(), offence: offence.clone(), amount: *amount };
+                            record_conduct(&state, |conduct| conduct.record_offence(offence));
+                            request_checkpoint(&state);
                         }
                     }
-                    _ => {}
+                    let _ = app_handle.emit("gameplay_event", &event);
                 }
+                _ => {}
             }
         }
-    });
+    }
     
+    // Persist the latest progress before the app goes away
+    let state = app_handle.state::<AppState>();
+    write_checkpoint(&state);
+    if let Ok(mut presence) = state.presence.lock() {
+        *presence = Presence::default();
+    }
+    if focus.reset(state.clock.now()) {
+        let settings = state.settings.lock()
+            .map(|settings| settings.focus.clone())
+            .unwrap_or_default();
+        apply_focus(&app_handle, &state, &focus, &settings);
+    }
+    info!("Telemetry loop stopped");
+}
+
+/// Change the current job's conduct report
+fn record_conduct(state: &AppState, change: impl FnOnce(&mut ConductReport)) {
+    if let Ok(mut conduct) = state.conduct.lock() {
+        change(&mut conduct);
+    }
+}
+
+/// Fill in the local IDs of the job's truck and trailer
+fn with_vehicle_ids(state: &AppState, job: &ActiveJob, submission: JobSubmission) -> JobSubmission {
+    let Ok(mut vehicles) = state.vehicles.lock() else {
+        return submission;
+    };
+    let now = state.clock.now();
+    let truck_id = job.truck.as_ref().map(|truck| vehicles.truck_id(truck, now));
+    let trailer_id = job.trailer.as_ref().map(|trailer| vehicles.trailer_id(trailer, now));
+    if let Err(e) = vehicles.save(&state.storage) {
+        warn!("Failed to save vehicle cache: {}", e);
+    }
+    submission.with_vehicle_ids(truck_id, trailer_id)
+}
+
+/// Submit a finished job in the background, so a slow or rate-limited API
+/// never holds up the telemetry loop. Logging out stops it, leaving the job
+/// queued for a later retry
+fn spawn_job_submission(app: &AppHandle, submission: JobSubmission, route: Vec<RoutePoint>) {
+    let app = app.clone();
+    let cancel = app.state::<AppState>().shutdown.session_token();
+    tauri::async_runtime::spawn(async move {
+        let unsent = submission.clone();
+        tokio::select! {
+            // Start submitting first, so the job is at least in the history
+            biased;
+            _ = submit_job(&app, submission, &route) => {}
+            _ = cancel.cancelled() => {
+                debug!("Submission of {} -> {} interrupted, keeping it queued", unsent.source_city, unsent.destination_city);
+                update_queue(&app.state::<AppState>(), |failed| failed.release(&unsent));
+            }
+        }
+    });
+}
+
+/// Submit a finished job, queueing it for a retry if that fails or no
+/// account is signed in
+async fn submit_job(app: &AppHandle, submission: JobSubmission, route: &[RoutePoint]) {
+    let state = app.state::<AppState>();
+    // Kept locally even when it never reaches the platform
+    record_history(&state, &submission, HistoryStatus::Pending, state.clock.now());
+    record_route(&state, &submission, route);
+    
+    let rules = state.settings.lock()
+        .map(|settings| settings.rules.clone())
+        .unwrap_or_default();
+    if let Some(reason) = rules.check(&submission) {
+        info!("Not submitting {} -> {}: {}", submission.source_city, submission.destination_city, reason);
+        if let Ok(history) = state.history.lock() {
+            if let Err(e) = history.set_status(&submission, HistoryStatus::Skipped, None, Some(&reason.to_string())) {
+                warn!("{}", e);
+            }
+        }
+        return;
+    }
+    
+    // Queued without an account, for whoever signs in next to send
+    let Some((user_id, token)) = current_account(&state) else {
+        info!("Signed out, queueing job {} -> {} for later", submission.source_city, submission.destination_city);
+        update_queue(&state, |failed| failed.push(None, submission));
+        return;
+    };
+    
+    // Hold the job until the maintenance window ends or the API is reachable
+    if submissions_paused(&state) || is_offline(&state) {
+        info!("Server in maintenance or offline, queueing job for later");
+        update_queue(&state, |failed| failed.push(Some(&user_id), submission));
+        return;
+    }
+    
+    // Queued while it is sent, so a checkpoint meanwhile keeps it
+    update_queue(&state, |failed| failed.begin(&user_id, submission.clone()));
+    let result = state.api.telemetry.submit_job(&token, &submission).await;
+    record_submission(app, &submission, &result);
+    match result {
+        Ok(_) => {
+            update_queue(&state, |failed| failed.resolve(&submission));
+            notify(
+                app,
+                NotificationEvent::JobSynced,
+                Notification::new(
+                    "Job synced",
+                    format!("{} → {} ({})", submission.source_city, submission.destination_city, submission.cargo),
+                ),
+            );
+        }
+        Err(ApiError::Maintenance(retry_at)) => {
+            update_queue(&state, |failed| failed.release(&submission));
+            enter_maintenance(app, None, retry_at);
+        }
+        Err(e) => {
+            error!("Failed to submit job: {}", e);
+            play_sound(&state, SoundEvent::SyncFailed);
+            requeue(&state, &submission, &e);
+            notify(
+                app,
+                NotificationEvent::SyncFailed,
+                Notification::new("Submission failed", e.to_string())
+                    .action("Retry", ToastAction::RetryFailedJobs)
+                    .action("View details", ToastAction::ViewSyncErrors),
+            );
+        }
+    }
+}
+
+/// The recovered job was picked up or replaced by the game
+fn forget_recovered_job(state: &AppState) {
+    if let Ok(mut recovered) = state.recovered_job.lock() {
+        recovered.take();
+    }
+}
+
+/// Offer the job interrupted in the last run to the frontend
+pub fn emit_recovered_job(app: &AppHandle, state: &AppState) {
+    let recovered = state.recovered_job.lock().ok().and_then(|recovered| recovered.clone());
+    if let Some(recovered) = recovered {
+        let _ = app.emit("job_recovery_available", &recovered);
+    }
+}
+
+/// Job interrupted in the last run that still awaits a decision
+#[command]
+pub fn get_recovered_job(state: State<'_, AppState>) -> Option<RecoveredJob> {
+    state.recovered_job.lock().ok().and_then(|recovered| recovered.clone())
+}
+
+/// Resume the job interrupted in the last run, submit it as a partial
+/// record, or discard it
+#[command]
+pub async fn recover_active_job(
+    action: RecoveryAction,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    if state.recovered_job.lock()?.is_none() {
+        return Err(AppError::NotFound("No interrupted job to recover".into()));
+    }
+    
+    // Stop tracking the job before it is handed over; a stopped reader
+    // isn't tracking it anyway
+    let game = if action == RecoveryAction::Resume {
+        None
+    } else {
+        state.telemetry.run_async(|telemetry| {
+            telemetry.abandon_job();
+            telemetry.get_state().game
+        }).await.unwrap_or_else(|| {
+            warn!("Telemetry reader has stopped, assuming the recovered job is from ETS2");
+            None
+        })
+    };
+    
+    let Some(recovered) = state.recovered_job.lock()?.take() else {
+        return Err(AppError::NotFound("No interrupted job to recover".into()));
+    };
+    info!(
+        "Recovered job {} -> {}: {:?}",
+        recovered.job.source_city, recovered.job.destination_city, action
+    );
+    
+    // Tracking was restored at startup and carries on
+    if action == RecoveryAction::Resume {
+        return Ok(());
+    }
+    
+    record_conduct(&state, |conduct| *conduct = ConductReport::default());
+    
+    if action == RecoveryAction::SubmitPartial {
+        let submission = checkpoint::partial_submission(&recovered, game.unwrap_or(Game::Ets2));
+        let submission = with_vehicle_ids(&state, &recovered.job, submission);
+        submit_job(&app, submission, &route::from_snapshots(&recovered.job.snapshots)).await;
+    }
+    Ok(())
+}
+
+/// Stop detecting and submitting jobs while staying connected to the game
+#[command]
+pub fn pause_tracking(app: AppHandle) -> Result<(), AppError> {
+    set_tracking_paused(&app, true)
+}
+
+/// Detect and submit jobs again; a job taken while paused stays untracked
+#[command]
+pub fn resume_tracking(app: AppHandle) -> Result<(), AppError> {
+    set_tracking_paused(&app, false)
+}
+
+/// Pause or resume job tracking, from a command or the tray menu
+pub fn set_tracking_paused(app: &AppHandle, paused: bool) -> Result<(), AppError> {
+    let state = app.state::<AppState>();
+    let now = state.clock.now();
+    // Called on the UI thread, so the reader is not waited for
+    if !state.telemetry.post(move |telemetry| telemetry.set_tracking_paused(paused, now)) {
+        return Err(AppError::TelemetryUnavailable("the telemetry reader has stopped".into()));
+    }
+    info!("Job tracking {}", if paused { "paused" } else { "resumed" });
+    let _ = app.emit("tracking_paused", paused);
+    Ok(())
+}
+
+/// Stop the telemetry reader and wait for it to save its progress
+#[command]
+pub async fn stop_telemetry(state: State<'_, AppState>) -> Result<(), AppError> {
+    state.services.stop(TELEMETRY_SERVICE).await;
+    Ok(())
+}
+
+/// Overlay window that follows game focus
+const OVERLAY_LABEL: &str = "overlay";
+
+/// Publish a focus change and show or hide the overlay to match
+fn apply_focus(app: &AppHandle, state: &AppState, tracker: &FocusTracker, settings: &FocusSettings) {
+    let current = tracker.current();
+    debug!("Game focus changed: {:?}", current);
+    if let Ok(mut focus) = state.game_focus.lock() {
+        *focus = current;
+    }
+    let _ = app.emit("game_focus", current);
+
+    if let (true, Some(overlay)) = (settings.overlay_follows_game, app.get_webview_window(OVERLAY_LABEL)) {
+        let result = if current.is_some_and(|focus| focus.focused) {
+            overlay.show()
+        } else {
+            overlay.hide()
+        };
+        if let Err(e) = result {
+            debug!("Failed to toggle overlay: {}", e);
+        }
+    }
+}
+
+/// Play the configured sound for `event`
+fn play_sound(state: &AppState, event: SoundEvent) {
+    // Prefer window focus; fall back to whether the game is running at all
+    let focused = state.game_focus.lock().ok().and_then(|focus| focus.map(|focus| focus.focused));
+    let in_game = focused.unwrap_or_else(|| {
+        state.telemetry.snapshot().state.connected
+    });
+    if let Ok(settings) = state.settings.lock() {
+        state.sounds.play(event, &settings.sounds, in_game);
+    }
+}
+
+/// Append to the audit log; failures are logged, never fatal
+fn audit(state: &AppState, event: AuditEvent) {
+    if let Ok(mut log) = state.audit.lock() {
+        if let Err(e) = log.append(event, state.clock.now()) {
+            warn!("{}", e);
+        }
+    }
+}
+
+/// Remember a subsystem failure for the troubleshooting panel
+fn record_error(state: &AppState, subsystem: Subsystem, code: &str, message: &str) {
+    if let Ok(mut errors) = state.last_errors.lock() {
+        errors.record(subsystem, code, message, state.clock.now());
+    }
+}
+
+fn record_api_error(state: &AppState, subsystem: Subsystem, error: &ApiError) {
+    record_error(state, subsystem, error.code(), &error.to_string());
+}
+
+/// Mark the subsystem's last error as resolved
+fn resolve_error(state: &AppState, subsystem: Subsystem) {
+    if let Ok(mut errors) = state.last_errors.lock() {
+        errors.resolve(subsystem, state.clock.now());
+    }
+}
+
+/// Add a delivered job to the local history; failures are logged, never fatal
+fn record_history(state: &AppState, submission: &JobSubmission, status: HistoryStatus, delivered_at: chrono::DateTime<chrono::Utc>) {
+    if let Ok(history) = state.history.lock() {
+        if let Err(e) = history.record(submission, status, delivered_at) {
+            warn!("{}", e);
+        }
+    }
+}
+
+/// Keep the job's route with its history entry; failures are logged, never fatal
+fn record_route(state: &AppState, submission: &JobSubmission, route: &[RoutePoint]) {
+    if route.is_empty() {
+        return;
+    }
+    if let Ok(history) = state.history.lock() {
+        if let Err(e) = history.record_route(submission, route) {
+            warn!("{}", e);
+        }
+    }
+}
+
+/// Server job ID of a submission already accepted with the same
+/// idempotency key
+fn confirmed_job_id(state: &AppState, submission: &JobSubmission) -> Option<String> {
+    let history = state.history.lock().ok()?;
+    history.confirmed_job_id(submission).unwrap_or_else(|e| {
+        warn!("{}", e);
+        None
+    })
+}
+
+/// Record a job submission outcome in sync health, the audit log and the
+/// local history
+fn record_submission(app: &AppHandle, submission: &JobSubmission, result: &Result<JobResponse, ApiError>) {
+    let state = app.state::<AppState>();
+    match result {
+        Ok(response) => audit(&state, AuditEvent::submitted(submission, &response.job_id)),
+        Err(e) => audit(&state, AuditEvent::submission_failed(submission, &e.to_string())),
+    }
+    let update = match result {
+        Ok(response) => (HistoryStatus::Submitted, Some(response.job_id.clone()), None),
+        // Queued until the maintenance window ends
+        Err(ApiError::Maintenance(_)) => (HistoryStatus::Pending, None, None),
+        Err(e) => (HistoryStatus::Failed, None, Some(e.to_string())),
+    };
+    if let Ok(history) = state.history.lock() {
+        if let Err(e) = history.set_status(submission, update.0, update.1.as_deref(), update.2.as_deref()) {
+            warn!("{}", e);
+        }
+    }
+    match result {
+        Ok(_) => resolve_error(&state, Subsystem::Sync),
+        Err(e) => record_api_error(&state, Subsystem::Sync, e),
+    }
+    record_sync(app, SyncChannel::Submission, result.as_ref().map(|_| ()).map_err(|e| e.to_string()));
+}
+
+/// Record a sync outcome and refresh the tray tooltip
+fn record_sync(app: &AppHandle, channel: SyncChannel, outcome: Result<(), String>) {
+    let state = app.state::<AppState>();
+    if let Ok(mut health) = state.sync_health.lock() {
+        health.record(channel, outcome, state.clock.now());
+    }
+    refresh_tray_tooltip(app);
+    publish_connection_status(app);
+}
+
+/// Tray tooltip: sync status plus the most important health warning
+fn refresh_tray_tooltip(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let Some(sync) = state.sync_health.lock().ok().map(|health| health.tooltip(state.clock.now())) else {
+        return;
+    };
+    let status = state.tray.lock().map(|tray| tray.status()).unwrap_or_default();
+    let mut tooltip = format!("{}\n{}", sync, status.label());
+    let warning = state.app_health.lock()
+        .ok()
+        .and_then(|health| health.as_ref().and_then(|health| health.warnings.first().cloned()));
+    if let Some(warning) = warning {
+        tooltip.push('\n');
+        tooltip.push_str(&warning);
+    }
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let _ = tray.set_tooltip(Some(tooltip));
+    }
+}
+
+/// Whether the server asked us to hold back submissions
+fn submissions_paused(state: &AppState) -> bool {
+    state.server_status.lock()
+        .map(|status| status.is_paused(state.clock.now()))
+        .unwrap_or(false)
+}
+
+/// Pause submissions and show the maintenance banner
+fn enter_maintenance(app: &AppHandle, message: Option<String>, retry_at: Option<chrono::DateTime<chrono::Utc>>) {
+    let state = app.state::<AppState>();
+    let now = state.clock.now();
+    let Ok(mut status) = state.server_status.lock() else {
+        return;
+    };
+    let was_paused = status.is_paused(now);
+    status.enter(message, retry_at, now);
+    let banner = status.status(now);
+    drop(status);
+    
+    if !was_paused {
+        warn!("Server maintenance, pausing submissions until {:?}", banner.retry_at);
+    }
+    let _ = app.emit("maintenance_mode", &banner);
+    publish_connection_status(app);
+}
+
+/// Hide the maintenance banner and flush jobs queued during the window
+fn leave_maintenance(app: &AppHandle) {
+    info!("Server maintenance ended, resuming submissions");
+    let _ = app.emit("maintenance_mode", &MaintenanceStatus {
+        active: false,
+        message: None,
+        retry_at: None,
+    });
+    publish_connection_status(app);
+    spawn_failed_job_retry(app);
+}
+
+/// How often the checkpointer looks for a due checkpoint
+const CHECKPOINT_TICK: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Write checkpoints every `CHECKPOINT_INTERVAL`, sooner when requested;
+/// runs apart from the telemetry loop so the queue is saved while it is stopped
+async fn run_checkpointer(app: AppHandle, cancel: CancellationToken) {
+    let mut interval = tokio::time::interval(CHECKPOINT_TICK);
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = interval.tick() => {}
+        }
+        let state = app.state::<AppState>();
+        let due = state.checkpointer.lock()
+            .map(|checkpointer| checkpointer.is_due(std::time::Instant::now()))
+            .unwrap_or(false);
+        if due {
+            write_checkpoint(&state);
+        }
+    }
+    debug!("Checkpointer stopped");
+}
+
+/// Write a checkpoint on the checkpointer's next tick
+fn request_checkpoint(state: &AppState) {
+    if let Ok(mut checkpointer) = state.checkpointer.lock() {
+        checkpointer.request();
+    }
+}
+
+/// Snapshot the tracked job and retry queue, in-flight jobs included, to disk
+fn write_checkpoint(state: &AppState) {
+    // Held throughout, so concurrent writes can't save an older snapshot last
+    let Ok(mut checkpointer) = state.checkpointer.lock() else {
+        return;
+    };
+    let job = state.telemetry.snapshot().job;
+    let queue = state.failed_jobs.lock()
+        .map(|failed| failed.jobs().to_vec())
+        .unwrap_or_default();
+    let conduct = state.conduct.lock()
+        .map(|conduct| conduct.clone())
+        .unwrap_or_default();
+    let (job, job_phase) = job.unzip();
+    
+    let checkpoint = Checkpoint {
+        saved_at: state.clock.now(),
+        job,
+        job_phase,
+        queue,
+        failed_jobs: Vec::new(),
+        conduct,
+    };
+    checkpointer.write(&checkpoint, &state.storage, std::time::Instant::now());
+}
+
+/// Most samples sent in one position upload
+const TRACE_BATCH_SAMPLES: usize = 60;
+
+/// Queue a live-map sample and upload the batch once its window has elapsed
+fn record_position(app: &AppHandle, state: &AppState, data: &TelemetryState) {
+    let enabled = state.settings.lock()
+        .map(|settings| settings.live_map.enabled)
+        .unwrap_or(false);
+    let Ok(mut batcher) = state.positions.lock() else {
+        return;
+    };
+    
+    if !enabled {
+        batcher.clear();
+        return;
+    }
+    let Some(position) = &data.position else {
+        return;
+    };
+    
+    let now = state.clock.now();
+    batcher.push(PositionSample {
+        at: now,
+        x: position.x,
+        z: position.z,
+        heading: position.heading,
+        speed: data.speed,
+    });
+    // Keep samples queued while the server is in maintenance
+    if !batcher.is_due(now) || submissions_paused(state) {
+        return;
+    }
+    let samples = batcher.take();
+    drop(batcher);
+    
+    let Some(token) = current_token(state) else {
+        return;
+    };
+    
+    let app = app.clone();
+    let cancel = state.shutdown.session_token();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        // A backlog left by failed uploads goes out in several batches
+        let mut chunks = samples.chunks(TRACE_BATCH_SAMPLES).map(<[_]>::to_vec).collect::<Vec<_>>().into_iter();
+        let mut progress = ProgressTracker::for_backlog(chunks.len());
+        if let Some(progress) = &progress {
+            let _ = app.emit("trace_upload_progress", progress.start());
+        }
+        while let Some(chunk) = chunks.next() {
+            let Some(batch) = PositionBatch::encode(&chunk) else {
+                continue;
+            };
+            let result = tokio::select! {
+                _ = cancel.cancelled() => return,
+                result = state.api.telemetry.upload_positions(&token, &batch) => result,
+            };
+            
+            let Ok(mut batcher) = state.positions.lock() else {
+                return;
+            };
+            if let Ok(response) = &result {
+                batcher.record_success();
+                if let Some(window) = response.batch_window {
+                    batcher.set_window(window);
+                }
+                drop(batcher);
+                if let Some(progress) = &mut progress {
+                    let _ = app.emit("trace_upload_progress", progress.advance());
+                }
+                continue;
+            }
+            
+            let unsent = chunk.into_iter().chain(chunks.by_ref().flatten()).collect();
+            batcher.requeue(unsent);
+            match result {
+                Ok(_) => {}
+                Err(ApiError::Maintenance(retry_at)) => {
+                    drop(batcher);
+                    enter_maintenance(&app, None, retry_at);
+                }
+                Err(ApiError::RateLimited(retry_at)) => {
+                    debug!("Position uploads rate limited until {:?}", retry_at);
+                    batcher.record_failure(state.clock.now(), retry_at);
+                }
+                Err(e) => {
+                    debug!("Position upload failed: {}", e);
+                    batcher.record_failure(state.clock.now(), None);
+                }
+            }
+            break;
+        }
+        if let Some(progress) = &progress {
+            let _ = app.emit("trace_upload_progress", progress.finish());
+        }
+    });
+}
+
+/// Retry job submissions that previously failed
+#[command]
+pub async fn retry_failed_jobs(app: AppHandle, state: State<'_, AppState>) -> Result<RetryResult, AppError> {
+    let (user_id, token) = require_account(&state)?;
+    if submissions_paused(&state) {
+        return Err(AppError::Maintenance);
+    }
+    if is_offline(&state) {
+        // Drained once the connection is back
+        return Ok(RetryResult { attempted: 0, succeeded: 0, remaining: queued_jobs(&state) });
+    }
+    let cancel = state.shutdown.session_token();
+    Ok(resubmit_failed_jobs(&app, &user_id, &token, &cancel).await)
+}
+
+/// Submit one failed or unsynced job from the local history again. The
+/// queued submission is used when there is one, so nothing recorded during
+/// the job is lost; otherwise it is rebuilt from the history entry
+#[command]
+pub async fn resubmit_job(local_id: i64, app: AppHandle, state: State<'_, AppState>) -> Result<ResubmitResult, AppError> {
+    let (user_id, token) = require_account(&state)?;
+    if submissions_paused(&state) {
+        return Err(AppError::Maintenance);
+    }
+    let unsynced = state.history.lock()?
+        .unsynced_job(local_id)?
+        .ok_or_else(|| AppError::NotFound(format!("No unsynced job {} in the history", local_id)))?;
+    let queued = state.failed_jobs.lock()
+        .ok()
+        .and_then(|mut failed| failed.remove(&user_id, &unsynced.digest));
+    let submission = match queued {
+        Some(submission) => submission,
+        None => {
+            state.history.lock()?.relink(local_id, &unsynced.submission)?;
+            unsynced.submission
+        }
+    };
+    
+    if let Some(job_id) = confirmed_job_id(&state, &submission) {
+        info!("Job {} already confirmed as {}", local_id, job_id);
+        state.history.lock()?.set_status(&submission, HistoryStatus::Submitted, Some(&job_id), None)?;
+        return Ok(ResubmitResult { job_id: Some(job_id), queued_jobs: queued_jobs(&state) });
+    }
+    if is_offline(&state) {
+        info!("Offline, job {} stays queued until the connection is back", local_id);
+        update_queue(&state, |failed| failed.push(Some(&user_id), submission));
+        return Ok(ResubmitResult { job_id: None, queued_jobs: queued_jobs(&state) });
+    }
+    
+    info!("Resubmitting job {} ({} -> {})", local_id, submission.source_city, submission.destination_city);
+    update_queue(&state, |failed| failed.begin(&user_id, submission.clone()));
+    let result = state.api.telemetry.submit_job(&token, &submission).await;
+    record_submission(&app, &submission, &result);
+    match result {
+        Ok(response) => {
+            update_queue(&state, |failed| failed.resolve(&submission));
+            Ok(ResubmitResult { job_id: Some(response.job_id), queued_jobs: queued_jobs(&state) })
+        }
+        Err(e) => {
+            requeue(&state, &submission, &e);
+            if let ApiError::Maintenance(retry_at) = &e {
+                enter_maintenance(&app, None, *retry_at);
+            }
+            Err(e.into())
+        }
+    }
+}
+
+/// Keep an in-flight job that failed queued for a later retry, unless
+/// retrying can't help; the history entry already says why it failed
+fn requeue(state: &AppState, submission: &JobSubmission, error: &ApiError) {
+    if error.rejects_job() {
+        warn!("Dropping job {} -> {} from the queue: {}", submission.source_city, submission.destination_city, error);
+        update_queue(state, |failed| failed.resolve(submission));
+    } else {
+        update_queue(state, |failed| failed.release(submission));
+    }
+}
+
+/// Change the retry queue and have it checkpointed
+fn update_queue(state: &AppState, change: impl FnOnce(&mut JobQueue)) {
+    if let Ok(mut failed) = state.failed_jobs.lock() {
+        change(&mut failed);
+    }
+    request_checkpoint(state);
+}
+
+/// Jobs waiting in the retry queue for the signed-in account, or for every
+/// account while signed out
+fn queued_jobs(state: &AppState) -> usize {
+    let user_id = current_user(state);
+    state.failed_jobs.lock()
+        .map(|failed| user_id.map_or_else(|| failed.len(), |user_id| failed.pending(&user_id)))
+        .unwrap_or_default()
+}
+
+/// Resubmit the queued jobs of `user_id` in batches of `MAX_BATCH_JOBS`;
+/// stops between batches once `cancel` fires or the server enters
+/// maintenance. Jobs stay in the queue, in flight, until their outcome is
+/// known, and the unattempted ones are left queued
+async fn resubmit_failed_jobs(app: &AppHandle, user_id: &str, token: &str, cancel: &CancellationToken) -> RetryResult {
+    let state = app.state::<AppState>();
+    let pending = state.failed_jobs.lock()
+        .map(|mut failed| failed.start_drain(user_id))
+        .unwrap_or_default();
+    
+    let mut attempted = 0;
+    let mut succeeded = 0;
+    let mut remaining = 0;
+    let mut maintenance = None;
+    let mut progress = ProgressTracker::for_backlog(pending.len());
+    if let Some(progress) = &progress {
+        let _ = app.emit("queue_drain_progress", progress.start());
+    }
+    let mut queued = Vec::new();
+    for submission in pending {
+        // A timed-out attempt may have reached the server after all
+        if let Some(job_id) = confirmed_job_id(&state, &submission) {
+            info!("Job already confirmed as {}, dropping it from the queue", job_id);
+            update_queue(&state, |failed| failed.resolve(&submission));
+            if let Some(progress) = &mut progress {
+                let _ = app.emit("queue_drain_progress", progress.advance());
+            }
+        } else {
+            queued.push(submission);
+        }
+    }
+    while !queued.is_empty() {
+        if cancel.is_cancelled() || maintenance.is_some() {
+            remaining += queued.len();
+            update_queue(&state, |failed| queued.iter().for_each(|submission| failed.release(submission)));
+            break;
+        }
+        let batch: Vec<_> = queued.drain(..queued.len().min(MAX_BATCH_JOBS)).collect();
+        attempted += batch.len();
+        let results = state.api.telemetry.submit_jobs_batch(token, &batch).await;
+        for (submission, result) in batch.into_iter().zip(results) {
+            record_submission(app, &submission, &result);
+            match result {
+                Ok(_) => {
+                    succeeded += 1;
+                    update_queue(&state, |failed| failed.resolve(&submission));
+                }
+                Err(e) => {
+                    debug!("Retry failed: {}", e);
+                    if let ApiError::Maintenance(retry_at) = e {
+                        maintenance = Some(retry_at);
+                    }
+                    if !e.rejects_job() {
+                        remaining += 1;
+                    }
+                    requeue(&state, &submission, &e);
+                }
+            }
+            if let Some(progress) = &mut progress {
+                let _ = app.emit("queue_drain_progress", progress.advance());
+            }
+        }
+    }
+    if let Some(progress) = &progress {
+        let _ = app.emit("queue_drain_progress", progress.finish());
+    }
+    
+
+    if let Some(retry_at) = maintenance {
+        enter_maintenance(app, None, retry_at);
+    }
+    
+    info!("Retried {} failed jobs, {} still failing", attempted, remaining);
+    RetryResult {
+        attempted,
+        succeeded,
+        remaining,
+    }
+}
+
+/// Show a native notification whose buttons call back into the backend,
+/// unless `event` is turned off in settings
+fn notify(app: &AppHandle, event: NotificationEvent, notification: Notification) {
+    let state = app.state::<AppState>();
+    let enabled = state.settings.lock()
+        .map(|settings| notifications::enabled_for(&settings.notifications, event))
+        .unwrap_or(true);
+    if !enabled {
+        debug!("{:?} notifications are off, skipping: {}", event, notification.title);
+        return;
+    }
+    let fullscreen = state.game_focus.lock()
+        .map(|focus| focus.is_some_and(|focus| focus.in_fullscreen_game()))
+        .unwrap_or(false);
+    let quiet = state.settings.lock()
+        .map(|settings| settings.focus.quiet_in_fullscreen)
+        .unwrap_or(true);
+    if fullscreen && quiet {
+        debug!("Game is fullscreen, suppressing notification: {}", notification.title);
+        return;
+    }
+    
+    let handle = app.clone();
+    notifications::show(&app.config().identifier, notification, move |action| {
+        handle_toast_action(&handle, action);
+    });
+}
+
+/// Show a sample notification, regardless of the per-event toggles
+#[command]
+pub fn test_notification(app: AppHandle) -> Result<(), AppError> {
+    let handle = app.clone();
+    notifications::show(
+        &app.config().identifier,
+        Notification::new("VTC Tracker", "Notifications are working"),
+        move |action| handle_toast_action(&handle, action),
+    );
+    Ok(())
+}
+
+/// Run the backend action behind a toast button
+fn handle_toast_action(app: &AppHandle, action: ToastAction) {
+    debug!("Toast action: {:?}", action);
+    match action {
+        ToastAction::RetryFailedJobs => spawn_failed_job_retry(app),
+        ToastAction::ViewSyncErrors => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            let _ = app.emit("show_sync_errors", ());
+        }
+    }
+}
+
+/// Resubmit queued jobs if signed in, outside maintenance and any are queued
+async fn drain_failed_jobs(app: &AppHandle, cancel: &CancellationToken) -> Option<RetryResult> {
+    let state = app.state::<AppState>();
+    let (user_id, token) = current_account(&state)?;
+    if submissions_paused(&state) || is_offline(&state) {
+        return None;
+    }
+    let has_pending = state.failed_jobs.lock()
+        .map(|failed| failed.pending(&user_id) > 0)
+        .unwrap_or(false);
+    if !has_pending {
+        return None;
+    }
+    Some(resubmit_failed_jobs(app, &user_id, &token, cancel).await)
+}
+
+/// Resubmit queued jobs in the background, notifying if some still fail
+fn spawn_failed_job_retry(app: &AppHandle) {
+    let app = app.clone();
+    let cancel = app.state::<AppState>().shutdown.session_token();
+    tauri::async_runtime::spawn(async move {
+        let Some(result) = drain_failed_jobs(&app, &cancel).await else {
+            return;
+        };
+        let state = app.state::<AppState>();
+        if result.remaining > 0 && !submissions_paused(&state) {
+            notify(
+                &app,
+                NotificationEvent::SyncFailed,
+                Notification::new(
+                    "Retry incomplete",
+                    format!("{} job(s) still could not be submitted", result.remaining),
+                )
+                .action("Retry", ToastAction::RetryFailedJobs),
+            );
+        }
+    });
+}
+
+/// Send heartbeat to server
+#[command]
+pub async fn send_heartbeat(app: AppHandle, state: State<'_, AppState>) -> Result<HeartbeatResult, AppError> {
+    let token = require_auth(&state)?;
+    Ok(HeartbeatResult { success: heartbeat(&app, &state, &token).await.is_some() })
+}
+
+/// Send heartbeats from the backend while signed in, at the pace the server asks for
+async fn run_heartbeat_scheduler(app: AppHandle, cancel: CancellationToken) {
+    let mut next = HeartbeatSchedule::new(app.state::<AppState>().clock.clone());
+    loop {
+        let state = app.state::<AppState>();
+        let session = state.shutdown.session_token();
+        if !next.is_due() {
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                // Logging out cuts the wait short so the UI updates at once
+                _ = session.cancelled() => next.reset(),
+                _ = tokio::time::sleep(next.wait()) => {}
+            }
+            continue;
+        }
+        let delay = match current_token(&state) {
+            None => schedule::LOGGED_OUT_CHECK,
+            Some(token) => match heartbeat(&app, &state, &token).await {
+                Some(next_heartbeat_in) => schedule::after_success(next_heartbeat_in),
+                None => {
+                    let failures = state.sync_health.lock()
+                        .map(|health| health.heartbeat.consecutive_failures)
+                        .unwrap_or(1);
+                    schedule::after_failure(failures)
+                }
+            },
+        };
+        next.schedule(delay);
+        publish_connection_status(&app);
+    }
+    debug!("Heartbeat scheduler stopped");
+}
+
+/// Pause before reopening a push channel the server closed normally
+const REALTIME_RECONNECT_DELAY: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Keep the push channel open while signed in and forward its messages as
+/// Tauri events
+async fn run_realtime(app: AppHandle, cancel: CancellationToken) {
+    let mut failures = 0u32;
+    loop {
+        let state = app.state::<AppState>();
+        let session = state.shutdown.session_token();
+        let delay = match current_token(&state) {
+            None => schedule::LOGGED_OUT_CHECK,
+            Some(token) => {
+                let result = state.api.realtime
+                    .run(&token, &session, |message| handle_push(&app, message))
+                    .await;
+                match result {
+                    Ok(()) => {
+                        failures = 0;
+                        REALTIME_RECONNECT_DELAY
+                    }
+                    Err(e) => {
+                        failures += 1;
+                        debug!("Realtime channel failed ({} in a row): {}", failures, e);
+                        schedule::after_failure(failures)
+                    }
+                }
+            }
+        };
+        
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = session.cancelled() => {}
+            _ = tokio::time::sleep(delay) => {}
+        }
+    }
+    debug!("Realtime channel stopped");
+}
+
+/// Forward a push message to the frontend, signing out first if asked to
+fn handle_push(app: &AppHandle, message: PushMessage) {
+    let Some(event) = message.event_name() else {
+        debug!("Ignoring unknown push message");
+        return;
+    };
+    info!("Push message: {}", event);
+    if let PushMessage::ForcedLogout { reason } = &message {
+        warn!("Signed out by the server: {}", reason.as_deref().unwrap_or("no reason given"));
+        end_session(&app.state::<AppState>());
+        publish_connection_status(app);
+    }
+    let _ = app.emit(event, &message);
+}
+
+/// Emit an `app_health` snapshot every 30 seconds
+async fn run_health_reporter(app: AppHandle, cancel: CancellationToken) {
+    let mut interval = tokio::time::interval(app_health::REPORT_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = interval.tick() => {}
+        }
+        let state = app.state::<AppState>();
+        let health = collect_health(&state);
+        if let Ok(mut latest) = state.app_health.lock() {
+            *latest = Some(health.clone());
+        }
+        let _ = app.emit("app_health", &health);
+        refresh_tray_tooltip(&app);
+    }
+    debug!("Health reporter stopped");
+}
+
+/// Gather the readings behind `app_health`
+fn collect_health(state: &AppState) -> AppHealth {
+    let inputs = HealthInputs {
+        now: state.clock.now(),
+        telemetry_connected: state.telemetry.snapshot().state.connected,
+        queued_jobs: queued_jobs(state),
+        last_heartbeat: state.sync_health.lock().ok().and_then(|health| health.heartbeat.last_success),
+        session_expires_at: state.auth.lock()
+            .ok()
+            .and_then(|auth| auth.get_session().map(|session| session.expires_at)),
+        free_disk_bytes: fs2::available_space(state.storage.dir()).ok(),
+        connection: state.connection.lock().map(|status| *status).unwrap_or(ConnectionStatus::LoggedOut),
+    };
+    AppHealth::assess(&inputs)
+}
+
+/// Latest health snapshot, collected now if none has been yet
+#[command]
+pub fn get_app_health(state: State<'_, AppState>) -> Result<AppHealth, AppError> {
+    let latest = state.app_health.lock()?.clone();
+    Ok(latest.unwrap_or_else(|| collect_health(&state)))
+}
+
+/// Current connection status
+#[command]
+pub fn get_connection_status(state: State<'_, AppState>) -> Result<ConnectionStatus, AppError> {
+    state.connection.lock()
+        .map(|status| *status)
+        .map_err(AppError::from)
+}
+
+/// Recompute the connection status and emit `connection_status` if it changed
+fn publish_connection_status(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let authenticated = current_token(&state).is_some();
+    let maintenance = submissions_paused(&state);
+    let sync = state.sync_health.lock()
+        .map(|health| health.status())
+        .unwrap_or(crate::sync_health::SyncStatus::Unknown);
+    let status = ConnectionStatus::derive(authenticated, maintenance, sync);
+    
+    let changed = state.connection.lock()
+        .map(|mut current| std::mem::replace(&mut *current, status) != status)
+        .unwrap_or(false);
+    if changed {
+        debug!("Connection status: {:?}", status);
+        let _ = app.emit("connection_status", status);
+    }
+}
+
+/// Send a heartbeat, updating sync health and maintenance state. Returns the
+/// server's next-heartbeat hint (seconds) on success
+async fn heartbeat(app: &AppHandle, state: &AppState, token: &str) -> Option<u32> {
+    let mut presence = state.presence.lock().map(|presence| presence.clone()).unwrap_or_default();
+    // The location is only shared with live map sharing on
+    let share_position = state.settings.lock()
+        .map(|settings| settings.live_map.enabled && !settings.privacy.hide_presence_position)
+        .unwrap_or(false);
+    if !share_position {
+        presence = presence.without_position();
+    }
+    let result = state.api.telemetry.send_heartbeat(token, &presence).await;
+    match &result {
+        Ok(_) => resolve_error(state, Subsystem::Sync),
+        Err(e) => record_api_error(state, Subsystem::Sync, e),
+    }
+    record_sync(app, SyncChannel::Heartbeat, result.as_ref().map(|_| ()).map_err(|e| e.to_string()));
+    match result {
+        Ok(response) => {
+            if let Ok(server_time) = chrono::DateTime::parse_from_rfc3339(&response.timestamp) {
+                if let Ok(mut health) = state.sync_health.lock() {
+                    health.record_server_time(server_time.with_timezone(&chrono::Utc), state.clock.now());
+                }
+            }
+            match response.maintenance {
+                Some(notice) => enter_maintenance(app, notice.message, notice.retry_at),
+                None => {
+                    let ended = state.server_status.lock()
+                        .map(|mut status| status.clear())
+                        .unwrap_or(false);
+                    if ended {
+                        leave_maintenance(app);
+                    }
+                }
+            }
+            response.success.then_some(response.next_heartbeat_in)
+        }
+        Err(ApiError::Maintenance(retry_at)) => {
+            enter_maintenance(app, None, retry_at);
+            None
+        }
+        Err(e) => {
+            debug!("Heartbeat failed: {}", e);
+            None
+        }
+    }
+}
+
+/// Go/no-go check before a long session such as a convoy
+#[command]
+pub async fn run_preflight(
+    hours: Option<u32>,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<PreflightReport, AppError> {
+    // A live heartbeat makes the server check reflect the current state
+    if let Some(token) = current_token(&state) {
+        heartbeat(&app, &state, &token).await;
+    }
+    
+    let now = state.clock.now();
+    let snapshot = state.telemetry.snapshot();
+    let (telemetry_connected, plugin_revision) = (snapshot.state.connected, snapshot.plugin_revision);
+    let inputs = PreflightInputs {
+        now,
+        hours: hours.unwrap_or(preflight::DEFAULT_HOURS),
+        session_expires_at: state.auth.lock()?
+            .get_session()
+            .map(|session| session.expires_at),
+        queued_jobs: queued_jobs(&state),
+        free_disk_bytes: fs2::available_space(state.storage.dir()).ok(),
+        storage_reset_required: state.storage.reset_required(),
+        telemetry_connected,
+        plugin_revision,
+        sync_status: state.sync_health.lock().map(|health| health.status())?,
+        maintenance: submissions_paused(&state),
+    };
+    
+    let report = preflight::evaluate(&inputs);
+    info!("Pre-flight check: {}", if report.go { "go" } else { "no-go" });
+    Ok(report)
+}
+
+/// Heartbeat and submission health
+#[command]
+pub fn get_sync_health(state: State<'_, AppState>) -> Result<SyncHealthReport, AppError> {
+    state.sync_health.lock()
+        .map(|health| health.report())
+        .map_err(AppError::from)
+}
+
+/// Log lines attached to feedback
+const FEEDBACK_LOG_LINES: usize = 200;
+/// Longest feedback message accepted
+const FEEDBACK_MAX_CHARS: usize = 5000;
+/// Log entries `read_logs` returns when not told how many
+const DEFAULT_LOG_LINES: usize = 500;
+
+/// Send a bug report or suggestion with the app version, a diagnostics
+/// summary and, if allowed, recent redacted logs; returns the ticket ID
+#[command]
+pub async fn send_feedback(
+    message: String,
+    include_logs: bool,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, AppError> {
+    let message = message.trim().to_string();
+    if message.is_empty() {
+        return Err(AppError::Invalid("Please describe the problem or suggestion".into()));
+    }
+    if message.chars().count() > FEEDBACK_MAX_CHARS {
+        return Err(AppError::Invalid(format!("Feedback is limited to {} characters", FEEDBACK_MAX_CHARS)));
+    }
+    
+    let logs = include_logs.then(|| {
+        logging::recent_lines(&logging::log_directory(), FEEDBACK_LOG_LINES)
+            .iter()
+            .map(|line| logging::redact(line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    let feedback = FeedbackRequest {
+        message,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        logs,
+        diagnostics: Some(diagnostics(&state)),
+    };
+    
+    let token = current_token(&state);
+    let response = state.api.support.send_feedback(token.as_deref(), &feedback).await?;
+    Ok(response.ticket_id)
+}
+
+/// Recent log entries for the in-app log console, oldest first. `level_filter`
+/// is the least severe level to include (e.g. `warn`), `since` an RFC 3339 time
+#[command]
+pub async fn read_logs(
+    lines: Option<usize>,
+    level_filter: Option<String>,
+    since: Option<String>,
+) -> Result<Vec<logging::LogEntry>, AppError> {
+    let level = level_filter
+        .filter(|level| !level.trim().is_empty())
+        .map(|level| level.trim().parse::<tracing::Level>()
+            .map_err(|_| AppError::Invalid(format!("Unknown log level: {}", level))))
+        .transpose()?;
+    let since = since
+        .map(|since| chrono::DateTime::parse_from_rfc3339(&since)
+            .map(|since| since.with_timezone(&chrono::Utc))
+            .map_err(|_| AppError::Invalid(format!("Invalid time: {}", since))))
+        .transpose()?;
+    let lines = lines.unwrap_or(DEFAULT_LOG_LINES);
+    
+    // Reading a week of logs can take a moment; keep it off the async workers
+    tokio::task::spawn_blocking(move || logging::read_entries(&logging::log_directory(), lines, level, since))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// Apply the log retention settings now instead of at the next start
+#[command]
+pub async fn purge_logs(state: State<'_, AppState>) -> Result<logging::PurgeReport, AppError> {
+    let settings = state.settings.lock()?.logging.clone();
+    let now = state.clock.now();
+    tokio::task::spawn_blocking(move || logging::purge(&logging::log_directory(), &settings, now))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))
+}
+
+/// React to the machine sleeping and waking up
+async fn run_power_monitor(app: AppHandle, cancel: CancellationToken) {
+    let (sender, mut events) = tokio::sync::mpsc::unbounded_channel();
+    let subscription = power::subscribe(sender);
+    // Without notifications, watch the wall clock instead
+    let mut detector = subscription.is_none().then(SleepDetector::new);
+    let mut interval = tokio::time::interval(power::WAKE_CHECK_INTERVAL);
+    loop {
+        let event = tokio::select! {
+            _ = cancel.cancelled() => break,
+            Some(event) = events.recv() => event,
+            _ = interval.tick() => {
+                let now = app.state::<AppState>().clock.now();
+                if !detector.as_mut().is_some_and(|detector| detector.observe(now)) {
+                    continue;
+                }
+                PowerEvent::Resume
+            }
+        };
+        handle_power_event(&app, event).await;
+    }
+    debug!("Power monitor stopped");
+}
+
+/// Flush state before sleeping; reconnect and re-check everything after
+async fn handle_power_event(app: &AppHandle, event: PowerEvent) {
+    let state = app.state::<AppState>();
+    let now = state.clock.now();
+    let _ = app.emit("power_event", &event);
+    match event {
+        PowerEvent::Suspend => {
+            info!("System suspending, saving state");
+            state.telemetry.run_async(move |telemetry| telemetry.suspend(now)).await;
+            write_checkpoint(&state);
+        }
+        PowerEvent::Resume => {
+            info!("System resumed, reconnecting");
+            state.telemetry.run_async(move |telemetry| telemetry.resume(now)).await;
+            if let Ok(mut health) = state.sync_health.lock() {
+                health.reset_clock_offset();
+            }
+            // The heartbeat re-checks connectivity and measures the clock offset again
+            if let Some(token) = current_token(&state) {
+                heartbeat(app, &state, &token).await;
+            }
+            publish_connection_status(app);
+        }
+    }
+}
+
+/// Look for a newer release every few hours
+async fn run_update_checker(app: AppHandle, cancel: CancellationToken) {
+    let mut interval = tokio::time::interval(updates::CHECK_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = interval.tick() => {}
+        }
+        if let Err(e) = check_updates(&app).await {
+            debug!("Update check failed: {}", e);
+        }
+    }
+    debug!("Update checker stopped");
+}
+
+/// Ask for the latest release, emitting `update_available` the first time
+/// a newer version is seen
+async fn check_updates(app: &AppHandle) -> Result<Option<UpdateInfo>, ApiError> {
+    let state = app.state::<AppState>();
+    let release = state.api.releases.latest().await?;
+    let Ok(mut known) = state.updates.lock() else {
+        return Ok(None);
+    };
+    if let Some(update) = known.offer(release, updates::CURRENT_VERSION) {
+        info!("Update available: {} -> {}", update.current_version, update.version);
+        let _ = app.emit("update_available", &update);
+    }
+    Ok(known.info(updates::CURRENT_VERSION))
+}
+
+/// Check for a newer release now
+#[command]
+pub async fn check_for_updates(app: AppHandle) -> Result<Option<UpdateInfo>, AppError> {
+    check_updates(&app).await.map_err(AppError::from)
+}
+
+/// Download and verify the available update, start its installer and quit
+#[command]
+pub async fn install_update(app: AppHandle, state: State<'_, AppState>) -> Result<(), AppError> {
+    let release = state.updates.lock()?
+        .release()
+        .cloned()
+        .ok_or(UpdateError::NotAvailable)?;
+    info!("Downloading update {}", release.version);
+    let bytes = state.api.releases.download(&release).await
+        .map_err(|e| UpdateError::Download(e.to_string()))?;
+    let installer = updates::save_installer(&state.storage.dir().join(updates::INSTALLER_DIR), &release, &bytes)?;
+    updates::launch_installer(&installer)?;
+
+    info!("Installer started, exiting");
+    quit(&app).await;
     Ok(())
 }
 
-/// Send heartbeat to server
+/// Summary of the app's state for bug reports; nothing personal
+fn diagnostics(state: &AppState) -> serde_json::Value {
+    let snapshot = state.telemetry.snapshot();
+    let (telemetry_connected, game, plugin_revision) = (snapshot.state.connected, snapshot.state.game, snapshot.plugin_revision);
+    serde_json::json!({
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "telemetryConnected": telemetry_connected,
+        "game": game,
+        "pluginRevision": plugin_revision,
+        "connection": state.connection.lock().ok().map(|status| *status),
+        "syncHealth": state.sync_health.lock().ok().map(|health| health.report()),
+        "queuedJobs": queued_jobs(state),
+        "storageResetRequired": state.storage.reset_required(),
+    })
+}
+
+/// Current maintenance banner state
 #[command]
-pub async fn send_heartbeat(state: State<'_, AppState>) -> Result<HeartbeatResult, String> {
-    let token = state.auth.lock()
-        .ok()
-        .and_then(|auth| auth.get_access_token().map(|s| s.to_string()));
+pub fn get_server_status(state: State<'_, AppState>) -> Result<MaintenanceStatus, AppError> {
+    let status = state.server_status.lock()?;
+    Ok(status.status(state.clock.now()))
+}
+
+/// Start recording a convoy; jobs and route are tagged until it is stopped.
+/// An ID naming a platform event also tracks attendance, submitted when the
+/// event ends
+#[command]
+pub async fn start_convoy(
+    convoy_id: String,
+    name: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<ConvoySession, AppError> {
+    let convoy_id = convoy_id.trim().to_string();
+    if convoy_id.is_empty() {
+        return Err(AppError::Invalid("Convoy ID is required".into()));
+    }
+    ensure_no_convoy(&*state.convoy.lock()?)?;
+    
+    // Manual IDs that aren't platform events are still recorded
+    let event = match current_token(&state) {
+        Some(token) => match state.api.vtc.get_event(&token, &convoy_id).await {
+            Ok(event) => Some(event),
+            Err(e) => {
+                debug!("No platform event for convoy {}: {}", convoy_id, e);
+                None
+            }
+        },
+        None => None,
+    };
+    
+    let mut convoy = state.convoy.lock()?;
+    ensure_no_convoy(&convoy)?;
+    let now = state.clock.now();
+    let session = match event {
+        Some(event) => {
+            info!("Convoy mode started for event {} ({} – {})", event.id, event.starts_at, event.ends_at);
+            ConvoySession::for_event(event, now)
+        }
+        None => {
+            info!("Convoy mode started: {}", convoy_id);
+            ConvoySession::start(convoy_id, name, now)
+        }
+    };
+    *convoy = Some(session.clone());
+    Ok(session)
+}
+
+fn ensure_no_convoy(convoy: &Option<ConvoySession>) -> Result<(), AppError> {
+    match convoy {
+        Some(current) => Err(AppError::Invalid(format!("Convoy {} is already being recorded", current.id))),
+        None => Ok(()),
+    }
+}
+
+/// Stop recording and return the combined convoy report
+#[command]
+pub fn stop_convoy(app: AppHandle, state: State<'_, AppState>) -> Result<ConvoyReport, AppError> {
+    let session = state.convoy.lock()?
+        .take()
+        .ok_or_else(|| AppError::NotFound("No convoy is being recorded".into()))?;
+    
+    info!("Convoy mode stopped: {} ({} jobs)", session.id, session.jobs.len());
+    submit_attendance(&app, &session);
+    Ok(session.finish(state.clock.now()))
+}
+
+/// Track event attendance, finishing the convoy once its event has ended
+fn record_convoy_attendance(app: &AppHandle, state: &AppState, data: &TelemetryState) {
+    let now = state.clock.now();
+    let ended = state.convoy.lock().ok().and_then(|mut convoy| {
+        let session = convoy.as_mut()?;
+        session.record_attendance(data, now);
+        if session.event_ended(now) { convoy.take() } else { None }
+    });
+    if let Some(session) = ended {
+        info!("Event {} ended ({} jobs)", session.id, session.jobs.len());
+        submit_attendance(app, &session);
+        let _ = app.emit("convoy_finished", session.finish(now));
+    }
+}
+
+/// Post attendance for a platform event in the background
+fn submit_attendance(app: &AppHandle, session: &ConvoySession) {
+    let Some(report) = session.attendance_report() else {
+        return;
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let Some(token) = current_token(&state) else {
+            warn!("Not signed in, attendance for event {} not submitted", report.event_id);
+            return;
+        };
+        match state.api.vtc.submit_attendance(&token, &report).await {
+            Ok(_) => {
+                let _ = app.emit("attendance_submitted", &report);
+            }
+            Err(e) => {
+                warn!("Attendance report failed: {}", e);
+                record_api_error(&state, Subsystem::Sync, &e);
+            }
+        }
+    });
+}
+
+/// Get the convoy currently being recorded
+#[command]
+pub fn get_convoy(state: State<'_, AppState>) -> Result<Option<ConvoySession>, AppError> {
+    state.convoy.lock()
+        .map(|convoy| convoy.clone())
+        .map_err(AppError::from)
+}
+
+/// Render a shareable stats card to PNG; returns the file path
+#[command]
+pub fn export_stats_card(stats: StatsSummary, path: Option<String>) -> Result<String, AppError> {
+    let png = stats_card::render_png(&stats)?;
+    
+    let path = match path {
+        Some(path) => std::path::PathBuf::from(path),
+        None => {
+            let dir = dirs::picture_dir()
+                .or_else(dirs::download_dir)
+                .unwrap_or_else(|| std::path::PathBuf::from("."));
+            let name: String = stats.period.chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+                .collect();
+            dir.join(format!("vtc-stats-{}.png", name))
+        }
+    };
+    
+    std::fs::write(&path, png)?;
+    info!("Stats card saved to {:?}", path);
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Result of importing a legacy logbook
+#[derive(Debug, Serialize)]
+pub struct ImportResult {
+    pub imported: usize,
+    pub duplicates: usize,
+    pub skipped_rows: usize,
+    pub submitted: usize,
+    pub failed: usize,
+    /// Left in the retry queue: offline, interrupted or failed for now
+    pub queued: usize,
+}
+
+/// Sent after each imported job is submitted
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportProgress {
+    pub done: usize,
+    pub total: usize,
+    pub submitted: usize,
+    pub failed: usize,
+}
+
+/// Import a logbook export from another tracker, optionally submitting the
+/// new jobs as historical (flagged as imported); like other submissions they
+/// are queued while offline and when they fail
+#[command]
+pub async fn import_logbook(
+    path: String,
+    format: LogbookFormat,
+    submit: bool,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ImportResult, AppError> {
+    let file = std::fs::File::open(&path)?;
+    let parsed = import::parse(format, file)?;
+    let total = parsed.jobs.len();
+    let added = import::store(&state.storage, parsed.jobs)?;
+    info!("Imported {} of {} jobs from {} export", added.len(), total, format);
+    for job in &added {
+        let delivered_at = job.delivered_at.unwrap_or_else(chrono::Utc::now);
+        record_history(&state, &job.to_submission(), HistoryStatus::Imported, delivered_at);
+    }
+    audit(&state, AuditEvent::ManualEdit {
+        action: "import".into(),
+        detail: serde_json::json!({ "format": format, "imported": added.len(), "total": total }),
+    });
     
-    let Some(token) = token else {
-        return Ok(HeartbeatResult { success: false });
+    let mut result = ImportResult {
+        imported: added.len(),
+        duplicates: total - added.len(),
+        skipped_rows: parsed.skipped_rows,
+        submitted: 0,
+        failed: 0,
+        queued: 0,
     };
+    if !submit || added.is_empty() {
+        return Ok(result);
+    }
+    
+    // Submissions carry the imported flag, so they stay marked as imported in the queue
+    let submissions: Vec<_> = added.iter().map(|job| job.to_submission()).collect();
+    if is_offline(&state) {
+        info!("Offline, queueing {} imported jobs", submissions.len());
+        let user_id = current_user(&state);
+        result.queued = submissions.len();
+        update_queue(&state, |failed| {
+            submissions.into_iter().for_each(|submission| failed.push(user_id.as_deref(), submission));
+        });
+        return Ok(result);
+    }
+    let (user_id, token) = require_account(&state)?;
+    if submissions_paused(&state) {
+        return Err(AppError::Maintenance);
+    }
+    let cancel = state.shutdown.session_token();
+    for (index, batch) in submissions.chunks(MAX_BATCH_JOBS).enumerate() {
+        // Signed out or the server entered maintenance: the rest waits in the queue
+        if cancel.is_cancelled() || submissions_paused(&state) {
+            let unsent = &submissions[index * MAX_BATCH_JOBS..];
+            result.queued += unsent.len();
+            update_queue(&state, |failed| {
+                unsent.iter().for_each(|submission| failed.push(Some(&user_id), submission.clone()));
+            });
+            break;
+        }
+        update_queue(&state, |failed| {
+            batch.iter().for_each(|submission| failed.begin(&user_id, submission.clone()));
+        });
+        let outcomes = state.api.telemetry.submit_jobs_batch(&token, batch).await;
+        for (submission, outcome) in batch.iter().zip(outcomes) {
+            record_submission(&app, submission, &outcome);
+            match outcome {
+                Ok(_) => {
+                    result.submitted += 1;
+                    update_queue(&state, |failed| failed.resolve(submission));
+                }
+                Err(e) => {
+                    debug!("Imported job submission failed: {}", e);
+                    result.failed += 1;
+                    if !e.rejects_job() {
+                        result.queued += 1;
+                    }
+                    requeue(&state, submission, &e);
+                    if let ApiError::Maintenance(retry_at) = e {
+                        enter_maintenance(&app, None, retry_at);
+                    }
+                }
+            }
+            let _ = app.emit("import_progress", ImportProgress {
+                done: result.submitted + result.failed,
+                total: added.len(),
+                submitted: result.submitted,
+                failed: result.failed,
+            });
+        }
+    }
+    Ok(result)
+}
+
+/// Result of submitting a hand-entered job
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManualJobResult {
+    pub history_id: i64,
+    /// `None` while offline: the job was queued instead
+    pub job_id: Option<String>,
+}
+
+/// Submit a delivery auto-detection missed, entered by hand; it is kept in
+/// the history as a manual entry and queued while offline or for a retry if
+/// submitting fails
+#[command]
+pub async fn submit_manual_job(
+    payload: ManualJob,
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<ManualJobResult, AppError> {
+    let now = state.clock.now();
+    let submission = payload.to_submission(now)?;
+    let (user_id, token) = require_account(&state)?;
+    if submissions_paused(&state) {
+        return Err(AppError::Maintenance);
+    }
+    
+    let history_id = state.history.lock()?.record_manual(&submission, payload.delivered_at.unwrap_or(now))?;
+    audit(&state, AuditEvent::ManualEdit {
+        action: "manual_job".into(),
+        detail: serde_json::json!({
+            "historyId": history_id,
+            "route": format!("{} -> {}", submission.source_city, submission.destination_city),
+        }),
+    });
+    if is_offline(&state) {
+        info!("Offline, queueing manual job {} -> {}", submission.source_city, submission.destination_city);
+        update_queue(&state, |failed| failed.push(Some(&user_id), submission));
+        return Ok(ManualJobResult { history_id, job_id: None });
+    }
+    info!("Submitting manual job {} -> {}", submission.source_city, submission.destination_city);
     
-    match state.api.send_heartbeat(&token).await {
-        Ok(response) => Ok(HeartbeatResult { success: response.success }),
+    update_queue(&state, |failed| failed.begin(&user_id, submission.clone()));
+    let result = state.api.telemetry.submit_job(&token, &submission).await;
+    record_submission(&app, &submission, &result);
+    match result {
+        Ok(response) => {
+            update_queue(&state, |failed| failed.resolve(&submission));
+            Ok(ManualJobResult { history_id, job_id: Some(response.job_id) })
+        }
         Err(e) => {
-            debug!("Heartbeat failed: {}", e);
-            Ok(HeartbeatResult { success: false })
+            requeue(&state, &submission, &e);
+            Err(e.into())
         }
     }
 }
 
+/// Get current settings
+#[command]
+pub fn get_settings(state: State<'_, AppState>) -> Result<Settings, AppError> {
+    state.settings.lock()
+        .map(|settings| settings.clone())
+        .map_err(AppError::from)
+}
+
+/// Replace and persist settings, applying the ones that take effect live
+#[command]
+pub fn update_settings(settings: Settings, app: AppHandle, state: State<'_, AppState>) -> Result<Settings, AppError> {
+    settings.validate()?;
+    let auto_start_changed = state.settings.lock()
+        .map(|current| current.general.auto_start != settings.general.auto_start)?;
+    if auto_start_changed {
+        autostart::set(settings.general.auto_start)?;
+    }
+    settings.save(&state.storage)?;
+    
+    let previous = {
+        let mut current = state.settings.lock()?;
+        std::mem::replace(&mut *current, settings.clone())
+    };
+    info!("Settings updated");
+    
+    if previous.general.api_url != settings.general.api_url {
+        let api_url = settings.general.api_url();
+        info!("API URL changed to {}", api_url);
+        if previous.general.api_origin() != settings.general.api_origin() {
+            forget_server(&app, &state);
+        }
+        state.api.set_base_url(&api_url);
+    }
+    if previous.network != settings.network {
+        info!("Network settings changed (proxy: {:?})", settings.network.proxy);
+        if let Err(e) = state.api.set_network(&settings.network) {
+            warn!("{}", e);
+        }
+    }
+    if previous.general.simulator() != settings.general.simulator() {
+        let game = settings.general.simulator();
+        info!("Telemetry simulator {}", game.map_or_else(|| "disabled".to_string(), |game| format!("enabled ({})", game)));
+        state.telemetry.post(move |telemetry| telemetry.set_simulator(game.map(Simulator::new)));
+    }
+    if previous.overlay.active() != settings.overlay.active() {
+        let _ = app.emit("overlay_layout_changed", settings.overlay.active());
+    }
+    logging::configure(&settings.logging);
+    if previous.logging.retention_days != settings.logging.retention_days
+        || previous.logging.max_total_mb != settings.logging.max_total_mb
+    {
+        logging::purge(&logging::log_directory(), &settings.logging, state.clock.now());
+    }
+    let _ = app.emit("settings_changed", &settings);
+    
+    Ok(settings)
+}
+
+/// Drop everything tied to the old API server: every linked account's
+/// tokens, which must never be sent elsewhere, and the retry queue, whose
+/// jobs were recorded for those accounts. The local history keeps the jobs
+fn forget_server(app: &AppHandle, state: &AppState) {
+    if current_token(state).is_some() {
+        revoke_session(app, "API server changed");
+    }
+    match update_accounts(state, std::mem::take) {
+        Ok(accounts) if !accounts.sessions.is_empty() => {
+            info!("API server changed, unlinked {} account(s)", accounts.sessions.len());
+        }
+        Ok(_) => {}
+        Err(e) => error!("Failed to remove stored sessions: {}", e),
+    }
+    update_queue(state, |failed| {
+        if !failed.is_empty() {
+            info!("API server changed, dropping {} queued job(s)", failed.len());
+        }
+        *failed = JobQueue::new();
+    });
+}
+
+/// Overlay preset the HUD should render
+#[command]
+pub fn get_overlay_layout(state: State<'_, AppState>) -> Result<OverlayPreset, AppError> {
+    state.settings.lock()
+        .map(|settings| settings.overlay.active())
+        .map_err(AppError::from)
+}
+
+/// Save an overlay preset, replacing one with the same name, and show it
+#[command]
+pub fn save_overlay_preset(preset: OverlayPreset, app: AppHandle, state: State<'_, AppState>) -> Result<OverlayPreset, AppError> {
+    preset.validate()?;
+    update_overlay(&app, &state, |overlay| {
+        overlay.active_preset = preset.name.clone();
+        overlay.upsert(preset);
+        Ok(())
+    })
+}
+
+/// Switch the overlay to a saved preset
+#[command]
+pub fn set_overlay_preset(name: String, app: AppHandle, state: State<'_, AppState>) -> Result<OverlayPreset, AppError> {
+    update_overlay(&app, &state, |overlay| {
+        if overlay.preset(&name).is_none() {
+            return Err(OverlayError::NotFound(name).into());
+        }
+        overlay.active_preset = name;
+        Ok(())
+    })
+}
+
+/// Saved preset as JSON for sharing
+#[command]
+pub fn export_overlay_preset(name: String, state: State<'_, AppState>) -> Result<String, AppError> {
+    let settings = state.settings.lock()?;
+    settings.overlay.preset(&name)
+        .map(OverlayPreset::to_json)
+        .ok_or_else(|| OverlayError::NotFound(name).into())
+}
+
+/// Save a preset shared as JSON without switching to it
+#[command]
+pub fn import_overlay_preset(json: String, app: AppHandle, state: State<'_, AppState>) -> Result<OverlayPreset, AppError> {
+    let preset = OverlayPreset::from_json(&json)?;
+    info!("Imported overlay preset {}", preset.name);
+    let imported = preset.clone();
+    update_overlay(&app, &state, |overlay| {
+        overlay.upsert(preset);
+        Ok(())
+    })?;
+    Ok(imported)
+}
+
+/// Change the overlay settings, persist them and push the active preset to
+/// the overlay window
+fn update_overlay(
+    app: &AppHandle,
+    state: &AppState,
+    change: impl FnOnce(&mut OverlaySettings) -> Result<(), AppError>,
+) -> Result<OverlayPreset, AppError> {
+    let settings = {
+        let mut settings = state.settings.lock()?;
+        let mut updated = settings.clone();
+        change(&mut updated.overlay)?;
+        updated.validate()?;
+        updated.save(&state.storage)?;
+        *settings = updated;
+        settings.clone()
+    };
+
+    let active = settings.overlay.active();
+    let _ = app.emit("overlay_layout_changed", &active);
+    let _ = app.emit("settings_changed", &settings);
+    Ok(active)
+}
+
+/// Check the audit log's hash chain
+#[command]
+pub fn verify_audit_log(state: State<'_, AppState>) -> Result<AuditVerification, AppError> {
+    state.audit.lock()
+        .map(|log| log.verify())
+        .map_err(AppError::from)
+}
+
+/// Copy the audit log to `path` for sharing in a dispute
+#[command]
+pub fn export_audit_log(path: String, state: State<'_, AppState>) -> Result<AuditVerification, AppError> {
+    let log = state.audit.lock()?;
+    let verification = log.export(std::path::Path::new(&path))?;
+    info!("Exported audit log ({} entries, valid: {})", verification.entries, verification.valid);
+    Ok(verification)
+}
+
+/// Page through locally recorded jobs, newest first
+#[command]
+pub fn get_job_history(page: u32, filter: Option<HistoryFilter>, state: State<'_, AppState>) -> Result<HistoryPage, AppError> {
+    state.history.lock()?
+        .page(page, &filter.unwrap_or_default())
+        .map_err(AppError::from)
+}
+
+/// Dashboard statistics over `period` of the local history, without the API
+#[command]
+pub fn get_local_stats(period: StatsPeriod, state: State<'_, AppState>) -> Result<LocalStats, AppError> {
+    let display = state.settings.lock()
+        .map(|settings| settings.general.display_currency)
+        .unwrap_or_default();
+    state.history.lock()?
+        .local_stats(period, display, state.clock.now())
+        .map_err(AppError::from)
+}
+
+/// Truck position on the mini-map; `None` while the game is closed or the
+/// truck is inside a privacy zone
+#[command]
+pub fn get_map_position(state: State<'_, AppState>) -> Result<Option<MapPosition>, AppError> {
+    Ok(state.map_position.lock()?.clone())
+}
+
+/// Route driven for a job in the local history, as GPX or GeoJSON
+#[command]
+pub fn export_route(job_id: i64, format: RouteFormat, state: State<'_, AppState>) -> Result<String, AppError> {
+    state.history.lock()?
+        .route(job_id)?
+        .map(|route| route.export(format))
+        .ok_or_else(|| AppError::NotFound(format!("No route was recorded for job {}", job_id)))
+}
+
+/// Totals over the local job history
+#[command]
+pub fn get_job_stats(state: State<'_, AppState>) -> Result<JobStats, AppError> {
+    let display = state.settings.lock()
+        .map(|settings| settings.general.display_currency)
+        .unwrap_or_default();
+    state.history.lock()?
+        .stats(display)
+        .map_err(AppError::from)
+}
+
+/// Speeding, collisions and fines of the current job (or the last one)
+#[command]
+pub fn get_violations_report(state: State<'_, AppState>) -> Result<ConductReport, AppError> {
+    state.conduct.lock()
+        .map(|conduct| conduct.clone())
+        .map_err(AppError::from)
+}
+
+/// Most recent error of each subsystem, for troubleshooting
+#[command]
+pub fn get_last_errors(state: State<'_, AppState>) -> Result<Vec<SubsystemError>, AppError> {
+    state.last_errors.lock()
+        .map(|errors| errors.all())
+        .map_err(AppError::from)
+}
+
+/// Telemetry detail the driver's VTC requires, if it set a policy
+#[command]
+pub fn get_telemetry_policy(state: State<'_, AppState>) -> Option<TelemetryPolicy> {
+    state.api.telemetry.policy()
+}
+
+/// Units, currency and features of `game`, or of the running game
+#[command]
+pub fn get_game_config(game: Option<Game>, state: State<'_, AppState>) -> GameData {
+    let running = state.telemetry.snapshot().state.game;
+    games::data(game.or(running).unwrap_or(Game::Ets2)).clone()
+}
+
+/// Whether the app starts with Windows
+#[command]
+pub fn get_autostart() -> bool {
+    autostart::is_enabled()
+}
+
+/// Register or unregister the app to start with Windows
+#[command]
+pub fn set_autostart(enabled: bool, app: AppHandle, state: State<'_, AppState>) -> Result<bool, AppError> {
+    autostart::set(enabled)?;
+    info!("Autostart {}", if enabled { "enabled" } else { "disabled" });
+    
+    let settings = {
+        let mut settings = state.settings.lock()?;
+        settings.general.auto_start = enabled;
+        settings.clone()
+    };
+    settings.save(&state.storage)?;
+    let _ = app.emit("settings_changed", &settings);
+    
+    Ok(enabled)
+}
+
+/// Get the token local clients (overlay, integrations) must present
+#[command]
+pub fn get_local_access_token(state: State<'_, AppState>) -> Result<String, AppError> {
+    state.local_token.lock()
+        .map(|token| token.as_str().to_string())
+        .map_err(AppError::from)
+}
+
+/// Rotate the local access token, disconnecting existing local clients
+#[command]
+pub fn regenerate_local_access_token(state: State<'_, AppState>) -> Result<String, AppError> {
+    let token = LocalAccessToken::regenerate(&state.storage)?;
+    let value = token.as_str().to_string();
+    
+    let mut current = state.local_token.lock()?;
+    *current = token;
+    
+    Ok(value)
+}
+
 /// Minimize window
 #[command]
 pub fn minimize_window(window: WebviewWindow) {
@@ -283,6 +2978,6 @@ pub fn hide_to_tray(window: WebviewWindow) {
 
 /// Close window
 #[command]
-pub fn close_window(app: AppHandle) {
-    app.exit(0);
+pub async fn close_window(app: AppHandle) {
+    quit(&app).await;
 }
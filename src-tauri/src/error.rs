@@ -0,0 +1,205 @@
+//! Error Module
+//!
+//! Error returned by every Tauri command. It serializes as `{ code, message }`
+//! so the frontend can branch on the kind of failure, e.g. send the driver
+//! to sign in again on `AUTH_EXPIRED`, instead of matching message text.
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+
+use crate::audit::AuditError;
+use crate::autostart::AutostartError;
+use crate::history::HistoryError;
+use crate::import::ImportError;
+use crate::manual::ManualJobError;
+use crate::overlay::OverlayError;
+use crate::settings::SettingsError;
+use crate::stats_card::CardError;
+use crate::storage::StorageError;
+use crate::sync::ApiError;
+use crate::updates::UpdateError;
+
+/// Command errors
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("Not authenticated")]
+    AuthRequired,
+
+    #[error("Session expired: {0}")]
+    AuthExpired(String),
+
+    #[error("Network error: {0}")]
+    Network(String),
+
+    #[error("Server error: {0}")]
+    Server(String),
+
+    #[error("Server is under maintenance, submissions are paused")]
+    Maintenance,
+
+    #[error("Too many requests, try again shortly")]
+    RateLimited,
+
+    #[error("Missing telemetry required by your VTC: {0}")]
+    Policy(String),
+
+    #[error("Telemetry unavailable: {0}")]
+    TelemetryUnavailable(String),
+
+    #[error("Storage error: {0}")]
+    Storage(String),
+
+    #[error("{0}")]
+    Invalid(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("File error: {0}")]
+    Io(String),
+
+    #[error("{0}")]
+    Internal(String),
+}
+
+impl AppError {
+    /// Stable code the frontend branches on
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::AuthRequired => "AUTH_REQUIRED",
+            AppError::AuthExpired(_) => "AUTH_EXPIRED",
+            AppError::Network(_) => "NETWORK",
+            AppError::Server(_) => "SERVER",
+            AppError::Maintenance => "MAINTENANCE",
+            AppError::RateLimited => "RATE_LIMITED",
+            AppError::Policy(_) => "POLICY",
+            AppError::TelemetryUnavailable(_) => "TELEMETRY_UNAVAILABLE",
+            AppError::Storage(_) => "STORAGE",
+            AppError::Invalid(_) => "INVALID",
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::Io(_) => "IO",
+            AppError::Internal(_) => "INTERNAL",
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut error = serializer.serialize_struct("AppError", 2)?;
+        error.serialize_field("code", self.code())?;
+        error.serialize_field("message", &self.to_string())?;
+        error.end()
+    }
+}
+
+impl From<ApiError> for AppError {
+    fn from(e: ApiError) -> Self {
+        match e {
+            ApiError::Network(message) => AppError::Network(message),
+            ApiError::Server(message) | ApiError::Rejected(message) | ApiError::Parse(message) => AppError::Server(message),
+            ApiError::Unauthorized(message) => AppError::AuthExpired(message),
+            ApiError::Maintenance(_) => AppError::Maintenance,
+            ApiError::RateLimited(_) => AppError::RateLimited,
+            ApiError::Policy(message) => AppError::Policy(message),
+        }
+    }
+}
+
+impl From<StorageError> for AppError {
+    fn from(e: StorageError) -> Self {
+        AppError::Storage(e.to_string())
+    }
+}
+
+impl From<HistoryError> for AppError {
+    fn from(e: HistoryError) -> Self {
+        AppError::Storage(e.to_string())
+    }
+}
+
+impl From<AuditError> for AppError {
+    fn from(e: AuditError) -> Self {
+        AppError::Storage(e.to_string())
+    }
+}
+
+impl From<ManualJobError> for AppError {
+    fn from(e: ManualJobError) -> Self {
+        AppError::Invalid(e.to_string())
+    }
+}
+
+impl From<SettingsError> for AppError {
+    fn from(e: SettingsError) -> Self {
+        AppError::Invalid(e.to_string())
+    }
+}
+
+impl From<OverlayError> for AppError {
+    fn from(e: OverlayError) -> Self {
+        match e {
+            OverlayError::NotFound(_) => AppError::NotFound(e.to_string()),
+            _ => AppError::Invalid(e.to_string()),
+        }
+    }
+}
+
+impl From<ImportError> for AppError {
+    fn from(e: ImportError) -> Self {
+        AppError::Invalid(e.to_string())
+    }
+}
+
+impl From<UpdateError> for AppError {
+    fn from(e: UpdateError) -> Self {
+        match e {
+            UpdateError::NotAvailable => AppError::NotFound(e.to_string()),
+            UpdateError::Download(message) => AppError::Network(message),
+            UpdateError::Io(_) => AppError::Storage(e.to_string()),
+            UpdateError::Checksum
+            | UpdateError::Signature(_)
+            | UpdateError::Version(_)
+            | UpdateError::Launch(_) => AppError::Internal(e.to_string()),
+        }
+    }
+}
+
+impl From<AutostartError> for AppError {
+    fn from(e: AutostartError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<CardError> for AppError {
+    fn from(e: CardError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        AppError::Io(e.to_string())
+    }
+}
+
+impl<T> From<std::sync::PoisonError<T>> for AppError {
+    fn from(e: std::sync::PoisonError<T>) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_code_and_message() {
+        let error = AppError::from(ApiError::Unauthorized("Token revoked".into()));
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({"code": "AUTH_EXPIRED", "message": "Session expired: Token revoked"}),
+        );
+        assert_eq!(AppError::from(ApiError::Maintenance(None)).code(), "MAINTENANCE");
+        assert_eq!(AppError::from(StorageError::Io("disk full".into())).code(), "STORAGE");
+    }
+}
@@ -1,24 +1,113 @@
 //! VTC Tracker Desktop Library
 //!
-//! Core modules for the desktop companion app.
+//! Tauri shell of the desktop companion app: commands, background services
+//! and OS integration on top of `vtc-tracker-core`, whose modules are
+//! re-exported here.
 
-pub mod auth;
-pub mod storage;
-pub mod sync;
-pub mod telemetry;
-pub mod logging;
+pub use vtc_tracker_core::{
+    app_health, audit, auth, checkpoint, clock, connectivity, convoy, currency, dedup, distances, events, focus,
+    games, heartbeat, history, import, integrity, job_queue, last_errors, local_api, local_auth, logging, maintenance, manual, minimap, multiplayer,
+    names, overlay, positions, power, preflight, privacy, processes, progress, reconnect, route, rules, scs,
+    server_status, settings, signing, snapshots, speeding, storage, tachograph, sync, sync_health, telemetry, vehicles,
+};
+
+pub mod audio;
+pub mod autostart;
+pub mod notifications;
+pub mod services;
+pub mod shutdown;
+pub mod stats_card;
+pub mod taskbar;
+pub mod tray;
+pub mod updates;
 pub mod commands;
+pub mod error;
 
 use std::sync::Mutex;
-use auth::AuthManager;
+use std::sync::Arc;
+use app_health::AppHealth;
+use audio::SoundPlayer;
+use audit::AuditLog;
+use auth::{AuthManager, TokenCache};
+use checkpoint::{Checkpointer, RecoveredJob};
+use clock::SharedClock;
+use connectivity::ConnectivityMonitor;
+use convoy::ConvoySession;
+use focus::GameFocus;
+use heartbeat::ConnectionStatus;
+use history::JobHistory;
+use job_queue::JobQueue;
+use last_errors::LastErrors;
+use local_auth::LocalAccessToken;
+use minimap::MapPosition;
 use storage::SecureStorage;
-use sync::ApiClient;
-use telemetry::TelemetryReader;
+use sync::{ApiClient, Presence};
+use sync_health::SyncHealth;
+use positions::PositionBatcher;
+use server_status::ServerStatus;
+use settings::Settings;
+use services::AppServices;
+use shutdown::Shutdown;
+use speeding::ConductReport;
+use telemetry::thread::TelemetryThread;
+use tray::TrayIndicator;
+use updates::Updates;
+use vehicles::VehicleCache;
 
 /// Application state shared across commands
 pub struct AppState {
     pub auth: Mutex<AuthManager>,
+    /// Wall clock behind every time-based decision (expiry, scheduling, retention)
+    pub clock: SharedClock,
+    /// Lock-light token reads for background services
+    pub tokens: Arc<TokenCache>,
     pub storage: SecureStorage,
     pub api: ApiClient,
-    pub telemetry: Mutex<TelemetryReader>,
+    pub telemetry: TelemetryThread,
+    pub settings: Mutex<Settings>,
+    pub local_token: Mutex<LocalAccessToken>,
+    pub positions: Mutex<PositionBatcher>,
+    /// Job submissions that failed and can be retried, per account
+    pub failed_jobs: Mutex<JobQueue>,
+    /// When the job and queue are next written to disk
+    pub checkpointer: Mutex<Checkpointer>,
+    /// Violations of the current job, or the last one until the next starts
+    pub conduct: Mutex<ConductReport>,
+    /// Job interrupted in the last run, until the driver decides on it
+    pub recovered_job: Mutex<Option<RecoveredJob>>,
+    /// Convoy currently being recorded, if any
+    pub convoy: Mutex<Option<ConvoySession>>,
+    /// Truck on the mini-map, `None` while hidden
+    pub map_position: Mutex<Option<MapPosition>>,
+    /// Latest presence for heartbeats, without the position inside privacy zones
+    pub presence: Mutex<Presence>,
+    /// Server-signaled maintenance window
+    pub server_status: Mutex<ServerStatus>,
+    /// Heartbeat and submission outcome counters
+    pub sync_health: Mutex<SyncHealth>,
+    /// State shown by the tray icon
+    pub tray: Mutex<TrayIndicator>,
+    /// API reachability from the connectivity probes; offline queues work
+    pub connectivity: Mutex<ConnectivityMonitor>,
+    /// Last connection status sent to the UI
+    pub connection: Mutex<ConnectionStatus>,
+    /// Most recent failure per subsystem
+    pub last_errors: Mutex<LastErrors>,
+    /// Latest `app_health` snapshot
+    pub app_health: Mutex<Option<AppHealth>>,
+    /// Game window focus, `None` while unknown
+    pub game_focus: Mutex<Option<GameFocus>>,
+    /// Hash-chained record of auth events and submissions
+    pub audit: Mutex<AuditLog>,
+    /// Local database of completed jobs
+    pub history: Mutex<JobHistory>,
+    /// Local IDs of the trucks and trailers the driver used
+    pub vehicles: Mutex<VehicleCache>,
+    /// Cancellation for background tasks on quit/logout
+    pub shutdown: Shutdown,
+    /// Latest release newer than this build
+    pub updates: Mutex<Updates>,
+    /// Supervised background services (telemetry loop, heartbeats, ...)
+    pub services: AppServices,
+    pub sounds: SoundPlayer,
 }
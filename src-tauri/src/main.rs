@@ -7,16 +7,35 @@
 
 
 use tauri::Manager;
-use tracing::info;
-use std::sync::Mutex;
+use tracing::{info, warn};
 
 use vtc_tracker_lib::{
+    audio::SoundPlayer,
+    audit::{self, AuditLog},
+    autostart,
     auth::AuthManager,
+    checkpoint,
+    clock,
+    connectivity::ConnectivityMonitor,
+    history::{self, JobHistory},
+    last_errors::LastErrors,
+    local_auth::LocalAccessToken,
+    positions::PositionBatcher,
+    server_status::ServerStatus,
+    services::AppServices,
+    settings::Settings,
+    shutdown::Shutdown,
+    signing::DeviceKey,
     storage::SecureStorage,
-    sync::ApiClient,
-    telemetry::TelemetryReader,
+    sync::{ApiClient, Presence},
+    sync_health::SyncHealth,
+    telemetry::{simulator::Simulator, thread::TelemetryThread, TelemetryReader},
+    tray::{self, TrayIndicator, TrayStatus, TrayView},
+    updates::Updates,
+    vehicles::VehicleCache,
     logging,
     commands,
+    heartbeat::ConnectionStatus,
     AppState,
 };
 
@@ -26,80 +45,210 @@ fn main() {
     info!("VTC Tracker Desktop starting...");
 
     // Initialize application state
+    let clock = clock::system();
     let storage = SecureStorage::new();
-    // TODO: Change this to your Render URL when deployed (e.g., "https://api.vtc-tracker.com")
-    const DEFAULT_API_URL: &str = "http://localhost:3000";
-
-    let api_base_url = std::env::var("VTC_API_URL")
-        .unwrap_or_else(|_| DEFAULT_API_URL.to_string());
+    let settings = Settings::load(&storage);
+    logging::configure(&settings.logging);
+    logging::purge(&logging::log_directory(), &settings.logging, clock.now());
+    let audit_log = AuditLog::open(storage.dir().join(audit::AUDIT_FILE));
+    let history = JobHistory::open(&storage.dir().join(history::HISTORY_FILE)).unwrap_or_else(|e| {
+        warn!("{}, keeping this session's jobs in memory", e);
+        JobHistory::in_memory().expect("in-memory SQLite database")
+    });
+    if let Err(e) = autostart::repair(settings.general.auto_start) {
+        warn!("Failed to repair autostart entry: {}", e);
+    }
+    let local_token = LocalAccessToken::load_or_create(&storage);
+    let device_key = DeviceKey::load_or_create(&storage);
+    let vehicles = VehicleCache::load(&storage);
+    let mut telemetry = TelemetryReader::new();
+    let recovery = checkpoint::recover(&storage, &mut telemetry);
+    if let Some(game) = settings.general.simulator() {
+        info!("Telemetry simulator enabled ({})", game);
+        telemetry.set_simulator(Some(Simulator::new(game)));
+    }
+    let api_base_url = settings.general.api_url();
     
+    let auth = AuthManager::with_clock(clock.clone());
+    let api = ApiClient::new(&api_base_url);
+    api.set_device_key(device_key);
+    if let Err(e) = api.set_network(&settings.network) {
+        warn!("{}, using the system proxy", e);
+    }
     let app_state = AppState {
-        auth: std::sync::Mutex::new(AuthManager::new()),
+        tokens: auth.token_cache(),
+        auth: std::sync::Mutex::new(auth),
+        clock,
         storage,
-        api: ApiClient::new(&api_base_url),
-        telemetry: std::sync::Mutex::new(TelemetryReader::new()),
+        api,
+        telemetry: TelemetryThread::spawn(telemetry).expect("telemetry reader thread"),
+        settings: std::sync::Mutex::new(settings),
+        local_token: std::sync::Mutex::new(local_token),
+        positions: std::sync::Mutex::new(PositionBatcher::new()),
+        failed_jobs: std::sync::Mutex::new(recovery.queue),
+        checkpointer: std::sync::Mutex::new(checkpoint::Checkpointer::new()),
+        conduct: std::sync::Mutex::new(recovery.job.as_ref().map(|job| job.conduct.clone()).unwrap_or_default()),
+        recovered_job: std::sync::Mutex::new(recovery.job),
+        convoy: std::sync::Mutex::new(None),
+        map_position: std::sync::Mutex::new(None),
+        presence: std::sync::Mutex::new(Presence::default()),
+        server_status: std::sync::Mutex::new(ServerStatus::new()),
+        sync_health: std::sync::Mutex::new(SyncHealth::new()),
+        tray: std::sync::Mutex::new(TrayIndicator::new()),
+        connectivity: std::sync::Mutex::new(ConnectivityMonitor::new()),
+        connection: std::sync::Mutex::new(ConnectionStatus::LoggedOut),
+        last_errors: std::sync::Mutex::new(LastErrors::new()),
+        app_health: std::sync::Mutex::new(None),
+        game_focus: std::sync::Mutex::new(None),
+        audit: std::sync::Mutex::new(audit_log),
+        history: std::sync::Mutex::new(history),
+        vehicles: std::sync::Mutex::new(vehicles),
+        updates: std::sync::Mutex::new(Updates::new()),
+        shutdown: Shutdown::new(),
+        services: AppServices::new(),
+        sounds: SoundPlayer::new(),
     };
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
 
-        .on_window_event(|window, event| match event {
-            tauri::WindowEvent::CloseRequested { api, .. } => {
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 window.hide().unwrap();
                 api.prevent_close();
             }
-            _ => {}
         })
         .manage(app_state)
         .invoke_handler(tauri::generate_handler![
             commands::get_stored_session,
+            commands::get_recovered_job,
+            commands::recover_active_job,
+            commands::list_accounts,
+            commands::switch_account,
+            commands::remove_account,
+            commands::get_storage_status,
             commands::verify_device_code,
+            commands::create_transfer_code,
+            commands::redeem_transfer_code,
             commands::logout,
             commands::start_telemetry,
+            commands::stop_telemetry,
             commands::send_heartbeat,
+            commands::get_connection_status,
+            commands::get_app_health,
+            commands::get_last_errors,
+            commands::read_logs,
+            commands::purge_logs,
+            commands::retry_failed_jobs,
+            commands::resubmit_job,
+            commands::get_connectivity,
+            commands::pause_tracking,
+            commands::resume_tracking,
+            commands::test_notification,
+            commands::start_convoy,
+            commands::stop_convoy,
+            commands::get_convoy,
+            commands::get_server_status,
+            commands::get_sync_health,
+            commands::run_preflight,
+            commands::export_stats_card,
+            commands::import_logbook,
+            commands::submit_manual_job,
+            commands::get_settings,
+            commands::update_settings,
+            commands::get_overlay_layout,
+            commands::save_overlay_preset,
+            commands::set_overlay_preset,
+            commands::export_overlay_preset,
+            commands::import_overlay_preset,
+            commands::verify_audit_log,
+            commands::export_audit_log,
+            commands::export_route,
+            commands::get_map_position,
+            commands::get_local_stats,
+            commands::get_job_history,
+            commands::get_job_stats,
+            commands::get_violations_report,
+            commands::get_telemetry_policy,
+            commands::get_game_config,
+            commands::send_feedback,
+            commands::check_for_updates,
+            commands::install_update,
+            commands::get_autostart,
+            commands::set_autostart,
+            commands::get_local_access_token,
+            commands::regenerate_local_access_token,
             commands::minimize_window,
             commands::hide_to_tray,
             commands::close_window,
         ])
         .setup(|app| {
-            let tray_menu = tauri::menu::Menu::with_items(app, &[
-                &tauri::menu::MenuItem::with_id(app, "show", "Show", true, None::<&str>)?,
-                &tauri::menu::MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?,
-            ])?;
+            let tray_menu = tray::menu(app.handle(), &TrayView::default())?;
 
-            tauri::tray::TrayIconBuilder::new()
-                .icon(app.default_window_icon().unwrap().clone())
+            tauri::tray::TrayIconBuilder::with_id(tray::TRAY_ID)
+                .icon(TrayStatus::NoGame.icon(app.default_window_icon().unwrap()))
+                .tooltip("VTC Tracker")
                 .menu(&tray_menu)
                 .on_menu_event(|app, event| match event.id().as_ref() {
-                    "show" => {
+                    tray::MENU_SHOW => {
                         if let Some(window) = app.get_webview_window("main") {
                             let _ = window.show();
                             let _ = window.set_focus();
                         }
                     }
-                    "quit" => {
-                        app.exit(0);
+                    tray::MENU_TOGGLE_TRACKING => {
+                        let paused = app.state::<AppState>().telemetry.snapshot().state.tracking_paused;
+                        if let Err(e) = commands::set_tracking_paused(app, !paused) {
+                            warn!("Failed to toggle tracking: {}", e);
+                        }
+                    }
+                    tray::MENU_OPEN_LOGS => tray::open_logs(),
+                    tray::MENU_QUIT => {
+                        let app = app.clone();
+                        tauri::async_runtime::spawn(async move { commands::quit(&app).await });
                     }
                     _ => {}
                 })
-                .on_tray_icon_event(|tray, event| match event {
-                    tauri::tray::TrayIconEvent::Click {
+                .on_tray_icon_event(|tray, event| {
+                    if let tauri::tray::TrayIconEvent::Click {
                         button: tauri::tray::MouseButton::Left,
                         ..
-                    } => {
+                    } = event {
                         let app = tray.app_handle();
                         if let Some(window) = app.get_webview_window("main") {
                             let _ = window.show();
                             let _ = window.set_focus();
                         }
                     }
-                    _ => {}
                 })
                 .build(app)?;
 
+            // Started with Windows: stay in the tray
+            if autostart::launched_at_login() {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.hide();
+                }
+            }
+
+            commands::start_services(app.handle());
+
+            // Settings, tokens and checkpoints were read before the webview existed
+            commands::emit_storage_reset(app.handle(), &app.state::<AppState>());
+            commands::emit_recovered_job(app.handle(), &app.state::<AppState>());
+
             info!("Application setup complete");
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("Error running VTC Tracker");
+        .build(tauri::generate_context!())
+        .expect("Error building VTC Tracker")
+        .run(|app, event| {
+            // Exits the OS asks for (logoff, shutdown) drain the services too
+            if let tauri::RunEvent::ExitRequested { api, .. } = event {
+                if !app.state::<AppState>().shutdown.is_finished() {
+                    api.prevent_exit();
+                    let app = app.clone();
+                    tauri::async_runtime::spawn(async move { commands::quit(&app).await });
+                }
+            }
+        });
 }
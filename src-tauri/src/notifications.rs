@@ -0,0 +1,138 @@
+//! Notifications Module
+//!
+//! Native toast notifications for key events, each of which can be turned
+//! off in settings, with buttons routed back into the backend.
+
+use tracing::debug;
+#[cfg(windows)]
+use tracing::warn;
+
+use crate::settings::NotificationSettings;
+
+/// Events that can raise a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationEvent {
+    JobSynced,
+    SyncFailed,
+    GameConnected,
+    SessionExpiring,
+}
+
+/// Whether `event` should raise a notification
+pub fn enabled_for(settings: &NotificationSettings, event: NotificationEvent) -> bool {
+    let enabled = match event {
+        NotificationEvent::JobSynced => settings.job_synced,
+        NotificationEvent::SyncFailed => settings.sync_failed,
+        NotificationEvent::GameConnected => settings.game_connected,
+        NotificationEvent::SessionExpiring => settings.session_expiring,
+    };
+    settings.enabled && enabled
+}
+
+/// Backend action triggered from a toast button
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastAction {
+    /// Retry failed job submissions
+    RetryFailedJobs,
+    /// Bring the main window up with sync error details
+    ViewSyncErrors,
+}
+
+impl ToastAction {
+    /// Argument string carried by the toast button
+    pub fn as_arg(self) -> &'static str {
+        match self {
+            ToastAction::RetryFailedJobs => "retry_failed_jobs",
+            ToastAction::ViewSyncErrors => "view_sync_errors",
+        }
+    }
+
+    /// Parse the argument string reported on activation
+    pub fn parse(arg: &str) -> Option<Self> {
+        match arg {
+            "retry_failed_jobs" => Some(ToastAction::RetryFailedJobs),
+            "view_sync_errors" => Some(ToastAction::ViewSyncErrors),
+            _ => None,
+        }
+    }
+}
+
+/// A toast to display
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub title: String,
+    pub body: String,
+    pub actions: Vec<(String, ToastAction)>,
+}
+
+impl Notification {
+    /// Create a notification without buttons
+    pub fn new(title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            body: body.into(),
+            actions: Vec::new(),
+        }
+    }
+
+    /// Add a button that triggers `action` when clicked
+    pub fn action(mut self, label: impl Into<String>, action: ToastAction) -> Self {
+        self.actions.push((label.into(), action));
+        self
+    }
+}
+
+/// Show a toast; `on_action` is invoked (on a system thread) when a button is clicked
+#[cfg(windows)]
+pub fn show<F>(app_id: &str, notification: Notification, on_action: F)
+where
+    F: Fn(ToastAction) + Send + 'static,
+{
+    use tauri_winrt_notification::Toast;
+
+    // Unpackaged dev builds have no registered AppUserModelID
+    let app_id = if cfg!(debug_assertions) { Toast::POWERSHELL_APP_ID } else { app_id };
+
+    debug!("Showing notification: {}", notification.title);
+    let toast = notification.actions.iter().fold(
+        Toast::new(app_id).title(&notification.title).text1(&notification.body),
+        |toast, (label, action)| toast.add_button(label, action.as_arg()),
+    );
+
+    let result = toast
+        .on_activated(move |arg| {
+            if let Some(action) = arg.as_deref().and_then(ToastAction::parse) {
+                on_action(action);
+            }
+            Ok(())
+        })
+        .show();
+
+    if let Err(e) = result {
+        warn!("Failed to show notification: {}", e);
+    }
+}
+
+/// Show a toast (no native toasts on this platform; logged only)
+#[cfg(not(windows))]
+pub fn show<F>(_app_id: &str, notification: Notification, _on_action: F)
+where
+    F: Fn(ToastAction) + Send + 'static,
+{
+    debug!("Notification: {} - {}", notification.title, notification.body);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_follow_their_toggle_and_the_master_switch() {
+        let mut settings = NotificationSettings { game_connected: false, ..Default::default() };
+        assert!(enabled_for(&settings, NotificationEvent::JobSynced));
+        assert!(!enabled_for(&settings, NotificationEvent::GameConnected));
+
+        settings.enabled = false;
+        assert!(!enabled_for(&settings, NotificationEvent::JobSynced));
+    }
+}
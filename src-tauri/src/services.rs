@@ -0,0 +1,198 @@
+//! Services Module
+//!
+//! Supervisor for the long-running background services (telemetry loop,
+//! heartbeats, queue draining, ...). The set of services is started once from
+//! setup, a service never runs twice, and one that exits while the app is
+//! still running is restarted according to its policy.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::shutdown::BackgroundTask;
+
+/// Longest wait between restarts of a service that keeps failing
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(60);
+/// A service that ran this long before exiting restarts without backoff
+const HEALTHY_RUN: Duration = Duration::from_secs(60);
+
+/// What to do when a service exits on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// Leave it stopped
+    Never,
+    /// Start it again after the delay, doubling it while it keeps failing
+    Backoff(Duration),
+}
+
+/// One run of a service
+pub type ServiceFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Background services by name
+pub struct AppServices {
+    init: Once,
+    tasks: Mutex<BTreeMap<&'static str, BackgroundTask>>,
+}
+
+impl AppServices {
+    pub fn new() -> Self {
+        Self { init: Once::new(), tasks: Mutex::default() }
+    }
+
+    /// Run `start` the first time this is called; later calls do nothing
+    pub fn init(&self, start: impl FnOnce(&Self)) {
+        self.init.call_once(|| start(self));
+    }
+
+    /// Start a service unless it is already running; `run` is called again
+    /// for every restart and must return once its token is cancelled
+    pub fn start<F>(&self, name: &'static str, policy: RestartPolicy, cancel: CancellationToken, run: F) -> bool
+    where
+        F: Fn(CancellationToken) -> ServiceFuture + Send + Sync + 'static,
+    {
+        let Ok(mut tasks) = self.tasks.lock() else {
+            return false;
+        };
+        if tasks.get(name).is_some_and(BackgroundTask::is_running) {
+            debug!("Service {} already running", name);
+            return false;
+        }
+        info!("Starting service {}", name);
+        let handle = tauri::async_runtime::spawn(supervise(name, policy, cancel.clone(), run));
+        tasks.insert(name, BackgroundTask::new(cancel, handle));
+        true
+    }
+
+    /// Whether the service is running (or waiting to restart)
+    pub fn is_running(&self, name: &str) -> bool {
+        self.tasks.lock()
+            .map(|tasks| tasks.get(name).is_some_and(BackgroundTask::is_running))
+            .unwrap_or(false)
+    }
+
+    /// Stop a service and wait for it to clean up
+    pub async fn stop(&self, name: &str) {
+        let task = self.tasks.lock().ok().and_then(|mut tasks| tasks.remove(name));
+        if let Some(task) = task {
+            task.stop().await;
+        }
+    }
+
+    /// Stop every service and wait until all of them have cleaned up
+    pub async fn stop_all(&self) {
+        let tasks = self.tasks.lock()
+            .map(|mut tasks| std::mem::take(&mut *tasks))
+            .unwrap_or_default();
+        // Cancel them all first so they wind down together
+        for task in tasks.values() {
+            task.cancel();
+        }
+        for (name, task) in tasks {
+            task.stop().await;
+            debug!("Service {} stopped for shutdown", name);
+        }
+    }
+}
+
+impl Default for AppServices {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run a service until `cancel` fires, restarting it per `policy`
+async fn supervise<F>(name: &'static str, policy: RestartPolicy, cancel: CancellationToken, run: F)
+where
+    F: Fn(CancellationToken) -> ServiceFuture + Send + Sync + 'static,
+{
+    let mut failures = 0u32;
+    loop {
+        let started = Instant::now();
+        // Own task, so a panic ends this run instead of the supervisor
+        let result = tauri::async_runtime::spawn(run(cancel.clone())).await;
+        if cancel.is_cancelled() {
+            break;
+        }
+        match result {
+            Ok(()) => warn!("Service {} stopped unexpectedly", name),
+            Err(e) => warn!("Service {} crashed: {}", name, e),
+        }
+        let RestartPolicy::Backoff(initial) = policy else {
+            break;
+        };
+        failures = if started.elapsed() >= HEALTHY_RUN { 0 } else { failures + 1 };
+        let delay = initial.saturating_mul(2u32.saturating_pow(failures.saturating_sub(1))).min(MAX_RESTART_DELAY);
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = tokio::time::sleep(delay) => {}
+        }
+        info!("Restarting service {}", name);
+    }
+    debug!("Service {} stopped", name);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn restarts_failed_service_once_per_exit() {
+        let services = AppServices::new();
+        let runs = Arc::new(AtomicU32::new(0));
+        let cancel = CancellationToken::new();
+        let start = |services: &AppServices| {
+            let counter = runs.clone();
+            services.start("flaky", RestartPolicy::Backoff(Duration::from_millis(5)), cancel.clone(), move |cancel| {
+                let run = counter.fetch_add(1, Ordering::SeqCst);
+                Box::pin(async move {
+                    // Fails twice, then runs until stopped
+                    if run >= 2 {
+                        cancel.cancelled().await;
+                    }
+                })
+            })
+        };
+
+        let mut started = Vec::new();
+        services.init(|services| started.push(start(services)));
+        services.init(|services| started.push(start(services)));
+        assert_eq!(started, [true]);
+
+        tauri::async_runtime::block_on(async {
+            while runs.load(Ordering::SeqCst) < 3 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+            assert!(services.is_running("flaky"));
+            services.stop("flaky").await;
+        });
+        assert!(!services.is_running("flaky"));
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn stop_all_waits_for_cleanup() {
+        let services = AppServices::new();
+        let cleaned_up = Arc::new(AtomicU32::new(0));
+        for name in ["first", "second"] {
+            let cleaned_up = cleaned_up.clone();
+            services.start(name, RestartPolicy::Never, CancellationToken::new(), move |cancel| {
+                let cleaned_up = cleaned_up.clone();
+                Box::pin(async move {
+                    cancel.cancelled().await;
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                    cleaned_up.fetch_add(1, Ordering::SeqCst);
+                })
+            });
+        }
+
+        tauri::async_runtime::block_on(services.stop_all());
+        assert_eq!(cleaned_up.load(Ordering::SeqCst), 2);
+        assert!(!services.is_running("first") && !services.is_running("second"));
+    }
+}
@@ -0,0 +1,139 @@
+//! Shutdown Module
+//!
+//! Cancellation tokens for background tasks. App-scoped tasks (telemetry loop,
+//! local servers) stop on quit; session-scoped tasks (uploads, heartbeats,
+//! queue draining) stop on logout as well. Quitting waits for the services
+//! to clean up before the tokens are cancelled and the process exits.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::async_runtime::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+/// Hands out cancellation tokens and cancels them on quit or logout
+pub struct Shutdown {
+    app: CancellationToken,
+    session: Mutex<CancellationToken>,
+    /// Set once a quit has started
+    quitting: AtomicBool,
+}
+
+impl Shutdown {
+    /// Create a fresh set of tokens
+    pub fn new() -> Self {
+        let app = CancellationToken::new();
+        let session = Mutex::new(app.child_token());
+        Self { app, session, quitting: AtomicBool::new(false) }
+    }
+
+    /// Token for tasks that run for the lifetime of the app
+    pub fn app_token(&self) -> CancellationToken {
+        self.app.child_token()
+    }
+
+    /// Token for tasks that act on behalf of the signed-in user
+    pub fn session_token(&self) -> CancellationToken {
+        self.session.lock()
+            .map(|session| session.child_token())
+            .unwrap_or_else(|_| self.app.child_token())
+    }
+
+    /// Stop session-scoped tasks (logout); later tasks get a new session token
+    pub fn end_session(&self) {
+        if let Ok(mut session) = self.session.lock() {
+            info!("Cancelling session tasks");
+            session.cancel();
+            *session = self.app.child_token();
+        }
+    }
+
+    /// Claim the quit; false if one is already under way
+    pub fn start_quit(&self) -> bool {
+        !self.quitting.swap(true, Ordering::SeqCst)
+    }
+
+    /// Whether `quit` has run, i.e. the services are stopped and the app may exit
+    pub fn is_finished(&self) -> bool {
+        self.app.is_cancelled()
+    }
+
+    /// Stop every background task still running (quit), once the services
+    /// have been stopped
+    pub fn quit(&self) {
+        info!("Cancelling all background tasks");
+        self.quitting.store(true, Ordering::SeqCst);
+        self.app.cancel();
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A background loop that can be stopped and must not run twice
+pub struct BackgroundTask {
+    cancel: CancellationToken,
+    handle: JoinHandle<()>,
+}
+
+impl BackgroundTask {
+    /// Track a spawned loop that exits when `cancel` fires
+    pub fn new(cancel: CancellationToken, handle: JoinHandle<()>) -> Self {
+        Self { cancel, handle }
+    }
+
+    /// Whether the loop is still going
+    pub fn is_running(&self) -> bool {
+        !self.handle.inner().is_finished()
+    }
+
+    /// Ask the loop to stop without waiting for it
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    /// Cancel the loop and wait for it to finish its cleanup
+    pub async fn stop(self) {
+        self.cancel.cancel();
+        let _ = self.handle.await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logout_only_stops_session_tasks() {
+        let shutdown = Shutdown::new();
+        let app_task = shutdown.app_token();
+        let upload = shutdown.session_token();
+
+        shutdown.end_session();
+        assert!(upload.is_cancelled());
+        assert!(!app_task.is_cancelled());
+        assert!(!shutdown.session_token().is_cancelled());
+
+        assert!(shutdown.start_quit());
+        assert!(!shutdown.start_quit());
+        assert!(!shutdown.is_finished());
+        shutdown.quit();
+        assert!(shutdown.is_finished());
+        assert!(app_task.is_cancelled());
+        assert!(shutdown.session_token().is_cancelled());
+    }
+
+    #[test]
+    fn stopped_task_finishes() {
+        let cancel = CancellationToken::new();
+        let token = cancel.clone();
+        let handle = tauri::async_runtime::spawn(async move { token.cancelled().await });
+        let task = BackgroundTask::new(cancel, handle);
+
+        assert!(task.is_running());
+        tauri::async_runtime::block_on(task.stop());
+    }
+}
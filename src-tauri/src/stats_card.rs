@@ -0,0 +1,143 @@
+//! Stats Card Module
+//!
+//! Renders a shareable statistics summary (monthly distance, jobs, top route)
+//! to PNG from an SVG template, so drivers can post it without screenshots.
+
+use serde::Deserialize;
+
+/// Card size in pixels
+const WIDTH: u32 = 800;
+const HEIGHT: u32 = 420;
+
+/// Statistics shown on the card
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsSummary {
+    /// Period label, e.g. "March 2025"
+    pub period: String,
+    pub driver: String,
+    pub distance_km: u64,
+    pub jobs: u32,
+    pub top_route: Option<TopRoute>,
+}
+
+/// Most-driven route in the period
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopRoute {
+    pub source_city: String,
+    pub destination_city: String,
+    pub count: u32,
+}
+
+/// Card rendering errors
+#[derive(Debug, thiserror::Error)]
+pub enum CardError {
+    #[error("Template error: {0}")]
+    Template(String),
+
+    #[error("Render error: {0}")]
+    Render(String),
+}
+
+/// Build the SVG template for `stats`
+pub fn render_svg(stats: &StatsSummary) -> String {
+    let top_route = stats.top_route.as_ref()
+        .map(|route| format!(
+            "{} → {} ({}×)",
+            escape(&route.source_city),
+            escape(&route.destination_city),
+            route.count
+        ))
+        .unwrap_or_else(|| "—".into());
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">
+  <defs>
+    <linearGradient id="bg" x1="0" y1="0" x2="1" y2="1">
+      <stop offset="0" stop-color="#0f172a"/>
+      <stop offset="1" stop-color="#1e3a5f"/>
+    </linearGradient>
+  </defs>
+  <rect width="{w}" height="{h}" rx="24" fill="url(#bg)"/>
+  <g font-family="Segoe UI, Arial, sans-serif" fill="#e2e8f0">
+    <text x="48" y="72" font-size="34" font-weight="bold">{driver}</text>
+    <text x="48" y="108" font-size="20" fill="#94a3b8">{period}</text>
+    <text x="48" y="210" font-size="56" font-weight="bold" fill="#38bdf8">{distance}</text>
+    <text x="48" y="244" font-size="18" fill="#94a3b8">kilometres driven</text>
+    <text x="440" y="210" font-size="56" font-weight="bold" fill="#4ade80">{jobs}</text>
+    <text x="440" y="244" font-size="18" fill="#94a3b8">jobs delivered</text>
+    <text x="48" y="320" font-size="18" fill="#94a3b8">Top route</text>
+    <text x="48" y="354" font-size="26">{top_route}</text>
+    <text x="752" y="396" font-size="14" fill="#64748b" text-anchor="end">VTC Tracker</text>
+  </g>
+</svg>"##,
+        w = WIDTH,
+        h = HEIGHT,
+        driver = escape(&stats.driver),
+        period = escape(&stats.period),
+        distance = group_thousands(stats.distance_km),
+        jobs = stats.jobs,
+        top_route = top_route,
+    )
+}
+
+/// Render the card to PNG bytes
+pub fn render_png(stats: &StatsSummary) -> Result<Vec<u8>, CardError> {
+    let mut options = resvg::usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+
+    let tree = resvg::usvg::Tree::from_str(&render_svg(stats), &options)
+        .map_err(|e| CardError::Template(e.to_string()))?;
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(WIDTH, HEIGHT)
+        .ok_or_else(|| CardError::Render("invalid card size".into()))?;
+    resvg::render(&tree, resvg::tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    pixmap.encode_png().map_err(|e| CardError::Render(e.to_string()))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn group_thousands(value: u64) -> String {
+    let digits = value.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_escaped_template_to_png() {
+        let stats = StatsSummary {
+            period: "March 2025".into(),
+            driver: "Tom & Jerry".into(),
+            distance_km: 12_480,
+            jobs: 37,
+            top_route: Some(TopRoute {
+                source_city: "Berlin".into(),
+                destination_city: "Hamburg".into(),
+                count: 6,
+            }),
+        };
+
+        let svg = render_svg(&stats);
+        assert!(svg.contains("Tom &amp; Jerry"));
+        assert!(svg.contains("12,480"));
+
+        let png = render_png(&stats).unwrap();
+        assert_eq!(&png[..4], b"\x89PNG");
+    }
+}
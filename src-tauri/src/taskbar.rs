@@ -0,0 +1,192 @@
+//! Taskbar Module
+//!
+//! Mirrors delivery progress onto the taskbar button (progress bar state and
+//! window title) so drivers who alt-tab can see it without the overlay.
+
+use chrono::{DateTime, Utc};
+use tauri::window::{ProgressBarState, ProgressBarStatus};
+use tauri::WebviewWindow;
+use tracing::debug;
+
+use crate::telemetry::{ActiveJob, JobPhase, TelemetryField, TelemetryState};
+
+/// Window title when no job is running
+const BASE_TITLE: &str = "VTC Tracker";
+/// Speed below which an in-transit job shows as paused (km/h)
+const STOPPED_SPEED: f32 = 1.0;
+
+/// Progress bar appearance
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskbarStatus {
+    Hidden,
+    Indeterminate,
+    Normal,
+    Paused,
+    Error,
+}
+
+/// What the taskbar button should show
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskbarProgress {
+    pub status: TaskbarStatus,
+    /// Percent of the route driven (0–100)
+    pub percent: Option<u64>,
+    pub title: String,
+}
+
+impl TaskbarProgress {
+    /// Derive the taskbar state from telemetry
+    pub fn from_state(state: &TelemetryState, now: DateTime<Utc>) -> Self {
+        let idle = Self { status: TaskbarStatus::Hidden, percent: None, title: BASE_TITLE.into() };
+
+        match (state.job_phase, state.active_job.as_ref()) {
+            (Some(JobPhase::Failed), _) => Self {
+                status: TaskbarStatus::Error,
+                percent: None,
+                title: format!("{} — job failed", BASE_TITLE),
+            },
+            // Without navigation data there is no progress to show
+            (Some(JobPhase::InTransit), Some(job)) if !state.is_known(TelemetryField::Navigation) => Self {
+                status: TaskbarStatus::Indeterminate,
+                percent: None,
+                title: format!("{} — {} → {}", BASE_TITLE, job.source_city, job.destination_city),
+            },
+            (Some(JobPhase::Offered | JobPhase::Accepted), Some(job)) => Self {
+                status: TaskbarStatus::Indeterminate,
+                percent: None,
+                title: format!("{} — {} → {}", BASE_TITLE, job.source_city, job.destination_city),
+            },
+            (Some(JobPhase::InTransit), Some(job)) => {
+                let status = if state.speed < STOPPED_SPEED {
+                    TaskbarStatus::Paused
+                } else {
+                    TaskbarStatus::Normal
+                };
+                let mut title = format!(
+                    "{} — {}% · {} km left",
+                    BASE_TITLE,
+                    percent_driven(job),
+                    job.distance_remaining
+                );
+                if let Some(minutes) = eta_minutes(job, now) {
+                    title.push_str(&format!(" · ETA {}h {:02}m", minutes / 60, minutes % 60));
+                }
+                Self { status, percent: Some(percent_driven(job)), title }
+            }
+            _ => idle,
+        }
+    }
+}
+
+/// Applies taskbar updates only when something changed
+#[derive(Debug, Default)]
+pub struct TaskbarIndicator {
+    last: Option<TaskbarProgress>,
+}
+
+impl TaskbarIndicator {
+    /// Create a new indicator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Push the current progress to the window's taskbar button
+    pub fn update(&mut self, window: &WebviewWindow, progress: TaskbarProgress) {
+        if self.last.as_ref() == Some(&progress) {
+            return;
+        }
+
+        let status = match progress.status {
+            TaskbarStatus::Hidden => ProgressBarStatus::None,
+            TaskbarStatus::Indeterminate => ProgressBarStatus::Indeterminate,
+            TaskbarStatus::Normal => ProgressBarStatus::Normal,
+            TaskbarStatus::Paused => ProgressBarStatus::Paused,
+            TaskbarStatus::Error => ProgressBarStatus::Error,
+        };
+        // Paused and error states need a value to render on Windows
+        let percent = progress.percent.or(match progress.status {
+            TaskbarStatus::Error => Some(100),
+            _ => None,
+        });
+
+        if let Err(e) = window.set_progress_bar(ProgressBarState { status: Some(status), progress: percent }) {
+            debug!("Failed to set taskbar progress: {}", e);
+        }
+        if let Err(e) = window.set_title(&progress.title) {
+            debug!("Failed to set window title: {}", e);
+        }
+        self.last = Some(progress);
+    }
+}
+
+fn percent_driven(job: &ActiveJob) -> u64 {
+    if job.distance_km == 0 {
+        return 0;
+    }
+    let driven = job.distance_km.saturating_sub(job.distance_remaining);
+    u64::from(driven) * 100 / u64::from(job.distance_km)
+}
+
+/// Remaining minutes at the average speed achieved so far on this job
+fn eta_minutes(job: &ActiveJob, now: DateTime<Utc>) -> Option<i64> {
+    let driven_km = f64::from(job.distance_km.saturating_sub(job.distance_remaining));
+    let elapsed_min = (now - job.started_at).num_seconds() as f64 / 60.0;
+    if driven_km < 1.0 || elapsed_min <= 0.0 {
+        return None;
+    }
+    let km_per_min = driven_km / elapsed_min;
+    Some((f64::from(job.distance_remaining) / km_per_min).round() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn in_transit_shows_progress_and_eta() {
+        let t = Utc.with_ymd_and_hms(2025, 1, 1, 12, 0, 0).unwrap();
+        let state = TelemetryState {
+            speed: 80.0,
+            job_phase: Some(JobPhase::InTransit),
+            active_job: Some(ActiveJob {
+                cargo: "Machinery".into(),
+                source_city: "Berlin".into(),
+                destination_city: "Hamburg".into(),
+                source_company: None,
+                destination_company: None,
+                distance_km: 300,
+                distance_remaining: 200,
+                revenue: 12_000,
+                started_at: t,
+                pickup_damage: Default::default(),
+                delivery_damage: None,
+                max_damage: Default::default(),
+                market: None,
+                trailer_ownership: None,
+                truck: None,
+                trailer: None,
+                legs: Vec::new(),
+                fuel: Default::default(),
+                odometer: Default::default(),
+                integrity: Default::default(),
+                snapshots: Default::default(),
+            }),
+            ..TelemetryState::default()
+        };
+
+        let progress = TaskbarProgress::from_state(&state, t + Duration::minutes(60));
+        assert_eq!(progress.status, TaskbarStatus::Normal);
+        assert_eq!(progress.percent, Some(33));
+        assert!(progress.title.ends_with("ETA 2h 00m"));
+
+        let no_navigation = TelemetryState { unknown: vec![TelemetryField::Navigation], ..state };
+        let progress = TaskbarProgress::from_state(&no_navigation, t + Duration::minutes(60));
+        assert_eq!(progress.status, TaskbarStatus::Indeterminate);
+        assert_eq!(progress.percent, None);
+        assert!(!progress.title.contains("ETA"));
+
+        let idle = TaskbarProgress::from_state(&TelemetryState::default(), t);
+        assert_eq!(idle.status, TaskbarStatus::Hidden);
+    }
+}
@@ -0,0 +1,204 @@
+//! Tray Module
+//!
+//! Reflects the tracker's state in the tray: a coloured badge on the app icon
+//! (grey: no game, green: connected, blue: on a job, red: sync problems), the
+//! status in the tooltip and a menu showing the current job.
+
+use tauri::image::Image;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::{AppHandle, Wry};
+use tracing::{debug, warn};
+
+use crate::logging;
+use crate::sync_health::SyncStatus;
+use crate::telemetry::TelemetryState;
+
+/// Tray icon ID, for tooltip updates
+pub const TRAY_ID: &str = "main";
+/// Menu item IDs handled in `main`
+pub const MENU_SHOW: &str = "show";
+pub const MENU_TOGGLE_TRACKING: &str = "toggle_tracking";
+pub const MENU_OPEN_LOGS: &str = "open_logs";
+pub const MENU_QUIT: &str = "quit";
+/// Informational items, never enabled
+const MENU_STATUS: &str = "status";
+const MENU_JOB: &str = "job";
+/// Badge radius as a fraction of the icon edge
+const BADGE_RADIUS: f32 = 0.3;
+
+/// Overall state shown by the icon badge
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrayStatus {
+    #[default]
+    NoGame,
+    Connected,
+    OnJob,
+    SyncError,
+}
+
+impl TrayStatus {
+    /// Sync problems take precedence, then the job, then the game
+    pub fn from_state(telemetry: &TelemetryState, sync: SyncStatus) -> Self {
+        if matches!(sync, SyncStatus::Degraded | SyncStatus::Offline) {
+            TrayStatus::SyncError
+        } else if telemetry.active_job.is_some() {
+            TrayStatus::OnJob
+        } else if telemetry.connected {
+            TrayStatus::Connected
+        } else {
+            TrayStatus::NoGame
+        }
+    }
+
+    /// First line of the tooltip and menu
+    pub fn label(self) -> &'static str {
+        match self {
+            TrayStatus::NoGame => "No game running",
+            TrayStatus::Connected => "Game connected",
+            TrayStatus::OnJob => "On a job",
+            TrayStatus::SyncError => "Sync problems",
+        }
+    }
+
+    fn color(self) -> [u8; 3] {
+        match self {
+            TrayStatus::NoGame => [140, 140, 140],
+            TrayStatus::Connected => [46, 204, 64],
+            TrayStatus::OnJob => [0, 116, 217],
+            TrayStatus::SyncError => [255, 65, 54],
+        }
+    }
+
+    /// `base` with this status' badge in the bottom right corner
+    pub fn icon(self, base: &Image<'_>) -> Image<'static> {
+        let (width, height) = (base.width(), base.height());
+        let mut rgba = base.rgba().to_vec();
+        let radius = width.min(height) as f32 * BADGE_RADIUS;
+        let (cx, cy) = (width as f32 - radius, height as f32 - radius);
+        let [r, g, b] = self.color();
+        for y in 0..height {
+            for x in 0..width {
+                let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+                if dx * dx + dy * dy <= radius * radius {
+                    let i = ((y * width + x) * 4) as usize;
+                    rgba[i..i + 4].copy_from_slice(&[r, g, b, 255]);
+                }
+            }
+        }
+        Image::new_owned(rgba, width, height)
+    }
+}
+
+/// What the tray shows
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrayView {
+    pub status: TrayStatus,
+    /// Summary of the active job
+    pub job: Option<String>,
+    pub tracking_paused: bool,
+}
+
+impl TrayView {
+    pub fn from_state(telemetry: &TelemetryState, sync: SyncStatus) -> Self {
+        let job = telemetry.active_job.as_ref().map(|job| {
+            format!(
+                "{}: {} → {}, {} km left",
+                job.cargo, job.source_city, job.destination_city, job.distance_remaining
+            )
+        });
+        Self {
+            status: TrayStatus::from_state(telemetry, sync),
+            job,
+            tracking_paused: telemetry.tracking_paused,
+        }
+    }
+}
+
+/// Tray menu for `view`
+pub fn menu(app: &AppHandle, view: &TrayView) -> tauri::Result<Menu<Wry>> {
+    let job = view.job.as_deref().unwrap_or("No active job");
+    let toggle = if view.tracking_paused { "Resume tracking" } else { "Pause tracking" };
+    Menu::with_items(app, &[
+        &MenuItem::with_id(app, MENU_STATUS, view.status.label(), false, None::<&str>)?,
+        &MenuItem::with_id(app, MENU_JOB, job, false, None::<&str>)?,
+        &PredefinedMenuItem::separator(app)?,
+        &MenuItem::with_id(app, MENU_SHOW, "Show", true, None::<&str>)?,
+        &MenuItem::with_id(app, MENU_TOGGLE_TRACKING, toggle, true, None::<&str>)?,
+        &MenuItem::with_id(app, MENU_OPEN_LOGS, "Open logs folder", true, None::<&str>)?,
+        &PredefinedMenuItem::separator(app)?,
+        &MenuItem::with_id(app, MENU_QUIT, "Quit", true, None::<&str>)?,
+    ])
+}
+
+/// Open the log directory in the file manager
+pub fn open_logs() {
+    let dir = logging::log_directory();
+    if let Err(e) = open::that_detached(&dir) {
+        warn!("Failed to open {}: {}", dir.display(), e);
+    }
+}
+
+/// Applies tray updates only when something changed
+#[derive(Debug, Default)]
+pub struct TrayIndicator {
+    last: Option<TrayView>,
+}
+
+impl TrayIndicator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Status currently shown
+    pub fn status(&self) -> TrayStatus {
+        self.last.as_ref().map(|view| view.status).unwrap_or_default()
+    }
+
+    /// Push `view` to the tray icon; returns true if the status changed
+    pub fn update(&mut self, app: &AppHandle, view: TrayView) -> bool {
+        if self.last.as_ref() == Some(&view) {
+            return false;
+        }
+        let Some(tray) = app.tray_by_id(TRAY_ID) else {
+            return false;
+        };
+        let status_changed = self.status() != view.status || self.last.is_none();
+        if status_changed {
+            if let Some(base) = app.default_window_icon() {
+                if let Err(e) = tray.set_icon(Some(view.status.icon(base))) {
+                    debug!("Failed to set tray icon: {}", e);
+                }
+            }
+        }
+        match menu(app, &view) {
+            Ok(menu) => {
+                if let Err(e) = tray.set_menu(Some(menu)) {
+                    debug!("Failed to set tray menu: {}", e);
+                }
+            }
+            Err(e) => debug!("Failed to build tray menu: {}", e),
+        }
+        self.last = Some(view);
+        status_changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sync_problems_win_and_badge_is_drawn_in_the_corner() {
+        let connected = TelemetryState { connected: true, ..TelemetryState::default() };
+        assert_eq!(TrayStatus::from_state(&TelemetryState::default(), SyncStatus::Online), TrayStatus::NoGame);
+        assert_eq!(TrayStatus::from_state(&connected, SyncStatus::Unknown), TrayStatus::Connected);
+        assert_eq!(TrayStatus::from_state(&connected, SyncStatus::Offline), TrayStatus::SyncError);
+        assert_eq!(TrayView::from_state(&connected, SyncStatus::Online).job, None);
+
+        let base = Image::new_owned(vec![0; 32 * 32 * 4], 32, 32);
+        let icon = TrayStatus::OnJob.icon(&base);
+        let pixel = |x: usize, y: usize| icon.rgba()[(y * 32 + x) * 4..(y * 32 + x) * 4 + 4].to_vec();
+        assert_eq!(pixel(26, 26), [0, 116, 217, 255]);
+        assert_eq!(pixel(2, 2), [0, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,276 @@
+//! Updates Module
+//!
+//! Checks the release endpoint for newer desktop builds and installs them,
+//! so drivers aren't stuck on builds that can't read newer telemetry layouts.
+//! Installers only run once their Ed25519 signature checks out against the
+//! release key built into the app; the checksum alone comes from the same
+//! response as the download URL and proves nothing. The signature covers the
+//! version along with the installer's checksum, so an old signed installer
+//! can't be passed off as a newer release.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::sync::ReleaseInfo;
+
+/// How often the background checker asks for a new release
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+/// Directory in app storage that downloaded installers go to
+pub const INSTALLER_DIR: &str = "updates";
+/// Version of this build
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Base64 Ed25519 public key installers are signed with, set at build time;
+/// builds without it can't install updates
+const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("VTC_RELEASE_PUBLIC_KEY");
+
+/// Update errors
+#[derive(Debug, thiserror::Error)]
+pub enum UpdateError {
+    #[error("No update available")]
+    NotAvailable,
+
+    #[error("Download failed: {0}")]
+    Download(String),
+
+    #[error("Downloaded installer does not match the release checksum")]
+    Checksum,
+
+    #[error("Installer signature is invalid: {0}")]
+    Signature(String),
+
+    #[error("Invalid release version: {0}")]
+    Version(String),
+
+    #[error("Failed to save installer: {0}")]
+    Io(String),
+
+    #[error("Failed to start installer: {0}")]
+    Launch(String),
+}
+
+/// Update offered to the driver (`update_available` payload)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub current_version: String,
+    pub version: String,
+    pub notes: String,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+/// Latest known release and which one the driver was told about
+#[derive(Debug, Default)]
+pub struct Updates {
+    available: Option<ReleaseInfo>,
+    announced: Option<String>,
+}
+
+impl Updates {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the latest release; returns the update to announce when it is
+    /// newer than `current` and wasn't announced yet
+    pub fn offer(&mut self, release: Option<ReleaseInfo>, current: &str) -> Option<UpdateInfo> {
+        self.available = release.filter(|release| is_newer(&release.version, current));
+        let info = self.info(current)?;
+        if self.announced.as_deref() == Some(info.version.as_str()) {
+            return None;
+        }
+        self.announced = Some(info.version.clone());
+        Some(info)
+    }
+
+    /// Release to install, if one is newer than this build
+    pub fn release(&self) -> Option<&ReleaseInfo> {
+        self.available.as_ref()
+    }
+
+    /// The available update, if any
+    pub fn info(&self, current: &str) -> Option<UpdateInfo> {
+        self.available.as_ref().map(|release| UpdateInfo {
+            current_version: current.to_string(),
+            version: release.version.clone(),
+            notes: release.notes.clone(),
+            published_at: release.published_at,
+        })
+    }
+}
+
+/// Whether `candidate` is a later version than `current`; pre-releases
+/// (`1.3.0-beta.1`) sort before their release
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    fn parse(version: &str) -> (Vec<u64>, bool) {
+        let version = version.trim().trim_start_matches('v');
+        let (numbers, pre) = match version.split_once('-') {
+            Some((numbers, _)) => (numbers, true),
+            None => (version, false),
+        };
+        let mut parts: Vec<u64> = numbers.split('.').map(|part| part.parse().unwrap_or(0)).collect();
+        parts.resize(3.max(parts.len()), 0);
+        (parts, pre)
+    }
+
+    let (candidate, candidate_pre) = parse(candidate);
+    let (current, current_pre) = parse(current);
+    match candidate.cmp(&current) {
+        std::cmp::Ordering::Equal => current_pre && !candidate_pre,
+        ordering => ordering.is_gt(),
+    }
+}
+
+/// Whether `version` is a plain semantic version (`1.2.3`, `1.3.0-beta.1`),
+/// safe to use in a file name
+fn is_semver(version: &str) -> bool {
+    let (numbers, pre) = match version.split_once('-') {
+        Some((numbers, pre)) => (numbers, Some(pre)),
+        None => (version, None),
+    };
+    let parts: Vec<&str> = numbers.split('.').collect();
+    parts.len() == 3
+        && parts.iter().all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+        && pre.map_or(true, |pre| {
+            pre.split('.').all(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_alphanumeric()))
+        })
+}
+
+/// What the release key signs: the version and the installer's hex SHA-256
+pub fn release_manifest(version: &str, sha256: &str) -> String {
+    format!("vtc-tracker {}\nsha256 {}\n", version, sha256.to_ascii_lowercase())
+}
+
+/// Check the release signature over the manifest of `release` and the
+/// installer's `digest` against `public_key`
+fn verify_signature(public_key: &str, release: &ReleaseInfo, digest: &str) -> Result<(), UpdateError> {
+    let invalid = |e: String| UpdateError::Signature(e);
+    let key: [u8; 32] = BASE64.decode(public_key.trim())
+        .map_err(|e| invalid(e.to_string()))?
+        .try_into()
+        .map_err(|_| invalid("malformed release key".into()))?;
+    let key = VerifyingKey::from_bytes(&key).map_err(|e| invalid(e.to_string()))?;
+    let signature: [u8; 64] = BASE64.decode(release.signature.trim())
+        .map_err(|e| invalid(e.to_string()))?
+        .try_into()
+        .map_err(|_| invalid("malformed signature".into()))?;
+    key.verify(release_manifest(&release.version, digest).as_bytes(), &Signature::from_bytes(&signature))
+        .map_err(|_| invalid("not signed with the release key".into()))
+}
+
+/// Check the installer against the release signature and checksum and write
+/// it to `dir`
+pub fn save_installer(dir: &Path, release: &ReleaseInfo, bytes: &[u8]) -> Result<PathBuf, UpdateError> {
+    let public_key = RELEASE_PUBLIC_KEY.ok_or_else(|| UpdateError::Signature("this build has no release key".into()))?;
+    write_installer(dir, public_key, release, bytes)
+}
+
+fn write_installer(dir: &Path, public_key: &str, release: &ReleaseInfo, bytes: &[u8]) -> Result<PathBuf, UpdateError> {
+    if !is_semver(&release.version) {
+        return Err(UpdateError::Version(release.version.clone()));
+    }
+    let digest = format!("{:x}", Sha256::digest(bytes));
+    if !digest.eq_ignore_ascii_case(release.sha256.trim()) {
+        return Err(UpdateError::Checksum);
+    }
+    verify_signature(public_key, release, &digest)?;
+    std::fs::create_dir_all(dir).map_err(|e| UpdateError::Io(e.to_string()))?;
+    let path = dir.join(format!("vtc-tracker-{}.msi", release.version));
+    std::fs::write(&path, bytes).map_err(|e| UpdateError::Io(e.to_string()))?;
+    Ok(path)
+}
+
+/// Start the installer; the app should exit right after so files can be replaced
+pub fn launch_installer(path: &Path) -> Result<(), UpdateError> {
+    #[cfg(windows)]
+    {
+        std::process::Command::new("msiexec")
+            .arg("/i")
+            .arg(path)
+            .arg("/passive")
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| UpdateError::Launch(e.to_string()))
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = path;
+        Err(UpdateError::Launch("installers only run on Windows".into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(version: &str, bytes: &[u8]) -> ReleaseInfo {
+        ReleaseInfo {
+            version: version.into(),
+            notes: "Reads plugin revision 13".into(),
+            published_at: None,
+            download_url: "https://example.com/installer.msi".into(),
+            sha256: format!("{:x}", Sha256::digest(bytes)),
+            signature: String::new(),
+        }
+    }
+
+    #[test]
+    fn compares_versions() {
+        assert!(is_newer("1.0.1", "1.0.0"));
+        assert!(is_newer("v1.10.0", "1.9.9"));
+        assert!(is_newer("1.1.0", "1.1.0-beta.2"));
+        assert!(!is_newer("1.1.0-beta.2", "1.1.0"));
+        assert!(!is_newer("1.0", "1.0.0"));
+    }
+
+    #[test]
+    fn announces_each_update_once() {
+        let mut updates = Updates::new();
+        assert_eq!(updates.offer(Some(release("1.0.0", b"")), "1.0.0"), None);
+        assert!(updates.release().is_none());
+
+        let announced = updates.offer(Some(release("1.1.0", b"")), "1.0.0").unwrap();
+        assert_eq!((announced.version.as_str(), announced.current_version.as_str()), ("1.1.0", "1.0.0"));
+        assert_eq!(updates.offer(Some(release("1.1.0", b"")), "1.0.0"), None);
+        assert_eq!(updates.info("1.0.0"), Some(announced));
+        assert!(updates.offer(Some(release("1.2.0", b"")), "1.0.0").is_some());
+    }
+
+    #[test]
+    fn rejects_unsigned_tampered_or_oddly_named_installers() {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let dir = std::env::temp_dir().join(format!("vtc-updates-{}", uuid::Uuid::new_v4()));
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let public_key = BASE64.encode(key.verifying_key().to_bytes());
+        let signed = |version: &str, bytes: &[u8]| {
+            let release = release(version, bytes);
+            let manifest = release_manifest(version, &release.sha256);
+            ReleaseInfo { signature: BASE64.encode(key.sign(manifest.as_bytes()).to_bytes()), ..release }
+        };
+
+        assert!(write_installer(&dir, &public_key, &signed("1.1.0", b"installer"), b"installer").unwrap().exists());
+        let unsigned = release("1.1.0", b"installer");
+        assert!(matches!(write_installer(&dir, &public_key, &unsigned, b"installer"), Err(UpdateError::Signature(_))));
+        let other_key = BASE64.encode(SigningKey::from_bytes(&[8; 32]).verifying_key().to_bytes());
+        let signed_installer = signed("1.1.0", b"installer");
+        assert!(matches!(write_installer(&dir, &other_key, &signed_installer, b"installer"), Err(UpdateError::Signature(_))));
+        let tampered = ReleaseInfo { sha256: release("1.1.0", b"other").sha256, ..signed("1.1.0", b"installer") };
+        assert!(matches!(write_installer(&dir, &public_key, &tampered, b"installer"), Err(UpdateError::Checksum)));
+        // An older signed installer offered as a newer version
+        let replayed = ReleaseInfo { version: "1.2.0".into(), ..signed("1.1.0", b"installer") };
+        assert!(matches!(write_installer(&dir, &public_key, &replayed, b"installer"), Err(UpdateError::Signature(_))));
+        for version in ["../../evil", "1.1", "1.1.0-beta/1", "1.1.0 "] {
+            let release = signed(version, b"installer");
+            assert!(matches!(write_installer(&dir, &public_key, &release, b"installer"), Err(UpdateError::Version(_))), "{}", version);
+        }
+        assert!(is_semver("1.3.0-beta.1"));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}